@@ -1,6 +1,6 @@
 #![no_std]
 
-use soroban_sdk::{contract, contractimpl, token, Address, Env};
+use soroban_sdk::{contract, contractimpl, token, Address, Env, Vec};
 
 #[contract]
 pub struct FlashLoanReceiverModifiedERC3156;
@@ -21,4 +21,27 @@ impl FlashLoanReceiverModifiedERC3156 {
             &amount,
         );
     }
+
+    pub fn exec_op_multi(
+        env: Env,
+        caller: Address,
+        tokens: Vec<Address>,
+        amounts: Vec<i128>,
+        _fees: Vec<i128>,
+    ) {
+        // require the caller to authorize the invocation
+        caller.require_auth();
+
+        // perform operations here
+        // ...
+
+        // Test - return the amounts to caller so they can repay the flash loan.
+        for (token, amount) in tokens.iter().zip(amounts.iter()) {
+            token::Client::new(&env, &token).transfer(
+                &env.current_contract_address(),
+                &caller,
+                &amount,
+            );
+        }
+    }
 }
@@ -1,12 +1,14 @@
 use crate::{
-    constants::SCALAR_7,
+    constants::{MAX_AUCTION_PAGE_SIZE, SCALAR_7},
     errors::PoolError,
-    pool::{Pool, User},
-    storage,
+    events::PoolEvents,
+    pool::{is_delisted, Pool, User},
+    storage::{self, AuctionCurve},
 };
 use cast::i128;
+use sep_41_token::TokenClient;
 use soroban_fixed_point_math::SorobanFixedPoint;
-use soroban_sdk::{contracttype, map, panic_with_error, Address, Env, Map, Vec};
+use soroban_sdk::{contracttype, map, panic_with_error, vec, Address, Env, Map, Vec};
 
 use super::{
     backstop_interest_auction::{create_interest_auction_data, fill_interest_auction},
@@ -57,11 +59,43 @@ pub struct AuctionData {
     pub block: u32,
 }
 
+/// An active auction with a quote of the auction if it were fully filled at the current block
+#[derive(Clone)]
+#[contracttype]
+pub struct AuctionEntry {
+    /// The user involved in the auction
+    pub user: Address,
+    /// The auction data stored on the ledger
+    pub auction_data: AuctionData,
+    /// The bid and lot if the auction were fully filled at the current block
+    pub quote: AuctionData,
+}
+
+/// The parameters of an auction to create
+#[derive(Clone)]
+#[contracttype]
+pub struct AuctionParams {
+    /// The type of auction to create
+    pub auction_type: u32,
+    /// The user involved in the auction
+    pub user: Address,
+    /// The assets being bid on
+    pub bid: Vec<Address>,
+    /// The assets being auctioned off
+    pub lot: Vec<Address>,
+    /// The percentage of the user's positions being auctioned
+    pub percent: u32,
+}
+
 /// Create a new auction. Stores the resulting auction to the ledger to begin on the next block.
 ///
+/// The keeper is owed a share of the lot when a user liquidation or interest auction is filled,
+/// if keeper rewards are configured.
+///
 /// Returns the AuctionData object created
 ///
 /// ### Arguments
+/// * `keeper` - The address creating the auction
 /// * `auction_type` - The type of auction being created
 /// * `user` - The user involved in the auction
 /// * `bid` - The assets being bid on
@@ -74,6 +108,55 @@ pub struct AuctionData {
 /// * If the auction is unable to be created
 pub fn create_auction(
     e: &Env,
+    keeper: &Address,
+    auction_type: u32,
+    user: &Address,
+    bid: &Vec<Address>,
+    lot: &Vec<Address>,
+    percent: u32,
+) -> AuctionData {
+    let mut pool = Pool::load(e);
+    build_auction(e, &mut pool, keeper, auction_type, user, bid, lot, percent)
+}
+
+/// Create a batch of new auctions. The pool, its reserves and oracle prices are loaded once and
+/// shared across the batch. Stores the resulting auctions to the ledger to begin on the next block.
+///
+/// Returns the AuctionData objects created, in the order of `params`
+///
+/// ### Arguments
+/// * `keeper` - The address creating the auctions
+/// * `params` - The parameters of each auction being created
+///
+/// ### Panics
+/// * If no auctions are requested
+/// * If any auction is unable to be created
+pub fn create_auctions(e: &Env, keeper: &Address, params: &Vec<AuctionParams>) -> Vec<AuctionData> {
+    if params.is_empty() {
+        panic_with_error!(e, PoolError::BadRequest);
+    }
+    let mut pool = Pool::load(e);
+    let mut auctions = vec![e];
+    for param in params.iter() {
+        auctions.push_back(build_auction(
+            e,
+            &mut pool,
+            keeper,
+            param.auction_type,
+            &param.user,
+            &param.bid,
+            &param.lot,
+            param.percent,
+        ));
+    }
+    auctions
+}
+
+#[allow(clippy::too_many_arguments)]
+fn build_auction(
+    e: &Env,
+    pool: &mut Pool,
+    keeper: &Address,
     auction_type: u32,
     user: &Address,
     bid: &Vec<Address>,
@@ -83,11 +166,22 @@ pub fn create_auction(
     // panics if auction_type parameter is not valid
     let auction_type_enum = AuctionType::from_u32(e, auction_type);
     let auction_data = match auction_type_enum {
-        AuctionType::UserLiquidation => create_user_liq_auction_data(e, user, bid, lot, percent),
-        AuctionType::BadDebtAuction => create_bad_debt_auction_data(e, user, bid, lot, percent),
-        AuctionType::InterestAuction => create_interest_auction_data(e, user, bid, lot, percent),
+        AuctionType::UserLiquidation => {
+            create_user_liq_auction_data(e, pool, user, bid, lot, percent)
+        }
+        AuctionType::BadDebtAuction => {
+            create_bad_debt_auction_data(e, pool, user, bid, lot, percent)
+        }
+        AuctionType::InterestAuction => {
+            create_interest_auction_data(e, pool, user, bid, lot, percent)
+        }
     };
     storage::set_auction(e, &auction_type, user, &auction_data);
+    // bad debt auctions are created as part of processing bad debt, and users cannot be rewarded
+    // for their own liquidation
+    if auction_type_enum != AuctionType::BadDebtAuction && keeper != user {
+        storage::set_auction_keeper(e, &auction_type, user, keeper);
+    }
     auction_data
 }
 
@@ -99,11 +193,23 @@ pub fn create_auction(
 /// * `auction_type` - The type of auction being created
 ///
 /// ### Panics
-/// If no auction exists for the user
+/// If no auction exists for the user, or the auction only liquidates liabilities in reserves that
+/// have finished delisting
 pub fn delete_liquidation(e: &Env, user: &Address) {
     if !storage::has_auction(e, &(AuctionType::UserLiquidation as u32), user) {
         panic_with_error!(e, PoolError::BadRequest);
     }
+    // liquidations of liabilities in delisted reserves do not depend on the user's health factor
+    let auction_data = storage::get_auction(e, &(AuctionType::UserLiquidation as u32), user);
+    if !auction_data.bid.is_empty()
+        && auction_data
+            .bid
+            .keys()
+            .iter()
+            .all(|asset| is_delisted(e, &asset))
+    {
+        panic_with_error!(e, PoolError::InvalidLiquidation);
+    }
     storage::del_auction(e, &(AuctionType::UserLiquidation as u32), user);
 }
 
@@ -131,18 +237,57 @@ pub fn fill(
         panic_with_error!(e, PoolError::InvalidLiquidation);
     }
     let auction_data = storage::get_auction(e, &auction_type, user);
-    let (to_fill_auction, remaining_auction) = scale_auction(e, &auction_data, percent_filled);
+    let curve = storage::get_auction_curve(e, auction_type);
+    let (mut to_fill_auction, remaining_auction) =
+        scale_auction(e, &auction_data, &curve, percent_filled);
+    let keeper_reward = load_keeper_reward(
+        e,
+        auction_type,
+        user,
+        &filler_state.address,
+        &to_fill_auction.lot,
+    );
     match AuctionType::from_u32(e, auction_type) {
         AuctionType::UserLiquidation => {
-            fill_user_liq_auction(e, pool, &to_fill_auction, user, filler_state)
+            // the user gives up the full lot, and the keeper's share is moved from the filler to
+            // the keeper as uncollateralized supply
+            fill_user_liq_auction(e, pool, &to_fill_auction, user, filler_state);
+            if let Some((keeper, keeper_lot)) = &keeper_reward {
+                let mut keeper_state = User::load(e, keeper);
+                for (asset, b_tokens) in keeper_lot.iter() {
+                    let mut reserve = pool.load_reserve(e, &asset, true);
+                    filler_state.remove_collateral(e, &mut reserve, b_tokens);
+                    keeper_state.add_supply(e, &mut reserve, b_tokens);
+                    pool.cache_reserve(reserve);
+                }
+                keeper_state.store(e);
+            }
         }
         AuctionType::BadDebtAuction => {
             fill_bad_debt_auction(e, pool, &to_fill_auction, filler_state)
         }
         AuctionType::InterestAuction => {
-            fill_interest_auction(e, pool, &to_fill_auction, &filler_state.address)
+            let mut filler_auction = to_fill_auction.clone();
+            if let Some((keeper, keeper_lot)) = &keeper_reward {
+                filler_auction.lot = remove_keeper_lot(&to_fill_auction.lot, keeper_lot);
+                for (asset, amount) in keeper_lot.iter() {
+                    let mut reserve = pool.load_reserve(e, &asset, true);
+                    reserve.data.backstop_credit -= amount;
+                    pool.cache_reserve(reserve);
+                    TokenClient::new(e, &asset).transfer(
+                        &e.current_contract_address(),
+                        keeper,
+                        &amount,
+                    );
+                }
+            }
+            fill_interest_auction(e, pool, &filler_auction, &filler_state.address)
         }
     };
+    if let Some((keeper, keeper_lot)) = keeper_reward {
+        to_fill_auction.lot = remove_keeper_lot(&to_fill_auction.lot, &keeper_lot);
+        PoolEvents::keeper_reward(e, auction_type, user.clone(), keeper, keeper_lot);
+    }
 
     if let Some(auction_to_store) = remaining_auction {
         storage::set_auction(e, &auction_type, user, &auction_to_store);
@@ -153,11 +298,122 @@ pub fn fill(
     to_fill_auction
 }
 
+/// Load the keeper owed a share of a filled lot and the share of the lot they are owed. No reward
+/// is owed if the keeper is filling the auction themselves.
+fn load_keeper_reward(
+    e: &Env,
+    auction_type: u32,
+    user: &Address,
+    filler: &Address,
+    lot: &Map<Address, i128>,
+) -> Option<(Address, Map<Address, i128>)> {
+    let keeper = storage::get_auction_keeper(e, &auction_type, user)?;
+    let lot_share = storage::get_keeper_config(e).lot_share;
+    if keeper == *filler || lot_share == 0 {
+        return None;
+    }
+    let mut keeper_lot = map![e];
+    for (asset, amount) in lot.iter() {
+        let keeper_amount = amount.fixed_mul_floor(e, &i128(lot_share), &SCALAR_7);
+        if keeper_amount > 0 {
+            keeper_lot.set(asset, keeper_amount);
+        }
+    }
+    if keeper_lot.is_empty() {
+        return None;
+    }
+    Some((keeper, keeper_lot))
+}
+
+/// Remove the keeper's share from a lot
+fn remove_keeper_lot(
+    lot: &Map<Address, i128>,
+    keeper_lot: &Map<Address, i128>,
+) -> Map<Address, i128> {
+    let mut filler_lot = lot.clone();
+    for (asset, keeper_amount) in keeper_lot.iter() {
+        let amount = lot.get(asset.clone()).unwrap_or(0) - keeper_amount;
+        if amount > 0 {
+            filler_lot.set(asset, amount);
+        } else {
+            filler_lot.remove(asset);
+        }
+    }
+    filler_lot
+}
+
+/// Quote the bid and lot of an auction if it were fully filled at the current block
+///
+/// ### Arguments
+/// * `auction_type` - The type of auction
+/// * `user` - The user involved in the auction
+///
+/// ### Panics
+/// If the auction does not exist
+pub fn get_auction_quote(e: &Env, auction_type: u32, user: &Address) -> AuctionData {
+    let auction_data = storage::get_auction(e, &auction_type, user);
+    let curve = storage::get_auction_curve(e, auction_type);
+    quote_auction(e, &auction_data, &curve)
+}
+
+/// Load a page of the active auctions of an auction type, with a quote of each auction if it
+/// were fully filled at the current block. Users in the auction index whose auction no longer
+/// exists are removed from the index as they are reached, and the last user in the index takes
+/// their position.
+///
+/// ### Arguments
+/// * `auction_type` - The type of auction
+/// * `start` - The position in the auction index to start at
+/// * `limit` - The max number of auctions to return
+///
+/// ### Panics
+/// If the auction type is invalid or the limit exceeds the max page size
+pub fn load_auctions(e: &Env, auction_type: u32, start: u32, limit: u32) -> Vec<AuctionEntry> {
+    AuctionType::from_u32(e, auction_type);
+    if limit > MAX_AUCTION_PAGE_SIZE {
+        panic_with_error!(e, PoolError::BadRequest);
+    }
+    let curve = storage::get_auction_curve(e, auction_type);
+    let mut count = storage::get_auction_count(e, &auction_type);
+    let mut position = start;
+    let mut entries = vec![e];
+    while position < count && entries.len() < limit {
+        match storage::get_auction_index(e, &auction_type, position) {
+            Some(user) if storage::has_auction(e, &auction_type, &user) => {
+                let auction_data = storage::get_auction(e, &auction_type, &user);
+                let quote = quote_auction(e, &auction_data, &curve);
+                entries.push_back(AuctionEntry {
+                    user,
+                    auction_data,
+                    quote,
+                });
+                position += 1;
+            }
+            _ => {
+                storage::remove_auction_index(e, &auction_type, position);
+                count -= 1;
+            }
+        }
+    }
+    entries
+}
+
+/// Quote the bid and lot of an auction if it were fully filled at the current block. Auctions
+/// that have not started yet are quoted as of their first block.
+fn quote_auction(e: &Env, auction_data: &AuctionData, curve: &AuctionCurve) -> AuctionData {
+    let mut started_auction = auction_data.clone();
+    started_auction.block = auction_data.block.min(e.ledger().sequence());
+    let (mut quote, _) = scale_auction(e, &started_auction, curve, 100);
+    quote.block = auction_data.block;
+    quote
+}
+
 /// Scale the auction based on the percent being filled and the amount of blocks that have passed
-/// since the auction began.
+/// since the auction began, following the auction's Dutch auction curve.
 ///
 /// ### Arguments
 /// * `auction_data` - The auction data to scale
+/// * `curve` - The Dutch auction curve of the auction type
 /// * `percent_filled` - The percentage being filled as a number (i.e. 15 => 15%)
 ///
 /// Returns the (Scaled Auction, Remaining Auction) such that:
@@ -170,6 +426,7 @@ pub fn fill(
 fn scale_auction(
     e: &Env,
     auction_data: &AuctionData,
+    curve: &AuctionCurve,
     percent_filled: u64,
 ) -> (AuctionData, Option<AuctionData>) {
     if percent_filled > 100 || percent_filled == 0 {
@@ -190,19 +447,23 @@ fn scale_auction(
     // determine block based auction modifiers
     let bid_modifier: i128;
     let lot_modifier: i128;
-    let per_block_scalar: i128 = 0_0050000; // modifier moves 0.5% every block
+    let lot_ramp = i128(curve.lot_ramp);
+    let bid_decay = i128(curve.bid_decay);
     let block_dif = i128(e.ledger().sequence() - auction_data.block);
-    if block_dif > 200 {
+    if block_dif > lot_ramp {
         // lot 100%, bid scaling down from 100% to 0%
         lot_modifier = SCALAR_7;
-        if block_dif < 400 {
-            bid_modifier = SCALAR_7 - (block_dif - 200) * per_block_scalar;
+        if block_dif < lot_ramp + bid_decay {
+            bid_modifier =
+                SCALAR_7 - SCALAR_7.fixed_mul_floor(e, &(block_dif - lot_ramp), &bid_decay);
         } else {
             bid_modifier = 0;
         }
     } else {
-        // lot scaling from 0% to 100%, bid 100%
-        lot_modifier = block_dif * per_block_scalar;
+        // lot scaling from the start premium to 100%, bid 100%
+        let start_premium = i128(curve.start_premium);
+        lot_modifier =
+            start_premium + (SCALAR_7 - start_premium).fixed_mul_floor(e, &block_dif, &lot_ramp);
         bid_modifier = SCALAR_7;
     }
 
@@ -248,7 +509,7 @@ fn scale_auction(
 mod tests {
     use crate::{
         pool::Positions,
-        storage::PoolConfig,
+        storage::{KeeperConfig, PoolConfig, ReserveDelisting},
         testutils::{self, create_comet_lp_pool, create_pool},
     };
 
@@ -386,6 +647,7 @@ mod tests {
 
             create_auction(
                 &e,
+                &Address::generate(&e),
                 1,
                 &backstop_address,
                 &vec![&e, underlying_0, underlying_1],
@@ -495,6 +757,7 @@ mod tests {
 
             create_auction(
                 &e,
+                &Address::generate(&e),
                 2,
                 &backstop_address,
                 &vec![&e, backstop_token_id],
@@ -611,6 +874,7 @@ mod tests {
             e.cost_estimate().budget().reset_unlimited();
             create_auction(
                 &e,
+                &Address::generate(&e),
                 0,
                 &samwise,
                 &vec![&e, underlying_2],
@@ -724,6 +988,7 @@ mod tests {
 
             create_auction(
                 &e,
+                &Address::generate(&e),
                 0,
                 &pool_address,
                 &vec![&e, underlying_2],
@@ -837,6 +1102,7 @@ mod tests {
 
             create_auction(
                 &e,
+                &Address::generate(&e),
                 0,
                 &backstop,
                 &vec![&e, underlying_2],
@@ -916,6 +1182,7 @@ mod tests {
 
             create_auction(
                 &e,
+                &Address::generate(&e),
                 3,
                 &backstop_address,
                 &vec![&e, backstop_token_id],
@@ -926,77 +1193,34 @@ mod tests {
     }
 
     #[test]
-    fn test_delete_user_liquidation() {
-        let e = Env::default();
-        e.mock_all_auths();
-
-        let pool_id = create_pool(&e);
-        let samwise = Address::generate(&e);
-
-        let auction_data = AuctionData {
-            bid: map![&e],
-            lot: map![&e],
-            block: 100,
-        };
-        e.as_contract(&pool_id, || {
-            storage::set_auction(
-                &e,
-                &(AuctionType::UserLiquidation as u32),
-                &samwise,
-                &auction_data,
-            );
-
-            delete_liquidation(&e, &samwise);
-            assert!(!storage::has_auction(
-                &e,
-                &(AuctionType::UserLiquidation as u32),
-                &samwise
-            ));
-        });
-    }
-
-    #[test]
-    #[should_panic(expected = "Error(Contract, #1200)")]
-    fn test_delete_user_liquidation_does_not_exist() {
-        let e = Env::default();
-        e.mock_all_auths();
-        let pool_id = create_pool(&e);
-
-        let samwise = Address::generate(&e);
-
-        e.as_contract(&pool_id, || {
-            delete_liquidation(&e, &samwise);
-        });
-    }
-
-    #[test]
-    fn test_fill() {
+    fn test_create_auctions_shares_pool() {
         let e = Env::default();
 
         e.mock_all_auths();
         e.ledger().set(LedgerInfo {
             timestamp: 12345,
             protocol_version: 22,
-            sequence_number: 175,
+            sequence_number: 50,
             network_id: Default::default(),
             base_reserve: 10,
-            min_temp_entry_ttl: 172800,
-            min_persistent_entry_ttl: 172800,
-            max_entry_ttl: 9999999,
+            min_temp_entry_ttl: 10,
+            min_persistent_entry_ttl: 10,
+            max_entry_ttl: 3110400,
         });
 
         let bombadil = Address::generate(&e);
-        let samwise = Address::generate(&e);
-        let frodo = Address::generate(&e);
 
         let pool_address = create_pool(&e);
-
-        let (oracle_address, _) = testutils::create_mock_oracle(&e);
+        let (oracle_address, oracle_client) = testutils::create_mock_oracle(&e);
 
         // creating reserves for a pool exhausts the budget
         e.cost_estimate().budget().reset_unlimited();
         let (underlying_0, _) = testutils::create_token_contract(&e, &bombadil);
-        let (mut reserve_config_0, reserve_data_0) = testutils::default_reserve_meta();
+        let (mut reserve_config_0, mut reserve_data_0) = testutils::default_reserve_meta();
+        reserve_data_0.last_time = 12345;
+        reserve_data_0.b_rate = 1_100_000_000_000;
+        reserve_config_0.c_factor = 0_8500000;
+        reserve_config_0.l_factor = 0_9000000;
         reserve_config_0.index = 0;
         testutils::create_reserve(
             &e,
@@ -1007,7 +1231,11 @@ mod tests {
         );
 
         let (underlying_1, _) = testutils::create_token_contract(&e, &bombadil);
-        let (mut reserve_config_1, reserve_data_1) = testutils::default_reserve_meta();
+        let (mut reserve_config_1, mut reserve_data_1) = testutils::default_reserve_meta();
+        reserve_data_1.b_rate = 1_200_000_000_000;
+        reserve_config_1.c_factor = 0_7500000;
+        reserve_config_1.l_factor = 0_7500000;
+        reserve_data_1.last_time = 12345;
         reserve_config_1.index = 1;
         testutils::create_reserve(
             &e,
@@ -1019,6 +1247,8 @@ mod tests {
 
         let (underlying_2, _) = testutils::create_token_contract(&e, &bombadil);
         let (mut reserve_config_2, reserve_data_2) = testutils::default_reserve_meta();
+        reserve_config_2.c_factor = 0_0000000;
+        reserve_config_2.l_factor = 0_7000000;
         reserve_config_2.index = 2;
         testutils::create_reserve(
             &e,
@@ -1027,24 +1257,21 @@ mod tests {
             &reserve_config_2,
             &reserve_data_2,
         );
-        e.cost_estimate().budget().reset_unlimited();
 
-        let auction_data = AuctionData {
-            bid: map![&e, (underlying_2.clone(), 1_2375000)],
-            lot: map![
+        oracle_client.set_data(
+            &bombadil,
+            &Asset::Other(Symbol::new(&e, "USD")),
+            &vec![
                 &e,
-                (underlying_0.clone(), 30_5595329),
-                (underlying_1.clone(), 1_5395739)
+                Asset::Stellar(underlying_0.clone()),
+                Asset::Stellar(underlying_1.clone()),
+                Asset::Stellar(underlying_2.clone()),
             ],
-            block: 176,
-        };
-        let pool_config = PoolConfig {
-            oracle: oracle_address,
-            min_collateral: 1_0000000,
-            bstop_rate: 0_1000000,
-            status: 0,
-            max_positions: 4,
-        };
+            &7,
+            &300,
+        );
+        oracle_client.set_price_stable(&vec![&e, 2_0000000, 4_0000000, 50_0000000]);
+
         let positions: Positions = Positions {
             collateral: map![
                 &e,
@@ -1054,72 +1281,696 @@ mod tests {
             liabilities: map![&e, (reserve_config_2.index, 02_7500000),],
             supply: map![&e],
         };
+        let pool_config = PoolConfig {
+            oracle: oracle_address,
+            min_collateral: 1_0000000,
+            bstop_rate: 0_1000000,
+            status: 0,
+            max_positions: 4,
+        };
+        let users = vec![
+            &e,
+            Address::generate(&e),
+            Address::generate(&e),
+            Address::generate(&e),
+            Address::generate(&e),
+            Address::generate(&e),
+            Address::generate(&e),
+        ];
+        let bid = vec![&e, underlying_2.clone()];
+        let lot = vec![&e, underlying_0.clone(), underlying_1.clone()];
+        let keeper = Address::generate(&e);
         e.as_contract(&pool_address, || {
-            storage::set_user_positions(&e, &samwise, &positions);
+            storage::set_backstop(&e, &Address::generate(&e));
             storage::set_pool_config(&e, &pool_config);
-            storage::set_auction(&e, &0, &samwise, &auction_data);
+            for user in users.iter() {
+                storage::set_user_positions(&e, &user, &positions);
+            }
 
-            e.ledger().set(LedgerInfo {
-                timestamp: 12345 + 200 * 5,
-                protocol_version: 22,
-                sequence_number: 176 + 200,
-                network_id: Default::default(),
-                base_reserve: 10,
-                min_temp_entry_ttl: 172800,
-                min_persistent_entry_ttl: 172800,
-                max_entry_ttl: 9999999,
-            });
+            // create the first three auctions one at a time
             e.cost_estimate().budget().reset_unlimited();
-            let mut pool = Pool::load(&e);
-            let mut frodo_state = User::load(&e, &frodo);
-            fill(&e, &mut pool, 0, &samwise, &mut frodo_state, 100);
-            let has_auction = storage::has_auction(&e, &0, &samwise);
-            assert_eq!(has_auction, false);
+            for i in 0..3 {
+                create_auction(&e, &keeper, 0, &users.get_unchecked(i), &bid, &lot, 45);
+            }
+            let single_cpu = e.cost_estimate().budget().cpu_instruction_cost();
+
+            // create the last three auctions as a batch
+            let mut params = vec![&e];
+            for i in 3..6 {
+                params.push_back(AuctionParams {
+                    auction_type: 0,
+                    user: users.get_unchecked(i),
+                    bid: bid.clone(),
+                    lot: lot.clone(),
+                    percent: 45,
+                });
+            }
+            e.cost_estimate().budget().reset_unlimited();
+            let auctions = create_auctions(&e, &keeper, &params);
+            let batch_cpu = e.cost_estimate().budget().cpu_instruction_cost();
+
+            assert_eq!(auctions.len(), 3);
+            for (i, auction) in auctions.iter().enumerate() {
+                let single = storage::get_auction(&e, &0, &users.get_unchecked(i as u32));
+                assert_eq!(auction.block, single.block);
+                assert_eq!(auction.bid, single.bid);
+                assert_eq!(auction.lot, single.lot);
+                assert!(storage::has_auction(
+                    &e,
+                    &0,
+                    &users.get_unchecked(i as u32 + 3)
+                ));
+            }
+            assert_eq!(
+                auctions.get_unchecked(0).bid.get_unchecked(underlying_2),
+                1_2375000
+            );
+            assert!(batch_cpu < single_cpu);
         });
     }
 
     #[test]
-    fn test_partial_fill() {
+    #[should_panic(expected = "Error(Contract, #1212)")]
+    fn test_create_auctions_already_in_progress() {
         let e = Env::default();
-
         e.mock_all_auths();
-        e.ledger().set(LedgerInfo {
-            timestamp: 12345,
-            protocol_version: 22,
-            sequence_number: 175,
-            network_id: Default::default(),
-            base_reserve: 10,
-            min_temp_entry_ttl: 172800,
-            min_persistent_entry_ttl: 172800,
-            max_entry_ttl: 9999999,
-        });
+        e.cost_estimate().budget().reset_unlimited();
 
-        let bombadil = Address::generate(&e);
+        let pool_address = create_pool(&e);
         let samwise = Address::generate(&e);
-        let frodo = Address::generate(&e);
+        let auction_data = AuctionData {
+            bid: map![&e],
+            lot: map![&e],
+            block: 50,
+        };
+        let params = vec![
+            &e,
+            AuctionParams {
+                auction_type: 0,
+                user: samwise.clone(),
+                bid: vec![&e],
+                lot: vec![&e],
+                percent: 50,
+            },
+        ];
+        e.as_contract(&pool_address, || {
+            storage::set_backstop(&e, &Address::generate(&e));
+            storage::set_auction(&e, &0, &samwise, &auction_data);
+            create_auctions(&e, &Address::generate(&e), &params);
+        });
+    }
 
+    #[test]
+    #[should_panic(expected = "Error(Contract, #1200)")]
+    fn test_create_auctions_empty() {
+        let e = Env::default();
         let pool_address = create_pool(&e);
 
-        let (oracle_address, _) = testutils::create_mock_oracle(&e);
+        e.as_contract(&pool_address, || {
+            create_auctions(&e, &Address::generate(&e), &vec![&e]);
+        });
+    }
 
-        // creating reserves for a pool exhausts the budget
-        e.cost_estimate().budget().reset_unlimited();
-        let (underlying_0, _) = testutils::create_token_contract(&e, &bombadil);
-        let (mut reserve_config_0, reserve_data_0) = testutils::default_reserve_meta();
-        reserve_config_0.index = 0;
-        testutils::create_reserve(
-            &e,
-            &pool_address,
-            &underlying_0,
-            &reserve_config_0,
-            &reserve_data_0,
-        );
+    #[test]
+    fn test_delete_user_liquidation() {
+        let e = Env::default();
+        e.mock_all_auths();
 
-        let (underlying_1, _) = testutils::create_token_contract(&e, &bombadil);
-        let (mut reserve_config_1, reserve_data_1) = testutils::default_reserve_meta();
-        reserve_config_1.index = 1;
-        testutils::create_reserve(
-            &e,
+        let pool_id = create_pool(&e);
+        let samwise = Address::generate(&e);
+
+        let auction_data = AuctionData {
+            bid: map![&e],
+            lot: map![&e],
+            block: 100,
+        };
+        e.as_contract(&pool_id, || {
+            storage::set_auction(
+                &e,
+                &(AuctionType::UserLiquidation as u32),
+                &samwise,
+                &auction_data,
+            );
+
+            delete_liquidation(&e, &samwise);
+            assert!(!storage::has_auction(
+                &e,
+                &(AuctionType::UserLiquidation as u32),
+                &samwise
+            ));
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "Error(Contract, #1211)")]
+    fn test_delete_user_liquidation_delisted_reserve() {
+        let e = Env::default();
+        e.mock_all_auths();
+        e.ledger().set(LedgerInfo {
+            timestamp: 12345,
+            protocol_version: 22,
+            sequence_number: 100,
+            network_id: Default::default(),
+            base_reserve: 10,
+            min_temp_entry_ttl: 10,
+            min_persistent_entry_ttl: 10,
+            max_entry_ttl: 3110400,
+        });
+
+        let pool_id = create_pool(&e);
+        let underlying_0 = Address::generate(&e);
+        let underlying_1 = Address::generate(&e);
+        let samwise = Address::generate(&e);
+
+        let auction_data = AuctionData {
+            bid: map![&e, (underlying_1.clone(), 10_0000000)],
+            lot: map![&e, (underlying_0.clone(), 20_0000000)],
+            block: 100,
+        };
+        e.as_contract(&pool_id, || {
+            storage::set_res_delisting(
+                &e,
+                &underlying_1,
+                &ReserveDelisting {
+                    c_factor: 0_8000000,
+                    start_time: 1000,
+                    end_time: 12345,
+                },
+            );
+            storage::set_auction(
+                &e,
+                &(AuctionType::UserLiquidation as u32),
+                &samwise,
+                &auction_data,
+            );
+
+            delete_liquidation(&e, &samwise);
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "Error(Contract, #1200)")]
+    fn test_delete_user_liquidation_does_not_exist() {
+        let e = Env::default();
+        e.mock_all_auths();
+        let pool_id = create_pool(&e);
+
+        let samwise = Address::generate(&e);
+
+        e.as_contract(&pool_id, || {
+            delete_liquidation(&e, &samwise);
+        });
+    }
+
+    #[test]
+    fn test_load_auctions() {
+        let e = Env::default();
+        e.mock_all_auths();
+        e.ledger().set(LedgerInfo {
+            timestamp: 12345,
+            protocol_version: 22,
+            sequence_number: 300,
+            network_id: Default::default(),
+            base_reserve: 10,
+            min_temp_entry_ttl: 172800,
+            min_persistent_entry_ttl: 172800,
+            max_entry_ttl: 9999999,
+        });
+
+        let pool_id = create_pool(&e);
+        let underlying_0 = Address::generate(&e);
+        let underlying_1 = Address::generate(&e);
+        let samwise = Address::generate(&e);
+        let frodo = Address::generate(&e);
+        let merry = Address::generate(&e);
+        let pippin = Address::generate(&e);
+
+        let auction_data = |block: u32| AuctionData {
+            bid: map![&e, (underlying_0.clone(), 100_0000000)],
+            lot: map![&e, (underlying_1.clone(), 100_0000000)],
+            block,
+        };
+        e.as_contract(&pool_id, || {
+            storage::set_auction(&e, &0, &samwise, &auction_data(200));
+            storage::set_auction(&e, &0, &frodo, &auction_data(301));
+            storage::set_auction(&e, &0, &merry, &auction_data(0));
+            storage::set_auction(&e, &0, &pippin, &auction_data(100));
+            storage::set_auction(&e, &1, &samwise, &auction_data(100));
+            // updating an auction does not re-index it
+            storage::set_auction(&e, &0, &samwise, &auction_data(200));
+            storage::del_auction(&e, &0, &pippin);
+
+            assert_eq!(storage::get_auction_count(&e, &0), 3);
+            assert_eq!(storage::get_auction_count(&e, &1), 1);
+            assert_eq!(storage::get_auction_count(&e, &2), 0);
+
+            let page = load_auctions(&e, 0, 0, 2);
+            assert_eq!(page.len(), 2);
+            let entry = page.get_unchecked(0);
+            assert_eq!(entry.user, samwise);
+            assert_eq!(entry.auction_data.block, 200);
+            assert_eq!(
+                entry.auction_data.lot.get_unchecked(underlying_1.clone()),
+                100_0000000
+            );
+            assert_eq!(
+                entry.quote.bid.get_unchecked(underlying_0.clone()),
+                100_0000000
+            );
+            assert_eq!(
+                entry.quote.lot.get_unchecked(underlying_1.clone()),
+                50_0000000
+            );
+            // auctions that have not started are quoted as of their first block
+            let entry = page.get_unchecked(1);
+            assert_eq!(entry.user, frodo);
+            assert_eq!(entry.quote.block, 301);
+            assert_eq!(
+                entry.quote.bid.get_unchecked(underlying_0.clone()),
+                100_0000000
+            );
+            assert_eq!(entry.quote.lot.len(), 0);
+
+            let page = load_auctions(&e, 0, 2, 2);
+            assert_eq!(page.len(), 1);
+            let entry = page.get_unchecked(0);
+            assert_eq!(entry.user, merry);
+            assert_eq!(
+                entry.quote.bid.get_unchecked(underlying_0.clone()),
+                50_0000000
+            );
+            assert_eq!(
+                entry.quote.lot.get_unchecked(underlying_1.clone()),
+                100_0000000
+            );
+
+            assert_eq!(load_auctions(&e, 0, 3, 2).len(), 0);
+            assert_eq!(load_auctions(&e, 1, 0, 50).len(), 1);
+        });
+    }
+
+    #[test]
+    fn test_load_auctions_removes_expired_auctions() {
+        let e = Env::default();
+        e.mock_all_auths();
+        e.ledger().set(LedgerInfo {
+            timestamp: 12345,
+            protocol_version: 22,
+            sequence_number: 300,
+            network_id: Default::default(),
+            base_reserve: 10,
+            min_temp_entry_ttl: 172800,
+            min_persistent_entry_ttl: 172800,
+            max_entry_ttl: 9999999,
+        });
+
+        let pool_id = create_pool(&e);
+        let underlying_0 = Address::generate(&e);
+        let underlying_1 = Address::generate(&e);
+        let samwise = Address::generate(&e);
+        let frodo = Address::generate(&e);
+        let merry = Address::generate(&e);
+        let pippin = Address::generate(&e);
+
+        let auction_data = AuctionData {
+            bid: map![&e, (underlying_0.clone(), 100_0000000)],
+            lot: map![&e, (underlying_1.clone(), 100_0000000)],
+            block: 300,
+        };
+        e.as_contract(&pool_id, || {
+            storage::set_auction(&e, &0, &pippin, &auction_data);
+            storage::set_auction(&e, &0, &samwise, &auction_data);
+            storage::set_auction(&e, &0, &frodo, &auction_data);
+            storage::set_auction(&e, &0, &merry, &auction_data);
+        });
+
+        // only pippin's auction is extended, so the others expire
+        e.ledger().set(LedgerInfo {
+            timestamp: 12345 + 2 * 24 * 60 * 60,
+            protocol_version: 22,
+            sequence_number: 300 + 2 * 17280,
+            network_id: Default::default(),
+            base_reserve: 10,
+            min_temp_entry_ttl: 172800,
+            min_persistent_entry_ttl: 172800,
+            max_entry_ttl: 9999999,
+        });
+        e.as_contract(&pool_id, || {
+            storage::set_auction(&e, &0, &pippin, &auction_data);
+        });
+        e.ledger().set(LedgerInfo {
+            timestamp: 12345 + 47 * 24 * 60 * 60,
+            protocol_version: 22,
+            sequence_number: 300 + 47 * 17280,
+            network_id: Default::default(),
+            base_reserve: 10,
+            min_temp_entry_ttl: 172800,
+            min_persistent_entry_ttl: 172800,
+            max_entry_ttl: 9999999,
+        });
+
+        e.as_contract(&pool_id, || {
+            assert!(!storage::has_auction(&e, &0, &merry));
+            assert_eq!(storage::get_auction_count(&e, &0), 4);
+
+            // creating an auction removes the expired last user before indexing the new one
+            storage::set_auction(&e, &0, &merry, &auction_data);
+            assert_eq!(storage::get_auction_count(&e, &0), 4);
+            assert_eq!(storage::get_auction_index(&e, &0, 3), Some(merry.clone()));
+
+            // loading the auctions removes the expired users it reaches
+            let page = load_auctions(&e, 0, 0, 50);
+            assert_eq!(page.len(), 2);
+            assert_eq!(page.get_unchecked(0).user, pippin);
+            assert_eq!(page.get_unchecked(1).user, merry);
+            assert_eq!(storage::get_auction_count(&e, &0), 2);
+            assert_eq!(storage::get_auction_index(&e, &0, 1), Some(merry.clone()));
+            assert_eq!(storage::get_auction_index(&e, &0, 2), None);
+
+            // deleting an auction moves the last user into its position
+            storage::del_auction(&e, &0, &pippin);
+            assert_eq!(storage::get_auction_count(&e, &0), 1);
+            assert_eq!(storage::get_auction_index(&e, &0, 0), Some(merry.clone()));
+            let page = load_auctions(&e, 0, 0, 50);
+            assert_eq!(page.len(), 1);
+            assert_eq!(page.get_unchecked(0).user, merry);
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "Error(Contract, #1200)")]
+    fn test_load_auctions_page_too_large() {
+        let e = Env::default();
+        let pool_id = create_pool(&e);
+
+        e.as_contract(&pool_id, || {
+            load_auctions(&e, 0, 0, 51);
+        });
+    }
+
+    #[test]
+    fn test_fill() {
+        let e = Env::default();
+
+        e.mock_all_auths();
+        e.ledger().set(LedgerInfo {
+            timestamp: 12345,
+            protocol_version: 22,
+            sequence_number: 175,
+            network_id: Default::default(),
+            base_reserve: 10,
+            min_temp_entry_ttl: 172800,
+            min_persistent_entry_ttl: 172800,
+            max_entry_ttl: 9999999,
+        });
+
+        let bombadil = Address::generate(&e);
+        let samwise = Address::generate(&e);
+        let frodo = Address::generate(&e);
+
+        let pool_address = create_pool(&e);
+
+        let (oracle_address, _) = testutils::create_mock_oracle(&e);
+
+        // creating reserves for a pool exhausts the budget
+        e.cost_estimate().budget().reset_unlimited();
+        let (underlying_0, _) = testutils::create_token_contract(&e, &bombadil);
+        let (mut reserve_config_0, reserve_data_0) = testutils::default_reserve_meta();
+        reserve_config_0.index = 0;
+        testutils::create_reserve(
+            &e,
+            &pool_address,
+            &underlying_0,
+            &reserve_config_0,
+            &reserve_data_0,
+        );
+
+        let (underlying_1, _) = testutils::create_token_contract(&e, &bombadil);
+        let (mut reserve_config_1, reserve_data_1) = testutils::default_reserve_meta();
+        reserve_config_1.index = 1;
+        testutils::create_reserve(
+            &e,
+            &pool_address,
+            &underlying_1,
+            &reserve_config_1,
+            &reserve_data_1,
+        );
+
+        let (underlying_2, _) = testutils::create_token_contract(&e, &bombadil);
+        let (mut reserve_config_2, reserve_data_2) = testutils::default_reserve_meta();
+        reserve_config_2.index = 2;
+        testutils::create_reserve(
+            &e,
+            &pool_address,
+            &underlying_2,
+            &reserve_config_2,
+            &reserve_data_2,
+        );
+        e.cost_estimate().budget().reset_unlimited();
+
+        let auction_data = AuctionData {
+            bid: map![&e, (underlying_2.clone(), 1_2375000)],
+            lot: map![
+                &e,
+                (underlying_0.clone(), 30_5595329),
+                (underlying_1.clone(), 1_5395739)
+            ],
+            block: 176,
+        };
+        let pool_config = PoolConfig {
+            oracle: oracle_address,
+            min_collateral: 1_0000000,
+            bstop_rate: 0_1000000,
+            status: 0,
+            max_positions: 4,
+        };
+        let positions: Positions = Positions {
+            collateral: map![
+                &e,
+                (reserve_config_0.index, 90_9100000),
+                (reserve_config_1.index, 04_5800000),
+            ],
+            liabilities: map![&e, (reserve_config_2.index, 02_7500000),],
+            supply: map![&e],
+        };
+        e.as_contract(&pool_address, || {
+            storage::set_user_positions(&e, &samwise, &positions);
+            storage::set_pool_config(&e, &pool_config);
+            storage::set_auction(&e, &0, &samwise, &auction_data);
+
+            e.ledger().set(LedgerInfo {
+                timestamp: 12345 + 200 * 5,
+                protocol_version: 22,
+                sequence_number: 176 + 200,
+                network_id: Default::default(),
+                base_reserve: 10,
+                min_temp_entry_ttl: 172800,
+                min_persistent_entry_ttl: 172800,
+                max_entry_ttl: 9999999,
+            });
+            e.cost_estimate().budget().reset_unlimited();
+            let mut pool = Pool::load(&e);
+            let mut frodo_state = User::load(&e, &frodo);
+            fill(&e, &mut pool, 0, &samwise, &mut frodo_state, 100);
+            let has_auction = storage::has_auction(&e, &0, &samwise);
+            assert_eq!(has_auction, false);
+        });
+    }
+
+    #[test]
+    fn test_fill_pays_keeper() {
+        let e = Env::default();
+
+        e.mock_all_auths();
+        e.ledger().set(LedgerInfo {
+            timestamp: 12345,
+            protocol_version: 22,
+            sequence_number: 175,
+            network_id: Default::default(),
+            base_reserve: 10,
+            min_temp_entry_ttl: 172800,
+            min_persistent_entry_ttl: 172800,
+            max_entry_ttl: 9999999,
+        });
+
+        let bombadil = Address::generate(&e);
+        let samwise = Address::generate(&e);
+        let frodo = Address::generate(&e);
+        let merry = Address::generate(&e);
+
+        let pool_address = create_pool(&e);
+
+        let (oracle_address, _) = testutils::create_mock_oracle(&e);
+
+        // creating reserves for a pool exhausts the budget
+        e.cost_estimate().budget().reset_unlimited();
+        let (underlying_0, _) = testutils::create_token_contract(&e, &bombadil);
+        let (mut reserve_config_0, reserve_data_0) = testutils::default_reserve_meta();
+        reserve_config_0.index = 0;
+        testutils::create_reserve(
+            &e,
+            &pool_address,
+            &underlying_0,
+            &reserve_config_0,
+            &reserve_data_0,
+        );
+
+        let (underlying_1, _) = testutils::create_token_contract(&e, &bombadil);
+        let (mut reserve_config_1, reserve_data_1) = testutils::default_reserve_meta();
+        reserve_config_1.index = 1;
+        testutils::create_reserve(
+            &e,
+            &pool_address,
+            &underlying_1,
+            &reserve_config_1,
+            &reserve_data_1,
+        );
+
+        let (underlying_2, _) = testutils::create_token_contract(&e, &bombadil);
+        let (mut reserve_config_2, reserve_data_2) = testutils::default_reserve_meta();
+        reserve_config_2.index = 2;
+        testutils::create_reserve(
+            &e,
+            &pool_address,
+            &underlying_2,
+            &reserve_config_2,
+            &reserve_data_2,
+        );
+        e.cost_estimate().budget().reset_unlimited();
+
+        let auction_data = AuctionData {
+            bid: map![&e, (underlying_2.clone(), 1_2375000)],
+            lot: map![
+                &e,
+                (underlying_0.clone(), 30_5595329),
+                (underlying_1.clone(), 1_5395739)
+            ],
+            block: 176,
+        };
+        let pool_config = PoolConfig {
+            oracle: oracle_address,
+            min_collateral: 1_0000000,
+            bstop_rate: 0_1000000,
+            status: 0,
+            max_positions: 4,
+        };
+        let positions: Positions = Positions {
+            collateral: map![
+                &e,
+                (reserve_config_0.index, 90_9100000),
+                (reserve_config_1.index, 04_5800000),
+            ],
+            liabilities: map![&e, (reserve_config_2.index, 02_7500000),],
+            supply: map![&e],
+        };
+        e.as_contract(&pool_address, || {
+            storage::set_user_positions(&e, &samwise, &positions);
+            storage::set_pool_config(&e, &pool_config);
+            storage::set_auction(&e, &0, &samwise, &auction_data);
+            storage::set_auction_keeper(&e, &0, &samwise, &merry);
+            storage::set_keeper_config(
+                &e,
+                &KeeperConfig {
+                    lot_share: 0_0100000,
+                    bad_debt_share: 0,
+                },
+            );
+
+            e.ledger().set(LedgerInfo {
+                timestamp: 12345 + 200 * 5,
+                protocol_version: 22,
+                sequence_number: 176 + 200,
+                network_id: Default::default(),
+                base_reserve: 10,
+                min_temp_entry_ttl: 172800,
+                min_persistent_entry_ttl: 172800,
+                max_entry_ttl: 9999999,
+            });
+            e.cost_estimate().budget().reset_unlimited();
+            let mut pool = Pool::load(&e);
+            let mut frodo_state = User::load(&e, &frodo);
+            let filled = fill(&e, &mut pool, 0, &samwise, &mut frodo_state, 100);
+            pool.store_cached_reserves(&e);
+            frodo_state.store(&e);
+            assert!(!storage::has_auction(&e, &0, &samwise));
+
+            // merry receives 1% of the lot as supply and frodo receives the rest as collateral
+            assert_eq!(filled.lot.get_unchecked(underlying_0.clone()), 30_2539376);
+            assert_eq!(filled.lot.get_unchecked(underlying_1.clone()), 1_5241782);
+            let merry_positions = storage::get_user_positions(&e, &merry);
+            assert_eq!(merry_positions.collateral.len(), 0);
+            assert_eq!(
+                merry_positions.supply.get_unchecked(reserve_config_0.index),
+                0_3055953
+            );
+            assert_eq!(
+                merry_positions.supply.get_unchecked(reserve_config_1.index),
+                0_0153957
+            );
+            let frodo_positions = storage::get_user_positions(&e, &frodo);
+            assert_eq!(
+                frodo_positions
+                    .collateral
+                    .get_unchecked(reserve_config_0.index),
+                30_2539376
+            );
+            assert_eq!(
+                frodo_positions
+                    .collateral
+                    .get_unchecked(reserve_config_1.index),
+                1_5241782
+            );
+            assert_eq!(
+                frodo_positions
+                    .liabilities
+                    .get_unchecked(reserve_config_2.index),
+                1_2375000
+            );
+        });
+    }
+
+    #[test]
+    fn test_partial_fill() {
+        let e = Env::default();
+
+        e.mock_all_auths();
+        e.ledger().set(LedgerInfo {
+            timestamp: 12345,
+            protocol_version: 22,
+            sequence_number: 175,
+            network_id: Default::default(),
+            base_reserve: 10,
+            min_temp_entry_ttl: 172800,
+            min_persistent_entry_ttl: 172800,
+            max_entry_ttl: 9999999,
+        });
+
+        let bombadil = Address::generate(&e);
+        let samwise = Address::generate(&e);
+        let frodo = Address::generate(&e);
+
+        let pool_address = create_pool(&e);
+
+        let (oracle_address, _) = testutils::create_mock_oracle(&e);
+
+        // creating reserves for a pool exhausts the budget
+        e.cost_estimate().budget().reset_unlimited();
+        let (underlying_0, _) = testutils::create_token_contract(&e, &bombadil);
+        let (mut reserve_config_0, reserve_data_0) = testutils::default_reserve_meta();
+        reserve_config_0.index = 0;
+        testutils::create_reserve(
+            &e,
+            &pool_address,
+            &underlying_0,
+            &reserve_config_0,
+            &reserve_data_0,
+        );
+
+        let (underlying_1, _) = testutils::create_token_contract(&e, &bombadil);
+        let (mut reserve_config_1, reserve_data_1) = testutils::default_reserve_meta();
+        reserve_config_1.index = 1;
+        testutils::create_reserve(
+            &e,
             &pool_address,
             &underlying_1,
             &reserve_config_1,
@@ -1658,7 +2509,8 @@ mod tests {
             min_persistent_entry_ttl: 172800,
             max_entry_ttl: 9999999,
         });
-        let (scaled_auction, remaining_auction) = scale_auction(&e, &base_auction_data, 100);
+        let (scaled_auction, remaining_auction) =
+            scale_auction(&e, &base_auction_data, &AuctionCurve::default(), 100);
         assert_eq!(
             scaled_auction.bid.get_unchecked(underlying_0.clone()),
             100_0000000
@@ -1677,7 +2529,8 @@ mod tests {
             min_persistent_entry_ttl: 172800,
             max_entry_ttl: 9999999,
         });
-        let (scaled_auction, remaining_auction) = scale_auction(&e, &base_auction_data, 100);
+        let (scaled_auction, remaining_auction) =
+            scale_auction(&e, &base_auction_data, &AuctionCurve::default(), 100);
         assert_eq!(
             scaled_auction.bid.get_unchecked(underlying_0.clone()),
             100_0000000
@@ -1699,7 +2552,8 @@ mod tests {
             min_persistent_entry_ttl: 172800,
             max_entry_ttl: 9999999,
         });
-        let (scaled_auction, remaining_auction) = scale_auction(&e, &base_auction_data, 100);
+        let (scaled_auction, remaining_auction) =
+            scale_auction(&e, &base_auction_data, &AuctionCurve::default(), 100);
         assert_eq!(
             scaled_auction.bid.get_unchecked(underlying_0.clone()),
             100_0000000
@@ -1721,7 +2575,8 @@ mod tests {
             min_persistent_entry_ttl: 172800,
             max_entry_ttl: 9999999,
         });
-        let (scaled_auction, remaining_auction) = scale_auction(&e, &base_auction_data, 100);
+        let (scaled_auction, remaining_auction) =
+            scale_auction(&e, &base_auction_data, &AuctionCurve::default(), 100);
         assert_eq!(
             scaled_auction.bid.get_unchecked(underlying_0.clone()),
             50_0000000
@@ -1743,7 +2598,8 @@ mod tests {
             min_persistent_entry_ttl: 172800,
             max_entry_ttl: 9999999,
         });
-        let (scaled_auction, remaining_auction) = scale_auction(&e, &base_auction_data, 100);
+        let (scaled_auction, remaining_auction) =
+            scale_auction(&e, &base_auction_data, &AuctionCurve::default(), 100);
         assert_eq!(scaled_auction.bid.len(), 0);
         assert_eq!(
             scaled_auction.lot.get_unchecked(underlying_1.clone()),
@@ -1752,6 +2608,105 @@ mod tests {
         assert!(remaining_auction.is_none());
     }
 
+    #[test]
+    fn test_scale_auction_custom_curve() {
+        let e = Env::default();
+        let underlying_0 = Address::generate(&e);
+        let underlying_1 = Address::generate(&e);
+
+        let base_auction_data = AuctionData {
+            bid: map![&e, (underlying_0.clone(), 100_0000000)],
+            lot: map![&e, (underlying_1.clone(), 100_0000000)],
+            block: 1000,
+        };
+        let curve = AuctionCurve {
+            lot_ramp: 100,
+            bid_decay: 50,
+            start_premium: 0_2000000,
+        };
+
+        // 0 blocks - lot starts at the premium
+        e.ledger().set(LedgerInfo {
+            timestamp: 12345,
+            protocol_version: 22,
+            sequence_number: 1000,
+            network_id: Default::default(),
+            base_reserve: 10,
+            min_temp_entry_ttl: 172800,
+            min_persistent_entry_ttl: 172800,
+            max_entry_ttl: 9999999,
+        });
+        let (scaled_auction, _) = scale_auction(&e, &base_auction_data, &curve, 100);
+        assert_eq!(
+            scaled_auction.bid.get_unchecked(underlying_0.clone()),
+            100_0000000
+        );
+        assert_eq!(
+            scaled_auction.lot.get_unchecked(underlying_1.clone()),
+            20_0000000
+        );
+
+        // 50 blocks
+        e.ledger().set(LedgerInfo {
+            timestamp: 12345,
+            protocol_version: 22,
+            sequence_number: 1050,
+            network_id: Default::default(),
+            base_reserve: 10,
+            min_temp_entry_ttl: 172800,
+            min_persistent_entry_ttl: 172800,
+            max_entry_ttl: 9999999,
+        });
+        let (scaled_auction, _) = scale_auction(&e, &base_auction_data, &curve, 100);
+        assert_eq!(
+            scaled_auction.bid.get_unchecked(underlying_0.clone()),
+            100_0000000
+        );
+        assert_eq!(
+            scaled_auction.lot.get_unchecked(underlying_1.clone()),
+            60_0000000
+        );
+
+        // 125 blocks
+        e.ledger().set(LedgerInfo {
+            timestamp: 12345,
+            protocol_version: 22,
+            sequence_number: 1125,
+            network_id: Default::default(),
+            base_reserve: 10,
+            min_temp_entry_ttl: 172800,
+            min_persistent_entry_ttl: 172800,
+            max_entry_ttl: 9999999,
+        });
+        let (scaled_auction, _) = scale_auction(&e, &base_auction_data, &curve, 100);
+        assert_eq!(
+            scaled_auction.bid.get_unchecked(underlying_0.clone()),
+            50_0000000
+        );
+        assert_eq!(
+            scaled_auction.lot.get_unchecked(underlying_1.clone()),
+            100_0000000
+        );
+
+        // 150 blocks
+        e.ledger().set(LedgerInfo {
+            timestamp: 12345,
+            protocol_version: 22,
+            sequence_number: 1150,
+            network_id: Default::default(),
+            base_reserve: 10,
+            min_temp_entry_ttl: 172800,
+            min_persistent_entry_ttl: 172800,
+            max_entry_ttl: 9999999,
+        });
+        let (scaled_auction, _) = scale_auction(&e, &base_auction_data, &curve, 100);
+        assert_eq!(scaled_auction.bid.len(), 0);
+        assert_eq!(
+            scaled_auction.lot.get_unchecked(underlying_1.clone()),
+            100_0000000
+        );
+    }
+
     #[test]
     #[should_panic(expected = "Error(Contract, #1211)")]
     fn test_fill_liquidation_same_address() {
@@ -1884,7 +2839,8 @@ mod tests {
             min_persistent_entry_ttl: 172800,
             max_entry_ttl: 9999999,
         });
-        let (scaled_auction, remaining_auction_option) = scale_auction(&e, &base_auction_data, 50);
+        let (scaled_auction, remaining_auction_option) =
+            scale_auction(&e, &base_auction_data, &AuctionCurve::default(), 50);
         let remaining_auction = remaining_auction_option.unwrap();
         assert_eq!(
             scaled_auction.bid.get_unchecked(underlying_0.clone()),
@@ -1912,7 +2868,8 @@ mod tests {
             max_entry_ttl: 9999999,
         });
 
-        let (scaled_auction, remaining_auction_option) = scale_auction(&e, &base_auction_data, 60);
+        let (scaled_auction, remaining_auction_option) =
+            scale_auction(&e, &base_auction_data, &AuctionCurve::default(), 60);
         let remaining_auction = remaining_auction_option.unwrap();
         assert_eq!(
             scaled_auction.bid.get_unchecked(underlying_0.clone()),
@@ -1943,7 +2900,8 @@ mod tests {
             max_entry_ttl: 9999999,
         });
 
-        let (scaled_auction, remaining_auction_option) = scale_auction(&e, &base_auction_data, 60);
+        let (scaled_auction, remaining_auction_option) =
+            scale_auction(&e, &base_auction_data, &AuctionCurve::default(), 60);
         let remaining_auction = remaining_auction_option.unwrap();
         assert_eq!(
             scaled_auction.bid.get_unchecked(underlying_0.clone()),
@@ -1973,7 +2931,8 @@ mod tests {
             min_persistent_entry_ttl: 172800,
             max_entry_ttl: 9999999,
         });
-        let (scaled_auction, remaining_auction_option) = scale_auction(&e, &base_auction_data, 50);
+        let (scaled_auction, remaining_auction_option) =
+            scale_auction(&e, &base_auction_data, &AuctionCurve::default(), 50);
         let remaining_auction = remaining_auction_option.unwrap();
         assert_eq!(scaled_auction.bid.len(), 0);
         assert_eq!(
@@ -2015,7 +2974,7 @@ mod tests {
             max_entry_ttl: 9999999,
         });
 
-        let (_, _) = scale_auction(&e, &base_auction_data, 0);
+        let (_, _) = scale_auction(&e, &base_auction_data, &AuctionCurve::default(), 0);
     }
 
     #[test]
@@ -2043,6 +3002,6 @@ mod tests {
             max_entry_ttl: 9999999,
         });
 
-        let (_, _) = scale_auction(&e, &base_auction_data, 101);
+        let (_, _) = scale_auction(&e, &base_auction_data, &AuctionCurve::default(), 101);
     }
 }
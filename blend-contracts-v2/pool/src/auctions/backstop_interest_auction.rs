@@ -10,6 +10,7 @@ use super::{AuctionData, AuctionType};
 
 pub fn create_interest_auction_data(
     e: &Env,
+    pool: &mut Pool,
     user: &Address,
     bid: &Vec<Address>,
     lot: &Vec<Address>,
@@ -26,7 +27,6 @@ pub fn create_interest_auction_data(
         panic_with_error!(e, PoolError::AuctionInProgress);
     }
 
-    let mut pool = Pool::load(e);
     if pool.config.max_positions < lot.len() {
         panic_with_error!(e, PoolError::MaxPositionsExceeded);
     }
@@ -170,7 +170,14 @@ mod tests {
                 &auction_data,
             );
 
-            create_interest_auction_data(&e, &backstop_address, &vec![&e], &vec![&e], 100);
+            create_interest_auction_data(
+                &e,
+                &mut Pool::load(&e),
+                &backstop_address,
+                &vec![&e],
+                &vec![&e],
+                100,
+            );
         });
     }
 
@@ -205,6 +212,7 @@ mod tests {
 
             create_interest_auction_data(
                 &e,
+                &mut Pool::load(&e),
                 &backstop_address,
                 &vec![&e, Address::generate(&e)],
                 &vec![&e, Address::generate(&e)],
@@ -248,6 +256,7 @@ mod tests {
 
             create_interest_auction_data(
                 &e,
+                &mut Pool::load(&e),
                 &Address::generate(&e),
                 &vec![&e, backstop_token_id.clone()],
                 &vec![&e, backstop_token_id.clone()],
@@ -290,6 +299,7 @@ mod tests {
             storage::set_backstop(&e, &backstop_address);
             create_interest_auction_data(
                 &e,
+                &mut Pool::load(&e),
                 &backstop_address,
                 &vec![&e, backstop_token_id.clone()],
                 &vec![&e, backstop_token_id.clone()],
@@ -398,6 +408,7 @@ mod tests {
 
             create_interest_auction_data(
                 &e,
+                &mut Pool::load(&e),
                 &backstop_address,
                 &vec![&e, backstop_token.clone()],
                 &vec![
@@ -481,6 +492,7 @@ mod tests {
 
             create_interest_auction_data(
                 &e,
+                &mut Pool::load(&e),
                 &backstop_address,
                 &vec![&e, underlying_0.clone()],
                 &vec![&e, underlying_0.clone()],
@@ -559,6 +571,7 @@ mod tests {
 
             create_interest_auction_data(
                 &e,
+                &mut Pool::load(&e),
                 &backstop_address,
                 &vec![&e, backstop_token_id.clone()],
                 &vec![&e, backstop_token_id.clone()],
@@ -637,6 +650,7 @@ mod tests {
 
             create_interest_auction_data(
                 &e,
+                &mut Pool::load(&e),
                 &backstop_address,
                 &vec![&e, backstop_token_id.clone()],
                 &vec![&e],
@@ -745,6 +759,7 @@ mod tests {
 
             create_interest_auction_data(
                 &e,
+                &mut Pool::load(&e),
                 &backstop_address,
                 &vec![&e, backstop_token_id.clone()],
                 &vec![
@@ -856,6 +871,7 @@ mod tests {
 
             let result = create_interest_auction_data(
                 &e,
+                &mut Pool::load(&e),
                 &backstop_address,
                 &vec![&e, backstop_token_id.clone()],
                 &vec![&e, underlying_0.clone(), underlying_1.clone()],
@@ -974,6 +990,7 @@ mod tests {
 
             let result = create_interest_auction_data(
                 &e,
+                &mut Pool::load(&e),
                 &backstop_address,
                 &vec![&e, backstop_token_id.clone()],
                 &vec![&e, underlying_0.clone(), underlying_1.clone()],
@@ -1087,6 +1104,7 @@ mod tests {
 
             let result = create_interest_auction_data(
                 &e,
+                &mut Pool::load(&e),
                 &backstop_address,
                 &vec![&e, backstop_token_id.clone()],
                 &vec![
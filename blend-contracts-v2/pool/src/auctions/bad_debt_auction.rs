@@ -14,6 +14,7 @@ use super::{AuctionData, AuctionType};
 
 pub fn create_bad_debt_auction_data(
     e: &Env,
+    pool: &mut Pool,
     user: &Address,
     bid: &Vec<Address>,
     lot: &Vec<Address>,
@@ -37,7 +38,6 @@ pub fn create_bad_debt_auction_data(
     };
 
     // validate and create bid auction data
-    let mut pool = Pool::load(e);
     if pool.config.max_positions < bid.len() {
         panic_with_error!(e, PoolError::MaxPositionsExceeded);
     }
@@ -211,6 +211,7 @@ mod tests {
 
             create_bad_debt_auction_data(
                 &e,
+                &mut Pool::load(&e),
                 &backstop_address,
                 &vec![&e],
                 &vec![&e, lp_token.clone()],
@@ -261,7 +262,14 @@ mod tests {
         });
 
         e.as_contract(&pool_address, || {
-            create_bad_debt_auction_data(&e, &samwise, &vec![&e], &vec![&e, lp_token.clone()], 100);
+            create_bad_debt_auction_data(
+                &e,
+                &mut Pool::load(&e),
+                &samwise,
+                &vec![&e],
+                &vec![&e, lp_token.clone()],
+                100,
+            );
         });
     }
 
@@ -321,6 +329,7 @@ mod tests {
 
             create_bad_debt_auction_data(
                 &e,
+                &mut Pool::load(&e),
                 &backstop_address,
                 &vec![&e],
                 &vec![&e, lp_token.clone()],
@@ -417,6 +426,7 @@ mod tests {
 
             create_bad_debt_auction_data(
                 &e,
+                &mut Pool::load(&e),
                 &backstop_address,
                 &vec![&e, lp_token.clone()],
                 &vec![&e, lp_token.clone()],
@@ -525,6 +535,7 @@ mod tests {
 
             create_bad_debt_auction_data(
                 &e,
+                &mut Pool::load(&e),
                 &backstop_address,
                 &vec![&e, underlying_0.clone(), underlying_1.clone()],
                 &vec![&e, lp_token.clone()],
@@ -620,6 +631,7 @@ mod tests {
 
             create_bad_debt_auction_data(
                 &e,
+                &mut Pool::load(&e),
                 &backstop_address,
                 &vec![&e],
                 &vec![&e, lp_token.clone()],
@@ -715,6 +727,7 @@ mod tests {
 
             create_bad_debt_auction_data(
                 &e,
+                &mut Pool::load(&e),
                 &backstop_address,
                 &vec![&e, underlying_0.clone()],
                 &vec![&e, underlying_0.clone()],
@@ -843,6 +856,7 @@ mod tests {
 
             create_bad_debt_auction_data(
                 &e,
+                &mut Pool::load(&e),
                 &backstop_address,
                 &vec![
                     &e,
@@ -974,6 +988,7 @@ mod tests {
 
             let result = create_bad_debt_auction_data(
                 &e,
+                &mut Pool::load(&e),
                 &backstop_address,
                 &vec![&e, underlying_0.clone(), underlying_1.clone()],
                 &vec![&e, lp_token.clone()],
@@ -1113,6 +1128,7 @@ mod tests {
 
             let result = create_bad_debt_auction_data(
                 &e,
+                &mut Pool::load(&e),
                 &backstop_address,
                 &vec![&e, underlying_0.clone(), underlying_1.clone()],
                 &vec![&e, lp_token.clone()],
@@ -1247,6 +1263,7 @@ mod tests {
 
             let result = create_bad_debt_auction_data(
                 &e,
+                &mut Pool::load(&e),
                 &backstop_address,
                 &vec![&e, underlying_0.clone(), underlying_1.clone()],
                 &vec![&e, lp_token.clone()],
@@ -1381,6 +1398,7 @@ mod tests {
 
             let result = create_bad_debt_auction_data(
                 &e,
+                &mut Pool::load(&e),
                 &backstop_address,
                 &vec![&e, underlying_0.clone(), underlying_1.clone()],
                 &vec![&e, lp_token.clone()],
@@ -1514,6 +1532,7 @@ mod tests {
 
             let result = create_bad_debt_auction_data(
                 &e,
+                &mut Pool::load(&e),
                 &backstop_address,
                 &vec![&e, underlying_0.clone()],
                 &vec![&e, lp_token.clone()],
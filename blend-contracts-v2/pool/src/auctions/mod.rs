@@ -4,3 +4,4 @@ mod bad_debt_auction;
 mod user_liquidation_auction;
 
 pub use auction::*;
+pub use user_liquidation_auction::calc_max_user_liq_percent;
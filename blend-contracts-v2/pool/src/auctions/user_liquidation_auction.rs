@@ -3,7 +3,9 @@ use soroban_fixed_point_math::SorobanFixedPoint;
 use soroban_sdk::{map, panic_with_error, Address, Env, Vec};
 
 use crate::auctions::auction::AuctionData;
-use crate::pool::{Pool, PositionData, User};
+use crate::pool::{
+    convert_fixed_loans, is_delisted, update_isolated_debt, Pool, PositionData, User,
+};
 use crate::Positions;
 use crate::{errors::PoolError, storage};
 
@@ -11,6 +13,7 @@ use super::AuctionType;
 
 pub fn create_user_liq_auction_data(
     e: &Env,
+    pool: &mut Pool,
     user: &Address,
     bid: &Vec<Address>,
     lot: &Vec<Address>,
@@ -26,28 +29,28 @@ pub fn create_user_liq_auction_data(
         panic_with_error!(e, PoolError::InvalidLiquidation);
     }
 
-    let mut liquidation_quote = AuctionData {
-        bid: map![e],
-        lot: map![e],
-        block: e.ledger().sequence() + 1,
-    };
-    let mut full_liquidation_quote = AuctionData {
-        bid: map![e],
-        lot: map![e],
-        block: e.ledger().sequence() + 1,
-    };
-    let mut pool = Pool::load(e);
     if pool.config.max_positions < (lot.len() + bid.len()) {
         panic_with_error!(e, PoolError::MaxPositionsExceeded);
     }
 
-    // this is used for checking the liquidation percent and should NOT be set
+    // this is used for checking the liquidation percent and should NOT be set, besides
+    // converting any fixed-term loans to variable rate liabilities so they can be auctioned
     let mut user_state = User::load(e, user);
+    if !user_state.fixed_loans.is_empty() {
+        convert_fixed_loans(e, pool, &mut user_state);
+        pool.store_cached_reserves(e);
+        user_state.store(e);
+    }
     let reserve_list = storage::get_res_list(e);
-    let position_data = PositionData::calculate_from_positions(e, &mut pool, &user_state.positions);
-
-    // ensure the user has less collateral than liabilities
-    if position_data.liability_base < position_data.collateral_base {
+    let position_data =
+        PositionData::calculate_from_positions(e, pool, &user_state.positions, user_state.emode);
+
+    // ensure the user has less collateral than liabilities, unless only liabilities in reserves
+    // that have finished delisting are auctioned. These are fully liquidated regardless of the
+    // user's health factor.
+    let is_healthy = position_data.liability_base < position_data.collateral_base;
+    let is_delisted_bid = !bid.is_empty() && bid.iter().all(|asset| is_delisted(e, &asset));
+    if is_healthy && (percent != 100 || !is_delisted_bid) {
         panic_with_error!(e, PoolError::InvalidLiquidation);
     }
 
@@ -87,8 +90,155 @@ pub fn create_user_liq_auction_data(
     if positions_auctioned.collateral.len() == 0 {
         panic_with_error!(e, PoolError::InvalidLot);
     }
+    // isolated collateral must be included in the lot
+    for index in user_state.positions.collateral.keys() {
+        if !positions_auctioned.collateral.contains_key(index) {
+            let reserve = pool.load_reserve(e, &reserve_list.get_unchecked(index), false);
+            if reserve.config.isolated {
+                panic_with_error!(e, PoolError::InvalidLot);
+            }
+        }
+    }
     let position_data_inc =
-        PositionData::calculate_from_positions(e, &mut pool, &positions_auctioned);
+        PositionData::calculate_from_positions(e, pool, &positions_auctioned, user_state.emode);
+
+    if is_healthy {
+        return match build_delisted_liq_quote(
+            e,
+            &position_data,
+            &positions_auctioned,
+            &position_data_inc,
+        ) {
+            Ok(auction_data) => auction_data,
+            Err(error) => panic_with_error!(e, error),
+        };
+    }
+    match build_user_liq_quote(
+        e,
+        pool,
+        &user_state,
+        &position_data,
+        &positions_auctioned,
+        &position_data_inc,
+        percent,
+    ) {
+        Ok(auction_data) => auction_data,
+        Err(error) => panic_with_error!(e, error),
+    }
+}
+
+/// Calculate the maximum percent of a user's positions that can currently be liquidated, when all of
+/// the user's liabilities are included in the bid and all of the user's collateral is included in the lot.
+///
+/// ### Arguments
+/// * `pool` - The pool
+/// * `user_state` - The user being liquidated
+/// * `position_data` - The position data of the user's positions
+///
+/// Returns the maximum liquidation percent, or 0 if the user cannot be liquidated
+pub fn calc_max_user_liq_percent(
+    e: &Env,
+    pool: &mut Pool,
+    user_state: &User,
+    position_data: &PositionData,
+) -> u32 {
+    let positions = &user_state.positions;
+    if user_state.address == e.current_contract_address()
+        || user_state.address == storage::get_backstop(e)
+        || storage::has_auction(
+            e,
+            &(AuctionType::UserLiquidation as u32),
+            &user_state.address,
+        )
+        || positions.liabilities.is_empty()
+        || positions.collateral.is_empty()
+        || position_data.liability_base < position_data.collateral_base
+        || pool.config.max_positions < positions.liabilities.len() + positions.collateral.len()
+    {
+        return 0;
+    }
+
+    // the supply positions do not impact the position data, so the auctioned positions
+    // share the position data of the user
+    let positions_auctioned = Positions {
+        liabilities: positions.liabilities.clone(),
+        collateral: positions.collateral.clone(),
+        supply: map![e],
+    };
+    // any percent over 95 is a full liquidation, which is validated at 95
+    if build_user_liq_quote(
+        e,
+        pool,
+        user_state,
+        position_data,
+        &positions_auctioned,
+        position_data,
+        100,
+    )
+    .is_ok()
+    {
+        return 100;
+    }
+    // a liquidation that is too large stays too large as the percent increases, and one that is too
+    // small stays too small as the percent decreases, so binary search for the largest percent that
+    // is not too large
+    let mut max_percent = 0;
+    let mut low = 0;
+    let mut high = 95;
+    while low < high {
+        let percent = (low + high + 1) / 2;
+        match build_user_liq_quote(
+            e,
+            pool,
+            user_state,
+            position_data,
+            &positions_auctioned,
+            position_data,
+            percent,
+        ) {
+            Ok(_) => {
+                max_percent = percent;
+                low = percent;
+            }
+            Err(PoolError::InvalidLiqTooLarge) => high = percent - 1,
+            Err(_) => low = percent,
+        }
+    }
+    max_percent
+}
+
+/// Build the auction data to liquidate a percent of the auctioned positions
+///
+/// ### Arguments
+/// * `pool` - The pool
+/// * `user_state` - The user being liquidated
+/// * `position_data` - The position data of all of the user's positions
+/// * `positions_auctioned` - The positions included in the auction
+/// * `position_data_inc` - The position data of the positions included in the auction
+/// * `percent` - The percent of the auctioned liabilities to liquidate
+///
+/// Returns the auction data, or the error the liquidation is invalid with
+fn build_user_liq_quote(
+    e: &Env,
+    pool: &mut Pool,
+    user_state: &User,
+    position_data: &PositionData,
+    positions_auctioned: &Positions,
+    position_data_inc: &PositionData,
+    percent: u32,
+) -> Result<AuctionData, PoolError> {
+    let mut liquidation_quote = AuctionData {
+        bid: map![e],
+        lot: map![e],
+        block: e.ledger().sequence() + 1,
+    };
+    let mut full_liquidation_quote = AuctionData {
+        bid: map![e],
+        lot: map![e],
+        block: e.ledger().sequence() + 1,
+    };
+    let reserve_list = storage::get_res_list(e);
+
     let is_all_collateral = position_data_inc.collateral_raw == position_data.collateral_raw;
     let is_all_positions =
         is_all_collateral && position_data_inc.liability_raw == position_data.liability_raw;
@@ -105,24 +255,7 @@ pub fn create_user_liq_auction_data(
         i128(percent_liquidated_to_check) * position_data.scalar / 100; // scale to decimal form with scalar decimals
 
     // ensure liquidation size is fair and the collateral is large enough to allow for the auction to price the liquidation
-    let avg_cf = position_data_inc.collateral_base.fixed_div_floor(
-        e,
-        &position_data_inc.collateral_raw,
-        &position_data_inc.scalar,
-    );
-    // avg_lf is the inverse of the average liability factor
-    let avg_lf = position_data_inc.liability_base.fixed_div_floor(
-        e,
-        &position_data_inc.liability_raw,
-        &position_data_inc.scalar,
-    );
-    let est_incentive = (position_data_inc.scalar
-        - avg_cf.fixed_div_ceil(e, &avg_lf, &position_data_inc.scalar))
-    .fixed_div_ceil(
-        e,
-        &(2 * position_data_inc.scalar),
-        &position_data_inc.scalar,
-    ) + position_data_inc.scalar;
+    let est_incentive = calc_est_incentive(e, position_data_inc);
 
     let est_withdrawn_collateral = position_data_inc
         .liability_raw
@@ -145,10 +278,12 @@ pub fn create_user_liq_auction_data(
         // as the missing collateral should be included in the liquidation to avoid
         // potentially bad liquidations
         if !is_all_collateral {
-            panic_with_error!(e, PoolError::InvalidLiquidation);
+            return Err(PoolError::InvalidLiquidation);
         }
     }
 
+    // the user's positions after the liquidation quote is filled
+    let mut new_positions = user_state.positions.clone();
     for (asset, amount) in positions_auctioned.collateral.iter() {
         let res_asset_address = reserve_list.get_unchecked(asset);
         let b_tokens_removed =
@@ -157,6 +292,13 @@ pub fn create_user_liq_auction_data(
             .lot
             .set(res_asset_address.clone(), b_tokens_removed);
         full_liquidation_quote.lot.set(res_asset_address, amount);
+        if b_tokens_removed == amount {
+            new_positions.collateral.remove(asset);
+        } else {
+            new_positions
+                .collateral
+                .set(asset, amount - b_tokens_removed);
+        }
     }
 
     for (asset, amount) in positions_auctioned.liabilities.iter() {
@@ -167,15 +309,17 @@ pub fn create_user_liq_auction_data(
             .bid
             .set(res_asset_address.clone(), d_tokens_removed);
         full_liquidation_quote.bid.set(res_asset_address, amount);
+        if d_tokens_removed == amount {
+            new_positions.liabilities.remove(asset);
+        } else {
+            new_positions
+                .liabilities
+                .set(asset, amount - d_tokens_removed);
+        }
     }
 
-    user_state.rm_positions(
-        e,
-        &mut pool,
-        liquidation_quote.lot.clone(),
-        liquidation_quote.bid.clone(),
-    );
-    let new_data = PositionData::calculate_from_positions(e, &mut pool, &user_state.positions);
+    let new_data =
+        PositionData::calculate_from_positions(e, pool, &new_positions, user_state.emode);
 
     if is_full_liquidation {
         // A full user liquidation was requested, validate that a full liquidation is not too large.
@@ -186,21 +330,103 @@ pub fn create_user_liq_auction_data(
         if est_withdrawn_collateral < position_data.collateral_raw
             && new_data.is_hf_over(e, 1_1500000)
         {
-            panic_with_error!(e, PoolError::InvalidLiqTooLarge)
+            return Err(PoolError::InvalidLiqTooLarge);
         };
-        full_liquidation_quote
+        Ok(full_liquidation_quote)
     } else {
         // Post-liq health factor must be under 1.15
         if new_data.is_hf_over(e, 1_1500000) {
-            panic_with_error!(e, PoolError::InvalidLiqTooLarge)
+            return Err(PoolError::InvalidLiqTooLarge);
         };
 
         // Post-liq heath factor must be over 1.03
         if new_data.is_hf_under(e, 1_0300000) {
-            panic_with_error!(e, PoolError::InvalidLiqTooSmall)
+            return Err(PoolError::InvalidLiqTooSmall);
         };
+        Ok(liquidation_quote)
+    }
+}
+
+/// Build the auction data to fully liquidate a user's liabilities in reserves that have finished
+/// delisting. The user's health factor is not considered, so the lot only needs to cover the value
+/// of the liabilities plus the estimated liquidation incentive.
+///
+/// ### Arguments
+/// * `position_data` - The position data of all of the user's positions
+/// * `positions_auctioned` - The positions included in the auction
+/// * `position_data_inc` - The position data of the positions included in the auction
+///
+/// Returns the auction data, or the error the liquidation is invalid with
+fn build_delisted_liq_quote(
+    e: &Env,
+    position_data: &PositionData,
+    positions_auctioned: &Positions,
+    position_data_inc: &PositionData,
+) -> Result<AuctionData, PoolError> {
+    let mut liquidation_quote = AuctionData {
+        bid: map![e],
+        lot: map![e],
+        block: e.ledger().sequence() + 1,
+    };
+    let reserve_list = storage::get_res_list(e);
+
+    let est_withdrawn_collateral = position_data_inc.liability_raw.fixed_mul_floor(
+        e,
+        &calc_est_incentive(e, position_data_inc),
+        &position_data_inc.scalar,
+    );
+    let mut est_withdrawn_collateral_pct = est_withdrawn_collateral.fixed_div_ceil(
+        e,
+        &position_data_inc.collateral_raw,
+        &position_data_inc.scalar,
+    );
+    // the missing collateral should be included if the lot cannot cover the liabilities
+    if est_withdrawn_collateral_pct > position_data_inc.scalar {
+        est_withdrawn_collateral_pct = position_data_inc.scalar;
+        if position_data_inc.collateral_raw != position_data.collateral_raw {
+            return Err(PoolError::InvalidLiquidation);
+        }
+    }
+
+    for (asset, amount) in positions_auctioned.collateral.iter() {
+        let b_tokens_removed =
+            amount.fixed_mul_ceil(e, &est_withdrawn_collateral_pct, &position_data.scalar);
+        liquidation_quote
+            .lot
+            .set(reserve_list.get_unchecked(asset), b_tokens_removed);
+    }
+    for (asset, amount) in positions_auctioned.liabilities.iter() {
         liquidation_quote
+            .bid
+            .set(reserve_list.get_unchecked(asset), amount);
     }
+    Ok(liquidation_quote)
+}
+
+/// Estimate the incentive paid to the liquidator of the positions included in an auction, as a
+/// multiplier of the liabilities liquidated
+///
+/// ### Arguments
+/// * `position_data_inc` - The position data of the positions included in the auction
+fn calc_est_incentive(e: &Env, position_data_inc: &PositionData) -> i128 {
+    let avg_cf = position_data_inc.collateral_base.fixed_div_floor(
+        e,
+        &position_data_inc.collateral_raw,
+        &position_data_inc.scalar,
+    );
+    // avg_lf is the inverse of the average liability factor
+    let avg_lf = position_data_inc.liability_base.fixed_div_floor(
+        e,
+        &position_data_inc.liability_raw,
+        &position_data_inc.scalar,
+    );
+    (position_data_inc.scalar - avg_cf.fixed_div_ceil(e, &avg_lf, &position_data_inc.scalar))
+        .fixed_div_ceil(
+            e,
+            &(2 * position_data_inc.scalar),
+            &position_data_inc.scalar,
+        )
+        + position_data_inc.scalar
 }
 
 pub fn fill_user_liq_auction(
@@ -211,8 +437,11 @@ pub fn fill_user_liq_auction(
     filler_state: &mut User,
 ) {
     let mut user_state = User::load(e, user);
+    let prev_positions = user_state.positions.clone();
     user_state.rm_positions(e, pool, auction_data.lot.clone(), auction_data.bid.clone());
     filler_state.add_positions(e, pool, auction_data.lot.clone(), auction_data.bid.clone());
+    // the filler's debt backed by isolated collateral is updated when their submit is validated
+    update_isolated_debt(e, pool, &prev_positions, &user_state.positions, false);
     user_state.store(e);
 }
 
@@ -221,8 +450,9 @@ mod tests {
 
     use crate::{
         auctions::auction::AuctionType,
+        constants::FIXED_LOAN_TERM,
         pool::Positions,
-        storage::{self, PoolConfig},
+        storage::{self, FixedLoan, PoolConfig, ReserveDelisting},
         testutils::{self, create_pool},
     };
 
@@ -279,7 +509,14 @@ mod tests {
                 &samwise,
                 &auction_data,
             );
-            create_user_liq_auction_data(&e, &samwise, &vec![&e], &vec![&e], liq_pct);
+            create_user_liq_auction_data(
+                &e,
+                &mut Pool::load(&e),
+                &samwise,
+                &vec![&e],
+                &vec![&e],
+                liq_pct,
+            );
         });
     }
 
@@ -315,7 +552,14 @@ mod tests {
         e.as_contract(&pool_address, || {
             storage::set_pool_config(&e, &pool_config);
             storage::set_backstop(&e, &backstop_address);
-            create_user_liq_auction_data(&e, &pool_address, &vec![&e], &vec![&e], liq_pct);
+            create_user_liq_auction_data(
+                &e,
+                &mut Pool::load(&e),
+                &pool_address,
+                &vec![&e],
+                &vec![&e],
+                liq_pct,
+            );
         });
     }
 
@@ -351,7 +595,14 @@ mod tests {
         e.as_contract(&pool_address, || {
             storage::set_pool_config(&e, &pool_config);
             storage::set_backstop(&e, &backstop_address);
-            create_user_liq_auction_data(&e, &backstop_address, &vec![&e], &vec![&e], liq_pct);
+            create_user_liq_auction_data(
+                &e,
+                &mut Pool::load(&e),
+                &backstop_address,
+                &vec![&e],
+                &vec![&e],
+                liq_pct,
+            );
         });
     }
 
@@ -387,7 +638,14 @@ mod tests {
         e.as_contract(&pool_address, || {
             storage::set_pool_config(&e, &pool_config);
             storage::set_backstop(&e, &backstop_address);
-            create_user_liq_auction_data(&e, &backstop_address, &vec![&e], &vec![&e], liq_pct);
+            create_user_liq_auction_data(
+                &e,
+                &mut Pool::load(&e),
+                &backstop_address,
+                &vec![&e],
+                &vec![&e],
+                liq_pct,
+            );
         });
     }
 
@@ -423,7 +681,14 @@ mod tests {
         e.as_contract(&pool_address, || {
             storage::set_pool_config(&e, &pool_config);
             storage::set_backstop(&e, &backstop_address);
-            create_user_liq_auction_data(&e, &backstop_address, &vec![&e], &vec![&e], liq_pct);
+            create_user_liq_auction_data(
+                &e,
+                &mut Pool::load(&e),
+                &backstop_address,
+                &vec![&e],
+                &vec![&e],
+                liq_pct,
+            );
         });
     }
 
@@ -518,6 +783,7 @@ mod tests {
 
             create_user_liq_auction_data(
                 &e,
+                &mut Pool::load(&e),
                 &samwise,
                 &vec![&e],
                 &vec![&e, underlying_0.clone()],
@@ -617,6 +883,7 @@ mod tests {
 
             create_user_liq_auction_data(
                 &e,
+                &mut Pool::load(&e),
                 &samwise,
                 &vec![&e, underlying_0.clone()],
                 &vec![&e, underlying_0.clone()],
@@ -716,6 +983,7 @@ mod tests {
 
             create_user_liq_auction_data(
                 &e,
+                &mut Pool::load(&e),
                 &samwise,
                 &vec![&e, underlying_1.clone()],
                 &vec![&e],
@@ -726,7 +994,7 @@ mod tests {
 
     #[test]
     #[should_panic(expected = "Error(Contract, #1222)")]
-    fn test_create_user_liquidation_invalid_lot_no_position() {
+    fn test_create_user_liquidation_invalid_lot_missing_isolated_collateral() {
         let e = Env::default();
 
         e.mock_all_auths();
@@ -758,6 +1026,7 @@ mod tests {
         reserve_config_0.c_factor = 0_8500000;
         reserve_config_0.l_factor = 0_9000000;
         reserve_config_0.index = 0;
+        reserve_config_0.isolated = true;
         testutils::create_reserve(
             &e,
             &pool_address,
@@ -782,6 +1051,18 @@ mod tests {
             &reserve_data_1,
         );
 
+        let (underlying_2, _) = testutils::create_token_contract(&e, &bombadil);
+        let (mut reserve_config_2, mut reserve_data_2) = testutils::default_reserve_meta();
+        reserve_data_2.last_time = 12345;
+        reserve_config_2.index = 2;
+        testutils::create_reserve(
+            &e,
+            &pool_address,
+            &underlying_2,
+            &reserve_config_2,
+            &reserve_data_2,
+        );
+
         oracle_client.set_data(
             &bombadil,
             &Asset::Other(Symbol::new(&e, "USD")),
@@ -789,15 +1070,20 @@ mod tests {
                 &e,
                 Asset::Stellar(underlying_0.clone()),
                 Asset::Stellar(underlying_1.clone()),
+                Asset::Stellar(underlying_2.clone()),
             ],
             &7,
             &300,
         );
-        oracle_client.set_price_stable(&vec![&e, 2_0000000, 4_0000000]);
+        oracle_client.set_price_stable(&vec![&e, 2_0000000, 4_0000000, 1_0000000]);
 
         let liq_pct = 50;
         let positions: Positions = Positions {
-            collateral: map![&e, (reserve_config_0.index, 100_0000000),],
+            collateral: map![
+                &e,
+                (reserve_config_0.index, 100_0000000),
+                (reserve_config_2.index, 10_0000000),
+            ],
             liabilities: map![&e, (reserve_config_1.index, 30_0000000),],
             supply: map![&e],
         };
@@ -815,17 +1101,18 @@ mod tests {
 
             create_user_liq_auction_data(
                 &e,
+                &mut Pool::load(&e),
                 &samwise,
                 &vec![&e, underlying_1.clone()],
-                &vec![&e, underlying_1.clone()],
+                &vec![&e, underlying_2.clone()],
                 liq_pct,
             );
         });
     }
 
     #[test]
-    #[should_panic(expected = "Error(Contract, #1208)")]
-    fn test_create_user_liquidation_checks_max_positions() {
+    #[should_panic(expected = "Error(Contract, #1222)")]
+    fn test_create_user_liquidation_invalid_lot_no_position() {
         let e = Env::default();
 
         e.mock_all_auths();
@@ -853,6 +1140,7 @@ mod tests {
         let (mut reserve_config_0, mut reserve_data_0) = testutils::default_reserve_meta();
         reserve_data_0.last_time = 12345;
         reserve_data_0.b_rate = 1_100_000_000_000;
+        reserve_data_0.d_rate = 1_150_000_000_000;
         reserve_config_0.c_factor = 0_8500000;
         reserve_config_0.l_factor = 0_9000000;
         reserve_config_0.index = 0;
@@ -866,10 +1154,11 @@ mod tests {
 
         let (underlying_1, _) = testutils::create_token_contract(&e, &bombadil);
         let (mut reserve_config_1, mut reserve_data_1) = testutils::default_reserve_meta();
+        reserve_data_1.last_time = 12345;
         reserve_data_1.b_rate = 1_200_000_000_000;
-        reserve_config_1.c_factor = 0_7500000;
+        reserve_data_1.d_rate = 1_300_000_000_000;
+        reserve_config_1.c_factor = 0_8000000;
         reserve_config_1.l_factor = 0_7500000;
-        reserve_data_1.last_time = 12345;
         reserve_config_1.index = 1;
         testutils::create_reserve(
             &e,
@@ -879,19 +1168,6 @@ mod tests {
             &reserve_data_1,
         );
 
-        let (underlying_2, _) = testutils::create_token_contract(&e, &bombadil);
-        let (mut reserve_config_2, reserve_data_2) = testutils::default_reserve_meta();
-        reserve_config_2.c_factor = 0_0000000;
-        reserve_config_2.l_factor = 0_7000000;
-        reserve_config_2.index = 2;
-        testutils::create_reserve(
-            &e,
-            &pool_address,
-            &underlying_2,
-            &reserve_config_2,
-            &reserve_data_2,
-        );
-
         oracle_client.set_data(
             &bombadil,
             &Asset::Other(Symbol::new(&e, "USD")),
@@ -899,21 +1175,16 @@ mod tests {
                 &e,
                 Asset::Stellar(underlying_0.clone()),
                 Asset::Stellar(underlying_1.clone()),
-                Asset::Stellar(underlying_2.clone()),
             ],
             &7,
             &300,
         );
-        oracle_client.set_price_stable(&vec![&e, 2_0000000, 4_0000000, 50_0000000]);
+        oracle_client.set_price_stable(&vec![&e, 2_0000000, 4_0000000]);
 
-        let liq_pct = 45;
+        let liq_pct = 50;
         let positions: Positions = Positions {
-            collateral: map![
-                &e,
-                (reserve_config_0.index, 90_9100000),
-                (reserve_config_1.index, 04_5800000),
-            ],
-            liabilities: map![&e, (reserve_config_2.index, 02_7500000),],
+            collateral: map![&e, (reserve_config_0.index, 100_0000000),],
+            liabilities: map![&e, (reserve_config_1.index, 30_0000000),],
             supply: map![&e],
         };
         let pool_config = PoolConfig {
@@ -921,7 +1192,7 @@ mod tests {
             min_collateral: 1_0000000,
             bstop_rate: 0_1000000,
             status: 0,
-            max_positions: 2,
+            max_positions: 4,
         };
         e.as_contract(&pool_address, || {
             storage::set_user_positions(&e, &samwise, &positions);
@@ -930,16 +1201,18 @@ mod tests {
 
             create_user_liq_auction_data(
                 &e,
+                &mut Pool::load(&e),
                 &samwise,
-                &vec![&e, underlying_2.clone()],
-                &vec![&e, underlying_0.clone(), underlying_1.clone()],
+                &vec![&e, underlying_1.clone()],
+                &vec![&e, underlying_1.clone()],
                 liq_pct,
             );
         });
     }
 
     #[test]
-    fn test_create_user_liquidation_auction_normal_scalars() {
+    #[should_panic(expected = "Error(Contract, #1208)")]
+    fn test_create_user_liquidation_checks_max_positions() {
         let e = Env::default();
 
         e.mock_all_auths();
@@ -1035,31 +1308,26 @@ mod tests {
             min_collateral: 1_0000000,
             bstop_rate: 0_1000000,
             status: 0,
-            max_positions: 4,
+            max_positions: 2,
         };
         e.as_contract(&pool_address, || {
             storage::set_user_positions(&e, &samwise, &positions);
             storage::set_pool_config(&e, &pool_config);
             storage::set_backstop(&e, &backstop_address);
 
-            let result = create_user_liq_auction_data(
+            create_user_liq_auction_data(
                 &e,
+                &mut Pool::load(&e),
                 &samwise,
                 &vec![&e, underlying_2.clone()],
                 &vec![&e, underlying_0.clone(), underlying_1.clone()],
                 liq_pct,
             );
-            assert_eq!(result.block, 51);
-            assert_eq!(result.bid.get_unchecked(underlying_2), 1_2375000);
-            assert_eq!(result.bid.len(), 1);
-            assert_eq!(result.lot.get_unchecked(underlying_0), 30_5595329);
-            assert_eq!(result.lot.get_unchecked(underlying_1), 1_5395739);
-            assert_eq!(result.lot.len(), 2);
         });
     }
 
     #[test]
-    fn test_create_user_liquidation_auction_weird_scalar() {
+    fn test_create_user_liquidation_auction_normal_scalars() {
         let e = Env::default();
 
         e.mock_all_auths();
@@ -1086,8 +1354,8 @@ mod tests {
         let (underlying_0, _) = testutils::create_token_contract(&e, &bombadil);
         let (mut reserve_config_0, mut reserve_data_0) = testutils::default_reserve_meta();
         reserve_data_0.last_time = 12345;
-        reserve_data_0.b_rate = 1_000_206_159_000;
-        reserve_config_0.c_factor = 0_9000000;
+        reserve_data_0.b_rate = 1_100_000_000_000;
+        reserve_config_0.c_factor = 0_8500000;
         reserve_config_0.l_factor = 0_9000000;
         reserve_config_0.index = 0;
         testutils::create_reserve(
@@ -1100,10 +1368,11 @@ mod tests {
 
         let (underlying_1, _) = testutils::create_token_contract(&e, &bombadil);
         let (mut reserve_config_1, mut reserve_data_1) = testutils::default_reserve_meta();
-        reserve_config_1.c_factor = 0_0000000;
-        reserve_config_1.l_factor = 0_9000000;
+        reserve_data_1.b_rate = 1_200_000_000_000;
+        reserve_config_1.c_factor = 0_7500000;
+        reserve_config_1.l_factor = 0_7500000;
+        reserve_data_1.last_time = 12345;
         reserve_config_1.index = 1;
-        reserve_data_1.d_rate = 1_000_201_748_000;
         testutils::create_reserve(
             &e,
             &pool_address,
@@ -1112,6 +1381,19 @@ mod tests {
             &reserve_data_1,
         );
 
+        let (underlying_2, _) = testutils::create_token_contract(&e, &bombadil);
+        let (mut reserve_config_2, reserve_data_2) = testutils::default_reserve_meta();
+        reserve_config_2.c_factor = 0_0000000;
+        reserve_config_2.l_factor = 0_7000000;
+        reserve_config_2.index = 2;
+        testutils::create_reserve(
+            &e,
+            &pool_address,
+            &underlying_2,
+            &reserve_config_2,
+            &reserve_data_2,
+        );
+
         oracle_client.set_data(
             &bombadil,
             &Asset::Other(Symbol::new(&e, "USD")),
@@ -1119,11 +1401,265 @@ mod tests {
                 &e,
                 Asset::Stellar(underlying_0.clone()),
                 Asset::Stellar(underlying_1.clone()),
+                Asset::Stellar(underlying_2.clone()),
             ],
-            &14,
+            &7,
             &300,
         );
-        oracle_client.set_price_stable(&vec![&e, 1418501_2444444, 1_0261166_9700969]);
+        oracle_client.set_price_stable(&vec![&e, 2_0000000, 4_0000000, 50_0000000]);
+
+        let liq_pct = 45;
+        let positions: Positions = Positions {
+            collateral: map![
+                &e,
+                (reserve_config_0.index, 90_9100000),
+                (reserve_config_1.index, 04_5800000),
+            ],
+            liabilities: map![&e, (reserve_config_2.index, 02_7500000),],
+            supply: map![&e],
+        };
+        let pool_config = PoolConfig {
+            oracle: oracle_address,
+            min_collateral: 1_0000000,
+            bstop_rate: 0_1000000,
+            status: 0,
+            max_positions: 4,
+        };
+        e.as_contract(&pool_address, || {
+            storage::set_user_positions(&e, &samwise, &positions);
+            storage::set_pool_config(&e, &pool_config);
+            storage::set_backstop(&e, &backstop_address);
+
+            let result = create_user_liq_auction_data(
+                &e,
+                &mut Pool::load(&e),
+                &samwise,
+                &vec![&e, underlying_2.clone()],
+                &vec![&e, underlying_0.clone(), underlying_1.clone()],
+                liq_pct,
+            );
+            assert_eq!(result.block, 51);
+            assert_eq!(result.bid.get_unchecked(underlying_2), 1_2375000);
+            assert_eq!(result.bid.len(), 1);
+            assert_eq!(result.lot.get_unchecked(underlying_0), 30_5595329);
+            assert_eq!(result.lot.get_unchecked(underlying_1), 1_5395739);
+            assert_eq!(result.lot.len(), 2);
+        });
+    }
+
+    #[test]
+    fn test_create_user_liquidation_auction_converts_fixed_loans() {
+        let e = Env::default();
+
+        e.mock_all_auths();
+        e.ledger().set(LedgerInfo {
+            timestamp: 12345,
+            protocol_version: 22,
+            sequence_number: 50,
+            network_id: Default::default(),
+            base_reserve: 10,
+            min_temp_entry_ttl: 10,
+            min_persistent_entry_ttl: 10,
+            max_entry_ttl: 3110400,
+        });
+
+        let bombadil = Address::generate(&e);
+        let samwise = Address::generate(&e);
+
+        let pool_address = create_pool(&e);
+        let (oracle_address, oracle_client) = testutils::create_mock_oracle(&e);
+        let backstop_address = Address::generate(&e);
+
+        // creating reserves for a pool exhausts the budget
+        e.cost_estimate().budget().reset_unlimited();
+        let (underlying_0, _) = testutils::create_token_contract(&e, &bombadil);
+        let (mut reserve_config_0, mut reserve_data_0) = testutils::default_reserve_meta();
+        reserve_data_0.last_time = 12345;
+        reserve_data_0.b_rate = 1_100_000_000_000;
+        reserve_config_0.c_factor = 0_8500000;
+        reserve_config_0.l_factor = 0_9000000;
+        reserve_config_0.index = 0;
+        testutils::create_reserve(
+            &e,
+            &pool_address,
+            &underlying_0,
+            &reserve_config_0,
+            &reserve_data_0,
+        );
+
+        let (underlying_1, _) = testutils::create_token_contract(&e, &bombadil);
+        let (mut reserve_config_1, mut reserve_data_1) = testutils::default_reserve_meta();
+        reserve_data_1.b_rate = 1_200_000_000_000;
+        reserve_config_1.c_factor = 0_7500000;
+        reserve_config_1.l_factor = 0_7500000;
+        reserve_data_1.last_time = 12345;
+        reserve_config_1.index = 1;
+        testutils::create_reserve(
+            &e,
+            &pool_address,
+            &underlying_1,
+            &reserve_config_1,
+            &reserve_data_1,
+        );
+
+        let (underlying_2, _) = testutils::create_token_contract(&e, &bombadil);
+        let (mut reserve_config_2, mut reserve_data_2) = testutils::default_reserve_meta();
+        reserve_config_2.c_factor = 0_0000000;
+        reserve_config_2.l_factor = 0_7000000;
+        reserve_config_2.index = 2;
+        reserve_data_2.last_time = 12345;
+        reserve_data_2.fixed_debt = 2_7500000;
+        testutils::create_reserve(
+            &e,
+            &pool_address,
+            &underlying_2,
+            &reserve_config_2,
+            &reserve_data_2,
+        );
+
+        oracle_client.set_data(
+            &bombadil,
+            &Asset::Other(Symbol::new(&e, "USD")),
+            &vec![
+                &e,
+                Asset::Stellar(underlying_0.clone()),
+                Asset::Stellar(underlying_1.clone()),
+                Asset::Stellar(underlying_2.clone()),
+            ],
+            &7,
+            &300,
+        );
+        oracle_client.set_price_stable(&vec![&e, 2_0000000, 4_0000000, 50_0000000]);
+
+        let liq_pct = 45;
+        let positions: Positions = Positions {
+            collateral: map![
+                &e,
+                (reserve_config_0.index, 90_9100000),
+                (reserve_config_1.index, 04_5800000),
+            ],
+            liabilities: map![&e],
+            supply: map![&e],
+        };
+        let fixed_loans = map![
+            &e,
+            (
+                reserve_config_2.index,
+                FixedLoan {
+                    principal: 2_7500000,
+                    rate: 0_0700000,
+                    last_time: 12345,
+                    maturity: 12345 + FIXED_LOAN_TERM,
+                }
+            )
+        ];
+        let pool_config = PoolConfig {
+            oracle: oracle_address,
+            min_collateral: 1_0000000,
+            bstop_rate: 0_1000000,
+            status: 0,
+            max_positions: 4,
+        };
+        e.as_contract(&pool_address, || {
+            storage::set_user_positions(&e, &samwise, &positions);
+            storage::set_user_fixed_loans(&e, &samwise, &fixed_loans);
+            storage::set_pool_config(&e, &pool_config);
+            storage::set_backstop(&e, &backstop_address);
+
+            let result = create_user_liq_auction_data(
+                &e,
+                &mut Pool::load(&e),
+                &samwise,
+                &vec![&e, underlying_2.clone()],
+                &vec![&e, underlying_0.clone(), underlying_1.clone()],
+                liq_pct,
+            );
+            assert_eq!(result.block, 51);
+            assert_eq!(result.bid.get_unchecked(underlying_2.clone()), 1_2375000);
+            assert_eq!(result.bid.len(), 1);
+            assert_eq!(result.lot.len(), 2);
+
+            // the fixed-term loan was converted to dTokens
+            assert_eq!(storage::get_user_fixed_loans(&e, &samwise).len(), 0);
+            let user_positions = storage::get_user_positions(&e, &samwise);
+            assert_eq!(
+                user_positions
+                    .liabilities
+                    .get_unchecked(reserve_config_2.index),
+                2_7500000
+            );
+            let reserve_data = storage::get_res_data(&e, &underlying_2);
+            assert_eq!(reserve_data.fixed_debt, 0);
+            assert_eq!(reserve_data.d_supply, reserve_data_2.d_supply + 2_7500000);
+        });
+    }
+
+    #[test]
+    fn test_create_user_liquidation_auction_weird_scalar() {
+        let e = Env::default();
+
+        e.mock_all_auths();
+        e.ledger().set(LedgerInfo {
+            timestamp: 12345,
+            protocol_version: 22,
+            sequence_number: 50,
+            network_id: Default::default(),
+            base_reserve: 10,
+            min_temp_entry_ttl: 10,
+            min_persistent_entry_ttl: 10,
+            max_entry_ttl: 3110400,
+        });
+
+        let bombadil = Address::generate(&e);
+        let samwise = Address::generate(&e);
+
+        let pool_address = create_pool(&e);
+        let (oracle_address, oracle_client) = testutils::create_mock_oracle(&e);
+        let backstop_address = Address::generate(&e);
+
+        // creating reserves for a pool exhausts the budget
+        e.cost_estimate().budget().reset_unlimited();
+        let (underlying_0, _) = testutils::create_token_contract(&e, &bombadil);
+        let (mut reserve_config_0, mut reserve_data_0) = testutils::default_reserve_meta();
+        reserve_data_0.last_time = 12345;
+        reserve_data_0.b_rate = 1_000_206_159_000;
+        reserve_config_0.c_factor = 0_9000000;
+        reserve_config_0.l_factor = 0_9000000;
+        reserve_config_0.index = 0;
+        testutils::create_reserve(
+            &e,
+            &pool_address,
+            &underlying_0,
+            &reserve_config_0,
+            &reserve_data_0,
+        );
+
+        let (underlying_1, _) = testutils::create_token_contract(&e, &bombadil);
+        let (mut reserve_config_1, mut reserve_data_1) = testutils::default_reserve_meta();
+        reserve_config_1.c_factor = 0_0000000;
+        reserve_config_1.l_factor = 0_9000000;
+        reserve_config_1.index = 1;
+        reserve_data_1.d_rate = 1_000_201_748_000;
+        testutils::create_reserve(
+            &e,
+            &pool_address,
+            &underlying_1,
+            &reserve_config_1,
+            &reserve_data_1,
+        );
+
+        oracle_client.set_data(
+            &bombadil,
+            &Asset::Other(Symbol::new(&e, "USD")),
+            &vec![
+                &e,
+                Asset::Stellar(underlying_0.clone()),
+                Asset::Stellar(underlying_1.clone()),
+            ],
+            &14,
+            &300,
+        );
+        oracle_client.set_price_stable(&vec![&e, 1418501_2444444, 1_0261166_9700969]);
 
         let liq_pct = 69;
         let positions: Positions = Positions {
@@ -1145,6 +1681,7 @@ mod tests {
 
             let result = create_user_liq_auction_data(
                 &e,
+                &mut Pool::load(&e),
                 &samwise,
                 &vec![&e, underlying_1.clone()],
                 &vec![&e, underlying_0.clone()],
@@ -1246,6 +1783,7 @@ mod tests {
 
             let result = create_user_liq_auction_data(
                 &e,
+                &mut Pool::load(&e),
                 &samwise,
                 &vec![&e, underlying_1.clone()],
                 &vec![&e, underlying_0.clone()],
@@ -1353,6 +1891,7 @@ mod tests {
 
             let result = create_user_liq_auction_data(
                 &e,
+                &mut Pool::load(&e),
                 &samwise,
                 &vec![&e, underlying_0.clone(), underlying_1.clone()],
                 &vec![&e, underlying_0.clone(), underlying_1.clone()],
@@ -1465,6 +2004,7 @@ mod tests {
             // validate 95% liquidation is valid
             let result_95 = create_user_liq_auction_data(
                 &e,
+                &mut Pool::load(&e),
                 &samwise,
                 &vec![&e, underlying_0.clone(), underlying_1.clone()],
                 &vec![&e, underlying_0.clone(), underlying_1.clone()],
@@ -1493,6 +2033,7 @@ mod tests {
             // validate if 95% is valid, a full liquidation can be completed
             let result_100 = create_user_liq_auction_data(
                 &e,
+                &mut Pool::load(&e),
                 &samwise,
                 &vec![&e, underlying_0.clone(), underlying_1.clone()],
                 &vec![&e, underlying_0.clone(), underlying_1.clone()],
@@ -1628,6 +2169,7 @@ mod tests {
 
             create_user_liq_auction_data(
                 &e,
+                &mut Pool::load(&e),
                 &samwise,
                 &vec![&e, underlying_2.clone()],
                 &vec![&e, underlying_0.clone(), underlying_1.clone()],
@@ -1744,6 +2286,7 @@ mod tests {
 
             create_user_liq_auction_data(
                 &e,
+                &mut Pool::load(&e),
                 &samwise,
                 &vec![&e, underlying_2.clone()],
                 &vec![&e, underlying_0.clone(), underlying_1.clone()],
@@ -1860,6 +2403,7 @@ mod tests {
 
             create_user_liq_auction_data(
                 &e,
+                &mut Pool::load(&e),
                 &samwise,
                 &vec![&e, underlying_2.clone()],
                 &vec![&e, underlying_0.clone(), underlying_1.clone()],
@@ -1869,11 +2413,10 @@ mod tests {
     }
 
     #[test]
-    fn test_create_user_liquidation_partial() {
+    fn test_calc_max_user_liq_percent() {
         let e = Env::default();
-        e.mock_all_auths();
-        e.cost_estimate().budget().reset_unlimited();
 
+        e.mock_all_auths();
         e.ledger().set(LedgerInfo {
             timestamp: 12345,
             protocol_version: 22,
@@ -1889,14 +2432,16 @@ mod tests {
         let samwise = Address::generate(&e);
 
         let pool_address = create_pool(&e);
+
         let (oracle_address, oracle_client) = testutils::create_mock_oracle(&e);
         let backstop_address = Address::generate(&e);
 
+        // creating reserves for a pool exhausts the budget
+        e.cost_estimate().budget().reset_unlimited();
         let (underlying_0, _) = testutils::create_token_contract(&e, &bombadil);
         let (mut reserve_config_0, mut reserve_data_0) = testutils::default_reserve_meta();
         reserve_data_0.last_time = 12345;
         reserve_data_0.b_rate = 1_100_000_000_000;
-        reserve_data_0.d_rate = 1_150_000_000_000;
         reserve_config_0.c_factor = 0_8500000;
         reserve_config_0.l_factor = 0_9000000;
         reserve_config_0.index = 0;
@@ -1910,11 +2455,10 @@ mod tests {
 
         let (underlying_1, _) = testutils::create_token_contract(&e, &bombadil);
         let (mut reserve_config_1, mut reserve_data_1) = testutils::default_reserve_meta();
-        reserve_data_1.last_time = 12345;
         reserve_data_1.b_rate = 1_200_000_000_000;
-        reserve_data_1.d_rate = 1_300_000_000_000;
-        reserve_config_1.c_factor = 0_8000000;
+        reserve_config_1.c_factor = 0_7500000;
         reserve_config_1.l_factor = 0_7500000;
+        reserve_data_1.last_time = 12345;
         reserve_config_1.index = 1;
         testutils::create_reserve(
             &e,
@@ -1924,6 +2468,19 @@ mod tests {
             &reserve_data_1,
         );
 
+        let (underlying_2, _) = testutils::create_token_contract(&e, &bombadil);
+        let (mut reserve_config_2, reserve_data_2) = testutils::default_reserve_meta();
+        reserve_config_2.c_factor = 0_0000000;
+        reserve_config_2.l_factor = 0_7000000;
+        reserve_config_2.index = 2;
+        testutils::create_reserve(
+            &e,
+            &pool_address,
+            &underlying_2,
+            &reserve_config_2,
+            &reserve_data_2,
+        );
+
         oracle_client.set_data(
             &bombadil,
             &Asset::Other(Symbol::new(&e, "USD")),
@@ -1931,11 +2488,158 @@ mod tests {
                 &e,
                 Asset::Stellar(underlying_0.clone()),
                 Asset::Stellar(underlying_1.clone()),
+                Asset::Stellar(underlying_2.clone()),
             ],
-            &7,
+            &6,
             &300,
         );
-        oracle_client.set_price_stable(&vec![&e, 2_0000000, 4_0000000]);
+        oracle_client.set_price_stable(&vec![&e, 2_000000, 4_000000, 50_000000]);
+
+        let pool_config = PoolConfig {
+            oracle: oracle_address,
+            min_collateral: 1_0000000,
+            bstop_rate: 0_1000000,
+            status: 0,
+            max_positions: 4,
+        };
+        let positions: Positions = Positions {
+            collateral: map![
+                &e,
+                (reserve_config_0.index, 90_9100000),
+                (reserve_config_1.index, 04_5800000),
+            ],
+            liabilities: map![&e, (reserve_config_2.index, 02_7500000),],
+            supply: map![&e],
+        };
+        e.as_contract(&pool_address, || {
+            storage::set_user_positions(&e, &samwise, &positions);
+            storage::set_pool_config(&e, &pool_config);
+            storage::set_backstop(&e, &backstop_address);
+
+            let mut pool = Pool::load(&e);
+            let user_state = User::load(&e, &samwise);
+            let position_data = PositionData::calculate_from_positions(
+                &e,
+                &mut pool,
+                &user_state.positions,
+                user_state.emode,
+            );
+            let max_pct = calc_max_user_liq_percent(&e, &mut pool, &user_state, &position_data);
+            // liquidating 25% is too small and 46% is too large
+            assert!(max_pct > 25 && max_pct < 46);
+            // the next percent is too large
+            let positions_auctioned = Positions {
+                liabilities: user_state.positions.liabilities.clone(),
+                collateral: user_state.positions.collateral.clone(),
+                supply: map![&e],
+            };
+            assert_eq!(
+                build_user_liq_quote(
+                    &e,
+                    &mut pool,
+                    &user_state,
+                    &position_data,
+                    &positions_auctioned,
+                    &position_data,
+                    max_pct + 1,
+                )
+                .err(),
+                Some(PoolError::InvalidLiqTooLarge)
+            );
+
+            let auction_data = create_user_liq_auction_data(
+                &e,
+                &mut Pool::load(&e),
+                &samwise,
+                &vec![&e, underlying_2.clone()],
+                &vec![&e, underlying_0.clone(), underlying_1.clone()],
+                max_pct,
+            );
+            assert_eq!(auction_data.bid.len(), 1);
+            assert_eq!(auction_data.lot.len(), 2);
+
+            // no liquidation is possible while an auction is in progress
+            storage::set_auction(
+                &e,
+                &(AuctionType::UserLiquidation as u32),
+                &samwise,
+                &auction_data,
+            );
+            assert_eq!(
+                calc_max_user_liq_percent(&e, &mut pool, &user_state, &position_data),
+                0
+            );
+        });
+    }
+
+    #[test]
+    fn test_create_user_liquidation_partial() {
+        let e = Env::default();
+        e.mock_all_auths();
+        e.cost_estimate().budget().reset_unlimited();
+
+        e.ledger().set(LedgerInfo {
+            timestamp: 12345,
+            protocol_version: 22,
+            sequence_number: 50,
+            network_id: Default::default(),
+            base_reserve: 10,
+            min_temp_entry_ttl: 10,
+            min_persistent_entry_ttl: 10,
+            max_entry_ttl: 3110400,
+        });
+
+        let bombadil = Address::generate(&e);
+        let samwise = Address::generate(&e);
+
+        let pool_address = create_pool(&e);
+        let (oracle_address, oracle_client) = testutils::create_mock_oracle(&e);
+        let backstop_address = Address::generate(&e);
+
+        let (underlying_0, _) = testutils::create_token_contract(&e, &bombadil);
+        let (mut reserve_config_0, mut reserve_data_0) = testutils::default_reserve_meta();
+        reserve_data_0.last_time = 12345;
+        reserve_data_0.b_rate = 1_100_000_000_000;
+        reserve_data_0.d_rate = 1_150_000_000_000;
+        reserve_config_0.c_factor = 0_8500000;
+        reserve_config_0.l_factor = 0_9000000;
+        reserve_config_0.index = 0;
+        testutils::create_reserve(
+            &e,
+            &pool_address,
+            &underlying_0,
+            &reserve_config_0,
+            &reserve_data_0,
+        );
+
+        let (underlying_1, _) = testutils::create_token_contract(&e, &bombadil);
+        let (mut reserve_config_1, mut reserve_data_1) = testutils::default_reserve_meta();
+        reserve_data_1.last_time = 12345;
+        reserve_data_1.b_rate = 1_200_000_000_000;
+        reserve_data_1.d_rate = 1_300_000_000_000;
+        reserve_config_1.c_factor = 0_8000000;
+        reserve_config_1.l_factor = 0_7500000;
+        reserve_config_1.index = 1;
+        testutils::create_reserve(
+            &e,
+            &pool_address,
+            &underlying_1,
+            &reserve_config_1,
+            &reserve_data_1,
+        );
+
+        oracle_client.set_data(
+            &bombadil,
+            &Asset::Other(Symbol::new(&e, "USD")),
+            &vec![
+                &e,
+                Asset::Stellar(underlying_0.clone()),
+                Asset::Stellar(underlying_1.clone()),
+            ],
+            &7,
+            &300,
+        );
+        oracle_client.set_price_stable(&vec![&e, 2_0000000, 4_0000000]);
 
         let liq_pct = 85;
         let positions: Positions = Positions {
@@ -1965,6 +2669,7 @@ mod tests {
 
             let result = create_user_liq_auction_data(
                 &e,
+                &mut Pool::load(&e),
                 &samwise,
                 &vec![&e, underlying_0.clone()],
                 &vec![&e, underlying_1.clone()],
@@ -2076,6 +2781,7 @@ mod tests {
 
             let result = create_user_liq_auction_data(
                 &e,
+                &mut Pool::load(&e),
                 &samwise,
                 &vec![&e, underlying_1.clone()],
                 &vec![&e, underlying_0.clone()],
@@ -2185,6 +2891,7 @@ mod tests {
 
             let result = create_user_liq_auction_data(
                 &e,
+                &mut Pool::load(&e),
                 &samwise,
                 &vec![&e, underlying_0.clone()],
                 &vec![&e, underlying_1.clone()],
@@ -2296,6 +3003,7 @@ mod tests {
 
             create_user_liq_auction_data(
                 &e,
+                &mut Pool::load(&e),
                 &samwise,
                 &vec![&e, underlying_1.clone()],
                 &vec![&e, underlying_1.clone()],
@@ -2400,6 +3108,7 @@ mod tests {
 
             create_user_liq_auction_data(
                 &e,
+                &mut Pool::load(&e),
                 &samwise,
                 &vec![&e, underlying_0.clone()],
                 &vec![&e, underlying_0.clone()],
@@ -2408,6 +3117,232 @@ mod tests {
         });
     }
 
+    #[test]
+    fn test_create_user_liquidation_delisted_reserve() {
+        let e = Env::default();
+
+        e.mock_all_auths();
+        e.ledger().set(LedgerInfo {
+            timestamp: 12345,
+            protocol_version: 22,
+            sequence_number: 50,
+            network_id: Default::default(),
+            base_reserve: 10,
+            min_temp_entry_ttl: 10,
+            min_persistent_entry_ttl: 10,
+            max_entry_ttl: 3110400,
+        });
+
+        let bombadil = Address::generate(&e);
+        let samwise = Address::generate(&e);
+
+        let pool_address = create_pool(&e);
+        let (oracle_address, oracle_client) = testutils::create_mock_oracle(&e);
+        let backstop_address = Address::generate(&e);
+
+        // creating reserves for a pool exhausts the budget
+        e.cost_estimate().budget().reset_unlimited();
+        let (underlying_0, _) = testutils::create_token_contract(&e, &bombadil);
+        let (mut reserve_config_0, mut reserve_data_0) = testutils::default_reserve_meta();
+        reserve_data_0.last_time = 12345;
+        reserve_data_0.b_rate = 1_100_000_000_000;
+        reserve_data_0.d_rate = 1_150_000_000_000;
+        reserve_config_0.c_factor = 0_8500000;
+        reserve_config_0.l_factor = 0_9000000;
+        reserve_config_0.index = 0;
+        testutils::create_reserve(
+            &e,
+            &pool_address,
+            &underlying_0,
+            &reserve_config_0,
+            &reserve_data_0,
+        );
+
+        let (underlying_1, _) = testutils::create_token_contract(&e, &bombadil);
+        let (mut reserve_config_1, mut reserve_data_1) = testutils::default_reserve_meta();
+        reserve_data_1.last_time = 12345;
+        reserve_data_1.b_rate = 1_200_000_000_000;
+        reserve_data_1.d_rate = 1_300_000_000_000;
+        reserve_config_1.c_factor = 0_8000000;
+        reserve_config_1.l_factor = 0_7500000;
+        reserve_config_1.index = 1;
+        reserve_config_1.enabled = false;
+        testutils::create_reserve(
+            &e,
+            &pool_address,
+            &underlying_1,
+            &reserve_config_1,
+            &reserve_data_1,
+        );
+
+        oracle_client.set_data(
+            &bombadil,
+            &Asset::Other(Symbol::new(&e, "USD")),
+            &vec![
+                &e,
+                Asset::Stellar(underlying_0.clone()),
+                Asset::Stellar(underlying_1.clone()),
+            ],
+            &7,
+            &300,
+        );
+        oracle_client.set_price_stable(&vec![&e, 2_0000000, 4_0000000]);
+
+        let liq_pct = 100;
+        // the user is healthy, but holds liabilities in a reserve that finished delisting
+        let positions: Positions = Positions {
+            collateral: map![&e, (reserve_config_0.index, 100_0000000),],
+            liabilities: map![&e, (reserve_config_1.index, 10_0000000),],
+            supply: map![&e],
+        };
+        let pool_config = PoolConfig {
+            oracle: oracle_address,
+            min_collateral: 1_0000000,
+            bstop_rate: 0_1000000,
+            status: 0,
+            max_positions: 4,
+        };
+        e.as_contract(&pool_address, || {
+            storage::set_user_positions(&e, &samwise, &positions);
+            storage::set_pool_config(&e, &pool_config);
+            storage::set_backstop(&e, &backstop_address);
+            storage::set_res_delisting(
+                &e,
+                &underlying_1,
+                &ReserveDelisting {
+                    c_factor: 0_8000000,
+                    start_time: 1000,
+                    end_time: 12345,
+                },
+            );
+
+            let result = create_user_liq_auction_data(
+                &e,
+                &mut Pool::load(&e),
+                &samwise,
+                &vec![&e, underlying_1.clone()],
+                &vec![&e, underlying_0.clone()],
+                liq_pct,
+            );
+            assert_eq!(result.block, 51);
+            assert_eq!(result.bid.get_unchecked(underlying_1.clone()), 10_0000000);
+            assert_eq!(result.bid.len(), 1);
+            assert_eq!(result.lot.get_unchecked(underlying_0.clone()), 27_9204600);
+            assert_eq!(result.lot.len(), 1);
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "Error(Contract, #1211)")]
+    fn test_create_user_liquidation_delisted_reserve_requires_full_liquidation() {
+        let e = Env::default();
+
+        e.mock_all_auths();
+        e.ledger().set(LedgerInfo {
+            timestamp: 12345,
+            protocol_version: 22,
+            sequence_number: 50,
+            network_id: Default::default(),
+            base_reserve: 10,
+            min_temp_entry_ttl: 10,
+            min_persistent_entry_ttl: 10,
+            max_entry_ttl: 3110400,
+        });
+
+        let bombadil = Address::generate(&e);
+        let samwise = Address::generate(&e);
+
+        let pool_address = create_pool(&e);
+        let (oracle_address, oracle_client) = testutils::create_mock_oracle(&e);
+        let backstop_address = Address::generate(&e);
+
+        // creating reserves for a pool exhausts the budget
+        e.cost_estimate().budget().reset_unlimited();
+        let (underlying_0, _) = testutils::create_token_contract(&e, &bombadil);
+        let (mut reserve_config_0, mut reserve_data_0) = testutils::default_reserve_meta();
+        reserve_data_0.last_time = 12345;
+        reserve_data_0.b_rate = 1_100_000_000_000;
+        reserve_data_0.d_rate = 1_150_000_000_000;
+        reserve_config_0.c_factor = 0_8500000;
+        reserve_config_0.l_factor = 0_9000000;
+        reserve_config_0.index = 0;
+        testutils::create_reserve(
+            &e,
+            &pool_address,
+            &underlying_0,
+            &reserve_config_0,
+            &reserve_data_0,
+        );
+
+        let (underlying_1, _) = testutils::create_token_contract(&e, &bombadil);
+        let (mut reserve_config_1, mut reserve_data_1) = testutils::default_reserve_meta();
+        reserve_data_1.last_time = 12345;
+        reserve_data_1.b_rate = 1_200_000_000_000;
+        reserve_data_1.d_rate = 1_300_000_000_000;
+        reserve_config_1.c_factor = 0_8000000;
+        reserve_config_1.l_factor = 0_7500000;
+        reserve_config_1.index = 1;
+        reserve_config_1.enabled = false;
+        testutils::create_reserve(
+            &e,
+            &pool_address,
+            &underlying_1,
+            &reserve_config_1,
+            &reserve_data_1,
+        );
+
+        oracle_client.set_data(
+            &bombadil,
+            &Asset::Other(Symbol::new(&e, "USD")),
+            &vec![
+                &e,
+                Asset::Stellar(underlying_0.clone()),
+                Asset::Stellar(underlying_1.clone()),
+            ],
+            &7,
+            &300,
+        );
+        oracle_client.set_price_stable(&vec![&e, 2_0000000, 4_0000000]);
+
+        let liq_pct = 50;
+        // the user is healthy, but holds liabilities in a reserve that finished delisting
+        let positions: Positions = Positions {
+            collateral: map![&e, (reserve_config_0.index, 100_0000000),],
+            liabilities: map![&e, (reserve_config_1.index, 10_0000000),],
+            supply: map![&e],
+        };
+        let pool_config = PoolConfig {
+            oracle: oracle_address,
+            min_collateral: 1_0000000,
+            bstop_rate: 0_1000000,
+            status: 0,
+            max_positions: 4,
+        };
+        e.as_contract(&pool_address, || {
+            storage::set_user_positions(&e, &samwise, &positions);
+            storage::set_pool_config(&e, &pool_config);
+            storage::set_backstop(&e, &backstop_address);
+            storage::set_res_delisting(
+                &e,
+                &underlying_1,
+                &ReserveDelisting {
+                    c_factor: 0_8000000,
+                    start_time: 1000,
+                    end_time: 12345,
+                },
+            );
+
+            create_user_liq_auction_data(
+                &e,
+                &mut Pool::load(&e),
+                &samwise,
+                &vec![&e, underlying_1.clone()],
+                &vec![&e, underlying_0.clone()],
+                liq_pct,
+            );
+        });
+    }
+
     #[test]
     fn test_fill_user_liquidation_auction() {
         let e = Env::default();
@@ -2583,6 +3518,104 @@ mod tests {
         });
     }
 
+    #[test]
+    fn test_fill_user_liquidation_auction_releases_isolated_debt() {
+        let e = Env::default();
+
+        e.mock_all_auths();
+        e.ledger().set(LedgerInfo {
+            timestamp: 12345,
+            protocol_version: 22,
+            sequence_number: 175,
+            network_id: Default::default(),
+            base_reserve: 10,
+            min_temp_entry_ttl: 17280,
+            min_persistent_entry_ttl: 17280,
+            max_entry_ttl: 9999999,
+        });
+
+        let bombadil = Address::generate(&e);
+        let samwise = Address::generate(&e);
+        let frodo = Address::generate(&e);
+
+        let pool_address = create_pool(&e);
+
+        let (underlying_0, _) = testutils::create_token_contract(&e, &bombadil);
+        let (mut reserve_config_0, mut reserve_data_0) = testutils::default_reserve_meta();
+        reserve_data_0.last_time = 12345;
+        reserve_config_0.isolated = true;
+        reserve_config_0.debt_ceiling = 1000_0000000;
+        testutils::create_reserve(
+            &e,
+            &pool_address,
+            &underlying_0,
+            &reserve_config_0,
+            &reserve_data_0,
+        );
+
+        let (underlying_1, _) = testutils::create_token_contract(&e, &bombadil);
+        let (mut reserve_config_1, mut reserve_data_1) = testutils::default_reserve_meta();
+        reserve_data_1.last_time = 12345;
+        reserve_config_1.index = 1;
+        testutils::create_reserve(
+            &e,
+            &pool_address,
+            &underlying_1,
+            &reserve_config_1,
+            &reserve_data_1,
+        );
+
+        let pool_config = PoolConfig {
+            oracle: Address::generate(&e),
+            min_collateral: 1_0000000,
+            bstop_rate: 0_1000000,
+            status: 0,
+            max_positions: 4,
+        };
+        let positions: Positions = Positions {
+            collateral: map![&e, (0, 100_0000000)],
+            liabilities: map![&e, (1, 60_0000000)],
+            supply: map![&e],
+        };
+        e.as_contract(&pool_address, || {
+            storage::set_user_positions(&e, &samwise, &positions);
+            storage::set_pool_config(&e, &pool_config);
+            // another user's debt is also backed by the isolated reserve
+            storage::set_isolated_debt(
+                &e,
+                &underlying_0,
+                &map![&e, (underlying_1.clone(), 75_0000000)],
+            );
+
+            let mut pool = Pool::load(&e);
+            let mut frodo_state = User::load(&e, &frodo);
+            let partial_auction = AuctionData {
+                bid: map![&e, (underlying_1.clone(), 20_0000000)],
+                lot: map![&e, (underlying_0.clone(), 40_0000000)],
+                block: 176,
+            };
+            fill_user_liq_auction(&e, &mut pool, &partial_auction, &samwise, &mut frodo_state);
+            assert_eq!(
+                storage::get_isolated_debt(&e, &underlying_0),
+                map![&e, (underlying_1.clone(), 55_0000000)]
+            );
+
+            // removing all of the isolated collateral releases the rest of the backed debt
+            let full_auction = AuctionData {
+                bid: map![&e, (underlying_1.clone(), 10_0000000)],
+                lot: map![&e, (underlying_0.clone(), 60_0000000)],
+                block: 176,
+            };
+            fill_user_liq_auction(&e, &mut pool, &full_auction, &samwise, &mut frodo_state);
+            assert_eq!(
+                storage::get_isolated_debt(&e, &underlying_0),
+                map![&e, (underlying_1.clone(), 15_0000000)]
+            );
+            let samwise_positions = storage::get_user_positions(&e, &samwise);
+            assert_eq!(samwise_positions.liabilities.get_unchecked(1), 30_0000000);
+        });
+    }
+
     #[test]
     fn test_fill_user_liquidation_auction_hits_target() {
         let e = Env::default();
@@ -2713,7 +3746,7 @@ mod tests {
             fill_user_liq_auction(&e, &mut pool, &mut auction_data, &samwise, &mut frodo_state);
             let samwise_positions = storage::get_user_positions(&e, &samwise);
             let samwise_hf =
-                PositionData::calculate_from_positions(&e, &mut pool, &samwise_positions)
+                PositionData::calculate_from_positions(&e, &mut pool, &samwise_positions, 0)
                     .as_health_factor(&e);
             assert_eq!(samwise_hf, 1_1458977);
         });
@@ -14,3 +14,43 @@ pub const SECONDS_PER_WEEK: u64 = 604800;
 
 /// Max amount of reserves that can be added to a pool
 pub const MAX_RESERVES: u32 = 50;
+
+/// Max amount of backup oracles that can be configured for a pool
+pub const MAX_BACKUP_ORACLES: u32 = 4;
+
+/// Max amount of oracle records that can be averaged for a reserve's TWAP price
+pub const MAX_TWAP_RECORDS: u32 = 24;
+
+/// The term of a fixed-rate loan, in seconds (4 weeks)
+pub const FIXED_LOAN_TERM: u64 = 4 * SECONDS_PER_WEEK;
+
+/// The premium added to the variable borrow rate when quoting a fixed-rate loan (7 decimals)
+pub const FIXED_LOAN_PREMIUM: i128 = 0_0100000;
+
+/// The max period a reserve's collateral and liability factors can ramp to new values over, in seconds (4 weeks)
+pub const MAX_FACTOR_RAMP_PERIOD: u64 = 4 * SECONDS_PER_WEEK;
+
+/// The minimum period a reserve's collateral factor ramps to zero over when delisted, in seconds (1 week)
+pub const MIN_DELISTING_PERIOD: u64 = SECONDS_PER_WEEK;
+
+/// The max value of a delisted reserve's liability that can be defaulted on as dust, as a fraction
+/// of one unit of the oracle's base asset (7 decimals)
+pub const MAX_DELISTING_DUST: i128 = 0_0100000;
+
+/// The max number of conditional orders a user can have registered at once
+pub const MAX_CONDITIONAL_ORDERS: u32 = 8;
+
+/// The default number of blocks an auction's lot scales from 0% to 100% over
+pub const DEFAULT_AUCTION_LOT_RAMP: u32 = 200;
+
+/// The default number of blocks an auction's bid scales from 100% to 0% over, once the lot reaches 100%
+pub const DEFAULT_AUCTION_BID_DECAY: u32 = 200;
+
+/// The max number of blocks an auction's lot ramp or bid decay can last (~1 day)
+pub const MAX_AUCTION_CURVE_BLOCKS: u32 = 17280;
+
+/// The max share of an auction lot or bad debt that can be paid to a keeper (1%)
+pub const MAX_KEEPER_SHARE: u32 = 0_0100000;
+
+/// The max number of auctions returned by a single page of the auction views
+pub const MAX_AUCTION_PAGE_SIZE: u32 = 50;
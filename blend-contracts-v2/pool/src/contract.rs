@@ -1,12 +1,17 @@
 use crate::{
-    auctions::{self, AuctionData},
+    auctions::{self, AuctionData, AuctionEntry, AuctionParams},
     emissions::{self, ReserveEmissionMetadata},
     events::PoolEvents,
-    pool::{self, FlashLoan, Positions, Request, Reserve},
-    storage::{self, ReserveConfig},
+    pool::{
+        self, FlashLoan, Positions, Request, Reserve, SignedSubmit, SubmitSimulation, UserHealth,
+    },
+    storage::{
+        self, AuctionCurve, ConditionalOrder, CreditAllowance, EModeCategory, FixedLoan,
+        KeeperConfig, OracleConfig, ReserveConfig, ReserveDelisting, ReserveFactorRamp,
+    },
     PoolConfig, ReserveEmissionData, UserEmissionData,
 };
-use soroban_sdk::{contract, contractclient, contractimpl, Address, Env, String, Vec};
+use soroban_sdk::{contract, contractclient, contractimpl, Address, BytesN, Env, Map, String, Vec};
 
 /// ### Pool
 ///
@@ -42,10 +47,12 @@ pub trait Pool {
     /// ### Arguments
     /// * `asset` - The underlying asset to add as a reserve
     /// * `config` - The ReserveConfig for the reserve
+    /// * `ramp_period` - The number of seconds an existing reserve's collateral and liability factors
+    ///                   ramp to their new values over once set, or 0 to apply them instantly
     ///
     /// ### Panics
     /// If the caller is not the admin
-    fn queue_set_reserve(e: Env, asset: Address, metadata: ReserveConfig);
+    fn queue_set_reserve(e: Env, asset: Address, metadata: ReserveConfig, ramp_period: u64);
 
     /// (Admin only) Cancels the queued set of a reserve in the pool
     ///
@@ -67,9 +74,292 @@ pub trait Pool {
     /// or has invalid metadata
     fn set_reserve(e: Env, asset: Address) -> u32;
 
+    /// (Admin only) Queues setting the backup oracle configuration for the pool
+    ///
+    /// Backup oracles must report prices with the same decimals as the pool's oracle. If `quorum`
+    /// is 0, backup oracles are used in order only when the pool's oracle cannot provide a valid price.
+    /// Otherwise, the median of all valid prices is used, and at least `quorum` valid prices are required.
+    ///
+    /// ### Arguments
+    /// * `config` - The OracleConfig for the pool
+    ///
+    /// ### Panics
+    /// If the caller is not the admin or the configuration is invalid
+    fn queue_set_oracle_config(e: Env, config: OracleConfig);
+
+    /// (Admin only) Cancels the queued set of the backup oracle configuration
+    ///
+    /// ### Panics
+    /// If the caller is not the admin
+    fn cancel_set_oracle_config(e: Env);
+
+    /// Executes the queued set of the backup oracle configuration
+    ///
+    /// ### Panics
+    /// If the oracle configuration is not queued or is not unlocked
+    fn set_oracle_config(e: Env);
+
+    /// (Admin only) Queues setting the Dutch auction curves for the pool
+    ///
+    /// Each curve is keyed by auction type. The lot ramps from `start_premium` to 100% over
+    /// `lot_ramp` blocks, then the bid decays from 100% to 0% over `bid_decay` blocks. Auction
+    /// types without a curve use the default of 200 blocks for each phase with no start premium.
+    ///
+    /// ### Arguments
+    /// * `config` - The Dutch auction curves, mapped by auction type
+    ///
+    /// ### Panics
+    /// If the caller is not the admin or the configuration is invalid
+    fn queue_set_auction_config(e: Env, config: Map<u32, AuctionCurve>);
+
+    /// (Admin only) Cancels the queued set of the Dutch auction curves
+    ///
+    /// ### Panics
+    /// If the caller is not the admin
+    fn cancel_set_auction_config(e: Env);
+
+    /// Executes the queued set of the Dutch auction curves
+    ///
+    /// ### Panics
+    /// If the auction configuration is not queued or is not unlocked
+    fn set_auction_config(e: Env);
+
+    /// (Admin only) Sets the reserves that can be borrowed by users holding isolated collateral
+    ///
+    /// ### Arguments
+    /// * `assets` - The underlying assets of the reserves
+    ///
+    /// ### Panics
+    /// If the caller is not the admin, or an asset is not a reserve or is included more than once
+    fn set_isolated_borrow_list(e: Env, assets: Vec<Address>);
+
+    /// (Admin only) Sets the swap router used to swap collateral for deleverage requests. The router
+    /// is trusted to execute swaps, but the pool requires each swap to return the minimum output
+    /// set by the user.
+    ///
+    /// ### Arguments
+    /// * `router` - The contract address of the swap router
+    ///
+    /// ### Panics
+    /// If the caller is not the admin
+    fn set_router(e: Env, router: Address);
+
+    /// (Admin only) Sets the rewards paid to keepers. Keepers that create user liquidation or
+    /// interest auctions are paid `lot_share` of each filled lot, and keepers that transfer bad
+    /// debt to the backstop are paid `bad_debt_share` of the bad debt from the reserve's backstop
+    /// credit. Each share is capped at 1%.
+    ///
+    /// ### Arguments
+    /// * `config` - The keeper reward configuration
+    ///
+    /// ### Panics
+    /// If the caller is not the admin, or a share exceeds the cap
+    fn set_keeper_config(e: Env, config: KeeperConfig);
+
+    /// (Admin only) Queues setting an e-mode category of correlated reserves. Users in the category
+    /// use the category's collateral and liability factors for the category's reserves, as long as
+    /// all of their liabilities are in the category.
+    ///
+    /// The category's factors must be at least the collateral and liability factors of each of its
+    /// reserves.
+    ///
+    /// ### Arguments
+    /// * `category_id` - The id of the e-mode category, starting at 1
+    /// * `category` - The e-mode category
+    ///
+    /// ### Panics
+    /// If the caller is not the admin, the category is already queued, or the category is invalid
+    fn queue_set_emode_category(e: Env, category_id: u32, category: EModeCategory);
+
+    /// (Admin only) Cancels the queued set of an e-mode category
+    ///
+    /// ### Arguments
+    /// * `category_id` - The id of the e-mode category
+    ///
+    /// ### Panics
+    /// If the caller is not the admin
+    fn cancel_set_emode_category(e: Env, category_id: u32);
+
+    /// Executes the queued set of an e-mode category
+    ///
+    /// ### Arguments
+    /// * `category_id` - The id of the e-mode category
+    ///
+    /// ### Panics
+    /// If the category is not queued, is not unlocked, or is no longer valid
+    fn set_emode_category(e: Env, category_id: u32);
+
+    /// (Admin only) Starts delisting a reserve. The reserve is disabled for new supply and borrows,
+    /// and its collateral factor ramps to zero over the delisting period.
+    ///
+    /// ### Arguments
+    /// * `asset` - The underlying asset of the reserve
+    /// * `period` - The number of seconds the collateral factor ramps to zero over
+    ///
+    /// ### Panics
+    /// If the caller is not the admin, the asset is not a reserve, the reserve is already being
+    /// delisted, or the period is shorter than one week
+    fn delist_reserve(e: Env, asset: Address, period: u64);
+
+    /// Cleans a user's remaining positions in a reserve that has finished delisting. Collateral is
+    /// moved to the user's uncollateralized supply, and dust liabilities are defaulted on, so the
+    /// reserve no longer counts towards the user's positions. Liabilities worth more than the dust
+    /// limit can instead be fully liquidated with a user liquidation auction, regardless of the
+    /// user's health factor.
+    ///
+    /// Returns a tuple of (b_tokens moved to supply, d_tokens defaulted on)
+    ///
+    /// ### Arguments
+    /// * `user` - The address of the user
+    /// * `asset` - The underlying asset of the delisted reserve
+    ///
+    /// ### Panics
+    /// If the reserve has not finished delisting, the user has an ongoing liquidation, or the user
+    /// has no positions to clean
+    fn clean_delisted_position(e: Env, user: Address, asset: Address) -> (i128, i128);
+
+    /// Sets the amount of an asset `delegate` can borrow against `delegator`'s positions. Setting
+    /// an amount of zero revokes the allowance.
+    ///
+    /// ### Arguments
+    /// * `delegator` - The address of the user whose positions are borrowed against
+    /// * `delegate` - The address of the user allowed to borrow
+    /// * `asset` - The underlying asset the delegate can borrow
+    /// * `amount` - The amount of underlying tokens the delegate can borrow
+    /// * `expiration` - The time the allowance expires
+    ///
+    /// ### Panics
+    /// If the caller is not the delegator, the delegator and delegate are the same, the asset is not
+    /// a reserve, or the allowance is already expired
+    fn approve_delegation(
+        e: Env,
+        delegator: Address,
+        delegate: Address,
+        asset: Address,
+        amount: i128,
+        expiration: u64,
+    );
+
+    /// Borrows an asset against `delegator`'s positions using a credit allowance granted to
+    /// `delegate`. The liability is recorded against `delegator`, and the allowance is decremented
+    /// by the amount borrowed.
+    ///
+    /// Returns the new positions for `delegator`
+    ///
+    /// ### Arguments
+    /// * `delegator` - The address of the user whose positions are borrowed against
+    /// * `delegate` - The address of the user borrowing with the allowance
+    /// * `to` - The address receiving the borrowed tokens
+    /// * `asset` - The underlying asset to borrow
+    /// * `amount` - The amount of underlying tokens to borrow
+    ///
+    /// ### Panics
+    /// If the caller is not the delegate, the allowance is expired or insufficient, or the borrow
+    /// is not able to be completed for cases like invalid health factor
+    fn delegated_borrow(
+        e: Env,
+        delegator: Address,
+        delegate: Address,
+        to: Address,
+        asset: Address,
+        amount: i128,
+    ) -> Positions;
+
+    /// Fetch the credit allowances `delegator` has granted `delegate`, mapped by underlying asset.
+    /// Expired allowances are included until they are revoked.
+    ///
+    /// ### Arguments
+    /// * `delegator` - The address of the user whose positions are borrowed against
+    /// * `delegate` - The address of the user allowed to borrow
+    fn get_credit_delegation(
+        e: Env,
+        delegator: Address,
+        delegate: Address,
+    ) -> Map<Address, CreditAllowance>;
+
+    /// Fetch the address that holds a sub-account of a user. The address can be used with any
+    /// user view, like `get_positions`, and to create liquidation auctions for the sub-account.
+    ///
+    /// ### Arguments
+    /// * `user` - The address of the user who owns the sub-account
+    /// * `sub_id` - The id of the sub-account
+    fn get_sub_account(e: Env, user: Address, sub_id: u32) -> Address;
+
+    /// Fetch the ed25519 public key a user signs off-chain requests with, if one is registered
+    ///
+    /// ### Arguments
+    /// * `user` - The address of the user
+    fn get_signing_key(e: Env, user: Address) -> Option<BytesN<32>>;
+
+    /// Check if a user's signed request nonce has been used or cancelled
+    ///
+    /// ### Arguments
+    /// * `user` - The address of the user
+    /// * `nonce` - The nonce of the signed request
+    fn is_nonce_used(e: Env, user: Address, nonce: u64) -> bool;
+
+    /// Fetch the conditional orders a user has registered, mapped by order id
+    ///
+    /// ### Arguments
+    /// * `user` - The address of the user
+    fn get_orders(e: Env, user: Address) -> Map<u32, ConditionalOrder>;
+
     /// Fetch the pool configuration
     fn get_config(e: Env) -> PoolConfig;
 
+    /// Fetch the backup oracle configuration
+    fn get_oracle_config(e: Env) -> OracleConfig;
+
+    /// Fetch the Dutch auction curves set for the pool, mapped by auction type
+    fn get_auction_config(e: Env) -> Map<u32, AuctionCurve>;
+
+    /// Fetch the reserves that can be borrowed by users holding isolated collateral
+    fn get_isolated_borrow_list(e: Env) -> Vec<Address>;
+
+    /// Fetch the swap router used for deleverage requests, if one is set
+    fn get_router(e: Env) -> Option<Address>;
+
+    /// Fetch the keeper reward configuration
+    fn get_keeper_config(e: Env) -> KeeperConfig;
+
+    /// Fetch the debt currently backed by an isolated reserve's collateral, as a map of
+    /// borrowed asset to d_tokens
+    ///
+    /// ### Arguments
+    /// * `asset` - The address of the isolated reserve asset
+    fn get_isolated_debt(e: Env, asset: Address) -> Map<Address, i128>;
+
+    /// Fetch an e-mode category, if it exists
+    ///
+    /// ### Arguments
+    /// * `category_id` - The id of the e-mode category
+    fn get_emode_category(e: Env, category_id: u32) -> Option<EModeCategory>;
+
+    /// Fetch the e-mode category id a user has entered, or 0 if the user is not in e-mode
+    ///
+    /// ### Arguments
+    /// * `user` - The address of the user
+    fn get_user_emode(e: Env, user: Address) -> u32;
+
+    /// Fetch the delisting schedule of a reserve, if the reserve is being delisted
+    ///
+    /// ### Arguments
+    /// * `asset` - The underlying asset of the reserve
+    fn get_reserve_delisting(e: Env, asset: Address) -> Option<ReserveDelisting>;
+
+    /// Fetch the factor ramp of a reserve, if one has been scheduled
+    ///
+    /// ### Arguments
+    /// * `asset` - The underlying asset of the reserve
+    fn get_reserve_factor_ramp(e: Env, asset: Address) -> Option<ReserveFactorRamp>;
+
+    /// Fetch the fixed-term loans a user holds, mapped by reserve index. The principal of each loan
+    /// includes interest up to the last time the loan was settled.
+    ///
+    /// ### Arguments
+    /// * `user` - The address of the user
+    fn get_fixed_loans(e: Env, user: Address) -> Map<u32, FixedLoan>;
+
     /// Fetch the admin address of the pool
     fn get_admin(e: Env) -> Address;
 
@@ -79,6 +369,13 @@ pub trait Pool {
     /// * `asset` - The address of the reserve asset
     fn get_reserve(e: Env, asset: Address) -> Reserve;
 
+    /// Fetch the amount of underlying tokens that can still be supplied to a reserve before its
+    /// supply cap is reached, updated to the current ledger
+    ///
+    /// ### Arguments
+    /// * `asset` - The address of the reserve asset
+    fn get_reserve_capacity(e: Env, asset: Address) -> i128;
+
     /// Fetch data about the pool and its reserves.
     ///
     /// Useful for external integrations that need to load all data about the pool
@@ -93,6 +390,29 @@ pub trait Pool {
     /// * `address` - The address to fetch positions for
     fn get_positions(e: Env, address: Address) -> Positions;
 
+    /// Simulate a set of requests against the pool for 'from' without transferring any tokens or
+    /// storing the resulting positions.
+    ///
+    /// Returns the resulting positions, the token transfers, the health factor, the effective
+    /// collateral and liabilities, and the codes of any errors validating the submit would fail with
+    ///
+    /// ### Arguments
+    /// * `from` - The address of the user whose positions are being simulated
+    /// * `requests` - A vec of requests to be processed
+    ///
+    /// ### Panics
+    /// If a request is invalid or is an auction request
+    fn simulate_submit(e: Env, from: Address, requests: Vec<Request>) -> SubmitSimulation;
+
+    /// Fetch the health of a user's positions, updated to the current ledger
+    ///
+    /// Returns the position data and health factor of the user, the maximum percent of the user's
+    /// positions that can currently be liquidated, and if the user is eligible for `bad_debt`
+    ///
+    /// ### Arguments
+    /// * `user` - The address of the user
+    fn get_user_health(e: Env, user: Address) -> UserHealth;
+
     /// Submit a set of requests to the pool where 'from' takes on the position, 'sender' sends any
     /// required tokens to the pool and 'to' receives any tokens sent from the pool.
     ///
@@ -135,16 +455,41 @@ pub trait Pool {
         requests: Vec<Request>,
     ) -> Positions;
 
+    /// Submit a set of requests to the pool where sub-account 'sub_id' of 'from' takes on the position,
+    /// 'spender' sends any required tokens to the pool and 'to' receives any tokens sent from the pool.
+    /// Each sub-account has its own positions, health, auctions and emissions. Sub-account 0 is 'from'.
+    /// Sub-accounts do not support transferring positions, delegation, flash loans or signed submits.
+    ///
+    /// Returns the new positions for the sub-account
+    ///
+    /// ### Arguments
+    /// * `from` - The address of the user who owns the sub-account
+    /// * `sub_id` - The id of the sub-account whose positions are being modified
+    /// * `spender` - The address of the user who is sending tokens to the pool
+    /// * `to` - The address of the user who is receiving tokens from the pool
+    /// * `requests` - A vec of requests to be processed
+    ///
+    /// ### Panics
+    /// If the request is not able to be completed for cases like insufficient funds or invalid health factor
+    fn submit_sub_account(
+        e: Env,
+        from: Address,
+        sub_id: u32,
+        spender: Address,
+        to: Address,
+        requests: Vec<Request>,
+    ) -> Positions;
+
     /// Submit flash loan and a set of requests to the pool where 'from' takes on the position. The flash loan will be invoked using
-    /// the 'flash_loan' arguments and 'from' as the caller. For the requests, 'from' sends any required tokens to the pool
-    /// using transfer_from and receives any tokens sent from the pool.
+    /// the 'flash_loan' arguments and 'from' as the caller. The receiver's moderc3156 `exec_op` is called for a single asset,
+    /// and its `exec_op_multi` is called once with all of the assets for multiple assets. For the requests, 'from' sends any required tokens to the pool using transfer_from and receives any tokens sent from the pool.
     ///
     /// Returns the new positions for 'from'
     ///
     /// ### Arguments
     /// * `from` - The address of the user whose positions are being modified and also the address of
     /// the user who is sending and receiving the tokens to the pool.
-    /// * `flash_loan` - Arguments relative to the flash loan: receiver contract, asset and borroed amount.
+    /// * `flash_loan` - Arguments relative to the flash loan: receiver contract and the (asset, amount) pairs to borrow.
     /// * `requests` - A vec of requests to be processed
     ///
     /// ### Panics
@@ -156,6 +501,155 @@ pub trait Pool {
         requests: Vec<Request>,
     ) -> Positions;
 
+    /// Flash loan an asset from the pool without creating a position. The tokens are sent to the
+    /// `receiver` contract, which is invoked with `caller` as the caller. The amount plus the
+    /// reserve's flash loan fee is then transferred from `caller` to the pool using transfer_from.
+    ///
+    /// Returns the fee paid
+    ///
+    /// ### Arguments
+    /// * `caller` - The address of the user taking the flash loan and repaying it
+    /// * `receiver` - The address of the flash loan receiver contract
+    /// * `asset` - The underlying asset to flash loan
+    /// * `amount` - The amount of underlying tokens to flash loan
+    ///
+    /// ### Panics
+    /// If the pool or reserve does not allow borrowing, or the flash loan and fee are not repaid
+    fn flash_loan_simple(
+        e: Env,
+        caller: Address,
+        receiver: Address,
+        asset: Address,
+        amount: i128,
+    ) -> i128;
+
+    /// Fill a user liquidation auction without taking on its positions. The lot's collateral is
+    /// withdrawn and sent to the `receiver` contract, whose `exec_liquidation` is invoked with
+    /// `filler` as the caller. The bid's liabilities are then repaid by transferring the underlying
+    /// from `filler` to the pool using transfer_from.
+    ///
+    /// Returns the filled auction, in bTokens for the lot and dTokens for the bid
+    ///
+    /// ### Arguments
+    /// * `filler` - The address of the user filling the auction and repaying the bid
+    /// * `receiver` - The address of the liquidation receiver contract
+    /// * `user` - The address of the user being liquidated
+    /// * `percent` - The percentage of the auction to fill (15 => 15%)
+    ///
+    /// ### Panics
+    /// If the auction does not exist, or the bid is not repaid
+    fn flash_liquidate(
+        e: Env,
+        filler: Address,
+        receiver: Address,
+        user: Address,
+        percent: u32,
+    ) -> AuctionData;
+
+    /// Move all of `from`'s positions in a set of reserves to `to`. Collateral, supply, liabilities
+    /// and fixed-term loans are moved atomically, and both users must remain healthy.
+    ///
+    /// Returns the new positions for `to`
+    ///
+    /// ### Arguments
+    /// * `from` - The address of the user whose positions are being moved
+    /// * `to` - The address of the user receiving the positions
+    /// * `reserves` - The underlying assets of the reserves to move positions in
+    ///
+    /// ### Panics
+    /// If either `from` or `to` has not authorized the call, no positions are moved, either user
+    /// has an ongoing liquidation, or either user exceeds the max positions or is left with an
+    /// invalid health factor
+    fn transfer_positions(e: Env, from: Address, to: Address, reserves: Vec<Address>) -> Positions;
+
+    /// Register the ed25519 public key `user` signs off-chain requests with. Registering a new key
+    /// replaces the previous one, but does not invalidate requests signed with it that have not been
+    /// submitted. Use `cancel_signed` to invalidate them.
+    ///
+    /// ### Arguments
+    /// * `user` - The address of the user
+    /// * `signing_key` - The ed25519 public key
+    ///
+    /// ### Panics
+    /// If the caller is not `user`
+    fn set_signing_key(e: Env, user: Address, signing_key: BytesN<32>);
+
+    /// Cancel a signed request of `user` before it is submitted
+    ///
+    /// ### Arguments
+    /// * `user` - The address of the user
+    /// * `nonce` - The nonce of the signed request
+    ///
+    /// ### Panics
+    /// If the caller is not `user` or the nonce has already been used
+    fn cancel_signed(e: Env, user: Address, nonce: u64);
+
+    /// Submit a set of requests signed off-chain by `signed.from` with their registered signing key.
+    /// The signature is an ed25519 signature over the XDR of `(pool_address, signed)`. Each nonce can
+    /// only be used once.
+    ///
+    /// 'signed.from' takes on the position and sends and receives any tokens. Tokens owed to the pool
+    /// and the relayer fee are transferred from 'signed.from' using transfer_from, so 'signed.from'
+    /// must have approved the pool.
+    ///
+    /// Returns the new positions for 'signed.from'
+    ///
+    /// ### Arguments
+    /// * `relayer` - The address submitting the signed request and receiving the fee
+    /// * `signed` - The signed request
+    /// * `signature` - The ed25519 signature of the signed request
+    ///
+    /// ### Panics
+    /// If the caller is not `relayer`, the signed request is expired, its nonce has been used, it is
+    /// bound to a different relayer, the signature is invalid, or the requests are not able to be completed
+    fn submit_signed(
+        e: Env,
+        relayer: Address,
+        signed: SignedSubmit,
+        signature: BytesN<64>,
+    ) -> Positions;
+
+    /// Register a conditional order for `user` that any keeper can execute once its condition holds.
+    /// Registering an order with an existing id replaces it.
+    ///
+    /// Conditions are checked against the user's health factor or a reserve's utilization, with the
+    /// threshold expressed in 7 decimals. When executed, the order's requests are submitted for `user`,
+    /// who sends and receives any tokens. Tokens owed to the pool and the keeper fee are transferred
+    /// from `user` using transfer_from, so `user` must have approved the pool.
+    ///
+    /// ### Arguments
+    /// * `user` - The address of the user
+    /// * `order_id` - The id of the order
+    /// * `order` - The conditional order
+    ///
+    /// ### Panics
+    /// If the caller is not `user`, the order is invalid or expired, or `user` has too many orders
+    fn set_order(e: Env, user: Address, order_id: u32, order: ConditionalOrder);
+
+    /// Cancel a conditional order of `user`
+    ///
+    /// ### Arguments
+    /// * `user` - The address of the user
+    /// * `order_id` - The id of the order
+    ///
+    /// ### Panics
+    /// If the caller is not `user` or the order does not exist
+    fn cancel_order(e: Env, user: Address, order_id: u32);
+
+    /// Execute a conditional order of `user` if its condition holds. The order is removed once executed.
+    ///
+    /// Returns the new positions for `user`
+    ///
+    /// ### Arguments
+    /// * `keeper` - The address executing the order and receiving the fee
+    /// * `user` - The address of the user who registered the order
+    /// * `order_id` - The id of the order
+    ///
+    /// ### Panics
+    /// If the caller is not `keeper`, the order does not exist or is expired, the condition does not
+    /// hold, or the requests are not able to be completed
+    fn execute_order(e: Env, keeper: Address, user: Address, order_id: u32) -> Positions;
+
     /// Manage bad debt. Debt is considered "bad" if there is no longer has any collateral posted.
     ///
     /// To manage a user's bad debt, all collateralized reserves for the user must be liquidated
@@ -164,12 +658,16 @@ pub trait Pool {
     /// To manage a backstop's bad debt, the backstop module must be below a critical threshold
     /// to allow bad debt to be burnt.
     ///
+    /// If keeper rewards are configured, `keeper` is paid a share of the bad debt from the
+    /// backstop credit, unless they are the user.
+    ///
     /// ### Arguments
+    /// * `keeper` - The address processing the bad debt
     /// * `user` - The user who currently possesses bad debt
     ///
     /// ### Panics
     /// If the user has collateral posted
-    fn bad_debt(e: Env, user: Address);
+    fn bad_debt(e: Env, keeper: Address, user: Address);
 
     /// Update the pool status based on the backstop state - backstop triggered status' are odd numbers
     /// * 1 = backstop active - if the minimum backstop deposit has been reached
@@ -249,6 +747,23 @@ pub trait Pool {
     /// * `to` - The Address to send the claimed tokens to
     fn claim(e: Env, from: Address, reserve_token_ids: Vec<u32>, to: Address) -> i128;
 
+    /// Claims outstanding emissions for a sub-account of the caller for the given reserve's
+    ///
+    /// Returns the number of tokens claimed
+    ///
+    /// ### Arguments
+    /// * `from` - The address of the user who owns the sub-account
+    /// * `sub_id` - The id of the sub-account claiming emissions
+    /// * `reserve_token_ids` - Vector of reserve token ids
+    /// * `to` - The Address to send the claimed tokens to
+    fn claim_sub_account(
+        e: Env,
+        from: Address,
+        sub_id: u32,
+        reserve_token_ids: Vec<u32>,
+        to: Address,
+    ) -> i128;
+
     /// Get the emissions data for a reserve token
     ///
     /// A reserve token id is a unique identifier for a position in a pool.
@@ -275,7 +790,15 @@ pub trait Pool {
 
     /// Create a new auction. Auctions are used to process liquidations, bad debt, and interest.
     ///
+    /// If keeper rewards are configured, `keeper` is paid a share of the lot each time a user
+    /// liquidation or interest auction is filled. No reward is paid if the auction is deleted,
+    /// expires, or `keeper` is the user being liquidated or the filler.
+    ///
+    /// A user liquidation auction whose bid only contains reserves that have finished delisting can
+    /// be created for a healthy user, with a percent of 100. It cannot be deleted by the user.
+    ///
     /// ### Arguments
+    /// * `keeper` - The address creating the auction
     /// * `auction_type` - The type of auction, 0 for liquidation auction, 1 for bad debt auction, and 2 for interest auction
     /// * `user` - The Address involved in the auction. This is generally the source of the assets being auctioned.
     ///            For bad debt and interest auctions, this is expected to be the backstop address.
@@ -285,6 +808,7 @@ pub trait Pool {
     ///               this is expected to be 100.
     fn new_auction(
         e: Env,
+        keeper: Address,
         auction_type: u32,
         user: Address,
         bid: Vec<Address>,
@@ -292,7 +816,22 @@ pub trait Pool {
         percent: u32,
     ) -> AuctionData;
 
-    /// Fetch an auction from the ledger. Returns a quote based on the current block.
+    /// Creates a batch of new auctions. Oracle prices and reserve data are loaded once and shared
+    /// across the batch, which makes creating many auctions at once cheaper than calling
+    /// `new_auction` for each.
+    ///
+    /// Returns the AuctionData objects created, in the order of `params`
+    ///
+    /// ### Arguments
+    /// * `keeper` - The address creating the auctions
+    /// * `params` - The parameters of each auction, as described in `new_auction`
+    ///
+    /// ### Panics
+    /// If no auctions are requested or any auction is unable to be created
+    fn new_auctions(e: Env, keeper: Address, params: Vec<AuctionParams>) -> Vec<AuctionData>;
+
+    /// Fetch an auction from the ledger. Returns a quote of the bid and lot if the auction
+    /// were fully filled at the current block.
     ///
     /// ### Arguments
     /// * `auction_type` - The type of auction, 0 for liquidation auction, 1 for bad debt auction, and 2 for interest auction
@@ -301,6 +840,37 @@ pub trait Pool {
     /// ### Panics
     /// If the auction does not exist
     fn get_auction(e: Env, auction_type: u32, user: Address) -> AuctionData;
+
+    /// Fetch the unscaled auction data stored on the ledger
+    ///
+    /// ### Arguments
+    /// * `auction_type` - The type of auction, 0 for liquidation auction, 1 for bad debt auction, and 2 for interest auction
+    /// * `user` - The Address involved in the auction
+    ///
+    /// ### Panics
+    /// If the auction does not exist
+    fn get_auction_data(e: Env, auction_type: u32, user: Address) -> AuctionData;
+
+    /// Fetch a page of the active auctions of an auction type. Each auction is returned with its
+    /// unscaled data and a quote of the bid and lot if it were fully filled at the current block.
+    /// Expired auctions are removed from the auction index as they are reached, and the last
+    /// auction in the index takes their position.
+    ///
+    /// ### Arguments
+    /// * `auction_type` - The type of auction, 0 for liquidation auction, 1 for bad debt auction, and 2 for interest auction
+    /// * `start` - The position in the auction index to start at
+    /// * `limit` - The max number of auctions to return, up to 50
+    ///
+    /// ### Panics
+    /// If the auction type is invalid or the limit is too large
+    fn get_auctions(e: Env, auction_type: u32, start: u32, limit: u32) -> Vec<AuctionEntry>;
+
+    /// Fetch the number of auctions in the auction index for an auction type. This can include
+    /// auctions that have expired.
+    ///
+    /// ### Arguments
+    /// * `auction_type` - The type of auction, 0 for liquidation auction, 1 for bad debt auction, and 2 for interest auction
+    fn get_auction_count(e: Env, auction_type: u32) -> u32;
 }
 
 #[contractimpl]
@@ -369,14 +939,14 @@ impl Pool for PoolContract {
         PoolEvents::update_pool(&e, admin, backstop_take_rate, max_positions, min_collateral);
     }
 
-    fn queue_set_reserve(e: Env, asset: Address, metadata: ReserveConfig) {
+    fn queue_set_reserve(e: Env, asset: Address, metadata: ReserveConfig, ramp_period: u64) {
         storage::extend_instance(&e);
         let admin = storage::get_admin(&e);
         admin.require_auth();
 
-        pool::execute_queue_set_reserve(&e, &asset, &metadata);
+        pool::execute_queue_set_reserve(&e, &asset, &metadata, ramp_period);
 
-        PoolEvents::queue_set_reserve(&e, admin, asset, metadata);
+        PoolEvents::queue_set_reserve(&e, admin, asset, metadata, ramp_period);
     }
 
     fn cancel_set_reserve(e: Env, asset: Address) {
@@ -396,10 +966,238 @@ impl Pool for PoolContract {
         index
     }
 
+    fn queue_set_oracle_config(e: Env, config: OracleConfig) {
+        storage::extend_instance(&e);
+        let admin = storage::get_admin(&e);
+        admin.require_auth();
+
+        pool::execute_queue_set_oracle_config(&e, &config);
+
+        PoolEvents::queue_set_oracle_config(&e, admin, config);
+    }
+
+    fn cancel_set_oracle_config(e: Env) {
+        storage::extend_instance(&e);
+        let admin = storage::get_admin(&e);
+        admin.require_auth();
+
+        pool::execute_cancel_queued_set_oracle_config(&e);
+
+        PoolEvents::cancel_set_oracle_config(&e, admin);
+    }
+
+    fn set_oracle_config(e: Env) {
+        storage::extend_instance(&e);
+        let config = pool::execute_set_oracle_config(&e);
+
+        PoolEvents::set_oracle_config(&e, config);
+    }
+
+    fn queue_set_auction_config(e: Env, config: Map<u32, AuctionCurve>) {
+        storage::extend_instance(&e);
+        let admin = storage::get_admin(&e);
+        admin.require_auth();
+
+        pool::execute_queue_set_auction_config(&e, &config);
+
+        PoolEvents::queue_set_auction_config(&e, admin, config);
+    }
+
+    fn cancel_set_auction_config(e: Env) {
+        storage::extend_instance(&e);
+        let admin = storage::get_admin(&e);
+        admin.require_auth();
+
+        pool::execute_cancel_queued_set_auction_config(&e);
+
+        PoolEvents::cancel_set_auction_config(&e, admin);
+    }
+
+    fn set_auction_config(e: Env) {
+        storage::extend_instance(&e);
+        let config = pool::execute_set_auction_config(&e);
+
+        PoolEvents::set_auction_config(&e, config);
+    }
+
+    fn set_isolated_borrow_list(e: Env, assets: Vec<Address>) {
+        storage::extend_instance(&e);
+        let admin = storage::get_admin(&e);
+        admin.require_auth();
+
+        pool::execute_set_isolated_borrow_list(&e, &assets);
+
+        PoolEvents::set_isolated_borrow_list(&e, admin, assets);
+    }
+
+    fn set_router(e: Env, router: Address) {
+        storage::extend_instance(&e);
+        let admin = storage::get_admin(&e);
+        admin.require_auth();
+
+        storage::set_router(&e, &router);
+
+        PoolEvents::set_router(&e, admin, router);
+    }
+
+    fn set_keeper_config(e: Env, config: KeeperConfig) {
+        storage::extend_instance(&e);
+        let admin = storage::get_admin(&e);
+        admin.require_auth();
+
+        pool::execute_set_keeper_config(&e, &config);
+
+        PoolEvents::set_keeper_config(&e, admin, config);
+    }
+
+    fn queue_set_emode_category(e: Env, category_id: u32, category: EModeCategory) {
+        storage::extend_instance(&e);
+        let admin = storage::get_admin(&e);
+        admin.require_auth();
+
+        pool::execute_queue_set_emode_category(&e, category_id, &category);
+
+        PoolEvents::queue_set_emode_category(&e, admin, category_id, category);
+    }
+
+    fn cancel_set_emode_category(e: Env, category_id: u32) {
+        storage::extend_instance(&e);
+        let admin = storage::get_admin(&e);
+        admin.require_auth();
+
+        pool::execute_cancel_queued_set_emode_category(&e, category_id);
+
+        PoolEvents::cancel_set_emode_category(&e, admin, category_id);
+    }
+
+    fn set_emode_category(e: Env, category_id: u32) {
+        storage::extend_instance(&e);
+        let category = pool::execute_set_emode_category(&e, category_id);
+
+        PoolEvents::set_emode_category(&e, category_id, category);
+    }
+
+    fn delist_reserve(e: Env, asset: Address, period: u64) {
+        storage::extend_instance(&e);
+        let admin = storage::get_admin(&e);
+        admin.require_auth();
+
+        let delisting = pool::execute_delist_reserve(&e, &asset, period);
+
+        PoolEvents::delist_reserve(&e, admin, asset, delisting);
+    }
+
+    fn clean_delisted_position(e: Env, user: Address, asset: Address) -> (i128, i128) {
+        storage::extend_instance(&e);
+        let (b_tokens_moved, d_tokens_defaulted) =
+            pool::execute_clean_delisted_position(&e, &user, &asset);
+
+        PoolEvents::clean_delisted_position(&e, asset, user, b_tokens_moved, d_tokens_defaulted);
+        (b_tokens_moved, d_tokens_defaulted)
+    }
+
+    fn approve_delegation(
+        e: Env,
+        delegator: Address,
+        delegate: Address,
+        asset: Address,
+        amount: i128,
+        expiration: u64,
+    ) {
+        storage::extend_instance(&e);
+        delegator.require_auth();
+
+        pool::execute_approve_delegation(&e, &delegator, &delegate, &asset, amount, expiration);
+
+        PoolEvents::approve_delegation(&e, delegator, delegate, asset, amount, expiration);
+    }
+
+    fn delegated_borrow(
+        e: Env,
+        delegator: Address,
+        delegate: Address,
+        to: Address,
+        asset: Address,
+        amount: i128,
+    ) -> Positions {
+        storage::extend_instance(&e);
+        delegate.require_auth();
+
+        pool::execute_delegated_borrow(&e, &delegator, &delegate, &to, &asset, amount)
+    }
+
+    fn get_credit_delegation(
+        e: Env,
+        delegator: Address,
+        delegate: Address,
+    ) -> Map<Address, CreditAllowance> {
+        storage::get_credit_delegation(&e, &delegator, &delegate)
+    }
+
+    fn get_sub_account(e: Env, user: Address, sub_id: u32) -> Address {
+        pool::sub_account_address(&e, &user, sub_id)
+    }
+
+    fn get_signing_key(e: Env, user: Address) -> Option<BytesN<32>> {
+        storage::get_signing_key(&e, &user)
+    }
+
+    fn is_nonce_used(e: Env, user: Address, nonce: u64) -> bool {
+        storage::is_nonce_used(&e, &user, nonce)
+    }
+
+    fn get_orders(e: Env, user: Address) -> Map<u32, ConditionalOrder> {
+        storage::get_user_orders(&e, &user)
+    }
+
     fn get_config(e: Env) -> PoolConfig {
         storage::get_pool_config(&e)
     }
 
+    fn get_oracle_config(e: Env) -> OracleConfig {
+        storage::get_oracle_config(&e)
+    }
+
+    fn get_auction_config(e: Env) -> Map<u32, AuctionCurve> {
+        storage::get_auction_config(&e)
+    }
+
+    fn get_isolated_borrow_list(e: Env) -> Vec<Address> {
+        storage::get_isolated_borrow_list(&e)
+    }
+
+    fn get_router(e: Env) -> Option<Address> {
+        storage::get_router(&e)
+    }
+
+    fn get_keeper_config(e: Env) -> KeeperConfig {
+        storage::get_keeper_config(&e)
+    }
+
+    fn get_isolated_debt(e: Env, asset: Address) -> Map<Address, i128> {
+        storage::get_isolated_debt(&e, &asset)
+    }
+
+    fn get_emode_category(e: Env, category_id: u32) -> Option<EModeCategory> {
+        storage::get_emode_category(&e, category_id)
+    }
+
+    fn get_user_emode(e: Env, user: Address) -> u32 {
+        storage::get_user_emode(&e, &user)
+    }
+
+    fn get_reserve_delisting(e: Env, asset: Address) -> Option<ReserveDelisting> {
+        storage::get_res_delisting(&e, &asset)
+    }
+
+    fn get_reserve_factor_ramp(e: Env, asset: Address) -> Option<ReserveFactorRamp> {
+        storage::get_res_factor_ramp(&e, &asset)
+    }
+
+    fn get_fixed_loans(e: Env, user: Address) -> Map<u32, FixedLoan> {
+        storage::get_user_fixed_loans(&e, &user)
+    }
+
     fn get_admin(e: Env) -> Address {
         storage::get_admin(&e)
     }
@@ -409,6 +1207,12 @@ impl Pool for PoolContract {
         Reserve::load(&e, &pool_config, &asset)
     }
 
+    fn get_reserve_capacity(e: Env, asset: Address) -> i128 {
+        let pool_config = storage::get_pool_config(&e);
+        let reserve = Reserve::load(&e, &pool_config, &asset);
+        reserve.remaining_supply_capacity(&e)
+    }
+
     fn get_market(e: Env) -> (PoolConfig, Vec<Reserve>) {
         let pool_config = storage::get_pool_config(&e);
         let res_list = storage::get_res_list(&e);
@@ -424,6 +1228,14 @@ impl Pool for PoolContract {
         storage::get_user_positions(&e, &address)
     }
 
+    fn simulate_submit(e: Env, from: Address, requests: Vec<Request>) -> SubmitSimulation {
+        pool::execute_simulate_submit(&e, &from, requests)
+    }
+
+    fn get_user_health(e: Env, user: Address) -> UserHealth {
+        pool::load_user_health(&e, &user)
+    }
+
     fn submit(
         e: Env,
         from: Address,
@@ -456,6 +1268,24 @@ impl Pool for PoolContract {
         pool::execute_submit(&e, &from, &spender, &to, requests, true)
     }
 
+    fn submit_sub_account(
+        e: Env,
+        from: Address,
+        sub_id: u32,
+        spender: Address,
+        to: Address,
+        requests: Vec<Request>,
+    ) -> Positions {
+        storage::extend_instance(&e);
+        spender.require_auth();
+        if from != spender {
+            from.require_auth();
+        }
+
+        let sub_account = pool::sub_account_address(&e, &from, sub_id);
+        pool::execute_submit(&e, &sub_account, &spender, &to, requests, false)
+    }
+
     fn flash_loan(
         e: Env,
         from: Address,
@@ -468,8 +1298,102 @@ impl Pool for PoolContract {
         pool::execute_submit_with_flash_loan(&e, &from, flash_loan, requests)
     }
 
-    fn bad_debt(e: Env, user: Address) {
-        pool::transfer_bad_debt_to_backstop(&e, &user);
+    fn flash_loan_simple(
+        e: Env,
+        caller: Address,
+        receiver: Address,
+        asset: Address,
+        amount: i128,
+    ) -> i128 {
+        storage::extend_instance(&e);
+        caller.require_auth();
+
+        pool::execute_flash_loan_simple(&e, &caller, &receiver, &asset, amount)
+    }
+
+    fn flash_liquidate(
+        e: Env,
+        filler: Address,
+        receiver: Address,
+        user: Address,
+        percent: u32,
+    ) -> AuctionData {
+        storage::extend_instance(&e);
+        filler.require_auth();
+
+        pool::execute_flash_liquidation(&e, &filler, &receiver, &user, percent as u64)
+    }
+
+    fn transfer_positions(e: Env, from: Address, to: Address, reserves: Vec<Address>) -> Positions {
+        storage::extend_instance(&e);
+        from.require_auth();
+        to.require_auth();
+
+        let (_, to_positions) = pool::execute_transfer_positions(&e, &from, &to, reserves.clone());
+
+        PoolEvents::transfer_positions(&e, from, to, reserves);
+        to_positions
+    }
+
+    fn set_signing_key(e: Env, user: Address, signing_key: BytesN<32>) {
+        storage::extend_instance(&e);
+        user.require_auth();
+
+        pool::execute_set_signing_key(&e, &user, &signing_key);
+
+        PoolEvents::set_signing_key(&e, user, signing_key);
+    }
+
+    fn cancel_signed(e: Env, user: Address, nonce: u64) {
+        storage::extend_instance(&e);
+        user.require_auth();
+
+        pool::execute_cancel_signed(&e, &user, nonce);
+
+        PoolEvents::cancel_signed(&e, user, nonce);
+    }
+
+    fn submit_signed(
+        e: Env,
+        relayer: Address,
+        signed: SignedSubmit,
+        signature: BytesN<64>,
+    ) -> Positions {
+        storage::extend_instance(&e);
+        relayer.require_auth();
+
+        pool::execute_submit_signed(&e, &relayer, signed, &signature)
+    }
+
+    fn set_order(e: Env, user: Address, order_id: u32, order: ConditionalOrder) {
+        storage::extend_instance(&e);
+        user.require_auth();
+
+        pool::execute_set_order(&e, &user, order_id, &order);
+
+        PoolEvents::set_order(&e, user, order_id, order);
+    }
+
+    fn cancel_order(e: Env, user: Address, order_id: u32) {
+        storage::extend_instance(&e);
+        user.require_auth();
+
+        pool::execute_cancel_order(&e, &user, order_id);
+
+        PoolEvents::cancel_order(&e, user, order_id);
+    }
+
+    fn execute_order(e: Env, keeper: Address, user: Address, order_id: u32) -> Positions {
+        storage::extend_instance(&e);
+        keeper.require_auth();
+
+        pool::execute_order(&e, &keeper, &user, order_id)
+    }
+
+    fn bad_debt(e: Env, keeper: Address, user: Address) {
+        keeper.require_auth();
+
+        pool::transfer_bad_debt_to_backstop(&e, &keeper, &user);
     }
 
     fn update_status(e: Env) -> u32 {
@@ -525,6 +1449,24 @@ impl Pool for PoolContract {
         amount_claimed
     }
 
+    fn claim_sub_account(
+        e: Env,
+        from: Address,
+        sub_id: u32,
+        reserve_token_ids: Vec<u32>,
+        to: Address,
+    ) -> i128 {
+        storage::extend_instance(&e);
+        from.require_auth();
+
+        let sub_account = pool::sub_account_address(&e, &from, sub_id);
+        let amount_claimed = emissions::execute_claim(&e, &sub_account, &reserve_token_ids, &to);
+
+        PoolEvents::claim(&e, sub_account, reserve_token_ids, amount_claimed);
+
+        amount_claimed
+    }
+
     fn get_reserve_emissions(e: Env, reserve_token_index: u32) -> Option<ReserveEmissionData> {
         storage::get_res_emis_data(&e, &reserve_token_index)
     }
@@ -541,6 +1483,7 @@ impl Pool for PoolContract {
 
     fn new_auction(
         e: Env,
+        keeper: Address,
         auction_type: u32,
         user: Address,
         bid: Vec<Address>,
@@ -548,14 +1491,46 @@ impl Pool for PoolContract {
         percent: u32,
     ) -> AuctionData {
         storage::extend_instance(&e);
+        keeper.require_auth();
 
-        let auction_data = auctions::create_auction(&e, auction_type, &user, &bid, &lot, percent);
+        let auction_data =
+            auctions::create_auction(&e, &keeper, auction_type, &user, &bid, &lot, percent);
 
         PoolEvents::new_auction(&e, auction_type, user, percent, auction_data.clone());
         auction_data
     }
 
+    fn new_auctions(e: Env, keeper: Address, params: Vec<AuctionParams>) -> Vec<AuctionData> {
+        storage::extend_instance(&e);
+        keeper.require_auth();
+
+        let auctions = auctions::create_auctions(&e, &keeper, &params);
+
+        for (param, auction_data) in params.iter().zip(auctions.iter()) {
+            PoolEvents::new_auction(
+                &e,
+                param.auction_type,
+                param.user,
+                param.percent,
+                auction_data,
+            );
+        }
+        auctions
+    }
+
     fn get_auction(e: Env, auction_type: u32, user: Address) -> AuctionData {
+        auctions::get_auction_quote(&e, auction_type, &user)
+    }
+
+    fn get_auction_data(e: Env, auction_type: u32, user: Address) -> AuctionData {
         storage::get_auction(&e, &auction_type, &user)
     }
+
+    fn get_auctions(e: Env, auction_type: u32, start: u32, limit: u32) -> Vec<AuctionEntry> {
+        auctions::load_auctions(&e, auction_type, start, limit)
+    }
+
+    fn get_auction_count(e: Env, auction_type: u32) -> u32 {
+        storage::get_auction_count(&e, &auction_type)
+    }
 }
@@ -0,0 +1,22 @@
+use soroban_sdk::{contractclient, Address, Env, Vec};
+
+/// The interface of a flash loan receiver contract that can take a flash loan of multiple assets.
+/// Flash loans of a single asset use the moderc3156 `exec_op` interface instead.
+#[contractclient(name = "MultiFlashLoanClient")]
+pub trait MultiFlashLoanReceiver {
+    /// Execute an operation with flash loaned tokens, already transferred to the receiver. Once the
+    /// operation completes, `caller` must hold the tokens owed so the pool can collect them.
+    ///
+    /// ### Arguments
+    /// * `caller` - The address of the user taking the flash loan
+    /// * `tokens` - The flash loaned assets
+    /// * `amounts` - The amount of each asset flash loaned
+    /// * `fees` - The fee owed for each asset
+    fn exec_op_multi(
+        e: Env,
+        caller: Address,
+        tokens: Vec<Address>,
+        amounts: Vec<i128>,
+        fees: Vec<i128>,
+    );
+}
@@ -1,2 +1,8 @@
 mod backstop;
 pub use backstop::{Client as BackstopClient, PoolBackstopData};
+
+mod flash_loan_receiver;
+pub use flash_loan_receiver::MultiFlashLoanClient;
+
+mod router;
+pub use router::SwapRouterClient;
@@ -0,0 +1,25 @@
+use soroban_sdk::{contractclient, Address, Env};
+
+/// The interface of a swap router the pool uses to swap collateral for deleverage requests
+#[contractclient(name = "SwapRouterClient")]
+pub trait SwapRouter {
+    /// Swap `amount_in` of `token_in`, already transferred to the router, for at least
+    /// `min_out` of `token_out`, sent to `to`.
+    ///
+    /// Returns the amount of `token_out` sent to `to`
+    ///
+    /// ### Arguments
+    /// * `token_in` - The token being swapped
+    /// * `token_out` - The token being received
+    /// * `amount_in` - The amount of `token_in` transferred to the router
+    /// * `min_out` - The minimum amount of `token_out` to receive
+    /// * `to` - The address receiving `token_out`
+    fn swap(
+        e: Env,
+        token_in: Address,
+        token_out: Address,
+        amount_in: i128,
+        min_out: i128,
+        to: Address,
+    ) -> i128;
+}
@@ -52,4 +52,17 @@ pub enum PoolError {
     InvalidLot = 1222,
     ReserveDisabled = 1223,
     MinCollateralNotMet = 1224,
+    ExceededBorrowCap = 1225,
+    ExceededSupplyCap = 1226,
+    InvalidIsolatedBorrow = 1227,
+    ExceededDebtCeiling = 1228,
+    InvalidEModeCategory = 1229,
+    InvalidFixedLoan = 1230,
+    InvalidDelisting = 1231,
+    InvalidDelegation = 1232,
+    InsufficientDelegation = 1233,
+    InvalidSignedRequest = 1234,
+    InvalidConditionalOrder = 1235,
+    OrderConditionNotMet = 1236,
+    InvalidDeleverage = 1237,
 }
@@ -1,6 +1,11 @@
-use soroban_sdk::{Address, Env, Symbol, Vec};
+use soroban_sdk::{Address, BytesN, Env, Map, Symbol, Vec};
 
-use crate::{AuctionData, ReserveConfig};
+use crate::{
+    storage::{
+        AuctionCurve, ConditionalOrder, EModeCategory, FixedLoan, KeeperConfig, ReserveDelisting,
+    },
+    AuctionData, OracleConfig, ReserveConfig,
+};
 
 pub struct PoolEvents {}
 
@@ -42,15 +47,22 @@ impl PoolEvents {
     /// Emitted when a new reserve configuration change is queued
     ///
     /// - topics - `["queue_set_reserve", admin: Address]`
-    /// - data - `[asset: Address, metadata: ReserveMetadata]`
+    /// - data - `[asset: Address, metadata: ReserveMetadata, ramp_period: u64]`
     ///
     /// ### Arguments
     /// * admin - The current admin of the pool
     /// * asset - The asset to change the reserve configuration of
     /// * metadata - The new reserve configuration
-    pub fn queue_set_reserve(e: &Env, admin: Address, asset: Address, metadata: ReserveConfig) {
+    /// * ramp_period - The number of seconds the factors ramp to their new values over
+    pub fn queue_set_reserve(
+        e: &Env,
+        admin: Address,
+        asset: Address,
+        metadata: ReserveConfig,
+        ramp_period: u64,
+    ) {
         let topics = (Symbol::new(&e, "queue_set_reserve"), admin);
-        e.events().publish(topics, (asset, metadata));
+        e.events().publish(topics, (asset, metadata, ramp_period));
     }
 
     /// Emitted when a queued reserve configuration change is cancelled
@@ -79,6 +91,201 @@ impl PoolEvents {
         e.events().publish(topics, (asset, index));
     }
 
+    /// Emitted when a new backup oracle configuration change is queued
+    ///
+    /// - topics - `["queue_set_oracle_config", admin: Address]`
+    /// - data - `config: OracleConfig`
+    ///
+    /// ### Arguments
+    /// * admin - The current admin of the pool
+    /// * config - The new backup oracle configuration
+    pub fn queue_set_oracle_config(e: &Env, admin: Address, config: OracleConfig) {
+        let topics = (Symbol::new(&e, "queue_set_oracle_config"), admin);
+        e.events().publish(topics, config);
+    }
+
+    /// Emitted when a queued backup oracle configuration change is cancelled
+    ///
+    /// - topics - `["cancel_set_oracle_config", admin: Address]`
+    /// - data - `()`
+    ///
+    /// ### Arguments
+    /// * admin - The current admin of the pool
+    pub fn cancel_set_oracle_config(e: &Env, admin: Address) {
+        let topics = (Symbol::new(&e, "cancel_set_oracle_config"), admin);
+        e.events().publish(topics, ());
+    }
+
+    /// Emitted when a backup oracle configuration change is set
+    ///
+    /// - topics - `["set_oracle_config"]`
+    /// - data - `config: OracleConfig`
+    ///
+    /// ### Arguments
+    /// * config - The new backup oracle configuration
+    pub fn set_oracle_config(e: &Env, config: OracleConfig) {
+        let topics = (Symbol::new(&e, "set_oracle_config"),);
+        e.events().publish(topics, config);
+    }
+
+    /// Emitted when a new Dutch auction curve configuration change is queued
+    ///
+    /// - topics - `["queue_set_auction_config", admin: Address]`
+    /// - data - `config: Map<u32, AuctionCurve>`
+    ///
+    /// ### Arguments
+    /// * admin - The current admin of the pool
+    /// * config - The new Dutch auction curves, mapped by auction type
+    pub fn queue_set_auction_config(e: &Env, admin: Address, config: Map<u32, AuctionCurve>) {
+        let topics = (Symbol::new(&e, "queue_set_auction_config"), admin);
+        e.events().publish(topics, config);
+    }
+
+    /// Emitted when a queued Dutch auction curve configuration change is cancelled
+    ///
+    /// - topics - `["cancel_set_auction_config", admin: Address]`
+    /// - data - `()`
+    ///
+    /// ### Arguments
+    /// * admin - The current admin of the pool
+    pub fn cancel_set_auction_config(e: &Env, admin: Address) {
+        let topics = (Symbol::new(&e, "cancel_set_auction_config"), admin);
+        e.events().publish(topics, ());
+    }
+
+    /// Emitted when a Dutch auction curve configuration change is set
+    ///
+    /// - topics - `["set_auction_config"]`
+    /// - data - `config: Map<u32, AuctionCurve>`
+    ///
+    /// ### Arguments
+    /// * config - The new Dutch auction curves, mapped by auction type
+    pub fn set_auction_config(e: &Env, config: Map<u32, AuctionCurve>) {
+        let topics = (Symbol::new(&e, "set_auction_config"),);
+        e.events().publish(topics, config);
+    }
+
+    /// Emitted when the swap router is set
+    ///
+    /// - topics - `["set_router", admin: Address]`
+    /// - data - `router: Address`
+    ///
+    /// ### Arguments
+    /// * admin - The current admin of the pool
+    /// * router - The contract address of the swap router
+    pub fn set_router(e: &Env, admin: Address, router: Address) {
+        let topics = (Symbol::new(e, "set_router"), admin);
+        e.events().publish(topics, router);
+    }
+
+    /// Emitted when the keeper reward configuration is set
+    ///
+    /// - topics - `["set_keeper_config", admin: Address]`
+    /// - data - `config: KeeperConfig`
+    ///
+    /// ### Arguments
+    /// * admin - The current admin of the pool
+    /// * config - The keeper reward configuration
+    pub fn set_keeper_config(e: &Env, admin: Address, config: KeeperConfig) {
+        let topics = (Symbol::new(e, "set_keeper_config"), admin);
+        e.events().publish(topics, config);
+    }
+
+    /// Emitted when the isolated borrow list is set
+    ///
+    /// - topics - `["set_isolated_borrow_list", admin: Address]`
+    /// - data - `assets: Vec<Address>`
+    ///
+    /// ### Arguments
+    /// * admin - The current admin of the pool
+    /// * assets - The reserves that can be borrowed against isolated collateral
+    pub fn set_isolated_borrow_list(e: &Env, admin: Address, assets: Vec<Address>) {
+        let topics = (Symbol::new(&e, "set_isolated_borrow_list"), admin);
+        e.events().publish(topics, assets);
+    }
+
+    /// Emitted when an e-mode category change is queued
+    ///
+    /// - topics - `["queue_set_emode_category", admin: Address, category_id: u32]`
+    /// - data - `category: EModeCategory`
+    ///
+    /// ### Arguments
+    /// * admin - The current admin of the pool
+    /// * category_id - The id of the e-mode category
+    /// * category - The new e-mode category
+    pub fn queue_set_emode_category(
+        e: &Env,
+        admin: Address,
+        category_id: u32,
+        category: EModeCategory,
+    ) {
+        let topics = (
+            Symbol::new(&e, "queue_set_emode_category"),
+            admin,
+            category_id,
+        );
+        e.events().publish(topics, category);
+    }
+
+    /// Emitted when a queued e-mode category change is cancelled
+    ///
+    /// - topics - `["cancel_set_emode_category", admin: Address, category_id: u32]`
+    /// - data - `()`
+    ///
+    /// ### Arguments
+    /// * admin - The current admin of the pool
+    /// * category_id - The id of the e-mode category
+    pub fn cancel_set_emode_category(e: &Env, admin: Address, category_id: u32) {
+        let topics = (
+            Symbol::new(&e, "cancel_set_emode_category"),
+            admin,
+            category_id,
+        );
+        e.events().publish(topics, ());
+    }
+
+    /// Emitted when an e-mode category is set
+    ///
+    /// - topics - `["set_emode_category", category_id: u32]`
+    /// - data - `category: EModeCategory`
+    ///
+    /// ### Arguments
+    /// * category_id - The id of the e-mode category
+    /// * category - The e-mode category
+    pub fn set_emode_category(e: &Env, category_id: u32, category: EModeCategory) {
+        let topics = (Symbol::new(&e, "set_emode_category"), category_id);
+        e.events().publish(topics, category);
+    }
+
+    /// Emitted when a price is sourced from a backup oracle because the primary oracle
+    /// could not provide a valid price
+    ///
+    /// - topics - `["oracle_fallback", asset: Address]`
+    /// - data - `[oracle: Address, price: i128]`
+    ///
+    /// ### Arguments
+    /// * asset - The asset the price was loaded for
+    /// * oracle - The backup oracle the price was sourced from
+    /// * price - The price used
+    pub fn oracle_fallback(e: &Env, asset: Address, oracle: Address, price: i128) {
+        let topics = (Symbol::new(&e, "oracle_fallback"), asset);
+        e.events().publish(topics, (oracle, price));
+    }
+
+    /// Emitted when a price is sourced as the median of the pool's oracles
+    ///
+    /// - topics - `["oracle_median", asset: Address]`
+    /// - data - `[sources: Vec<Address>, price: i128]`
+    ///
+    /// ### Arguments
+    /// * asset - The asset the price was loaded for
+    /// * sources - The oracles that provided a valid price
+    /// * price - The median price used
+    pub fn oracle_median(e: &Env, asset: Address, sources: Vec<Address>, price: i128) {
+        let topics = (Symbol::new(&e, "oracle_median"), asset);
+        e.events().publish(topics, (sources, price));
+    }
+
     /// Emitted when pool status is updated (non-admin)
     ///
     /// - topics - `["set_status"]`
@@ -159,6 +366,21 @@ impl PoolEvents {
         e.events().publish(topics, d_tokens);
     }
 
+    /// Emitted when a keeper is rewarded for transferring bad debt to the backstop
+    ///
+    /// - topics - `["bad_debt_reward", keeper: Address, user: Address]`
+    /// - data - `[asset: Address, amount: i128]`
+    ///
+    /// ### Arguments
+    /// * keeper - The keeper that transferred the bad debt
+    /// * user - The user with bad debt
+    /// * asset - The asset the reward is paid in
+    /// * amount - The amount of underlying tokens paid out of the backstop credit
+    pub fn bad_debt_reward(e: &Env, keeper: Address, user: Address, asset: Address, amount: i128) {
+        let topics = (Symbol::new(e, "bad_debt_reward"), keeper, user);
+        e.events().publish(topics, (asset, amount));
+    }
+
     /// Emitted when bad debt is defaulted
     ///
     /// - topics - `["defaulted_debt", asset: Address]`
@@ -250,6 +472,29 @@ impl PoolEvents {
         e.events().publish(topics, (tokens_out, b_tokens_burnt));
     }
 
+    /// Emitted when collateral is swapped to repay a liability during a deleverage
+    ///
+    /// - topics - `["deleverage", collateral: Address, liability: Address, from: Address]`
+    /// - data - `[amount_in: i128, amount_out: i128]`
+    ///
+    /// ### Arguments
+    /// * collateral - The collateral asset swapped
+    /// * liability - The liability asset received from the swap
+    /// * from - The address whose position is being modified
+    /// * amount_in - The amount of collateral tokens swapped
+    /// * amount_out - The amount of liability tokens received from the swap
+    pub fn deleverage(
+        e: &Env,
+        collateral: Address,
+        liability: Address,
+        from: Address,
+        amount_in: i128,
+        amount_out: i128,
+    ) {
+        let topics = (Symbol::new(e, "deleverage"), collateral, liability, from);
+        e.events().publish(topics, (amount_in, amount_out));
+    }
+
     /// Emitted when tokens are borrowed
     ///
     /// - topics - `["borrow", asset: Address, from: Address]`
@@ -303,6 +548,52 @@ impl PoolEvents {
         e.events().publish(topics, (tokens_out, d_tokens_minted));
     }
 
+    /// Emitted during a simple flash loan
+    ///
+    /// - topics - `["flash_loan_simple", asset: Address, caller: Address, receiver: Address]`
+    /// - data - `[amount: i128, fee: i128]`
+    ///
+    /// ### Arguments
+    /// * asset - The asset
+    /// * caller - The address that took and repaid the flash loan
+    /// * receiver - The address of the flash loan receiver contract
+    /// * amount - The amount of tokens sent from the pool
+    /// * fee - The fee paid to the pool
+    pub fn flash_loan_simple(
+        e: &Env,
+        asset: Address,
+        caller: Address,
+        receiver: Address,
+        amount: i128,
+        fee: i128,
+    ) {
+        let topics = (Symbol::new(e, "flash_loan_simple"), asset, caller, receiver);
+        e.events().publish(topics, (amount, fee));
+    }
+
+    /// Emitted when a user liquidation auction is filled with a flash liquidation
+    ///
+    /// - topics - `["flash_liquidation", user: Address, filler: Address]`
+    /// - data - `[receiver: Address, lot: Map<Address, i128>, bid: Map<Address, i128>]`
+    ///
+    /// ### Arguments
+    /// * user - The address of the user being liquidated
+    /// * filler - The address that filled the auction and repaid the bid
+    /// * receiver - The address of the liquidation receiver contract
+    /// * lot - The underlying tokens sent to the receiver, mapped by asset
+    /// * bid - The underlying tokens repaid to the pool, mapped by asset
+    pub fn flash_liquidation(
+        e: &Env,
+        user: Address,
+        filler: Address,
+        receiver: Address,
+        lot: Map<Address, i128>,
+        bid: Map<Address, i128>,
+    ) {
+        let topics = (Symbol::new(e, "flash_liquidation"), user, filler);
+        e.events().publish(topics, (receiver, lot, bid));
+    }
+
     /// Emitted when a reserve gulps excess tokens
     ///
     /// - topics - `["gulp", asset: Address]`
@@ -361,6 +652,28 @@ impl PoolEvents {
             .publish(topics, (filler, fill_percent, filled_auction_data));
     }
 
+    /// Emitted when the keeper that created an auction is paid a share of a filled lot
+    ///
+    /// - topics - `["keeper_reward", keeper: Address, auction_type: u32]`
+    /// - data - `[user: Address, lot: Map<Address, i128>]`
+    ///
+    /// ### Arguments
+    /// * auction_type - The type of auction
+    /// * user - The auction user
+    /// * keeper - The keeper that created the auction
+    /// * lot - The share of the lot paid to the keeper, in bTokens for user liquidation auctions
+    ///         and underlying tokens for interest auctions
+    pub fn keeper_reward(
+        e: &Env,
+        auction_type: u32,
+        user: Address,
+        keeper: Address,
+        lot: Map<Address, i128>,
+    ) {
+        let topics = (Symbol::new(e, "keeper_reward"), keeper, auction_type);
+        e.events().publish(topics, (user, lot));
+    }
+
     /// Emitted when a liquidation auction is deleted
     ///
     /// - topics - `["delete_liquidation_auction", from: Address]`
@@ -372,4 +685,261 @@ impl PoolEvents {
         let topics = (Symbol::new(&e, "delete_liquidation_auction"), from);
         e.events().publish(topics, ());
     }
+
+    /// Emitted when a user changes their e-mode category
+    ///
+    /// - topics - `["set_emode", user: Address]`
+    /// - data - `category_id: u32`
+    ///
+    /// ### Arguments
+    /// * user - The user changing their e-mode category
+    /// * category_id - The e-mode category entered, or 0 if e-mode was disabled
+    pub fn set_emode(e: &Env, user: Address, category_id: u32) {
+        let topics = (Symbol::new(&e, "set_emode"), user);
+        e.events().publish(topics, category_id);
+    }
+
+    /// Emitted when a fixed-term loan is borrowed
+    ///
+    /// - topics - `["borrow_fixed", asset: Address, from: Address]`
+    /// - data - `[tokens_out: i128, loan: FixedLoan]`
+    ///
+    /// ### Arguments
+    /// * asset - The asset
+    /// * from - The address taking out the loan
+    /// * tokens_out - The amount of tokens sent from the pool
+    /// * loan - The fixed-term loan after the borrow
+    pub fn borrow_fixed(e: &Env, asset: Address, from: Address, tokens_out: i128, loan: FixedLoan) {
+        let topics = (Symbol::new(e, "borrow_fixed"), asset, from);
+        e.events().publish(topics, (tokens_out, loan));
+    }
+
+    /// Emitted when a fixed-term loan is repaid
+    ///
+    /// - topics - `["repay_fixed", asset: Address, from: Address]`
+    /// - data - `[tokens_in: i128, principal: i128]`
+    ///
+    /// ### Arguments
+    /// * asset - The asset
+    /// * from - The address repaying the loan
+    /// * tokens_in - The amount of tokens repaid
+    /// * principal - The amount still owed on the loan after the repayment
+    pub fn repay_fixed(e: &Env, asset: Address, from: Address, tokens_in: i128, principal: i128) {
+        let topics = (Symbol::new(e, "repay_fixed"), asset, from);
+        e.events().publish(topics, (tokens_in, principal));
+    }
+
+    /// Emitted when a matured fixed-term loan is rolled over into a new term
+    ///
+    /// - topics - `["roll_fixed_loan", asset: Address, from: Address]`
+    /// - data - `loan: FixedLoan`
+    ///
+    /// ### Arguments
+    /// * asset - The asset
+    /// * from - The address rolling over the loan
+    /// * loan - The fixed-term loan after the roll-over
+    pub fn roll_fixed_loan(e: &Env, asset: Address, from: Address, loan: FixedLoan) {
+        let topics = (Symbol::new(e, "roll_fixed_loan"), asset, from);
+        e.events().publish(topics, loan);
+    }
+
+    /// Emitted when a reserve starts being delisted
+    ///
+    /// - topics - `["delist_reserve", admin: Address, asset: Address]`
+    /// - data - `delisting: ReserveDelisting`
+    ///
+    /// ### Arguments
+    /// * admin - The current admin
+    /// * asset - The underlying asset of the reserve
+    /// * delisting - The delisting schedule of the reserve
+    pub fn delist_reserve(e: &Env, admin: Address, asset: Address, delisting: ReserveDelisting) {
+        let topics = (Symbol::new(e, "delist_reserve"), admin, asset);
+        e.events().publish(topics, delisting);
+    }
+
+    /// Emitted when a user's positions in a delisted reserve are cleaned
+    ///
+    /// - topics - `["clean_delisted_position", asset: Address, user: Address]`
+    /// - data - `[b_tokens_moved: i128, d_tokens_defaulted: i128]`
+    ///
+    /// ### Arguments
+    /// * asset - The underlying asset of the delisted reserve
+    /// * user - The user whose positions were cleaned
+    /// * b_tokens_moved - The collateral b_tokens moved to the user's supply
+    /// * d_tokens_defaulted - The dust d_tokens defaulted on
+    pub fn clean_delisted_position(
+        e: &Env,
+        asset: Address,
+        user: Address,
+        b_tokens_moved: i128,
+        d_tokens_defaulted: i128,
+    ) {
+        let topics = (Symbol::new(e, "clean_delisted_position"), asset, user);
+        e.events()
+            .publish(topics, (b_tokens_moved, d_tokens_defaulted));
+    }
+
+    /// Emitted when a delegator sets a delegate's credit allowance
+    ///
+    /// - topics - `["approve_delegation", delegator: Address, delegate: Address]`
+    /// - data - `[asset: Address, amount: i128, expiration: u64]`
+    ///
+    /// ### Arguments
+    /// * delegator - The address of the user whose positions are borrowed against
+    /// * delegate - The address of the user allowed to borrow
+    /// * asset - The underlying asset the delegate can borrow
+    /// * amount - The amount of underlying tokens the delegate can borrow
+    /// * expiration - The time the allowance expires
+    pub fn approve_delegation(
+        e: &Env,
+        delegator: Address,
+        delegate: Address,
+        asset: Address,
+        amount: i128,
+        expiration: u64,
+    ) {
+        let topics = (Symbol::new(e, "approve_delegation"), delegator, delegate);
+        e.events().publish(topics, (asset, amount, expiration));
+    }
+
+    /// Emitted when a delegate borrows against a delegator's positions
+    ///
+    /// - topics - `["delegated_borrow", asset: Address, delegator: Address, delegate: Address]`
+    /// - data - `[amount: i128, remaining_allowance: i128]`
+    ///
+    /// ### Arguments
+    /// * asset - The underlying asset borrowed
+    /// * delegator - The address of the user whose positions are borrowed against
+    /// * delegate - The address of the user borrowing with the allowance
+    /// * amount - The amount of underlying tokens borrowed
+    /// * remaining_allowance - The amount of underlying tokens the delegate can still borrow
+    pub fn delegated_borrow(
+        e: &Env,
+        asset: Address,
+        delegator: Address,
+        delegate: Address,
+        amount: i128,
+        remaining_allowance: i128,
+    ) {
+        let topics = (
+            Symbol::new(e, "delegated_borrow"),
+            asset,
+            delegator,
+            delegate,
+        );
+        e.events().publish(topics, (amount, remaining_allowance));
+    }
+
+    /// Emitted when a user's positions are moved to another address
+    ///
+    /// - topics - `["transfer_positions", from: Address, to: Address]`
+    /// - data - `reserves: Vec<Address>`
+    ///
+    /// ### Arguments
+    /// * from - The address of the user whose positions were moved
+    /// * to - The address of the user receiving the positions
+    /// * reserves - The underlying assets of the reserves positions were moved in
+    pub fn transfer_positions(e: &Env, from: Address, to: Address, reserves: Vec<Address>) {
+        let topics = (Symbol::new(e, "transfer_positions"), from, to);
+        e.events().publish(topics, reserves);
+    }
+
+    /// Emitted when a user registers a signing key for off-chain requests
+    ///
+    /// - topics - `["set_signing_key", user: Address]`
+    /// - data - `signing_key: BytesN<32>`
+    ///
+    /// ### Arguments
+    /// * user - The address of the user
+    /// * signing_key - The ed25519 public key
+    pub fn set_signing_key(e: &Env, user: Address, signing_key: BytesN<32>) {
+        let topics = (Symbol::new(e, "set_signing_key"), user);
+        e.events().publish(topics, signing_key);
+    }
+
+    /// Emitted when a user cancels a signed request
+    ///
+    /// - topics - `["cancel_signed", user: Address]`
+    /// - data - `nonce: u64`
+    ///
+    /// ### Arguments
+    /// * user - The address of the user
+    /// * nonce - The nonce of the cancelled signed request
+    pub fn cancel_signed(e: &Env, user: Address, nonce: u64) {
+        let topics = (Symbol::new(e, "cancel_signed"), user);
+        e.events().publish(topics, nonce);
+    }
+
+    /// Emitted when a relayer submits a signed request
+    ///
+    /// - topics - `["submit_signed", from: Address, relayer: Address]`
+    /// - data - `[nonce: u64, fee_asset: Address, fee: i128]`
+    ///
+    /// ### Arguments
+    /// * from - The address of the user who signed the request
+    /// * relayer - The address that submitted the request and received the fee
+    /// * nonce - The nonce of the signed request
+    /// * fee_asset - The token the relayer fee was paid in
+    /// * fee - The fee paid to the relayer
+    pub fn submit_signed(
+        e: &Env,
+        from: Address,
+        relayer: Address,
+        nonce: u64,
+        fee_asset: Address,
+        fee: i128,
+    ) {
+        let topics = (Symbol::new(e, "submit_signed"), from, relayer);
+        e.events().publish(topics, (nonce, fee_asset, fee));
+    }
+
+    /// Emitted when a user registers a conditional order
+    ///
+    /// - topics - `["set_order", user: Address, order_id: u32]`
+    /// - data - `order: ConditionalOrder`
+    ///
+    /// ### Arguments
+    /// * user - The address of the user
+    /// * order_id - The id of the order
+    /// * order - The conditional order
+    pub fn set_order(e: &Env, user: Address, order_id: u32, order: ConditionalOrder) {
+        let topics = (Symbol::new(e, "set_order"), user, order_id);
+        e.events().publish(topics, order);
+    }
+
+    /// Emitted when a user cancels a conditional order
+    ///
+    /// - topics - `["cancel_order", user: Address, order_id: u32]`
+    /// - data - `()`
+    ///
+    /// ### Arguments
+    /// * user - The address of the user
+    /// * order_id - The id of the order
+    pub fn cancel_order(e: &Env, user: Address, order_id: u32) {
+        let topics = (Symbol::new(e, "cancel_order"), user, order_id);
+        e.events().publish(topics, ());
+    }
+
+    /// Emitted when a keeper executes a conditional order
+    ///
+    /// - topics - `["execute_order", user: Address, keeper: Address]`
+    /// - data - `[order_id: u32, fee_asset: Address, fee: i128]`
+    ///
+    /// ### Arguments
+    /// * user - The address of the user who registered the order
+    /// * keeper - The address that executed the order and received the fee
+    /// * order_id - The id of the order
+    /// * fee_asset - The token the keeper fee was paid in
+    /// * fee - The fee paid to the keeper
+    pub fn execute_order(
+        e: &Env,
+        user: Address,
+        keeper: Address,
+        order_id: u32,
+        fee_asset: Address,
+        fee: i128,
+    ) {
+        let topics = (Symbol::new(e, "execute_order"), user, keeper);
+        e.events().publish(topics, (order_id, fee_asset, fee));
+    }
 }
@@ -4,7 +4,7 @@
 extern crate std;
 
 #[cfg(any(test, feature = "testutils"))]
-pub use pool::{Pool as PoolState, PositionData, Reserve};
+pub use pool::{Pool as PoolState, PositionData, Reserve, UserHealth};
 
 mod auctions;
 mod constants;
@@ -18,12 +18,16 @@ mod storage;
 mod testutils;
 mod validator;
 
-pub use auctions::{AuctionData, AuctionType};
+pub use auctions::{AuctionData, AuctionEntry, AuctionParams, AuctionType};
 pub use contract::*;
 pub use emissions::ReserveEmissionMetadata;
 pub use errors::PoolError;
-pub use pool::{FlashLoan, Positions, Request, RequestType};
+pub use pool::{
+    FlashLoan, OrderCondition, Positions, Request, RequestType, SignedSubmit, SubmitSimulation,
+};
 pub use storage::{
-    AuctionKey, PoolConfig, PoolDataKey, PoolEmissionConfig, ReserveConfig, ReserveData,
-    ReserveEmissionData, UserEmissionData, UserReserveKey,
+    AuctionCurve, AuctionIndexKey, AuctionKey, ConditionalOrder, CreditAllowance, DelegationKey,
+    EModeCategory, FixedLoan, KeeperConfig, NonceKey, OracleConfig, PoolConfig, PoolDataKey,
+    PoolEmissionConfig, QueuedAuctionInit, QueuedEModeInit, ReserveConfig, ReserveData,
+    ReserveDelisting, ReserveEmissionData, ReserveFactorRamp, UserEmissionData, UserReserveKey,
 };
@@ -1,9 +1,14 @@
+use sep_41_token::TokenClient;
 use soroban_sdk::Map;
-use soroban_sdk::{contracttype, panic_with_error, Address, Env, Vec};
+use soroban_sdk::{contracttype, panic_with_error, unwrap::UnwrapOptimized, Address, Env, Vec};
 
+use crate::constants::FIXED_LOAN_TERM;
+use crate::dependencies::SwapRouterClient;
 use crate::events::PoolEvents;
-use crate::{auctions, errors::PoolError, validator::require_nonnegative};
+use crate::storage::FixedLoan;
+use crate::{auctions, errors::PoolError, storage, validator::require_nonnegative};
 
+use super::fixed_loan::{quote_fixed_rate, settle_fixed_loan};
 use super::pool::Pool;
 use super::User;
 
@@ -30,6 +35,11 @@ pub enum RequestType {
     FillBadDebtAuction = 7,
     FillInterestAuction = 8,
     DeleteLiquidationAuction = 9,
+    SetEMode = 10,
+    BorrowFixed = 11,
+    RepayFixed = 12,
+    RollFixedLoan = 13,
+    Deleverage = 14,
 }
 
 impl RequestType {
@@ -49,6 +59,11 @@ impl RequestType {
             7 => RequestType::FillBadDebtAuction,
             8 => RequestType::FillInterestAuction,
             9 => RequestType::DeleteLiquidationAuction,
+            10 => RequestType::SetEMode,
+            11 => RequestType::BorrowFixed,
+            12 => RequestType::RepayFixed,
+            13 => RequestType::RollFixedLoan,
+            14 => RequestType::Deleverage,
             _ => panic_with_error!(e, PoolError::BadRequest),
         }
     }
@@ -57,14 +72,15 @@ impl RequestType {
 #[contracttype]
 pub struct FlashLoan {
     pub contract: Address,
-    pub asset: Address,
-    pub amount: i128,
+    pub assets: Vec<(Address, i128)>, // the (asset, amount) pairs to flash loan
 }
 
 /// Transfer actions to be taken by the sender and pool
 pub struct Actions {
     pub spender_transfer: Map<Address, i128>,
     pub pool_transfer: Map<Address, i128>,
+    // the (collateral, liability, amount_in, min_out) swaps of deleverage requests
+    pub swaps: Vec<(Address, Address, i128, i128)>,
     pub check_health: bool,
 }
 
@@ -74,6 +90,7 @@ impl Actions {
         Actions {
             spender_transfer: Map::new(e),
             pool_transfer: Map::new(e),
+            swaps: Vec::new(e),
             check_health: false,
         }
     }
@@ -94,6 +111,18 @@ impl Actions {
         );
     }
 
+    /// Add a swap of collateral for a liability asset the pool needs to make through the router
+    pub fn add_swap(
+        &mut self,
+        collateral: &Address,
+        liability: &Address,
+        amount_in: i128,
+        min_out: i128,
+    ) {
+        self.swaps
+            .push_back((collateral.clone(), liability.clone(), amount_in, min_out));
+    }
+
     // just a simple flag since we won't need
     // to switch it back to false once set to true.
     pub fn do_check_health(&mut self) {
@@ -124,10 +153,32 @@ pub fn build_actions_from_request(
     requests: Vec<Request>,
 ) -> Actions {
     let mut actions = Actions::new(e);
+    // the collateral asset and amount withdrawn by a deleverage request, to be swapped and repaid
+    // by the request that follows it
+    let mut deleverage: Option<(Address, i128)> = None;
     for request in requests.iter() {
         // verify the request is allowed
         require_nonnegative(e, &request.amount);
         pool.require_action_allowed(e, request.request_type);
+        if let Some((collateral, amount_in)) = deleverage.take() {
+            let (tokens_in, d_tokens_burnt) = apply_deleverage_repay(
+                e,
+                &mut actions,
+                pool,
+                from_state,
+                &collateral,
+                amount_in,
+                &request,
+            );
+            PoolEvents::repay(
+                e,
+                request.address.clone(),
+                from_state.address.clone(),
+                tokens_in,
+                d_tokens_burnt,
+            );
+            continue;
+        }
         match RequestType::from_u32(e, request.request_type) {
             RequestType::Supply => {
                 let b_tokens_minted = apply_supply(e, &mut actions, pool, from_state, &request);
@@ -259,8 +310,61 @@ pub fn build_actions_from_request(
                 actions.do_check_health();
                 PoolEvents::delete_liquidation_auction(e, from_state.address.clone());
             }
+            RequestType::SetEMode => {
+                // Note: request address is ignored, and the amount is the e-mode category id
+                let category_id = apply_set_emode(e, &mut actions, from_state, &request);
+                PoolEvents::set_emode(e, from_state.address.clone(), category_id);
+            }
+            RequestType::BorrowFixed => {
+                let loan = apply_borrow_fixed(e, &mut actions, pool, from_state, &request);
+                PoolEvents::borrow_fixed(
+                    e,
+                    request.address.clone(),
+                    from_state.address.clone(),
+                    request.amount,
+                    loan,
+                );
+            }
+            RequestType::RepayFixed => {
+                let (tokens_in, principal) =
+                    apply_repay_fixed(e, &mut actions, pool, from_state, &request);
+                PoolEvents::repay_fixed(
+                    e,
+                    request.address.clone(),
+                    from_state.address.clone(),
+                    tokens_in,
+                    principal,
+                );
+            }
+            RequestType::RollFixedLoan => {
+                // Note: request amount is ignored
+                let loan = apply_roll_fixed_loan(e, &mut actions, pool, from_state, &request);
+                PoolEvents::roll_fixed_loan(
+                    e,
+                    request.address.clone(),
+                    from_state.address.clone(),
+                    loan,
+                );
+            }
+            RequestType::Deleverage => {
+                // Note: the following request must be a repay of the liability to swap the
+                // collateral for, where the amount is the minimum output of the swap
+                let (tokens_out, b_tokens_burnt) =
+                    remove_collateral(e, &mut actions, pool, from_state, &request);
+                PoolEvents::withdraw_collateral(
+                    e,
+                    request.address.clone(),
+                    from_state.address.clone(),
+                    tokens_out,
+                    b_tokens_burnt,
+                );
+                deleverage = Some((request.address.clone(), tokens_out));
+            }
         }
     }
+    if deleverage.is_some() {
+        panic_with_error!(e, PoolError::InvalidDeleverage);
+    }
 
     actions
 }
@@ -282,6 +386,7 @@ fn apply_supply(
     let b_tokens_minted = reserve.to_b_token_down(e, request.amount);
     user.add_supply(e, &mut reserve, b_tokens_minted);
     actions.add_for_spender_transfer(&reserve.asset, request.amount);
+    reserve.require_below_supply_cap(e);
     pool.cache_reserve(reserve);
     b_tokens_minted
 }
@@ -332,6 +437,7 @@ fn apply_supply_collateral(
     if reserve.total_supply(e) > reserve.config.collateral_cap {
         panic_with_error!(e, PoolError::ExceededCollateralCap);
     }
+    reserve.require_below_supply_cap(e);
     pool.cache_reserve(reserve);
     b_tokens_minted
 }
@@ -347,6 +453,22 @@ fn apply_withdraw_collateral(
     pool: &mut Pool,
     user: &mut User,
     request: &Request,
+) -> (i128, i128) {
+    let (tokens_out, to_burn) = remove_collateral(e, actions, pool, user, request);
+    actions.add_for_pool_transfer(&request.address, tokens_out);
+    (tokens_out, to_burn)
+}
+
+/// Remove collateral from the user for a "withdraw collateral" or "deleverage" request, without
+/// transferring the tokens out of the pool
+///
+/// Returns the amount of tokens removed and b_tokens burnt
+fn remove_collateral(
+    e: &Env,
+    actions: &mut Actions,
+    pool: &mut Pool,
+    user: &mut User,
+    request: &Request,
 ) -> (i128, i128) {
     let mut reserve = pool.load_reserve(e, &request.address, true);
     let cur_b_tokens = user.get_collateral(reserve.config.index);
@@ -357,7 +479,6 @@ fn apply_withdraw_collateral(
         tokens_out = reserve.to_asset_from_b_token(e, cur_b_tokens);
     }
     user.remove_collateral(e, &mut reserve, to_burn);
-    actions.add_for_pool_transfer(&reserve.asset, tokens_out);
     actions.do_check_health();
     pool.cache_reserve(reserve);
     (tokens_out, to_burn)
@@ -380,6 +501,7 @@ fn apply_borrow(
     let d_tokens_minted = reserve.to_d_token_up(e, request.amount);
     user.add_liabilities(e, &mut reserve, d_tokens_minted);
     reserve.require_utilization_below_max(e);
+    reserve.require_below_borrow_cap(e);
     actions.add_for_pool_transfer(&reserve.asset, request.amount);
     actions.do_check_health();
     pool.cache_reserve(reserve);
@@ -419,17 +541,235 @@ fn apply_repay(
     }
 }
 
+/// Apply the "repay" request that follows a "deleverage" request to the pool
+///
+/// Repays the liability with the minimum output of the swap, and queues a swap of the collateral
+/// removed by the deleverage request for the liability asset. The swap is executed through the
+/// pool's swap router by `execute_swaps` once the submit has been validated. Any tokens left over
+/// after the liability is fully repaid are sent to "to".
+///
+/// Returns the repayment amount and d_tokens_burnt
+///
+/// ### Panics
+/// If the request is not a repay, no router is set, or the assets are the same
+fn apply_deleverage_repay(
+    e: &Env,
+    actions: &mut Actions,
+    pool: &mut Pool,
+    user: &mut User,
+    collateral: &Address,
+    amount_in: i128,
+    request: &Request,
+) -> (i128, i128) {
+    if request.request_type != RequestType::Repay as u32
+        || collateral == &request.address
+        || storage::get_router(e).is_none()
+    {
+        panic_with_error!(e, PoolError::InvalidDeleverage);
+    }
+    let mut reserve = pool.load_reserve(e, &request.address, true);
+    let cur_d_tokens = user.get_liabilities(reserve.config.index);
+    let mut d_tokens_burnt = reserve.to_d_token_down(e, request.amount);
+    let mut repayment_amount = request.amount;
+    if d_tokens_burnt > cur_d_tokens {
+        d_tokens_burnt = cur_d_tokens;
+        repayment_amount = reserve.to_asset_from_d_token(e, cur_d_tokens);
+        actions.add_for_pool_transfer(&reserve.asset, request.amount - repayment_amount);
+    }
+    user.remove_liabilities(e, &mut reserve, d_tokens_burnt);
+    actions.add_swap(collateral, &reserve.asset, amount_in, request.amount);
+    pool.cache_reserve(reserve);
+    (repayment_amount, d_tokens_burnt)
+}
+
+/// Execute the swaps queued by deleverage requests through the pool's swap router. Any tokens
+/// received over the minimum output of a swap are added to the pool's transfers to "to".
+///
+/// This must only be called after the submit has been validated, as it moves the pool's tokens.
+///
+/// ### Arguments
+/// * actions - The actions built from the requests
+/// * from - The address whose position was deleveraged
+///
+/// ### Panics
+/// If a swap does not return at least its minimum output
+pub fn execute_swaps(e: &Env, actions: &mut Actions, from: &Address) {
+    if actions.swaps.is_empty() {
+        return;
+    }
+    let router = storage::get_router(e).unwrap_optimized();
+    let pool_address = e.current_contract_address();
+    for (collateral, liability, amount_in, min_out) in actions.swaps.iter() {
+        let token_out = TokenClient::new(e, &liability);
+        let pre_balance = token_out.balance(&pool_address);
+        TokenClient::new(e, &collateral).transfer(&pool_address, &router, &amount_in);
+        SwapRouterClient::new(e, &router).swap(
+            &collateral,
+            &liability,
+            &amount_in,
+            &min_out,
+            &pool_address,
+        );
+        let amount_out = token_out.balance(&pool_address) - pre_balance;
+        if amount_out < min_out {
+            panic_with_error!(e, PoolError::InvalidDeleverage);
+        }
+        if amount_out > min_out {
+            actions.add_for_pool_transfer(&liability, amount_out - min_out);
+        }
+        PoolEvents::deleverage(
+            e,
+            collateral,
+            liability,
+            from.clone(),
+            amount_in,
+            amount_out,
+        );
+    }
+}
+
+/// Apply a "set e-mode" request to the user
+///
+/// Sets the user's e-mode category and flags the user for a health check. A category id
+/// of 0 disables e-mode for the user.
+///
+/// Returns the category id the user entered
+fn apply_set_emode(e: &Env, actions: &mut Actions, user: &mut User, request: &Request) -> u32 {
+    if request.amount > u32::MAX as i128 {
+        panic_with_error!(e, PoolError::InvalidEModeCategory);
+    }
+    let category_id = request.amount as u32;
+    if category_id != 0 && storage::get_emode_category(e, category_id).is_none() {
+        panic_with_error!(e, PoolError::InvalidEModeCategory);
+    }
+    // fixed-term loans are valued with the reserve's liability factor, so they cannot be held in e-mode
+    if category_id != 0 && !user.fixed_loans.is_empty() {
+        panic_with_error!(e, PoolError::InvalidFixedLoan);
+    }
+    user.emode = category_id;
+    actions.do_check_health();
+    category_id
+}
+
+/// Apply a "borrow fixed" request to the pool
+///
+/// Opens a fixed-term loan at a rate quoted from the reserve's current borrow rate after the
+/// borrow. A user can hold one fixed-term loan per reserve, and cannot hold fixed-term loans
+/// while in e-mode.
+///
+/// Appends any necessary actions to the actions list, updates the user and pool's state
+///
+/// Returns the new fixed-term loan
+fn apply_borrow_fixed(
+    e: &Env,
+    actions: &mut Actions,
+    pool: &mut Pool,
+    user: &mut User,
+    request: &Request,
+) -> FixedLoan {
+    let mut reserve = pool.load_reserve(e, &request.address, true);
+    reserve.require_action_allowed(e, request.request_type);
+    if request.amount == 0 || user.emode != 0 || user.fixed_loans.contains_key(reserve.config.index)
+    {
+        panic_with_error!(e, PoolError::InvalidFixedLoan);
+    }
+    reserve.data.fixed_debt += request.amount;
+    reserve.require_utilization_below_max(e);
+    reserve.require_below_borrow_cap(e);
+    let now = e.ledger().timestamp();
+    let loan = FixedLoan {
+        principal: request.amount,
+        rate: quote_fixed_rate(e, &reserve),
+        last_time: now,
+        maturity: now + FIXED_LOAN_TERM,
+    };
+    user.fixed_loans.set(reserve.config.index, loan.clone());
+    actions.add_for_pool_transfer(&reserve.asset, request.amount);
+    actions.do_check_health();
+    pool.cache_reserve(reserve);
+    loan
+}
+
+/// Apply a "repay fixed" request to the pool
+///
+/// Settles the interest accrued on the fixed-term loan before repaying it. Any amount over
+/// what is owed is refunded.
+///
+/// Appends any necessary actions to the actions list, updates the user and pool's state
+///
+/// Returns the repayment amount and the principal remaining on the loan
+fn apply_repay_fixed(
+    e: &Env,
+    actions: &mut Actions,
+    pool: &mut Pool,
+    user: &mut User,
+    request: &Request,
+) -> (i128, i128) {
+    let mut reserve = pool.load_reserve(e, &request.address, true);
+    let mut loan = match user.fixed_loans.get(reserve.config.index) {
+        Some(loan) => loan,
+        None => panic_with_error!(e, PoolError::InvalidFixedLoan),
+    };
+    settle_fixed_loan(e, &mut reserve, pool.config.bstop_rate, &mut loan);
+    let repayment_amount = request.amount.min(loan.principal);
+    actions.add_for_spender_transfer(&reserve.asset, request.amount);
+    if request.amount > repayment_amount {
+        actions.add_for_pool_transfer(&reserve.asset, request.amount - repayment_amount);
+    }
+    loan.principal -= repayment_amount;
+    reserve.data.fixed_debt -= repayment_amount;
+    if loan.principal == 0 {
+        user.fixed_loans.remove(reserve.config.index);
+    } else {
+        user.fixed_loans.set(reserve.config.index, loan.clone());
+    }
+    pool.cache_reserve(reserve);
+    (repayment_amount, loan.principal)
+}
+
+/// Apply a "roll fixed loan" request to the pool
+///
+/// Settles the interest accrued on a matured fixed-term loan, and locks a newly quoted rate
+/// for another term.
+///
+/// Appends any necessary actions to the actions list, updates the user and pool's state
+///
+/// Returns the rolled over fixed-term loan
+fn apply_roll_fixed_loan(
+    e: &Env,
+    actions: &mut Actions,
+    pool: &mut Pool,
+    user: &mut User,
+    request: &Request,
+) -> FixedLoan {
+    let mut reserve = pool.load_reserve(e, &request.address, true);
+    reserve.require_action_allowed(e, request.request_type);
+    let now = e.ledger().timestamp();
+    let mut loan = match user.fixed_loans.get(reserve.config.index) {
+        Some(loan) if loan.maturity <= now => loan,
+        _ => panic_with_error!(e, PoolError::InvalidFixedLoan),
+    };
+    settle_fixed_loan(e, &mut reserve, pool.config.bstop_rate, &mut loan);
+    loan.rate = quote_fixed_rate(e, &reserve);
+    loan.maturity = now + FIXED_LOAN_TERM;
+    user.fixed_loans.set(reserve.config.index, loan.clone());
+    actions.do_check_health();
+    pool.cache_reserve(reserve);
+    loan
+}
+
 #[cfg(test)]
 mod tests {
 
     use crate::{
         constants::SCALAR_7,
-        storage::{self, PoolConfig},
+        storage::{EModeCategory, PoolConfig},
         testutils::{self, create_comet_lp_pool, create_pool},
         AuctionData, AuctionType, Positions,
     };
 
     use super::*;
+    use sep_41_token::testutils::MockTokenClient;
     use soroban_sdk::{
         map,
         testutils::{Address as _, Ledger, LedgerInfo},
@@ -1934,8 +2274,8 @@ mod tests {
     }
 
     #[test]
-    #[should_panic(expected = "Error(Contract, #1223)")]
-    fn test_build_actions_panic_borrow_disabled_asset() {
+    #[should_panic(expected = "Error(Contract, #1226)")]
+    fn test_exceed_supply_cap_supply_collateral() {
         let e = Env::default();
         e.mock_all_auths();
 
@@ -1945,7 +2285,7 @@ mod tests {
 
         let (underlying, _) = testutils::create_token_contract(&e, &bombadil);
         let (mut reserve_config, reserve_data) = testutils::default_reserve_meta();
-        reserve_config.enabled = false;
+        reserve_config.supply_cap = 110_0000000; // Set low supply cap
         testutils::create_reserve(&e, &pool, &underlying, &reserve_config, &reserve_data);
 
         let pool_config = PoolConfig {
@@ -1959,24 +2299,24 @@ mod tests {
         let requests = vec![
             &e,
             Request {
-                request_type: RequestType::Borrow as u32,
+                request_type: RequestType::SupplyCollateral as u32,
                 address: underlying.clone(),
-                amount: 20_0000000,
+                amount: 20_0000000, // Try to supply more than cap, with 100 already supplied
             },
         ];
 
         e.as_contract(&pool, || {
             storage::set_pool_config(&e, &pool_config);
             let mut pool = Pool::load(&e);
-            let mut user = User::load(&e, &samwise);
 
+            let mut user = User::load(&e, &samwise);
             build_actions_from_request(&e, &mut pool, &mut user, requests);
         });
     }
 
     #[test]
-    #[should_panic(expected = "Error(Contract, #1223)")]
-    fn test_build_actions_panic_supply_disabled_asset() {
+    #[should_panic(expected = "Error(Contract, #1226)")]
+    fn test_exceed_supply_cap_supply() {
         let e = Env::default();
         e.mock_all_auths();
 
@@ -1986,7 +2326,7 @@ mod tests {
 
         let (underlying, _) = testutils::create_token_contract(&e, &bombadil);
         let (mut reserve_config, reserve_data) = testutils::default_reserve_meta();
-        reserve_config.enabled = false;
+        reserve_config.supply_cap = 110_0000000; // Set low supply cap
         testutils::create_reserve(&e, &pool, &underlying, &reserve_config, &reserve_data);
 
         let pool_config = PoolConfig {
@@ -1997,21 +2337,886 @@ mod tests {
             max_positions: 1,
         };
 
+        let requests = vec![
+            &e,
+            Request {
+                request_type: RequestType::Supply as u32,
+                address: underlying.clone(),
+                amount: 20_0000000, // Try to supply more than cap, with 100 already supplied
+            },
+        ];
+
+        e.as_contract(&pool, || {
+            storage::set_pool_config(&e, &pool_config);
+            let mut pool = Pool::load(&e);
+
+            let mut user = User::load(&e, &samwise);
+            build_actions_from_request(&e, &mut pool, &mut user, requests);
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "Error(Contract, #1225)")]
+    fn test_exceed_borrow_cap() {
+        let e = Env::default();
+        e.mock_all_auths();
+
+        let bombadil = Address::generate(&e);
+        let samwise = Address::generate(&e);
+        let pool = testutils::create_pool(&e);
+
+        let (underlying, _) = testutils::create_token_contract(&e, &bombadil);
+        let (mut reserve_config, reserve_data) = testutils::default_reserve_meta();
+        reserve_config.borrow_cap = 80_0000000; // Set low borrow cap
+        testutils::create_reserve(&e, &pool, &underlying, &reserve_config, &reserve_data);
+
+        let pool_config = PoolConfig {
+            oracle: Address::generate(&e),
+            min_collateral: 1_0000000,
+            bstop_rate: 0_2000000,
+            status: 0,
+            max_positions: 2,
+        };
+
         let requests = vec![
             &e,
             Request {
                 request_type: RequestType::SupplyCollateral as u32,
                 address: underlying.clone(),
-                amount: 20_0000000,
+                amount: 100_0000000,
+            },
+            Request {
+                request_type: RequestType::Borrow as u32,
+                address: underlying.clone(),
+                amount: 5_0000001, // Try to borrow more than cap
             },
         ];
 
         e.as_contract(&pool, || {
             storage::set_pool_config(&e, &pool_config);
             let mut pool = Pool::load(&e);
+
+            let mut user = User::load(&e, &samwise);
+            build_actions_from_request(&e, &mut pool, &mut user, requests);
+        });
+    }
+
+    #[test]
+    fn test_build_actions_from_request_set_emode() {
+        let e = Env::default();
+        e.mock_all_auths();
+
+        let bombadil = Address::generate(&e);
+        let samwise = Address::generate(&e);
+        let pool = testutils::create_pool(&e);
+
+        let (underlying, _) = testutils::create_token_contract(&e, &bombadil);
+        let (reserve_config, reserve_data) = testutils::default_reserve_meta();
+        testutils::create_reserve(&e, &pool, &underlying, &reserve_config, &reserve_data);
+
+        let pool_config = PoolConfig {
+            oracle: Address::generate(&e),
+            min_collateral: 1_0000000,
+            bstop_rate: 0_2000000,
+            status: 0,
+            max_positions: 2,
+        };
+        let category = EModeCategory {
+            c_factor: 0_9500000,
+            l_factor: 0_9500000,
+            assets: vec![&e, underlying.clone()],
+        };
+
+        e.as_contract(&pool, || {
+            storage::set_pool_config(&e, &pool_config);
+            storage::set_emode_category(&e, 1, &category);
+            let mut pool = Pool::load(&e);
             let mut user = User::load(&e, &samwise);
 
+            let requests = vec![
+                &e,
+                Request {
+                    request_type: RequestType::SetEMode as u32,
+                    address: underlying.clone(),
+                    amount: 1,
+                },
+            ];
+            let actions = build_actions_from_request(&e, &mut pool, &mut user, requests);
+            assert_eq!(actions.check_health, true);
+            assert_eq!(actions.spender_transfer.len(), 0);
+            assert_eq!(actions.pool_transfer.len(), 0);
+            assert_eq!(user.emode, 1);
+
+            let requests = vec![
+                &e,
+                Request {
+                    request_type: RequestType::SetEMode as u32,
+                    address: underlying.clone(),
+                    amount: 0,
+                },
+            ];
             build_actions_from_request(&e, &mut pool, &mut user, requests);
+            assert_eq!(user.emode, 0);
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "Error(Contract, #1229)")]
+    fn test_build_actions_from_request_set_emode_invalid_category() {
+        let e = Env::default();
+        e.mock_all_auths();
+
+        let bombadil = Address::generate(&e);
+        let samwise = Address::generate(&e);
+        let pool = testutils::create_pool(&e);
+
+        let (underlying, _) = testutils::create_token_contract(&e, &bombadil);
+        let (reserve_config, reserve_data) = testutils::default_reserve_meta();
+        testutils::create_reserve(&e, &pool, &underlying, &reserve_config, &reserve_data);
+
+        let pool_config = PoolConfig {
+            oracle: Address::generate(&e),
+            min_collateral: 1_0000000,
+            bstop_rate: 0_2000000,
+            status: 0,
+            max_positions: 2,
+        };
+
+        let requests = vec![
+            &e,
+            Request {
+                request_type: RequestType::SetEMode as u32,
+                address: underlying.clone(),
+                amount: 2,
+            },
+        ];
+
+        e.as_contract(&pool, || {
+            storage::set_pool_config(&e, &pool_config);
+            let mut pool = Pool::load(&e);
+            let mut user = User::load(&e, &samwise);
+
+            build_actions_from_request(&e, &mut pool, &mut user, requests);
+        });
+    }
+
+    /***** fixed-term loans *****/
+
+    #[test]
+    fn test_build_actions_from_request_borrow_fixed() {
+        let e = Env::default();
+        e.mock_all_auths();
+
+        let bombadil = Address::generate(&e);
+        let samwise = Address::generate(&e);
+        let pool = testutils::create_pool(&e);
+
+        let (underlying, _) = testutils::create_token_contract(&e, &bombadil);
+        let (reserve_config, mut reserve_data) = testutils::default_reserve_meta();
+        reserve_data.last_time = 600;
+        testutils::create_reserve(&e, &pool, &underlying, &reserve_config, &reserve_data);
+        e.ledger().set(LedgerInfo {
+            timestamp: 600,
+            protocol_version: 22,
+            sequence_number: 1234,
+            network_id: Default::default(),
+            base_reserve: 10,
+            min_temp_entry_ttl: 10,
+            min_persistent_entry_ttl: 10,
+            max_entry_ttl: 3110400,
+        });
+        let pool_config = PoolConfig {
+            oracle: Address::generate(&e),
+            min_collateral: 1_0000000,
+            bstop_rate: 0_2000000,
+            status: 0,
+            max_positions: 2,
+        };
+        e.as_contract(&pool, || {
+            storage::set_pool_config(&e, &pool_config);
+
+            let mut pool = Pool::load(&e);
+
+            let requests = vec![
+                &e,
+                Request {
+                    request_type: RequestType::BorrowFixed as u32,
+                    address: underlying.clone(),
+                    amount: 10_0000000,
+                },
+            ];
+            let mut user = User::load(&e, &samwise);
+            let actions = build_actions_from_request(&e, &mut pool, &mut user, requests);
+
+            assert_eq!(actions.check_health, true);
+
+            let spender_transfer = actions.spender_transfer;
+            let pool_transfer = actions.pool_transfer;
+            assert_eq!(spender_transfer.len(), 0);
+            assert_eq!(pool_transfer.len(), 1);
+            assert_eq!(pool_transfer.get_unchecked(underlying.clone()), 10_0000000);
+
+            assert_eq!(user.positions.liabilities.len(), 0);
+            assert_eq!(user.fixed_loans.len(), 1);
+            assert_eq!(user.has_liabilities(), true);
+            let loan = user.fixed_loans.get_unchecked(0);
+            assert_eq!(loan.principal, 10_0000000);
+            // utilization is 85% after the borrow
+            assert_eq!(loan.rate, 0_3200000);
+            assert_eq!(loan.last_time, 600);
+            assert_eq!(loan.maturity, 600 + FIXED_LOAN_TERM);
+
+            let reserve = pool.load_reserve(&e, &underlying, false);
+            assert_eq!(reserve.data.fixed_debt, 10_0000000);
+            assert_eq!(reserve.data.d_supply, reserve_data.d_supply);
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "Error(Contract, #1230)")]
+    fn test_build_actions_from_request_borrow_fixed_existing_loan() {
+        let e = Env::default();
+        e.mock_all_auths();
+
+        let bombadil = Address::generate(&e);
+        let samwise = Address::generate(&e);
+        let pool = testutils::create_pool(&e);
+
+        let (underlying, _) = testutils::create_token_contract(&e, &bombadil);
+        let (reserve_config, mut reserve_data) = testutils::default_reserve_meta();
+        reserve_data.fixed_debt = 1_0000000;
+        testutils::create_reserve(&e, &pool, &underlying, &reserve_config, &reserve_data);
+
+        let pool_config = PoolConfig {
+            oracle: Address::generate(&e),
+            min_collateral: 1_0000000,
+            bstop_rate: 0_2000000,
+            status: 0,
+            max_positions: 2,
+        };
+        let fixed_loans = map![
+            &e,
+            (
+                0,
+                FixedLoan {
+                    principal: 1_0000000,
+                    rate: 0_0700000,
+                    last_time: 0,
+                    maturity: FIXED_LOAN_TERM,
+                }
+            )
+        ];
+        e.as_contract(&pool, || {
+            storage::set_pool_config(&e, &pool_config);
+            storage::set_user_fixed_loans(&e, &samwise, &fixed_loans);
+
+            let mut pool = Pool::load(&e);
+
+            let requests = vec![
+                &e,
+                Request {
+                    request_type: RequestType::BorrowFixed as u32,
+                    address: underlying.clone(),
+                    amount: 1_0000000,
+                },
+            ];
+            let mut user = User::load(&e, &samwise);
+            build_actions_from_request(&e, &mut pool, &mut user, requests);
+        });
+    }
+
+    #[test]
+    fn test_build_actions_from_request_repay_fixed_over_balance() {
+        let e = Env::default();
+        e.mock_all_auths();
+
+        let bombadil = Address::generate(&e);
+        let samwise = Address::generate(&e);
+        let pool = testutils::create_pool(&e);
+
+        // a tenth of a year after the loan was taken
+        let timestamp = 600 + 31536000 / 10;
+        let (underlying, _) = testutils::create_token_contract(&e, &bombadil);
+        let (reserve_config, mut reserve_data) = testutils::default_reserve_meta();
+        reserve_data.fixed_debt = 10_0000000;
+        reserve_data.last_time = timestamp;
+        testutils::create_reserve(&e, &pool, &underlying, &reserve_config, &reserve_data);
+        e.ledger().set(LedgerInfo {
+            timestamp,
+            protocol_version: 22,
+            sequence_number: 1234,
+            network_id: Default::default(),
+            base_reserve: 10,
+            min_temp_entry_ttl: 10,
+            min_persistent_entry_ttl: 10,
+            max_entry_ttl: 3110400,
+        });
+        let pool_config = PoolConfig {
+            oracle: Address::generate(&e),
+            min_collateral: 1_0000000,
+            bstop_rate: 0_2000000,
+            status: 0,
+            max_positions: 2,
+        };
+        let fixed_loans = map![
+            &e,
+            (
+                0,
+                FixedLoan {
+                    principal: 10_0000000,
+                    rate: 0_1000000,
+                    last_time: 600,
+                    maturity: 600 + FIXED_LOAN_TERM * 52,
+                }
+            )
+        ];
+        e.as_contract(&pool, || {
+            storage::set_pool_config(&e, &pool_config);
+            storage::set_user_fixed_loans(&e, &samwise, &fixed_loans);
+
+            let mut pool = Pool::load(&e);
+
+            let requests = vec![
+                &e,
+                Request {
+                    request_type: RequestType::RepayFixed as u32,
+                    address: underlying.clone(),
+                    amount: 11_0000000,
+                },
+            ];
+            let mut user = User::load(&e, &samwise);
+            let actions = build_actions_from_request(&e, &mut pool, &mut user, requests);
+
+            assert_eq!(actions.check_health, false);
+
+            let spender_transfer = actions.spender_transfer;
+            let pool_transfer = actions.pool_transfer;
+            assert_eq!(spender_transfer.len(), 1);
+            assert_eq!(
+                spender_transfer.get_unchecked(underlying.clone()),
+                11_0000000
+            );
+            assert_eq!(pool_transfer.len(), 1);
+            assert_eq!(pool_transfer.get_unchecked(underlying.clone()), 0_9000000);
+
+            assert_eq!(user.fixed_loans.len(), 0);
+            assert_eq!(user.has_liabilities(), false);
+
+            let reserve = pool.load_reserve(&e, &underlying, false);
+            assert_eq!(reserve.data.fixed_debt, 0);
+            assert_eq!(reserve.data.backstop_credit, 0_0200000);
+        });
+    }
+
+    #[test]
+    fn test_build_actions_from_request_roll_fixed_loan() {
+        let e = Env::default();
+        e.mock_all_auths();
+
+        let bombadil = Address::generate(&e);
+        let samwise = Address::generate(&e);
+        let pool = testutils::create_pool(&e);
+
+        let timestamp = 600 + FIXED_LOAN_TERM;
+        let (underlying, _) = testutils::create_token_contract(&e, &bombadil);
+        let (reserve_config, mut reserve_data) = testutils::default_reserve_meta();
+        reserve_data.fixed_debt = 10_0000000;
+        reserve_data.last_time = timestamp;
+        testutils::create_reserve(&e, &pool, &underlying, &reserve_config, &reserve_data);
+        e.ledger().set(LedgerInfo {
+            timestamp,
+            protocol_version: 22,
+            sequence_number: 1234,
+            network_id: Default::default(),
+            base_reserve: 10,
+            min_temp_entry_ttl: 10,
+            min_persistent_entry_ttl: 10,
+            max_entry_ttl: 3110400,
+        });
+        let pool_config = PoolConfig {
+            oracle: Address::generate(&e),
+            min_collateral: 1_0000000,
+            bstop_rate: 0_2000000,
+            status: 0,
+            max_positions: 2,
+        };
+        let fixed_loans = map![
+            &e,
+            (
+                0,
+                FixedLoan {
+                    principal: 10_0000000,
+                    rate: 0_1000000,
+                    last_time: 600,
+                    maturity: timestamp,
+                }
+            )
+        ];
+        e.as_contract(&pool, || {
+            storage::set_pool_config(&e, &pool_config);
+            storage::set_user_fixed_loans(&e, &samwise, &fixed_loans);
+
+            let mut pool = Pool::load(&e);
+
+            let requests = vec![
+                &e,
+                Request {
+                    request_type: RequestType::RollFixedLoan as u32,
+                    address: underlying.clone(),
+                    amount: 0,
+                },
+            ];
+            let mut user = User::load(&e, &samwise);
+            let actions = build_actions_from_request(&e, &mut pool, &mut user, requests);
+
+            assert_eq!(actions.check_health, true);
+            assert_eq!(actions.spender_transfer.len(), 0);
+            assert_eq!(actions.pool_transfer.len(), 0);
+
+            let loan = user.fixed_loans.get_unchecked(0);
+            assert_eq!(loan.principal, 10_0767124);
+            assert_eq!(loan.rate, 0_3206135);
+            assert_eq!(loan.last_time, timestamp);
+            assert_eq!(loan.maturity, timestamp + FIXED_LOAN_TERM);
+
+            let reserve = pool.load_reserve(&e, &underlying, false);
+            assert_eq!(reserve.data.fixed_debt, 10_0767124);
+            assert_eq!(reserve.data.backstop_credit, 0_0153424);
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "Error(Contract, #1230)")]
+    fn test_build_actions_from_request_roll_fixed_loan_before_maturity() {
+        let e = Env::default();
+        e.mock_all_auths();
+
+        let bombadil = Address::generate(&e);
+        let samwise = Address::generate(&e);
+        let pool = testutils::create_pool(&e);
+
+        let (underlying, _) = testutils::create_token_contract(&e, &bombadil);
+        let (reserve_config, mut reserve_data) = testutils::default_reserve_meta();
+        reserve_data.fixed_debt = 10_0000000;
+        testutils::create_reserve(&e, &pool, &underlying, &reserve_config, &reserve_data);
+
+        let pool_config = PoolConfig {
+            oracle: Address::generate(&e),
+            min_collateral: 1_0000000,
+            bstop_rate: 0_2000000,
+            status: 0,
+            max_positions: 2,
+        };
+        let fixed_loans = map![
+            &e,
+            (
+                0,
+                FixedLoan {
+                    principal: 10_0000000,
+                    rate: 0_1000000,
+                    last_time: 0,
+                    maturity: FIXED_LOAN_TERM,
+                }
+            )
+        ];
+        e.as_contract(&pool, || {
+            storage::set_pool_config(&e, &pool_config);
+            storage::set_user_fixed_loans(&e, &samwise, &fixed_loans);
+
+            let mut pool = Pool::load(&e);
+
+            let requests = vec![
+                &e,
+                Request {
+                    request_type: RequestType::RollFixedLoan as u32,
+                    address: underlying.clone(),
+                    amount: 0,
+                },
+            ];
+            let mut user = User::load(&e, &samwise);
+            build_actions_from_request(&e, &mut pool, &mut user, requests);
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "Error(Contract, #1230)")]
+    fn test_build_actions_from_request_set_emode_with_fixed_loans() {
+        let e = Env::default();
+        e.mock_all_auths();
+
+        let bombadil = Address::generate(&e);
+        let samwise = Address::generate(&e);
+        let pool = testutils::create_pool(&e);
+
+        let (underlying, _) = testutils::create_token_contract(&e, &bombadil);
+        let (reserve_config, mut reserve_data) = testutils::default_reserve_meta();
+        reserve_data.fixed_debt = 1_0000000;
+        testutils::create_reserve(&e, &pool, &underlying, &reserve_config, &reserve_data);
+
+        let pool_config = PoolConfig {
+            oracle: Address::generate(&e),
+            min_collateral: 1_0000000,
+            bstop_rate: 0_2000000,
+            status: 0,
+            max_positions: 2,
+        };
+        let category = EModeCategory {
+            c_factor: 0_9500000,
+            l_factor: 0_9500000,
+            assets: vec![&e, underlying.clone()],
+        };
+        let fixed_loans = map![
+            &e,
+            (
+                0,
+                FixedLoan {
+                    principal: 1_0000000,
+                    rate: 0_0700000,
+                    last_time: 0,
+                    maturity: FIXED_LOAN_TERM,
+                }
+            )
+        ];
+
+        let requests = vec![
+            &e,
+            Request {
+                request_type: RequestType::SetEMode as u32,
+                address: underlying.clone(),
+                amount: 1,
+            },
+        ];
+
+        e.as_contract(&pool, || {
+            storage::set_pool_config(&e, &pool_config);
+            storage::set_emode_category(&e, 1, &category);
+            storage::set_user_fixed_loans(&e, &samwise, &fixed_loans);
+            let mut pool = Pool::load(&e);
+            let mut user = User::load(&e, &samwise);
+
+            build_actions_from_request(&e, &mut pool, &mut user, requests);
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "Error(Contract, #1223)")]
+    fn test_build_actions_panic_borrow_disabled_asset() {
+        let e = Env::default();
+        e.mock_all_auths();
+
+        let bombadil = Address::generate(&e);
+        let samwise = Address::generate(&e);
+        let pool = testutils::create_pool(&e);
+
+        let (underlying, _) = testutils::create_token_contract(&e, &bombadil);
+        let (mut reserve_config, reserve_data) = testutils::default_reserve_meta();
+        reserve_config.enabled = false;
+        testutils::create_reserve(&e, &pool, &underlying, &reserve_config, &reserve_data);
+
+        let pool_config = PoolConfig {
+            oracle: Address::generate(&e),
+            min_collateral: 1_0000000,
+            bstop_rate: 0_2000000,
+            status: 0,
+            max_positions: 1,
+        };
+
+        let requests = vec![
+            &e,
+            Request {
+                request_type: RequestType::Borrow as u32,
+                address: underlying.clone(),
+                amount: 20_0000000,
+            },
+        ];
+
+        e.as_contract(&pool, || {
+            storage::set_pool_config(&e, &pool_config);
+            let mut pool = Pool::load(&e);
+            let mut user = User::load(&e, &samwise);
+
+            build_actions_from_request(&e, &mut pool, &mut user, requests);
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "Error(Contract, #1223)")]
+    fn test_build_actions_panic_supply_disabled_asset() {
+        let e = Env::default();
+        e.mock_all_auths();
+
+        let bombadil = Address::generate(&e);
+        let samwise = Address::generate(&e);
+        let pool = testutils::create_pool(&e);
+
+        let (underlying, _) = testutils::create_token_contract(&e, &bombadil);
+        let (mut reserve_config, reserve_data) = testutils::default_reserve_meta();
+        reserve_config.enabled = false;
+        testutils::create_reserve(&e, &pool, &underlying, &reserve_config, &reserve_data);
+
+        let pool_config = PoolConfig {
+            oracle: Address::generate(&e),
+            min_collateral: 1_0000000,
+            bstop_rate: 0_2000000,
+            status: 0,
+            max_positions: 1,
+        };
+
+        let requests = vec![
+            &e,
+            Request {
+                request_type: RequestType::SupplyCollateral as u32,
+                address: underlying.clone(),
+                amount: 20_0000000,
+            },
+        ];
+
+        e.as_contract(&pool, || {
+            storage::set_pool_config(&e, &pool_config);
+            let mut pool = Pool::load(&e);
+            let mut user = User::load(&e, &samwise);
+
+            build_actions_from_request(&e, &mut pool, &mut user, requests);
+        });
+    }
+
+    /***** deleverage *****/
+
+    /// Setup a pool with two reserves and a swap router that pays out half of a token for each
+    /// token swapped, where samwise has 20 collateral of the first reserve and 5 liabilities of
+    /// the second
+    fn setup_deleverage(
+        e: &Env,
+        set_router: bool,
+    ) -> (Address, Address, Address, Address, Address) {
+        e.ledger().set(LedgerInfo {
+            timestamp: 600,
+            protocol_version: 22,
+            sequence_number: 1234,
+            network_id: Default::default(),
+            base_reserve: 10,
+            min_temp_entry_ttl: 10,
+            min_persistent_entry_ttl: 10,
+            max_entry_ttl: 3110400,
+        });
+
+        let bombadil = Address::generate(e);
+        let samwise = Address::generate(e);
+        let pool = create_pool(e);
+        let (router, router_client) = testutils::create_mock_swap_router(e);
+
+        let (underlying_0, _) = testutils::create_token_contract(e, &bombadil);
+        let (reserve_config, mut reserve_data) = testutils::default_reserve_meta();
+        reserve_data.last_time = 600;
+        testutils::create_reserve(e, &pool, &underlying_0, &reserve_config, &reserve_data);
+
+        let (underlying_1, underlying_1_client) = testutils::create_token_contract(e, &bombadil);
+        testutils::create_reserve(e, &pool, &underlying_1, &reserve_config, &reserve_data);
+
+        underlying_1_client.mint(&router, &100_0000000);
+        router_client.set_rate(&0_5000000);
+
+        let pool_config = PoolConfig {
+            oracle: Address::generate(e),
+            min_collateral: 1_0000000,
+            bstop_rate: 0_2000000,
+            status: 0,
+            max_positions: 4,
+        };
+        e.as_contract(&pool, || {
+            storage::set_pool_config(e, &pool_config);
+            if set_router {
+                storage::set_router(e, &router);
+            }
+            storage::set_user_positions(
+                e,
+                &samwise,
+                &Positions {
+                    liabilities: map![e, (1, 5_0000000)],
+                    collateral: map![e, (0, 20_0000000)],
+                    supply: map![e],
+                },
+            );
+        });
+        (pool, samwise, router, underlying_0, underlying_1)
+    }
+
+    fn deleverage_requests(
+        e: &Env,
+        underlying_0: &Address,
+        underlying_1: &Address,
+        amount: i128,
+        min_out: i128,
+    ) -> Vec<Request> {
+        vec![
+            e,
+            Request {
+                request_type: RequestType::Deleverage as u32,
+                address: underlying_0.clone(),
+                amount,
+            },
+            Request {
+                request_type: RequestType::Repay as u32,
+                address: underlying_1.clone(),
+                amount: min_out,
+            },
+        ]
+    }
+
+    #[test]
+    fn test_build_actions_from_request_deleverage() {
+        let e = Env::default();
+        e.mock_all_auths();
+        let (pool, samwise, router, underlying_0, underlying_1) = setup_deleverage(&e, true);
+        let underlying_0_client = MockTokenClient::new(&e, &underlying_0);
+
+        e.as_contract(&pool, || {
+            let mut pool_state = Pool::load(&e);
+            let mut user = User::load(&e, &samwise);
+
+            let requests =
+                deleverage_requests(&e, &underlying_0, &underlying_1, 6_0000000, 2_9000000);
+            let actions = build_actions_from_request(&e, &mut pool_state, &mut user, requests);
+
+            assert_eq!(actions.check_health, true);
+            assert_eq!(actions.spender_transfer.len(), 0);
+            assert_eq!(actions.pool_transfer.len(), 0);
+            assert_eq!(actions.swaps.len(), 1);
+            assert_eq!(
+                actions.swaps.get_unchecked(0),
+                (
+                    underlying_0.clone(),
+                    underlying_1.clone(),
+                    6_0000000,
+                    2_9000000
+                )
+            );
+
+            // the liability is repaid with the minimum output of the swap
+            assert_eq!(user.get_collateral(0), 14_0000000);
+            assert_eq!(user.get_liabilities(1), 2_1000000);
+
+            // no tokens are swapped until the submit is validated
+            assert_eq!(underlying_0_client.balance(&router), 0);
+
+            let reserve = pool_state.load_reserve(&e, &underlying_0, false);
+            assert_eq!(reserve.data.b_supply, 100_0000000 - 6_0000000);
+            let reserve = pool_state.load_reserve(&e, &underlying_1, false);
+            assert_eq!(reserve.data.d_supply, 75_0000000 - 2_9000000);
+        });
+    }
+
+    #[test]
+    fn test_build_actions_from_request_deleverage_over_liability() {
+        let e = Env::default();
+        e.mock_all_auths();
+        let (pool, samwise, _, underlying_0, underlying_1) = setup_deleverage(&e, true);
+
+        e.as_contract(&pool, || {
+            let mut pool_state = Pool::load(&e);
+            let mut user = User::load(&e, &samwise);
+
+            let requests =
+                deleverage_requests(&e, &underlying_0, &underlying_1, 12_0000000, 5_5000000);
+            let actions = build_actions_from_request(&e, &mut pool_state, &mut user, requests);
+
+            // the minimum output of 5.5 tokens repays 5 of liabilities
+            assert_eq!(actions.spender_transfer.len(), 0);
+            assert_eq!(
+                actions.pool_transfer.get_unchecked(underlying_1.clone()),
+                0_5000000
+            );
+            assert_eq!(user.get_collateral(0), 8_0000000);
+            assert_eq!(user.positions.liabilities.len(), 0);
+        });
+    }
+
+    #[test]
+    fn test_execute_swaps() {
+        let e = Env::default();
+        e.mock_all_auths();
+        let (pool, samwise, router, underlying_0, underlying_1) = setup_deleverage(&e, true);
+        let underlying_0_client = MockTokenClient::new(&e, &underlying_0);
+        let underlying_1_client = MockTokenClient::new(&e, &underlying_1);
+
+        e.as_contract(&pool, || {
+            let mut pool_state = Pool::load(&e);
+            let mut user = User::load(&e, &samwise);
+            let pre_pool_balance_1 = underlying_1_client.balance(&pool);
+
+            let requests =
+                deleverage_requests(&e, &underlying_0, &underlying_1, 6_0000000, 2_9000000);
+            let mut actions = build_actions_from_request(&e, &mut pool_state, &mut user, requests);
+            execute_swaps(&e, &mut actions, &samwise);
+
+            // the swap returns 3 tokens, and the 0.1 over the minimum output is sent to "to"
+            assert_eq!(underlying_0_client.balance(&router), 6_0000000);
+            assert_eq!(
+                underlying_1_client.balance(&pool),
+                pre_pool_balance_1 + 3_0000000
+            );
+            assert_eq!(
+                actions.pool_transfer.get_unchecked(underlying_1.clone()),
+                0_1000000
+            );
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "Error(Contract, #1237)")]
+    fn test_execute_swaps_under_min_out() {
+        let e = Env::default();
+        e.mock_all_auths();
+        let (pool, samwise, _, underlying_0, underlying_1) = setup_deleverage(&e, true);
+
+        e.as_contract(&pool, || {
+            let mut pool_state = Pool::load(&e);
+            let mut user = User::load(&e, &samwise);
+
+            let requests =
+                deleverage_requests(&e, &underlying_0, &underlying_1, 6_0000000, 3_0000001);
+            let mut actions = build_actions_from_request(&e, &mut pool_state, &mut user, requests);
+            execute_swaps(&e, &mut actions, &samwise);
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "Error(Contract, #1237)")]
+    fn test_build_actions_from_request_deleverage_requires_repay() {
+        let e = Env::default();
+        e.mock_all_auths();
+        let (pool, samwise, _, underlying_0, _) = setup_deleverage(&e, true);
+
+        e.as_contract(&pool, || {
+            let mut pool_state = Pool::load(&e);
+            let mut user = User::load(&e, &samwise);
+
+            let requests = vec![
+                &e,
+                Request {
+                    request_type: RequestType::Deleverage as u32,
+                    address: underlying_0.clone(),
+                    amount: 6_0000000,
+                },
+            ];
+            build_actions_from_request(&e, &mut pool_state, &mut user, requests);
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "Error(Contract, #1237)")]
+    fn test_build_actions_from_request_deleverage_no_router() {
+        let e = Env::default();
+        e.mock_all_auths();
+        let (pool, samwise, _, underlying_0, underlying_1) = setup_deleverage(&e, false);
+
+        e.as_contract(&pool, || {
+            let mut pool_state = Pool::load(&e);
+            let mut user = User::load(&e, &samwise);
+
+            let requests =
+                deleverage_requests(&e, &underlying_0, &underlying_1, 6_0000000, 2_9000000);
+            build_actions_from_request(&e, &mut pool_state, &mut user, requests);
         });
     }
 }
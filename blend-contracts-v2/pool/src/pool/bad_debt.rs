@@ -1,22 +1,30 @@
+use cast::i128;
+use sep_41_token::TokenClient;
+use soroban_fixed_point_math::SorobanFixedPoint;
 use soroban_sdk::{panic_with_error, Address, Env};
 
 use crate::{
+    constants::SCALAR_7,
     errors::PoolError,
     events::PoolEvents,
     storage::{self},
 };
 
-use super::{user::User, Pool};
+use super::{isolation::update_isolated_debt, user::User, Pool};
 
 /// Transfer bad debt from a user to the backstop. Validates that the user does hold bad debt
 /// and transfers all held d_tokens to the backstop.
 ///
+/// If keeper rewards are configured, the keeper is paid a share of the bad debt in the underlying
+/// asset out of the reserve's backstop credit, up to the credit available.
+///
 /// ### Arguments
+/// * `keeper` - The address transferring the bad debt
 /// * `user` - The user who has bad debt
 ///
 /// ### Panics
 /// If the user does not have bad debt
-pub fn transfer_bad_debt_to_backstop(e: &Env, user: &Address) {
+pub fn transfer_bad_debt_to_backstop(e: &Env, keeper: &Address, user: &Address) {
     let backstop_address = storage::get_backstop(e);
     if user.clone() == backstop_address {
         panic_with_error!(e, PoolError::BadRequest);
@@ -34,16 +42,40 @@ pub fn transfer_bad_debt_to_backstop(e: &Env, user: &Address) {
     let backstop_state = User::load(e, &backstop_address);
     let mut new_user_state = user_state.clone();
     let mut new_backstop_state = backstop_state.clone();
+    // users cannot be rewarded for transferring their own bad debt
+    let bad_debt_share = if keeper == user {
+        0
+    } else {
+        storage::get_keeper_config(e).bad_debt_share
+    };
     for (reserve_index, liability_balance) in user_state.positions.liabilities.iter() {
         let asset = reserve_list.get_unchecked(reserve_index);
         let mut reserve = pool.load_reserve(e, &asset, true);
         new_backstop_state.add_liabilities(e, &mut reserve, liability_balance);
         new_user_state.remove_liabilities(e, &mut reserve, liability_balance);
+
+        let reward = reserve
+            .to_asset_from_d_token(e, liability_balance)
+            .fixed_mul_floor(e, &i128(bad_debt_share), &SCALAR_7)
+            .min(reserve.data.backstop_credit);
+        if reward > 0 {
+            reserve.data.backstop_credit -= reward;
+            TokenClient::new(e, &asset).transfer(&e.current_contract_address(), keeper, &reward);
+            PoolEvents::bad_debt_reward(e, keeper.clone(), user.clone(), asset.clone(), reward);
+        }
         pool.cache_reserve(reserve);
 
         PoolEvents::bad_debt(e, user.clone(), asset, liability_balance);
     }
 
+    // any debt backed by isolated collateral was released when the user's collateral was removed
+    update_isolated_debt(
+        e,
+        &mut pool,
+        &user_state.positions,
+        &new_user_state.positions,
+        false,
+    );
     pool.store_cached_reserves(e);
     new_backstop_state.store(e);
     new_user_state.store(e);
@@ -51,7 +83,11 @@ pub fn transfer_bad_debt_to_backstop(e: &Env, user: &Address) {
 
 #[cfg(test)]
 mod tests {
-    use crate::{pool::Positions, storage::PoolConfig, testutils};
+    use crate::{
+        pool::Positions,
+        storage::{KeeperConfig, PoolConfig},
+        testutils,
+    };
 
     use super::*;
     use soroban_sdk::{
@@ -110,7 +146,7 @@ mod tests {
             storage::set_user_positions(&e, &samwise, &user_positions);
 
             e.cost_estimate().budget().reset_unlimited();
-            transfer_bad_debt_to_backstop(&e, &samwise);
+            transfer_bad_debt_to_backstop(&e, &Address::generate(&e), &samwise);
 
             let new_user_positions = storage::get_user_positions(&e, &samwise);
             let new_backstop_positions = storage::get_user_positions(&e, &backstop);
@@ -127,6 +163,145 @@ mod tests {
         });
     }
 
+    #[test]
+    fn test_transfer_bad_debt_keeps_isolated_debt() {
+        let e = Env::default();
+        e.mock_all_auths();
+
+        e.ledger().set(LedgerInfo {
+            timestamp: 600,
+            protocol_version: 22,
+            sequence_number: 123,
+            network_id: Default::default(),
+            base_reserve: 10,
+            min_temp_entry_ttl: 10,
+            min_persistent_entry_ttl: 10,
+            max_entry_ttl: 3110400,
+        });
+
+        let pool = testutils::create_pool(&e);
+        let backstop = Address::generate(&e);
+
+        let samwise = Address::generate(&e);
+        let bombadil = Address::generate(&e);
+
+        let (underlying_0, _) = testutils::create_token_contract(&e, &bombadil);
+        let (mut reserve_config, reserve_data) = testutils::default_reserve_meta();
+        reserve_config.isolated = true;
+        reserve_config.debt_ceiling = 100_0000000;
+        testutils::create_reserve(&e, &pool, &underlying_0, &reserve_config, &reserve_data);
+
+        let (underlying_1, _) = testutils::create_token_contract(&e, &bombadil);
+        let (mut reserve_config, reserve_data) = testutils::default_reserve_meta();
+        reserve_config.index = 1;
+        testutils::create_reserve(&e, &pool, &underlying_1, &reserve_config, &reserve_data);
+
+        let pool_config = PoolConfig {
+            oracle: Address::generate(&e),
+            min_collateral: 1_0000000,
+            bstop_rate: 0_1000000,
+            status: 0,
+            max_positions: 2,
+        };
+        // samwise's isolated collateral was liquidated, releasing the debt it backed
+        let user_positions = Positions {
+            liabilities: map![&e, (1, 25_0000000)],
+            collateral: map![&e],
+            supply: map![&e],
+        };
+        let isolated_debt = map![&e, (underlying_1.clone(), 40_0000000)];
+        e.as_contract(&pool, || {
+            storage::set_pool_config(&e, &pool_config);
+            storage::set_backstop(&e, &backstop);
+            storage::set_user_positions(&e, &samwise, &user_positions);
+            storage::set_isolated_debt(&e, &underlying_0, &isolated_debt);
+
+            e.cost_estimate().budget().reset_unlimited();
+            transfer_bad_debt_to_backstop(&e, &Address::generate(&e), &samwise);
+
+            let new_backstop_positions = storage::get_user_positions(&e, &backstop);
+            assert_eq!(
+                new_backstop_positions.liabilities.get_unchecked(1),
+                25_0000000
+            );
+            assert_eq!(storage::get_isolated_debt(&e, &underlying_0), isolated_debt);
+        });
+    }
+
+    #[test]
+    fn test_transfer_bad_debt_pays_keeper() {
+        let e = Env::default();
+        e.mock_all_auths();
+
+        e.ledger().set(LedgerInfo {
+            timestamp: 600,
+            protocol_version: 22,
+            sequence_number: 123,
+            network_id: Default::default(),
+            base_reserve: 10,
+            min_temp_entry_ttl: 10,
+            min_persistent_entry_ttl: 10,
+            max_entry_ttl: 3110400,
+        });
+
+        let pool = testutils::create_pool(&e);
+        let backstop = Address::generate(&e);
+
+        let samwise = Address::generate(&e);
+        let bombadil = Address::generate(&e);
+        let frodo = Address::generate(&e);
+
+        let (underlying_0, underlying_0_client) = testutils::create_token_contract(&e, &bombadil);
+        let (reserve_config, mut reserve_data) = testutils::default_reserve_meta();
+        reserve_data.last_time = 600;
+        reserve_data.backstop_credit = 1_0000000;
+        testutils::create_reserve(&e, &pool, &underlying_0, &reserve_config, &reserve_data);
+
+        let (underlying_1, underlying_1_client) = testutils::create_token_contract(&e, &bombadil);
+        let (mut reserve_config, mut reserve_data) = testutils::default_reserve_meta();
+        reserve_config.index = 1;
+        reserve_data.last_time = 600;
+        reserve_data.backstop_credit = 0_1000000;
+        testutils::create_reserve(&e, &pool, &underlying_1, &reserve_config, &reserve_data);
+
+        let pool_config = PoolConfig {
+            oracle: Address::generate(&e),
+            min_collateral: 1_0000000,
+            bstop_rate: 0_1000000,
+            status: 0,
+            max_positions: 2,
+        };
+        let user_positions = Positions {
+            liabilities: map![&e, (0, 24_0000000), (1, 25_0000000)],
+            collateral: map![&e],
+            supply: map![&e],
+        };
+        e.as_contract(&pool, || {
+            storage::set_pool_config(&e, &pool_config);
+            storage::set_backstop(&e, &backstop);
+            storage::set_user_positions(&e, &samwise, &user_positions);
+            storage::set_keeper_config(
+                &e,
+                &KeeperConfig {
+                    lot_share: 0,
+                    bad_debt_share: 0_0100000,
+                },
+            );
+
+            e.cost_estimate().budget().reset_unlimited();
+            transfer_bad_debt_to_backstop(&e, &frodo, &samwise);
+
+            // the reward is 1% of the bad debt, capped at the reserve's backstop credit
+            assert_eq!(underlying_0_client.balance(&frodo), 0_2400000);
+            assert_eq!(underlying_1_client.balance(&frodo), 0_1000000);
+            assert_eq!(
+                storage::get_res_data(&e, &underlying_0).backstop_credit,
+                0_7600000
+            );
+            assert_eq!(storage::get_res_data(&e, &underlying_1).backstop_credit, 0);
+        });
+    }
+
     #[test]
     #[should_panic(expected = "Error(Contract, #1200)")]
     fn test_transfer_bad_debt_with_collateral_panics() {
@@ -177,7 +352,7 @@ mod tests {
             storage::set_backstop(&e, &backstop);
             storage::set_user_positions(&e, &samwise, &user_positions);
 
-            transfer_bad_debt_to_backstop(&e, &samwise);
+            transfer_bad_debt_to_backstop(&e, &Address::generate(&e), &samwise);
         });
     }
 
@@ -228,7 +403,7 @@ mod tests {
             storage::set_user_positions(&e, &samwise, &user_positions);
 
             e.cost_estimate().budget().reset_unlimited();
-            transfer_bad_debt_to_backstop(&e, &samwise);
+            transfer_bad_debt_to_backstop(&e, &Address::generate(&e), &samwise);
         });
     }
 
@@ -282,7 +457,7 @@ mod tests {
             storage::set_user_positions(&e, &samwise, &user_positions);
 
             e.cost_estimate().budget().reset_unlimited();
-            transfer_bad_debt_to_backstop(&e, &backstop);
+            transfer_bad_debt_to_backstop(&e, &Address::generate(&e), &backstop);
         });
     }
 }
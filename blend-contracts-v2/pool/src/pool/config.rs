@@ -1,13 +1,21 @@
 use crate::{
-    constants::{MAX_RESERVES, SCALAR_12, SCALAR_7, SECONDS_PER_WEEK},
+    constants::{
+        MAX_AUCTION_CURVE_BLOCKS, MAX_BACKUP_ORACLES, MAX_FACTOR_RAMP_PERIOD, MAX_KEEPER_SHARE,
+        MAX_RESERVES, MAX_TWAP_RECORDS, SCALAR_12, SCALAR_7, SECONDS_PER_WEEK,
+    },
     errors::PoolError,
     storage::{
-        self, has_queued_reserve_set, PoolConfig, QueuedReserveInit, ReserveConfig, ReserveData,
+        self, has_queued_reserve_set, AuctionCurve, KeeperConfig, OracleConfig, PoolConfig,
+        QueuedAuctionInit, QueuedOracleInit, QueuedReserveInit, ReserveConfig, ReserveData,
+        ReserveFactorRamp,
     },
 };
-use soroban_sdk::{panic_with_error, Address, Env, String};
+use cast::i128;
+use sep_40_oracle::PriceFeedClient;
+use soroban_fixed_point_math::SorobanFixedPoint;
+use soroban_sdk::{panic_with_error, Address, Env, Map, String};
 
-use super::pool::Pool;
+use super::{interest::InterestRateModel, pool::Pool};
 
 /// Initialize the pool
 ///
@@ -57,11 +65,22 @@ pub fn execute_update_pool(
 }
 
 /// Execute a queueing a reserve initialization for the pool
-pub fn execute_queue_set_reserve(e: &Env, asset: &Address, metadata: &ReserveConfig) {
-    if has_queued_reserve_set(e, asset) {
+///
+/// If the reserve already exists, its collateral and liability factors ramp linearly to the new
+/// values over `ramp_period` seconds once the initialization is executed.
+pub fn execute_queue_set_reserve(
+    e: &Env,
+    asset: &Address,
+    metadata: &ReserveConfig,
+    ramp_period: u64,
+) {
+    if has_queued_reserve_set(e, asset) || storage::has_res_delisting(e, asset) {
         panic_with_error!(&e, PoolError::BadRequest)
     }
     require_valid_reserve_metadata(e, metadata);
+    if ramp_period > MAX_FACTOR_RAMP_PERIOD {
+        panic_with_error!(&e, PoolError::InvalidReserveMetadata);
+    }
     let mut unlock_time = e.ledger().timestamp();
     // require a timelock if pool status is not setup
     if storage::get_pool_config(e).status != 6 {
@@ -72,6 +91,7 @@ pub fn execute_queue_set_reserve(e: &Env, asset: &Address, metadata: &ReserveCon
         &QueuedReserveInit {
             new_config: metadata.clone(),
             unlock_time,
+            ramp_period,
         },
         &asset,
     );
@@ -89,16 +109,142 @@ pub fn execute_set_reserve(e: &Env, asset: &Address) -> u32 {
     if queued_init.unlock_time > e.ledger().timestamp() {
         panic_with_error!(e, PoolError::InitNotUnlocked);
     }
+    // a reserve being delisted cannot be updated
+    if storage::has_res_delisting(e, asset) {
+        panic_with_error!(e, PoolError::InvalidDelisting);
+    }
 
     // remove queued reserve
     storage::del_queued_reserve_set(e, asset);
 
     // initialize reserve
-    initialize_reserve(e, asset, &queued_init.new_config)
+    initialize_reserve(e, asset, &queued_init.new_config, queued_init.ramp_period)
+}
+
+/// Execute queueing a backup oracle configuration for the pool
+pub fn execute_queue_set_oracle_config(e: &Env, config: &OracleConfig) {
+    if storage::has_queued_oracle_config(e) {
+        panic_with_error!(&e, PoolError::BadRequest)
+    }
+    let pool_config = storage::get_pool_config(e);
+    require_valid_oracle_config(e, &pool_config, config);
+    let mut unlock_time = e.ledger().timestamp();
+    // require a timelock if pool status is not setup
+    if pool_config.status != 6 {
+        unlock_time += SECONDS_PER_WEEK;
+    }
+    storage::set_queued_oracle_config(
+        e,
+        &QueuedOracleInit {
+            new_config: config.clone(),
+            unlock_time,
+        },
+    );
+}
+
+/// Execute cancelling a queued backup oracle configuration for the pool
+pub fn execute_cancel_queued_set_oracle_config(e: &Env) {
+    storage::del_queued_oracle_config(e);
+}
+
+/// Execute a queued backup oracle configuration for the pool
+pub fn execute_set_oracle_config(e: &Env) -> OracleConfig {
+    let queued_init = storage::get_queued_oracle_config(e);
+
+    if queued_init.unlock_time > e.ledger().timestamp() {
+        panic_with_error!(e, PoolError::InitNotUnlocked);
+    }
+
+    storage::del_queued_oracle_config(e);
+    storage::set_oracle_config(e, &queued_init.new_config);
+    queued_init.new_config
+}
+
+/// Execute queueing a Dutch auction curve configuration for the pool
+pub fn execute_queue_set_auction_config(e: &Env, config: &Map<u32, AuctionCurve>) {
+    if storage::has_queued_auction_config(e) {
+        panic_with_error!(&e, PoolError::BadRequest)
+    }
+    require_valid_auction_config(e, config);
+    let pool_config = storage::get_pool_config(e);
+    let mut unlock_time = e.ledger().timestamp();
+    // require a timelock if pool status is not setup
+    if pool_config.status != 6 {
+        unlock_time += SECONDS_PER_WEEK;
+    }
+    storage::set_queued_auction_config(
+        e,
+        &QueuedAuctionInit {
+            new_config: config.clone(),
+            unlock_time,
+        },
+    );
+}
+
+/// Execute cancelling a queued Dutch auction curve configuration for the pool
+pub fn execute_cancel_queued_set_auction_config(e: &Env) {
+    storage::del_queued_auction_config(e);
+}
+
+/// Execute a queued Dutch auction curve configuration for the pool
+pub fn execute_set_auction_config(e: &Env) -> Map<u32, AuctionCurve> {
+    let queued_init = storage::get_queued_auction_config(e);
+
+    if queued_init.unlock_time > e.ledger().timestamp() {
+        panic_with_error!(e, PoolError::InitNotUnlocked);
+    }
+
+    storage::del_queued_auction_config(e);
+    storage::set_auction_config(e, &queued_init.new_config);
+    queued_init.new_config
+}
+
+/// Execute setting the keeper reward configuration for the pool
+///
+/// ### Panics
+/// If either share exceeds the max keeper share
+pub fn execute_set_keeper_config(e: &Env, config: &KeeperConfig) {
+    if config.lot_share > MAX_KEEPER_SHARE || config.bad_debt_share > MAX_KEEPER_SHARE {
+        panic_with_error!(e, PoolError::InvalidPoolConfigArgs);
+    }
+    storage::set_keeper_config(e, config);
+}
+
+/// Calculate the collateral and liability factors of a reserve with a factor ramp at the current
+/// ledger time
+///
+/// ### Arguments
+/// * `ramp` - The factor ramp of the reserve
+/// * `config` - The configuration of the reserve, containing the factors being ramped to
+///
+/// ### Returns
+/// * (u32, u32) - (collateral factor, liability factor) expressed in 7 decimals
+pub fn calc_ramped_factors(
+    e: &Env,
+    ramp: &ReserveFactorRamp,
+    config: &ReserveConfig,
+) -> (u32, u32) {
+    let now = e.ledger().timestamp();
+    if now >= ramp.end_time {
+        return (config.c_factor, config.l_factor);
+    }
+    if now <= ramp.start_time {
+        return (ramp.c_factor, ramp.l_factor);
+    }
+    let elapsed = i128(now - ramp.start_time);
+    let period = i128(ramp.end_time - ramp.start_time);
+    let interpolate = |from: u32, to: u32| -> u32 {
+        let delta = (i128(to) - i128(from)).fixed_mul_floor(e, &elapsed, &period);
+        (i128(from) + delta) as u32
+    };
+    (
+        interpolate(ramp.c_factor, config.c_factor),
+        interpolate(ramp.l_factor, config.l_factor),
+    )
 }
 
 /// sets reserve data for the pool
-fn initialize_reserve(e: &Env, asset: &Address, config: &ReserveConfig) -> u32 {
+fn initialize_reserve(e: &Env, asset: &Address, config: &ReserveConfig, ramp_period: u64) -> u32 {
     let index: u32;
     // if reserve already exists, ensure index and scalar do not change
     if storage::has_res(e, asset) {
@@ -118,9 +264,30 @@ fn initialize_reserve(e: &Env, asset: &Address, config: &ReserveConfig) -> u32 {
             || reserve_config.r_two != config.r_two
             || reserve_config.r_three != config.r_three
             || reserve_config.util != config.util
+            || reserve_config.ir_model != config.ir_model
+            || reserve_config.util_two != config.util_two
         {
             reserve.data.ir_mod = SCALAR_7;
         }
+        // ramp the factors from their current values to avoid instantly changing user health
+        if ramp_period > 0
+            && (reserve.config.c_factor != config.c_factor
+                || reserve.config.l_factor != config.l_factor)
+        {
+            let now = e.ledger().timestamp();
+            storage::set_res_factor_ramp(
+                e,
+                asset,
+                &ReserveFactorRamp {
+                    c_factor: reserve.config.c_factor,
+                    l_factor: reserve.config.l_factor,
+                    start_time: now,
+                    end_time: now + ramp_period,
+                },
+            );
+        } else {
+            storage::del_res_factor_ramp(e, asset);
+        }
         reserve.store(e);
     } else {
         index = storage::push_res_list(e, asset);
@@ -129,6 +296,7 @@ fn initialize_reserve(e: &Env, asset: &Address, config: &ReserveConfig) -> u32 {
             d_rate: SCALAR_12,
             ir_mod: SCALAR_7,
             d_supply: 0,
+            fixed_debt: 0,
             b_supply: 0,
             last_time: e.ledger().timestamp(),
             backstop_credit: 0,
@@ -149,6 +317,14 @@ fn initialize_reserve(e: &Env, asset: &Address, config: &ReserveConfig) -> u32 {
         r_three: config.r_three,
         reactivity: config.reactivity,
         collateral_cap: config.collateral_cap,
+        twap_records: config.twap_records,
+        borrow_cap: config.borrow_cap,
+        supply_cap: config.supply_cap,
+        isolated: config.isolated,
+        debt_ceiling: config.debt_ceiling,
+        ir_model: config.ir_model,
+        util_two: config.util_two,
+        flash_fee: config.flash_fee,
         enabled: config.enabled,
     };
     storage::set_res_config(e, asset, &reserve_config);
@@ -168,11 +344,51 @@ fn require_valid_reserve_metadata(e: &Env, metadata: &ReserveConfig) {
         || metadata.r_base < 0_0001000
         || (metadata.r_one > metadata.r_two || metadata.r_two > metadata.r_three)
         || (metadata.reactivity > 0_0001000)
+        || metadata.twap_records > MAX_TWAP_RECORDS
+        || metadata.borrow_cap < 0
+        || metadata.supply_cap < 0
+        || metadata.debt_ceiling < 0
+        || metadata.ir_model > InterestRateModel::Fixed as u32
+        || metadata.flash_fee > 0_1000000
+        || (metadata.ir_model == InterestRateModel::Kinked as u32
+            && (metadata.util_two <= metadata.util || metadata.util_two >= SCALAR_7_U32))
     {
         panic_with_error!(e, PoolError::InvalidReserveMetadata);
     }
 }
 
+fn require_valid_oracle_config(e: &Env, pool_config: &PoolConfig, config: &OracleConfig) {
+    if config.oracles.len() > MAX_BACKUP_ORACLES || config.quorum > config.oracles.len() + 1 {
+        panic_with_error!(e, PoolError::InvalidPoolConfigArgs);
+    }
+
+    // backup oracles must be unique and report prices with the same decimals as the primary oracle
+    let decimals = PriceFeedClient::new(e, &pool_config.oracle).decimals();
+    for (index, oracle) in config.oracles.iter().enumerate() {
+        if oracle == pool_config.oracle
+            || config.oracles.first_index_of(&oracle) != Some(index as u32)
+            || PriceFeedClient::new(e, &oracle).decimals() != decimals
+        {
+            panic_with_error!(e, PoolError::InvalidPoolConfigArgs);
+        }
+    }
+}
+
+fn require_valid_auction_config(e: &Env, config: &Map<u32, AuctionCurve>) {
+    for (auction_type, curve) in config.iter() {
+        // auction types are 0 (user liquidation), 1 (bad debt) and 2 (interest)
+        if auction_type > 2
+            || curve.lot_ramp == 0
+            || curve.lot_ramp > MAX_AUCTION_CURVE_BLOCKS
+            || curve.bid_decay == 0
+            || curve.bid_decay > MAX_AUCTION_CURVE_BLOCKS
+            || curve.start_premium >= SCALAR_7 as u32
+        {
+            panic_with_error!(e, PoolError::InvalidPoolConfigArgs);
+        }
+    }
+}
+
 fn require_valid_pool_config(e: &Env, config: &PoolConfig) {
     // ensure backstop is [0,1)
     if config.bstop_rate >= SCALAR_7 as u32 {
@@ -192,11 +408,12 @@ fn require_valid_pool_config(e: &Env, config: &PoolConfig) {
 
 #[cfg(test)]
 mod tests {
-    use crate::storage::QueuedReserveInit;
+    use crate::storage::{QueuedReserveInit, ReserveDelisting};
     use crate::testutils;
 
     use super::*;
     use soroban_sdk::testutils::{Address as _, Ledger, LedgerInfo};
+    use soroban_sdk::{map, vec};
 
     #[test]
     fn test_execute_initialize() {
@@ -412,6 +629,14 @@ mod tests {
             r_three: 1_5000000,
             reactivity: 100,
             collateral_cap: 1000000000000000000,
+            twap_records: 0,
+            borrow_cap: 1000000000000000000,
+            supply_cap: 1000000000000000000,
+            isolated: false,
+            debt_ceiling: 0,
+            ir_model: 0,
+            util_two: 0,
+            flash_fee: 0,
             enabled: true,
         };
         let pool_config = PoolConfig {
@@ -423,7 +648,7 @@ mod tests {
         };
         e.as_contract(&pool, || {
             storage::set_pool_config(&e, &pool_config);
-            execute_queue_set_reserve(&e, &asset_id_0, &metadata);
+            execute_queue_set_reserve(&e, &asset_id_0, &metadata, 0);
             let queued_res = storage::get_queued_reserve_set(&e, &asset_id_0);
             let res_config_0 = queued_res.new_config;
             assert_eq!(res_config_0.decimals, metadata.decimals);
@@ -463,6 +688,14 @@ mod tests {
             r_three: 1_5000000,
             reactivity: 100,
             collateral_cap: 1000000000000000000,
+            twap_records: 0,
+            borrow_cap: 1000000000000000000,
+            supply_cap: 1000000000000000000,
+            isolated: false,
+            debt_ceiling: 0,
+            ir_model: 0,
+            util_two: 0,
+            flash_fee: 0,
             enabled: true,
         };
         let pool_config = PoolConfig {
@@ -474,7 +707,7 @@ mod tests {
         };
         e.as_contract(&pool, || {
             storage::set_pool_config(&e, &pool_config);
-            execute_queue_set_reserve(&e, &asset_id_0, &metadata);
+            execute_queue_set_reserve(&e, &asset_id_0, &metadata, 0);
             let queued_init = storage::get_queued_reserve_set(&e, &asset_id_0);
             assert_eq!(queued_init.new_config.decimals, metadata.decimals);
             assert_eq!(queued_init.new_config.c_factor, metadata.c_factor);
@@ -517,6 +750,14 @@ mod tests {
             r_three: 1_5000000,
             reactivity: 100,
             collateral_cap: 1000000000000000000,
+            twap_records: 0,
+            borrow_cap: 1000000000000000000,
+            supply_cap: 1000000000000000000,
+            isolated: false,
+            debt_ceiling: 0,
+            ir_model: 0,
+            util_two: 0,
+            flash_fee: 0,
             enabled: true,
         };
         let pool_config = PoolConfig {
@@ -528,13 +769,13 @@ mod tests {
         };
         e.as_contract(&pool, || {
             storage::set_pool_config(&e, &pool_config);
-            execute_queue_set_reserve(&e, &asset_id_0, &metadata);
+            execute_queue_set_reserve(&e, &asset_id_0, &metadata, 0);
             let queued_res = storage::get_queued_reserve_set(&e, &asset_id_0);
             let res_config_0 = queued_res.new_config;
             assert_eq!(res_config_0.index, 0);
 
             // try and queue the same reserve
-            execute_queue_set_reserve(&e, &asset_id_0, &metadata);
+            execute_queue_set_reserve(&e, &asset_id_0, &metadata, 0);
         });
     }
 
@@ -560,6 +801,14 @@ mod tests {
             r_three: 1_5000000,
             reactivity: 100,
             collateral_cap: 1000000000000000000,
+            twap_records: 0,
+            borrow_cap: 1000000000000000000,
+            supply_cap: 1000000000000000000,
+            isolated: false,
+            debt_ceiling: 0,
+            ir_model: 0,
+            util_two: 0,
+            flash_fee: 0,
             enabled: true,
         };
         let pool_config = PoolConfig {
@@ -571,7 +820,30 @@ mod tests {
         };
         e.as_contract(&pool, || {
             storage::set_pool_config(&e, &pool_config);
-            execute_queue_set_reserve(&e, &asset_id, &metadata);
+            execute_queue_set_reserve(&e, &asset_id, &metadata, 0);
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "Error(Contract, #1202)")]
+    fn test_queue_set_reserve_validates_ramp_period() {
+        let e = Env::default();
+        e.mock_all_auths();
+        let pool = testutils::create_pool(&e);
+        let bombadil = Address::generate(&e);
+        let (asset_id, _) = testutils::create_token_contract(&e, &bombadil);
+
+        let (metadata, _) = testutils::default_reserve_meta();
+        let pool_config = PoolConfig {
+            oracle: Address::generate(&e),
+            min_collateral: 1_0000000,
+            bstop_rate: 0_1000000,
+            status: 0,
+            max_positions: 2,
+        };
+        e.as_contract(&pool, || {
+            storage::set_pool_config(&e, &pool_config);
+            execute_queue_set_reserve(&e, &asset_id, &metadata, MAX_FACTOR_RAMP_PERIOD + 1);
         });
     }
 
@@ -597,6 +869,14 @@ mod tests {
             r_three: 1_5000000,
             reactivity: 100,
             collateral_cap: 1000000000000000000,
+            twap_records: 0,
+            borrow_cap: 1000000000000000000,
+            supply_cap: 1000000000000000000,
+            isolated: false,
+            debt_ceiling: 0,
+            ir_model: 0,
+            util_two: 0,
+            flash_fee: 0,
             enabled: true,
         };
         e.as_contract(&pool, || {
@@ -605,6 +885,7 @@ mod tests {
                 &QueuedReserveInit {
                     new_config: metadata.clone(),
                     unlock_time: e.ledger().timestamp(),
+                    ramp_period: 0,
                 },
                 &asset_id_0,
             );
@@ -637,6 +918,14 @@ mod tests {
             r_three: 1_5000000,
             reactivity: 100,
             collateral_cap: 1000000000000000000,
+            twap_records: 0,
+            borrow_cap: 1000000000000000000,
+            supply_cap: 1000000000000000000,
+            isolated: false,
+            debt_ceiling: 0,
+            ir_model: 0,
+            util_two: 0,
+            flash_fee: 0,
             enabled: true,
         };
         e.as_contract(&pool, || {
@@ -645,6 +934,7 @@ mod tests {
                 &QueuedReserveInit {
                     new_config: metadata.clone(),
                     unlock_time: e.ledger().timestamp(),
+                    ramp_period: 0,
                 },
                 &asset_id_0,
             );
@@ -686,6 +976,14 @@ mod tests {
             r_three: 1_5000000,
             reactivity: 100,
             collateral_cap: 1000000000000000000,
+            twap_records: 0,
+            borrow_cap: 1000000000000000000,
+            supply_cap: 1000000000000000000,
+            isolated: false,
+            debt_ceiling: 0,
+            ir_model: 0,
+            util_two: 0,
+            flash_fee: 0,
             enabled: true,
         };
         e.as_contract(&pool, || {
@@ -694,6 +992,7 @@ mod tests {
                 &QueuedReserveInit {
                     new_config: metadata.clone(),
                     unlock_time: e.ledger().timestamp() + 1,
+                    ramp_period: 0,
                 },
                 &asset_id_0,
             );
@@ -701,6 +1000,41 @@ mod tests {
         });
     }
 
+    #[test]
+    #[should_panic(expected = "Error(Contract, #1231)")]
+    fn test_execute_set_reserve_delisting() {
+        let e = Env::default();
+        e.mock_all_auths();
+        let pool = testutils::create_pool(&e);
+        let bombadil = Address::generate(&e);
+
+        let (underlying, _) = testutils::create_token_contract(&e, &bombadil);
+        let (reserve_config, reserve_data) = testutils::default_reserve_meta();
+        testutils::create_reserve(&e, &pool, &underlying, &reserve_config, &reserve_data);
+
+        e.as_contract(&pool, || {
+            storage::set_queued_reserve_set(
+                &e,
+                &QueuedReserveInit {
+                    new_config: reserve_config.clone(),
+                    unlock_time: e.ledger().timestamp(),
+                    ramp_period: 0,
+                },
+                &underlying,
+            );
+            storage::set_res_delisting(
+                &e,
+                &underlying,
+                &ReserveDelisting {
+                    c_factor: reserve_config.c_factor,
+                    start_time: 0,
+                    end_time: SECONDS_PER_WEEK,
+                },
+            );
+            execute_set_reserve(&e, &underlying);
+        });
+    }
+
     #[test]
     fn test_execute_set_reserve_update() {
         let e = Env::default();
@@ -757,6 +1091,7 @@ mod tests {
                 &QueuedReserveInit {
                     new_config: new_metadata.clone(),
                     unlock_time: e.ledger().timestamp(),
+                    ramp_period: 0,
                 },
                 &underlying,
             );
@@ -783,6 +1118,104 @@ mod tests {
         });
     }
 
+    #[test]
+    fn test_execute_set_reserve_update_ramps_factors() {
+        let e = Env::default();
+        e.mock_all_auths();
+        e.ledger().set(LedgerInfo {
+            timestamp: 10000,
+            protocol_version: 22,
+            sequence_number: 100,
+            network_id: Default::default(),
+            base_reserve: 10,
+            min_temp_entry_ttl: 10,
+            min_persistent_entry_ttl: 10,
+            max_entry_ttl: 3110400,
+        });
+
+        let pool = testutils::create_pool(&e);
+        let bombadil = Address::generate(&e);
+
+        let (underlying, _) = testutils::create_token_contract(&e, &bombadil);
+        let (reserve_config, mut reserve_data) = testutils::default_reserve_meta();
+        reserve_data.last_time = 10000;
+        testutils::create_reserve(&e, &pool, &underlying, &reserve_config, &reserve_data);
+
+        let mut new_metadata = reserve_config.clone();
+        new_metadata.c_factor = 0_5000000;
+        new_metadata.l_factor = 0_9000000;
+
+        let pool_config = PoolConfig {
+            oracle: Address::generate(&e),
+            min_collateral: 1_0000000,
+            bstop_rate: 0_1000000,
+            status: 0,
+            max_positions: 2,
+        };
+        e.as_contract(&pool, || {
+            storage::set_pool_config(&e, &pool_config);
+
+            storage::set_queued_reserve_set(
+                &e,
+                &QueuedReserveInit {
+                    new_config: new_metadata.clone(),
+                    unlock_time: e.ledger().timestamp(),
+                    ramp_period: SECONDS_PER_WEEK,
+                },
+                &underlying,
+            );
+            execute_set_reserve(&e, &underlying);
+            let res_config_updated = storage::get_res_config(&e, &underlying);
+            assert_eq!(res_config_updated.c_factor, new_metadata.c_factor);
+            assert_eq!(res_config_updated.l_factor, new_metadata.l_factor);
+
+            let ramp = storage::get_res_factor_ramp(&e, &underlying).unwrap();
+            assert_eq!(ramp.c_factor, reserve_config.c_factor);
+            assert_eq!(ramp.l_factor, reserve_config.l_factor);
+            assert_eq!(ramp.start_time, 10000);
+            assert_eq!(ramp.end_time, 10000 + SECONDS_PER_WEEK);
+
+            // the factors are unchanged until time passes
+            let mut pool = Pool::load(&e);
+            let reserve = pool.load_reserve(&e, &underlying, false);
+            assert_eq!(reserve.config.c_factor, reserve_config.c_factor);
+            assert_eq!(reserve.config.l_factor, reserve_config.l_factor);
+
+            // halfway through the ramp
+            e.ledger().set(LedgerInfo {
+                timestamp: 10000 + SECONDS_PER_WEEK / 2,
+                protocol_version: 22,
+                sequence_number: 200,
+                network_id: Default::default(),
+                base_reserve: 10,
+                min_temp_entry_ttl: 10,
+                min_persistent_entry_ttl: 10,
+                max_entry_ttl: 3110400,
+            });
+            let mut pool = Pool::load(&e);
+            let reserve = pool.load_reserve(&e, &underlying, false);
+            assert_eq!(reserve.config.c_factor, 0_6250000);
+            assert_eq!(reserve.config.l_factor, 0_8250000);
+
+            // setting the reserve again without a ramp applies the factors instantly
+            storage::set_queued_reserve_set(
+                &e,
+                &QueuedReserveInit {
+                    new_config: reserve_config.clone(),
+                    unlock_time: e.ledger().timestamp(),
+                    ramp_period: 0,
+                },
+                &underlying,
+            );
+            execute_set_reserve(&e, &underlying);
+            assert!(storage::get_res_factor_ramp(&e, &underlying).is_none());
+            let mut pool = Pool::load(&e);
+            let reserve = pool.load_reserve(&e, &underlying, false);
+            assert_eq!(reserve.config.c_factor, reserve_config.c_factor);
+            assert_eq!(reserve.config.l_factor, reserve_config.l_factor);
+        });
+    }
+
     #[test]
     fn test_execute_set_reserve_update_resets_ir_mod() {
         let e = Env::default();
@@ -835,6 +1268,7 @@ mod tests {
                 &QueuedReserveInit {
                     new_config: new_metadata.clone(),
                     unlock_time: e.ledger().timestamp(),
+                    ramp_period: 0,
                 },
                 &underlying,
             );
@@ -860,6 +1294,68 @@ mod tests {
         });
     }
 
+    #[test]
+    fn test_execute_set_reserve_update_ir_model_resets_ir_mod() {
+        let e = Env::default();
+        e.mock_all_auths();
+        e.ledger().set(LedgerInfo {
+            timestamp: 500,
+            protocol_version: 22,
+            sequence_number: 100,
+            network_id: Default::default(),
+            base_reserve: 10,
+            min_temp_entry_ttl: 10,
+            min_persistent_entry_ttl: 10,
+            max_entry_ttl: 3110400,
+        });
+
+        let pool = testutils::create_pool(&e);
+        let bombadil = Address::generate(&e);
+
+        let (underlying, _) = testutils::create_token_contract(&e, &bombadil);
+        let (mut reserve_config, mut reserve_data) = testutils::default_reserve_meta();
+        reserve_config.ir_model = InterestRateModel::Kinked as u32;
+        reserve_config.util_two = 0_9000000;
+        reserve_data.ir_mod = 1_100_000_000;
+        testutils::create_reserve(&e, &pool, &underlying, &reserve_config, &reserve_data);
+
+        // only the interest rate model changes, back to reactive
+        let mut new_metadata = reserve_config.clone();
+        new_metadata.ir_model = InterestRateModel::Reactive as u32;
+        new_metadata.util_two = 0;
+
+        let pool_config = PoolConfig {
+            oracle: Address::generate(&e),
+            min_collateral: 1_0000000,
+            bstop_rate: 0_1000000,
+            status: 0,
+            max_positions: 2,
+        };
+        e.as_contract(&pool, || {
+            storage::set_pool_config(&e, &pool_config);
+
+            storage::set_queued_reserve_set(
+                &e,
+                &QueuedReserveInit {
+                    new_config: new_metadata.clone(),
+                    unlock_time: e.ledger().timestamp(),
+                    ramp_period: 0,
+                },
+                &underlying,
+            );
+            execute_set_reserve(&e, &underlying);
+            let res_config_updated = storage::get_res_config(&e, &underlying);
+            assert_eq!(
+                res_config_updated.ir_model,
+                InterestRateModel::Reactive as u32
+            );
+            assert_eq!(res_config_updated.util_two, 0);
+
+            let res_data = storage::get_res_data(&e, &underlying);
+            assert_eq!(res_data.ir_mod, 1_0000000);
+        });
+    }
+
     #[test]
     #[should_panic(expected = "Error(Contract, #1202)")]
     fn test_execute_set_reserve_validates_decimals_stay_same() {
@@ -896,6 +1392,14 @@ mod tests {
             r_three: 1_5000000,
             reactivity: 105,
             collateral_cap: 1000000000000000000,
+            twap_records: 0,
+            borrow_cap: 1000000000000000000,
+            supply_cap: 1000000000000000000,
+            isolated: false,
+            debt_ceiling: 0,
+            ir_model: 0,
+            util_two: 0,
+            flash_fee: 0,
             enabled: true,
         };
 
@@ -914,6 +1418,7 @@ mod tests {
                 &QueuedReserveInit {
                     new_config: new_metadata.clone(),
                     unlock_time: e.ledger().timestamp(),
+                    ramp_period: 0,
                 },
                 &underlying,
             );
@@ -944,12 +1449,20 @@ mod tests {
             r_three: 1_5000000,
             reactivity: 100,
             collateral_cap: 1000000000000000000,
+            twap_records: 0,
+            borrow_cap: 1000000000000000000,
+            supply_cap: 1000000000000000000,
+            isolated: false,
+            debt_ceiling: 0,
+            ir_model: 0,
+            util_two: 0,
+            flash_fee: 0,
             enabled: true,
         };
         e.as_contract(&pool, || {
-            initialize_reserve(&e, &asset_id_0, &metadata);
+            initialize_reserve(&e, &asset_id_0, &metadata, 0);
 
-            initialize_reserve(&e, &asset_id_1, &metadata);
+            initialize_reserve(&e, &asset_id_1, &metadata, 0);
             let res_config_0 = storage::get_res_config(&e, &asset_id_0);
             let res_config_1 = storage::get_res_config(&e, &asset_id_1);
             assert_eq!(res_config_0.decimals, metadata.decimals);
@@ -984,6 +1497,14 @@ mod tests {
             r_three: 1_5000000,
             reactivity: 100,
             collateral_cap: 1000000000000000000,
+            twap_records: 0,
+            borrow_cap: 1000000000000000000,
+            supply_cap: 1000000000000000000,
+            isolated: false,
+            debt_ceiling: 0,
+            ir_model: 0,
+            util_two: 0,
+            flash_fee: 0,
             enabled: true,
         };
         require_valid_reserve_metadata(&e, &metadata);
@@ -992,13 +1513,12 @@ mod tests {
     }
 
     #[test]
-    #[should_panic(expected = "Error(Contract, #1202)")]
-    fn test_validate_reserve_metadata_validates_decimals() {
+    fn test_validate_reserve_metadata_kinked_model() {
         let e = Env::default();
 
         let metadata = ReserveConfig {
             index: 0,
-            decimals: 19,
+            decimals: 18,
             c_factor: 0_7500000,
             l_factor: 0_7500000,
             util: 0_5000000,
@@ -1009,6 +1529,14 @@ mod tests {
             r_three: 1_5000000,
             reactivity: 100,
             collateral_cap: 1000000000000000000,
+            twap_records: 0,
+            borrow_cap: 1000000000000000000,
+            supply_cap: 1000000000000000000,
+            isolated: false,
+            debt_ceiling: 0,
+            ir_model: 1,
+            util_two: 0_9000000,
+            flash_fee: 0,
             enabled: true,
         };
         require_valid_reserve_metadata(&e, &metadata);
@@ -1016,13 +1544,13 @@ mod tests {
 
     #[test]
     #[should_panic(expected = "Error(Contract, #1202)")]
-    fn test_validate_reserve_metadata_validates_c_factor() {
+    fn test_validate_reserve_metadata_validates_ir_model() {
         let e = Env::default();
 
         let metadata = ReserveConfig {
             index: 0,
             decimals: 18,
-            c_factor: 1_0000001,
+            c_factor: 0_7500000,
             l_factor: 0_7500000,
             util: 0_5000000,
             max_util: 0_9500000,
@@ -1032,6 +1560,14 @@ mod tests {
             r_three: 1_5000000,
             reactivity: 100,
             collateral_cap: 1000000000000000000,
+            twap_records: 0,
+            borrow_cap: 1000000000000000000,
+            supply_cap: 1000000000000000000,
+            isolated: false,
+            debt_ceiling: 0,
+            ir_model: 3,
+            util_two: 0,
+            flash_fee: 0,
             enabled: true,
         };
         require_valid_reserve_metadata(&e, &metadata);
@@ -1039,14 +1575,14 @@ mod tests {
 
     #[test]
     #[should_panic(expected = "Error(Contract, #1202)")]
-    fn test_validate_reserve_metadata_validates_l_factor() {
+    fn test_validate_reserve_metadata_validates_util_two_over_util() {
         let e = Env::default();
 
         let metadata = ReserveConfig {
             index: 0,
             decimals: 18,
             c_factor: 0_7500000,
-            l_factor: 1_0000001,
+            l_factor: 0_7500000,
             util: 0_5000000,
             max_util: 0_9500000,
             r_base: 0_0001000,
@@ -1055,6 +1591,14 @@ mod tests {
             r_three: 1_5000000,
             reactivity: 100,
             collateral_cap: 1000000000000000000,
+            twap_records: 0,
+            borrow_cap: 1000000000000000000,
+            supply_cap: 1000000000000000000,
+            isolated: false,
+            debt_ceiling: 0,
+            ir_model: 1,
+            util_two: 0_5000000,
+            flash_fee: 0,
             enabled: true,
         };
         require_valid_reserve_metadata(&e, &metadata);
@@ -1062,7 +1606,7 @@ mod tests {
 
     #[test]
     #[should_panic(expected = "Error(Contract, #1202)")]
-    fn test_validate_reserve_metadata_validates_util() {
+    fn test_validate_reserve_metadata_validates_util_two_under_max() {
         let e = Env::default();
 
         let metadata = ReserveConfig {
@@ -1070,7 +1614,7 @@ mod tests {
             decimals: 18,
             c_factor: 0_7500000,
             l_factor: 0_7500000,
-            util: 0_9000001,
+            util: 0_5000000,
             max_util: 0_9500000,
             r_base: 0_0001000,
             r_one: 0_0500000,
@@ -1078,6 +1622,14 @@ mod tests {
             r_three: 1_5000000,
             reactivity: 100,
             collateral_cap: 1000000000000000000,
+            twap_records: 0,
+            borrow_cap: 1000000000000000000,
+            supply_cap: 1000000000000000000,
+            isolated: false,
+            debt_ceiling: 0,
+            ir_model: 1,
+            util_two: 1_0000000,
+            flash_fee: 0,
             enabled: true,
         };
         require_valid_reserve_metadata(&e, &metadata);
@@ -1085,22 +1637,30 @@ mod tests {
 
     #[test]
     #[should_panic(expected = "Error(Contract, #1202)")]
-    fn test_validate_reserve_metadata_validates_max_util() {
+    fn test_validate_reserve_metadata_validates_decimals() {
         let e = Env::default();
 
         let metadata = ReserveConfig {
             index: 0,
-            decimals: 18,
+            decimals: 19,
             c_factor: 0_7500000,
             l_factor: 0_7500000,
             util: 0_5000000,
-            max_util: 1_0000001,
+            max_util: 0_9500000,
             r_base: 0_0001000,
             r_one: 0_0500000,
             r_two: 0_5000000,
             r_three: 1_5000000,
             reactivity: 100,
             collateral_cap: 1000000000000000000,
+            twap_records: 0,
+            borrow_cap: 1000000000000000000,
+            supply_cap: 1000000000000000000,
+            isolated: false,
+            debt_ceiling: 0,
+            ir_model: 0,
+            util_two: 0,
+            flash_fee: 0,
             enabled: true,
         };
         require_valid_reserve_metadata(&e, &metadata);
@@ -1108,22 +1668,30 @@ mod tests {
 
     #[test]
     #[should_panic(expected = "Error(Contract, #1202)")]
-    fn test_validate_reserve_metadata_validates_r_base_too_high() {
+    fn test_validate_reserve_metadata_validates_c_factor() {
         let e = Env::default();
 
         let metadata = ReserveConfig {
             index: 0,
             decimals: 18,
-            c_factor: 0_7500000,
+            c_factor: 1_0000001,
             l_factor: 0_7500000,
             util: 0_5000000,
             max_util: 0_9500000,
-            r_base: 1_0000000,
+            r_base: 0_0001000,
             r_one: 0_0500000,
             r_two: 0_5000000,
             r_three: 1_5000000,
             reactivity: 100,
             collateral_cap: 1000000000000000000,
+            twap_records: 0,
+            borrow_cap: 1000000000000000000,
+            supply_cap: 1000000000000000000,
+            isolated: false,
+            debt_ceiling: 0,
+            ir_model: 0,
+            util_two: 0,
+            flash_fee: 0,
             enabled: true,
         };
         require_valid_reserve_metadata(&e, &metadata);
@@ -1131,7 +1699,131 @@ mod tests {
 
     #[test]
     #[should_panic(expected = "Error(Contract, #1202)")]
-    fn test_validate_reserve_metadata_validates_r_base_too_low() {
+    fn test_validate_reserve_metadata_validates_l_factor() {
+        let e = Env::default();
+
+        let metadata = ReserveConfig {
+            index: 0,
+            decimals: 18,
+            c_factor: 0_7500000,
+            l_factor: 1_0000001,
+            util: 0_5000000,
+            max_util: 0_9500000,
+            r_base: 0_0001000,
+            r_one: 0_0500000,
+            r_two: 0_5000000,
+            r_three: 1_5000000,
+            reactivity: 100,
+            collateral_cap: 1000000000000000000,
+            twap_records: 0,
+            borrow_cap: 1000000000000000000,
+            supply_cap: 1000000000000000000,
+            isolated: false,
+            debt_ceiling: 0,
+            ir_model: 0,
+            util_two: 0,
+            flash_fee: 0,
+            enabled: true,
+        };
+        require_valid_reserve_metadata(&e, &metadata);
+    }
+
+    #[test]
+    #[should_panic(expected = "Error(Contract, #1202)")]
+    fn test_validate_reserve_metadata_validates_util() {
+        let e = Env::default();
+
+        let metadata = ReserveConfig {
+            index: 0,
+            decimals: 18,
+            c_factor: 0_7500000,
+            l_factor: 0_7500000,
+            util: 0_9000001,
+            max_util: 0_9500000,
+            r_base: 0_0001000,
+            r_one: 0_0500000,
+            r_two: 0_5000000,
+            r_three: 1_5000000,
+            reactivity: 100,
+            collateral_cap: 1000000000000000000,
+            twap_records: 0,
+            borrow_cap: 1000000000000000000,
+            supply_cap: 1000000000000000000,
+            isolated: false,
+            debt_ceiling: 0,
+            ir_model: 0,
+            util_two: 0,
+            flash_fee: 0,
+            enabled: true,
+        };
+        require_valid_reserve_metadata(&e, &metadata);
+    }
+
+    #[test]
+    #[should_panic(expected = "Error(Contract, #1202)")]
+    fn test_validate_reserve_metadata_validates_max_util() {
+        let e = Env::default();
+
+        let metadata = ReserveConfig {
+            index: 0,
+            decimals: 18,
+            c_factor: 0_7500000,
+            l_factor: 0_7500000,
+            util: 0_5000000,
+            max_util: 1_0000001,
+            r_base: 0_0001000,
+            r_one: 0_0500000,
+            r_two: 0_5000000,
+            r_three: 1_5000000,
+            reactivity: 100,
+            collateral_cap: 1000000000000000000,
+            twap_records: 0,
+            borrow_cap: 1000000000000000000,
+            supply_cap: 1000000000000000000,
+            isolated: false,
+            debt_ceiling: 0,
+            ir_model: 0,
+            util_two: 0,
+            flash_fee: 0,
+            enabled: true,
+        };
+        require_valid_reserve_metadata(&e, &metadata);
+    }
+
+    #[test]
+    #[should_panic(expected = "Error(Contract, #1202)")]
+    fn test_validate_reserve_metadata_validates_r_base_too_high() {
+        let e = Env::default();
+
+        let metadata = ReserveConfig {
+            index: 0,
+            decimals: 18,
+            c_factor: 0_7500000,
+            l_factor: 0_7500000,
+            util: 0_5000000,
+            max_util: 0_9500000,
+            r_base: 1_0000000,
+            r_one: 0_0500000,
+            r_two: 0_5000000,
+            r_three: 1_5000000,
+            reactivity: 100,
+            collateral_cap: 1000000000000000000,
+            twap_records: 0,
+            borrow_cap: 1000000000000000000,
+            supply_cap: 1000000000000000000,
+            isolated: false,
+            debt_ceiling: 0,
+            ir_model: 0,
+            util_two: 0,
+            flash_fee: 0,
+            enabled: true,
+        };
+        require_valid_reserve_metadata(&e, &metadata);
+    }
+
+    #[test]
+    #[should_panic(expected = "Error(Contract, #1202)")]
+    fn test_validate_reserve_metadata_validates_r_base_too_low() {
         let e = Env::default();
 
         let metadata = ReserveConfig {
@@ -1147,6 +1839,14 @@ mod tests {
             r_three: 1_5000000,
             reactivity: 100,
             collateral_cap: 1000000000000000000,
+            twap_records: 0,
+            borrow_cap: 1000000000000000000,
+            supply_cap: 1000000000000000000,
+            isolated: false,
+            debt_ceiling: 0,
+            ir_model: 0,
+            util_two: 0,
+            flash_fee: 0,
             enabled: true,
         };
         require_valid_reserve_metadata(&e, &metadata);
@@ -1170,6 +1870,14 @@ mod tests {
             r_three: 1_5000000,
             reactivity: 100,
             collateral_cap: 1000000000000000000,
+            twap_records: 0,
+            borrow_cap: 1000000000000000000,
+            supply_cap: 1000000000000000000,
+            isolated: false,
+            debt_ceiling: 0,
+            ir_model: 0,
+            util_two: 0,
+            flash_fee: 0,
             enabled: true,
         };
         require_valid_reserve_metadata(&e, &metadata);
@@ -1193,8 +1901,587 @@ mod tests {
             r_three: 1_5000000,
             reactivity: 0_0001001,
             collateral_cap: 1000000000000000000,
+            twap_records: 0,
+            borrow_cap: 1000000000000000000,
+            supply_cap: 1000000000000000000,
+            isolated: false,
+            debt_ceiling: 0,
+            ir_model: 0,
+            util_two: 0,
+            flash_fee: 0,
+            enabled: true,
+        };
+        require_valid_reserve_metadata(&e, &metadata);
+    }
+
+    #[test]
+    #[should_panic(expected = "Error(Contract, #1202)")]
+    fn test_validate_reserve_metadata_validates_twap_records() {
+        let e = Env::default();
+
+        let metadata = ReserveConfig {
+            index: 0,
+            decimals: 7,
+            c_factor: 0_7500000,
+            l_factor: 0_7500000,
+            util: 0_5000000,
+            max_util: 0_9500000,
+            r_base: 0_0100000,
+            r_one: 0_0500000,
+            r_two: 0_5000000,
+            r_three: 1_5000000,
+            reactivity: 100,
+            collateral_cap: 1000000000000000000,
+            twap_records: MAX_TWAP_RECORDS + 1,
+            borrow_cap: 1000000000000000000,
+            supply_cap: 1000000000000000000,
+            isolated: false,
+            debt_ceiling: 0,
+            ir_model: 0,
+            util_two: 0,
+            flash_fee: 0,
             enabled: true,
         };
         require_valid_reserve_metadata(&e, &metadata);
     }
+
+    #[test]
+    #[should_panic(expected = "Error(Contract, #1202)")]
+    fn test_validate_reserve_metadata_validates_borrow_cap() {
+        let e = Env::default();
+
+        let metadata = ReserveConfig {
+            index: 0,
+            decimals: 7,
+            c_factor: 0_7500000,
+            l_factor: 0_7500000,
+            util: 0_5000000,
+            max_util: 0_9500000,
+            r_base: 0_0100000,
+            r_one: 0_0500000,
+            r_two: 0_5000000,
+            r_three: 1_5000000,
+            reactivity: 100,
+            collateral_cap: 1000000000000000000,
+            twap_records: 0,
+            borrow_cap: -1,
+            supply_cap: 1000000000000000000,
+            isolated: false,
+            debt_ceiling: 0,
+            ir_model: 0,
+            util_two: 0,
+            flash_fee: 0,
+            enabled: true,
+        };
+        require_valid_reserve_metadata(&e, &metadata);
+    }
+
+    #[test]
+    #[should_panic(expected = "Error(Contract, #1202)")]
+    fn test_validate_reserve_metadata_validates_flash_fee() {
+        let e = Env::default();
+
+        let metadata = ReserveConfig {
+            index: 0,
+            decimals: 7,
+            c_factor: 0_7500000,
+            l_factor: 0_7500000,
+            util: 0_5000000,
+            max_util: 0_9500000,
+            r_base: 0_0100000,
+            r_one: 0_0500000,
+            r_two: 0_5000000,
+            r_three: 1_5000000,
+            reactivity: 100,
+            collateral_cap: 1000000000000000000,
+            twap_records: 0,
+            borrow_cap: 1000000000000000000,
+            supply_cap: 1000000000000000000,
+            isolated: false,
+            debt_ceiling: 0,
+            ir_model: 0,
+            util_two: 0,
+            flash_fee: 0_1000001,
+            enabled: true,
+        };
+        require_valid_reserve_metadata(&e, &metadata);
+    }
+
+    #[test]
+    #[should_panic(expected = "Error(Contract, #1202)")]
+    fn test_validate_reserve_metadata_validates_supply_cap() {
+        let e = Env::default();
+
+        let metadata = ReserveConfig {
+            index: 0,
+            decimals: 7,
+            c_factor: 0_7500000,
+            l_factor: 0_7500000,
+            util: 0_5000000,
+            max_util: 0_9500000,
+            r_base: 0_0100000,
+            r_one: 0_0500000,
+            r_two: 0_5000000,
+            r_three: 1_5000000,
+            reactivity: 100,
+            collateral_cap: 1000000000000000000,
+            twap_records: 0,
+            borrow_cap: 1000000000000000000,
+            supply_cap: -1,
+            isolated: false,
+            debt_ceiling: 0,
+            ir_model: 0,
+            util_two: 0,
+            flash_fee: 0,
+            enabled: true,
+        };
+        require_valid_reserve_metadata(&e, &metadata);
+    }
+
+    #[test]
+    fn test_queue_set_oracle_config() {
+        let e = Env::default();
+        e.mock_all_auths();
+        let pool = testutils::create_pool(&e);
+        let oracles = testutils::create_mock_oracles(&e, 3, &vec![&e, Address::generate(&e)], 7);
+
+        let pool_config = PoolConfig {
+            oracle: oracles[0].0.clone(),
+            min_collateral: 1_0000000,
+            bstop_rate: 0_1000000,
+            status: 0,
+            max_positions: 2,
+        };
+        let oracle_config = OracleConfig {
+            oracles: vec![&e, oracles[1].0.clone(), oracles[2].0.clone()],
+            quorum: 2,
+        };
+        e.as_contract(&pool, || {
+            storage::set_pool_config(&e, &pool_config);
+            execute_queue_set_oracle_config(&e, &oracle_config);
+            let queued_init = storage::get_queued_oracle_config(&e);
+            assert_eq!(queued_init.new_config.oracles, oracle_config.oracles);
+            assert_eq!(queued_init.new_config.quorum, 2);
+            assert_eq!(
+                queued_init.unlock_time,
+                e.ledger().timestamp() + SECONDS_PER_WEEK
+            );
+            // active config is unchanged until set
+            assert_eq!(storage::get_oracle_config(&e).oracles.len(), 0);
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "Error(Contract, #1200)")]
+    fn test_queue_set_oracle_config_already_queued() {
+        let e = Env::default();
+        e.mock_all_auths();
+        let pool = testutils::create_pool(&e);
+        let oracles = testutils::create_mock_oracles(&e, 2, &vec![&e, Address::generate(&e)], 7);
+
+        let pool_config = PoolConfig {
+            oracle: oracles[0].0.clone(),
+            min_collateral: 1_0000000,
+            bstop_rate: 0_1000000,
+            status: 6,
+            max_positions: 2,
+        };
+        let oracle_config = OracleConfig {
+            oracles: vec![&e, oracles[1].0.clone()],
+            quorum: 0,
+        };
+        e.as_contract(&pool, || {
+            storage::set_pool_config(&e, &pool_config);
+            execute_queue_set_oracle_config(&e, &oracle_config);
+            execute_queue_set_oracle_config(&e, &oracle_config);
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "Error(Contract, #1201)")]
+    fn test_queue_set_oracle_config_duplicate_oracle() {
+        let e = Env::default();
+        e.mock_all_auths();
+        let pool = testutils::create_pool(&e);
+        let oracles = testutils::create_mock_oracles(&e, 2, &vec![&e, Address::generate(&e)], 7);
+
+        let backup = oracles[1].0.clone();
+        let pool_config = PoolConfig {
+            oracle: oracles[0].0.clone(),
+            min_collateral: 1_0000000,
+            bstop_rate: 0_1000000,
+            status: 0,
+            max_positions: 2,
+        };
+        let oracle_config = OracleConfig {
+            oracles: vec![&e, backup.clone(), backup],
+            quorum: 0,
+        };
+        e.as_contract(&pool, || {
+            storage::set_pool_config(&e, &pool_config);
+            execute_queue_set_oracle_config(&e, &oracle_config);
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "Error(Contract, #1201)")]
+    fn test_queue_set_oracle_config_primary_as_backup() {
+        let e = Env::default();
+        e.mock_all_auths();
+        let pool = testutils::create_pool(&e);
+        let oracles = testutils::create_mock_oracles(&e, 1, &vec![&e, Address::generate(&e)], 7);
+
+        let oracle = oracles[0].0.clone();
+        let pool_config = PoolConfig {
+            oracle: oracle.clone(),
+            min_collateral: 1_0000000,
+            bstop_rate: 0_1000000,
+            status: 0,
+            max_positions: 2,
+        };
+        let oracle_config = OracleConfig {
+            oracles: vec![&e, oracle],
+            quorum: 0,
+        };
+        e.as_contract(&pool, || {
+            storage::set_pool_config(&e, &pool_config);
+            execute_queue_set_oracle_config(&e, &oracle_config);
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "Error(Contract, #1201)")]
+    fn test_queue_set_oracle_config_quorum_too_large() {
+        let e = Env::default();
+        e.mock_all_auths();
+        let pool = testutils::create_pool(&e);
+        let oracles = testutils::create_mock_oracles(&e, 2, &vec![&e, Address::generate(&e)], 7);
+
+        let pool_config = PoolConfig {
+            oracle: oracles[0].0.clone(),
+            min_collateral: 1_0000000,
+            bstop_rate: 0_1000000,
+            status: 0,
+            max_positions: 2,
+        };
+        let oracle_config = OracleConfig {
+            oracles: vec![&e, oracles[1].0.clone()],
+            quorum: 3,
+        };
+        e.as_contract(&pool, || {
+            storage::set_pool_config(&e, &pool_config);
+            execute_queue_set_oracle_config(&e, &oracle_config);
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "Error(Contract, #1201)")]
+    fn test_queue_set_oracle_config_decimals_mismatch() {
+        let e = Env::default();
+        e.mock_all_auths();
+        let pool = testutils::create_pool(&e);
+        let oracles = testutils::create_mock_oracles(&e, 1, &vec![&e, Address::generate(&e)], 7);
+        let backups = testutils::create_mock_oracles(&e, 1, &vec![&e, Address::generate(&e)], 14);
+
+        let pool_config = PoolConfig {
+            oracle: oracles[0].0.clone(),
+            min_collateral: 1_0000000,
+            bstop_rate: 0_1000000,
+            status: 0,
+            max_positions: 2,
+        };
+        let oracle_config = OracleConfig {
+            oracles: vec![&e, backups[0].0.clone()],
+            quorum: 0,
+        };
+        e.as_contract(&pool, || {
+            storage::set_pool_config(&e, &pool_config);
+            execute_queue_set_oracle_config(&e, &oracle_config);
+        });
+    }
+
+    #[test]
+    fn test_execute_cancel_queued_set_oracle_config() {
+        let e = Env::default();
+        e.mock_all_auths();
+        let pool = testutils::create_pool(&e);
+
+        e.as_contract(&pool, || {
+            storage::set_queued_oracle_config(
+                &e,
+                &QueuedOracleInit {
+                    new_config: OracleConfig {
+                        oracles: vec![&e, Address::generate(&e)],
+                        quorum: 0,
+                    },
+                    unlock_time: e.ledger().timestamp(),
+                },
+            );
+            execute_cancel_queued_set_oracle_config(&e);
+            assert!(!storage::has_queued_oracle_config(&e));
+        });
+    }
+
+    #[test]
+    fn test_execute_set_oracle_config() {
+        let e = Env::default();
+        e.mock_all_auths();
+        let pool = testutils::create_pool(&e);
+
+        let oracle_config = OracleConfig {
+            oracles: vec![&e, Address::generate(&e), Address::generate(&e)],
+            quorum: 2,
+        };
+        e.as_contract(&pool, || {
+            storage::set_queued_oracle_config(
+                &e,
+                &QueuedOracleInit {
+                    new_config: oracle_config.clone(),
+                    unlock_time: e.ledger().timestamp(),
+                },
+            );
+            let result = execute_set_oracle_config(&e);
+            assert_eq!(result.oracles, oracle_config.oracles);
+            assert_eq!(result.quorum, 2);
+            let stored = storage::get_oracle_config(&e);
+            assert_eq!(stored.oracles, oracle_config.oracles);
+            assert_eq!(stored.quorum, 2);
+            assert!(!storage::has_queued_oracle_config(&e));
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "Error(Contract, #1203)")]
+    fn test_execute_set_oracle_config_requires_unlock() {
+        let e = Env::default();
+        e.mock_all_auths();
+        let pool = testutils::create_pool(&e);
+
+        e.as_contract(&pool, || {
+            storage::set_queued_oracle_config(
+                &e,
+                &QueuedOracleInit {
+                    new_config: OracleConfig {
+                        oracles: vec![&e, Address::generate(&e)],
+                        quorum: 0,
+                    },
+                    unlock_time: e.ledger().timestamp() + 1,
+                },
+            );
+            execute_set_oracle_config(&e);
+        });
+    }
+
+    #[test]
+    fn test_queue_set_auction_config() {
+        let e = Env::default();
+        e.mock_all_auths();
+        let pool = testutils::create_pool(&e);
+        e.ledger().set(LedgerInfo {
+            timestamp: 10000,
+            protocol_version: 22,
+            sequence_number: 100,
+            network_id: Default::default(),
+            base_reserve: 10,
+            min_temp_entry_ttl: 172800,
+            min_persistent_entry_ttl: 172800,
+            max_entry_ttl: 9999999,
+        });
+
+        let pool_config = PoolConfig {
+            oracle: Address::generate(&e),
+            min_collateral: 1_0000000,
+            bstop_rate: 0_1000000,
+            status: 0,
+            max_positions: 2,
+        };
+        let auction_config = map![
+            &e,
+            (
+                0,
+                AuctionCurve {
+                    lot_ramp: 100,
+                    bid_decay: 300,
+                    start_premium: 0_1000000,
+                }
+            )
+        ];
+        e.as_contract(&pool, || {
+            storage::set_pool_config(&e, &pool_config);
+            execute_queue_set_auction_config(&e, &auction_config);
+            let queued = storage::get_queued_auction_config(&e);
+            assert_eq!(queued.unlock_time, 10000 + SECONDS_PER_WEEK);
+            let curve = queued.new_config.get_unchecked(0);
+            assert_eq!(curve.lot_ramp, 100);
+            assert_eq!(curve.bid_decay, 300);
+            assert_eq!(curve.start_premium, 0_1000000);
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "Error(Contract, #1201)")]
+    fn test_queue_set_auction_config_invalid_type() {
+        let e = Env::default();
+        e.mock_all_auths();
+        let pool = testutils::create_pool(&e);
+
+        let auction_config = map![&e, (3, AuctionCurve::default())];
+        e.as_contract(&pool, || {
+            execute_queue_set_auction_config(&e, &auction_config);
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "Error(Contract, #1201)")]
+    fn test_queue_set_auction_config_zero_ramp() {
+        let e = Env::default();
+        e.mock_all_auths();
+        let pool = testutils::create_pool(&e);
+
+        let auction_config = map![
+            &e,
+            (
+                1,
+                AuctionCurve {
+                    lot_ramp: 0,
+                    bid_decay: 200,
+                    start_premium: 0,
+                }
+            )
+        ];
+        e.as_contract(&pool, || {
+            execute_queue_set_auction_config(&e, &auction_config);
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "Error(Contract, #1201)")]
+    fn test_queue_set_auction_config_premium_too_large() {
+        let e = Env::default();
+        e.mock_all_auths();
+        let pool = testutils::create_pool(&e);
+
+        let auction_config = map![
+            &e,
+            (
+                2,
+                AuctionCurve {
+                    lot_ramp: 200,
+                    bid_decay: 200,
+                    start_premium: 1_0000000,
+                }
+            )
+        ];
+        e.as_contract(&pool, || {
+            execute_queue_set_auction_config(&e, &auction_config);
+        });
+    }
+
+    #[test]
+    fn test_execute_set_auction_config() {
+        let e = Env::default();
+        e.mock_all_auths();
+        let pool = testutils::create_pool(&e);
+
+        let curve = AuctionCurve {
+            lot_ramp: 50,
+            bid_decay: 400,
+            start_premium: 0,
+        };
+        e.as_contract(&pool, || {
+            storage::set_queued_auction_config(
+                &e,
+                &QueuedAuctionInit {
+                    new_config: map![&e, (1, curve.clone())],
+                    unlock_time: e.ledger().timestamp(),
+                },
+            );
+            let result = execute_set_auction_config(&e);
+            assert_eq!(result.len(), 1);
+            let stored = storage::get_auction_curve(&e, 1);
+            assert_eq!(stored.lot_ramp, 50);
+            assert_eq!(stored.bid_decay, 400);
+            // unset auction types use the default curve
+            let default = storage::get_auction_curve(&e, 0);
+            assert_eq!(default.lot_ramp, 200);
+            assert_eq!(default.bid_decay, 200);
+            assert_eq!(default.start_premium, 0);
+            assert!(!storage::has_queued_auction_config(&e));
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "Error(Contract, #1203)")]
+    fn test_execute_set_auction_config_requires_unlock() {
+        let e = Env::default();
+        e.mock_all_auths();
+        let pool = testutils::create_pool(&e);
+
+        e.as_contract(&pool, || {
+            storage::set_queued_auction_config(
+                &e,
+                &QueuedAuctionInit {
+                    new_config: map![&e, (0, AuctionCurve::default())],
+                    unlock_time: e.ledger().timestamp() + 1,
+                },
+            );
+            execute_set_auction_config(&e);
+        });
+    }
+
+    #[test]
+    fn test_execute_cancel_queued_set_auction_config() {
+        let e = Env::default();
+        e.mock_all_auths();
+        let pool = testutils::create_pool(&e);
+
+        e.as_contract(&pool, || {
+            storage::set_queued_auction_config(
+                &e,
+                &QueuedAuctionInit {
+                    new_config: map![&e, (0, AuctionCurve::default())],
+                    unlock_time: e.ledger().timestamp(),
+                },
+            );
+            execute_cancel_queued_set_auction_config(&e);
+            assert!(!storage::has_queued_auction_config(&e));
+        });
+    }
+
+    #[test]
+    fn test_execute_set_keeper_config() {
+        let e = Env::default();
+        let pool = testutils::create_pool(&e);
+
+        e.as_contract(&pool, || {
+            assert_eq!(storage::get_keeper_config(&e).lot_share, 0);
+            execute_set_keeper_config(
+                &e,
+                &KeeperConfig {
+                    lot_share: 0_0050000,
+                    bad_debt_share: MAX_KEEPER_SHARE,
+                },
+            );
+            let config = storage::get_keeper_config(&e);
+            assert_eq!(config.lot_share, 0_0050000);
+            assert_eq!(config.bad_debt_share, MAX_KEEPER_SHARE);
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "Error(Contract, #1201)")]
+    fn test_execute_set_keeper_config_share_too_large() {
+        let e = Env::default();
+        let pool = testutils::create_pool(&e);
+
+        e.as_contract(&pool, || {
+            execute_set_keeper_config(
+                &e,
+                &KeeperConfig {
+                    lot_share: MAX_KEEPER_SHARE + 1,
+                    bad_debt_share: 0,
+                },
+            );
+        });
+    }
 }
@@ -0,0 +1,373 @@
+use soroban_sdk::{panic_with_error, vec, Address, Env};
+
+use crate::{
+    errors::PoolError,
+    events::PoolEvents,
+    storage::{self, CreditAllowance},
+};
+
+use super::{
+    actions::{Request, RequestType},
+    submit::execute_submit,
+    Positions,
+};
+
+/// Set the amount of an asset a delegate can borrow against a delegator's positions. Setting an
+/// amount of zero revokes the allowance.
+///
+/// ### Arguments
+/// * `delegator` - The address of the user whose positions are borrowed against
+/// * `delegate` - The address of the user allowed to borrow
+/// * `asset` - The underlying asset the delegate can borrow
+/// * `amount` - The amount of underlying tokens the delegate can borrow
+/// * `expiration` - The time the allowance expires
+///
+/// ### Panics
+/// If the delegator and delegate are the same, the asset is not a reserve, or the allowance is
+/// already expired
+pub fn execute_approve_delegation(
+    e: &Env,
+    delegator: &Address,
+    delegate: &Address,
+    asset: &Address,
+    amount: i128,
+    expiration: u64,
+) {
+    if amount < 0 {
+        panic_with_error!(e, PoolError::NegativeAmountError);
+    }
+    if delegator == delegate || !storage::has_res(e, asset) {
+        panic_with_error!(e, PoolError::InvalidDelegation);
+    }
+
+    let mut allowances = storage::get_credit_delegation(e, delegator, delegate);
+    if amount == 0 {
+        allowances.remove(asset.clone());
+    } else {
+        if expiration <= e.ledger().timestamp() {
+            panic_with_error!(e, PoolError::InvalidDelegation);
+        }
+        allowances.set(asset.clone(), CreditAllowance { amount, expiration });
+    }
+    storage::set_credit_delegation(e, delegator, delegate, &allowances);
+}
+
+/// Borrow an asset against a delegator's positions using a credit allowance. The liability is
+/// recorded against the delegator and the allowance is decremented by the amount borrowed.
+///
+/// ### Arguments
+/// * `delegator` - The address of the user whose positions are borrowed against
+/// * `delegate` - The address of the user borrowing with the allowance
+/// * `to` - The address receiving the borrowed tokens
+/// * `asset` - The underlying asset to borrow
+/// * `amount` - The amount of underlying tokens to borrow
+///
+/// ### Returns
+/// * Positions - The delegator's positions after the borrow
+///
+/// ### Panics
+/// If the allowance is expired or insufficient, or if the borrow is not able to be completed
+pub fn execute_delegated_borrow(
+    e: &Env,
+    delegator: &Address,
+    delegate: &Address,
+    to: &Address,
+    asset: &Address,
+    amount: i128,
+) -> Positions {
+    if amount < 0 {
+        panic_with_error!(e, PoolError::NegativeAmountError);
+    }
+    let mut allowances = storage::get_credit_delegation(e, delegator, delegate);
+    let mut allowance = match allowances.get(asset.clone()) {
+        Some(allowance)
+            if allowance.expiration > e.ledger().timestamp() && allowance.amount >= amount =>
+        {
+            allowance
+        }
+        _ => panic_with_error!(e, PoolError::InsufficientDelegation),
+    };
+    allowance.amount -= amount;
+    if allowance.amount == 0 {
+        allowances.remove(asset.clone());
+    } else {
+        allowances.set(asset.clone(), allowance.clone());
+    }
+    storage::set_credit_delegation(e, delegator, delegate, &allowances);
+
+    let requests = vec![
+        e,
+        Request {
+            request_type: RequestType::Borrow as u32,
+            address: asset.clone(),
+            amount,
+        },
+    ];
+    let positions = execute_submit(e, delegator, delegate, to, requests, false);
+
+    PoolEvents::delegated_borrow(
+        e,
+        asset.clone(),
+        delegator.clone(),
+        delegate.clone(),
+        amount,
+        allowance.amount,
+    );
+    positions
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        storage::PoolConfig,
+        testutils::{self, create_pool},
+    };
+    use sep_40_oracle::testutils::Asset;
+    use sep_41_token::testutils::MockTokenClient;
+    use soroban_sdk::{
+        map,
+        testutils::{Address as _, Ledger, LedgerInfo},
+        Symbol,
+    };
+
+    /// Setup a pool with two reserves where samwise has supplied collateral of the first reserve
+    fn setup_pool(e: &Env) -> (Address, Address, Address, Address) {
+        let bombadil = Address::generate(e);
+        let samwise = Address::generate(e);
+        let pool = create_pool(e);
+        let (oracle, oracle_client) = testutils::create_mock_oracle(e);
+
+        let (underlying_0, _) = testutils::create_token_contract(e, &bombadil);
+        let (reserve_config, reserve_data) = testutils::default_reserve_meta();
+        testutils::create_reserve(e, &pool, &underlying_0, &reserve_config, &reserve_data);
+
+        let (underlying_1, _) = testutils::create_token_contract(e, &bombadil);
+        let (reserve_config, reserve_data) = testutils::default_reserve_meta();
+        testutils::create_reserve(e, &pool, &underlying_1, &reserve_config, &reserve_data);
+
+        oracle_client.set_data(
+            &bombadil,
+            &Asset::Other(Symbol::new(e, "USD")),
+            &vec![
+                e,
+                Asset::Stellar(underlying_0.clone()),
+                Asset::Stellar(underlying_1.clone()),
+            ],
+            &7,
+            &300,
+        );
+        oracle_client.set_price_stable(&vec![e, 1_0000000, 5_0000000]);
+
+        let pool_config = PoolConfig {
+            oracle,
+            min_collateral: 1_0000000,
+            bstop_rate: 0_1000000,
+            status: 0,
+            max_positions: 4,
+        };
+        e.as_contract(&pool, || {
+            storage::set_pool_config(e, &pool_config);
+            storage::set_user_positions(
+                e,
+                &samwise,
+                &Positions {
+                    liabilities: map![e],
+                    collateral: map![e, (0, 100_0000000)],
+                    supply: map![e],
+                },
+            );
+        });
+        (pool, samwise, underlying_0, underlying_1)
+    }
+
+    #[test]
+    fn test_execute_approve_delegation() {
+        let e = Env::default();
+        e.mock_all_auths();
+        e.ledger().set(LedgerInfo {
+            timestamp: 1000,
+            protocol_version: 22,
+            sequence_number: 1234,
+            network_id: Default::default(),
+            base_reserve: 10,
+            min_temp_entry_ttl: 10,
+            min_persistent_entry_ttl: 10,
+            max_entry_ttl: 3110400,
+        });
+
+        let (pool, samwise, _, underlying_1) = setup_pool(&e);
+        let frodo = Address::generate(&e);
+
+        e.as_contract(&pool, || {
+            execute_approve_delegation(&e, &samwise, &frodo, &underlying_1, 5_0000000, 2000);
+
+            let allowances = storage::get_credit_delegation(&e, &samwise, &frodo);
+            let allowance = allowances.get_unchecked(underlying_1.clone());
+            assert_eq!(allowance.amount, 5_0000000);
+            assert_eq!(allowance.expiration, 2000);
+            assert_eq!(
+                storage::get_credit_delegation(&e, &frodo, &samwise).len(),
+                0
+            );
+
+            // an amount of zero revokes the allowance
+            execute_approve_delegation(&e, &samwise, &frodo, &underlying_1, 0, 0);
+            assert_eq!(
+                storage::get_credit_delegation(&e, &samwise, &frodo).len(),
+                0
+            );
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "Error(Contract, #1232)")]
+    fn test_execute_approve_delegation_expired() {
+        let e = Env::default();
+        e.mock_all_auths();
+        e.ledger().set(LedgerInfo {
+            timestamp: 1000,
+            protocol_version: 22,
+            sequence_number: 1234,
+            network_id: Default::default(),
+            base_reserve: 10,
+            min_temp_entry_ttl: 10,
+            min_persistent_entry_ttl: 10,
+            max_entry_ttl: 3110400,
+        });
+
+        let (pool, samwise, _, underlying_1) = setup_pool(&e);
+        let frodo = Address::generate(&e);
+
+        e.as_contract(&pool, || {
+            execute_approve_delegation(&e, &samwise, &frodo, &underlying_1, 5_0000000, 1000);
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "Error(Contract, #1232)")]
+    fn test_execute_approve_delegation_to_self() {
+        let e = Env::default();
+        e.mock_all_auths();
+        e.ledger().set(LedgerInfo {
+            timestamp: 1000,
+            protocol_version: 22,
+            sequence_number: 1234,
+            network_id: Default::default(),
+            base_reserve: 10,
+            min_temp_entry_ttl: 10,
+            min_persistent_entry_ttl: 10,
+            max_entry_ttl: 3110400,
+        });
+
+        let (pool, samwise, _, underlying_1) = setup_pool(&e);
+
+        e.as_contract(&pool, || {
+            execute_approve_delegation(&e, &samwise, &samwise, &underlying_1, 5_0000000, 2000);
+        });
+    }
+
+    #[test]
+    fn test_execute_delegated_borrow() {
+        let e = Env::default();
+        e.cost_estimate().budget().reset_unlimited();
+        e.mock_all_auths_allowing_non_root_auth();
+        e.ledger().set(LedgerInfo {
+            timestamp: 1000,
+            protocol_version: 22,
+            sequence_number: 1234,
+            network_id: Default::default(),
+            base_reserve: 10,
+            min_temp_entry_ttl: 10,
+            min_persistent_entry_ttl: 10,
+            max_entry_ttl: 3110400,
+        });
+
+        let (pool, samwise, _, underlying_1) = setup_pool(&e);
+        let frodo = Address::generate(&e);
+        let merry = Address::generate(&e);
+        let underlying_1_client = MockTokenClient::new(&e, &underlying_1);
+
+        e.as_contract(&pool, || {
+            execute_approve_delegation(&e, &samwise, &frodo, &underlying_1, 5_0000000, 2000);
+
+            let positions =
+                execute_delegated_borrow(&e, &samwise, &frodo, &merry, &underlying_1, 2_0000000);
+
+            // the liability is recorded against the delegator
+            assert_eq!(positions.liabilities.get_unchecked(1), 1_9999962);
+            assert_eq!(
+                storage::get_user_positions(&e, &samwise)
+                    .liabilities
+                    .get_unchecked(1),
+                1_9999962
+            );
+            assert_eq!(storage::get_user_positions(&e, &frodo).liabilities.len(), 0);
+            assert_eq!(underlying_1_client.balance(&merry), 2_0000000);
+
+            let allowance = storage::get_credit_delegation(&e, &samwise, &frodo)
+                .get_unchecked(underlying_1.clone());
+            assert_eq!(allowance.amount, 3_0000000);
+
+            // borrowing the remaining allowance removes it
+            execute_delegated_borrow(&e, &samwise, &frodo, &merry, &underlying_1, 3_0000000);
+            assert_eq!(
+                storage::get_credit_delegation(&e, &samwise, &frodo).len(),
+                0
+            );
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "Error(Contract, #1233)")]
+    fn test_execute_delegated_borrow_over_allowance() {
+        let e = Env::default();
+        e.cost_estimate().budget().reset_unlimited();
+        e.mock_all_auths_allowing_non_root_auth();
+        e.ledger().set(LedgerInfo {
+            timestamp: 1000,
+            protocol_version: 22,
+            sequence_number: 1234,
+            network_id: Default::default(),
+            base_reserve: 10,
+            min_temp_entry_ttl: 10,
+            min_persistent_entry_ttl: 10,
+            max_entry_ttl: 3110400,
+        });
+
+        let (pool, samwise, _, underlying_1) = setup_pool(&e);
+        let frodo = Address::generate(&e);
+
+        e.as_contract(&pool, || {
+            execute_approve_delegation(&e, &samwise, &frodo, &underlying_1, 5_0000000, 2000);
+            execute_delegated_borrow(&e, &samwise, &frodo, &frodo, &underlying_1, 5_0000001);
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "Error(Contract, #1233)")]
+    fn test_execute_delegated_borrow_expired() {
+        let e = Env::default();
+        e.cost_estimate().budget().reset_unlimited();
+        e.mock_all_auths_allowing_non_root_auth();
+        e.ledger().set(LedgerInfo {
+            timestamp: 1000,
+            protocol_version: 22,
+            sequence_number: 1234,
+            network_id: Default::default(),
+            base_reserve: 10,
+            min_temp_entry_ttl: 10,
+            min_persistent_entry_ttl: 10,
+            max_entry_ttl: 3110400,
+        });
+
+        let (pool, samwise, _, underlying_1) = setup_pool(&e);
+        let frodo = Address::generate(&e);
+
+        e.as_contract(&pool, || {
+            execute_approve_delegation(&e, &samwise, &frodo, &underlying_1, 5_0000000, 2000);
+            e.ledger().set_timestamp(2000);
+            execute_delegated_borrow(&e, &samwise, &frodo, &frodo, &underlying_1, 1_0000000);
+        });
+    }
+}
@@ -0,0 +1,535 @@
+use cast::i128;
+use soroban_fixed_point_math::SorobanFixedPoint;
+use soroban_sdk::{panic_with_error, Address, Env};
+
+use crate::{
+    constants::{MAX_DELISTING_DUST, MIN_DELISTING_PERIOD, SCALAR_7},
+    errors::PoolError,
+    storage::{self, ReserveDelisting},
+    AuctionType,
+};
+
+use super::{config::calc_ramped_factors, isolation::update_isolated_debt, Pool, User};
+
+/// Calculate the collateral factor of a reserve being delisted at the current ledger time
+///
+/// ### Arguments
+/// * `delisting` - The delisting schedule of the reserve
+///
+/// ### Returns
+/// * u32 - The collateral factor expressed in 7 decimals
+pub fn calc_delisting_c_factor(e: &Env, delisting: &ReserveDelisting) -> u32 {
+    let now = e.ledger().timestamp();
+    if now >= delisting.end_time {
+        return 0;
+    }
+    if now <= delisting.start_time {
+        return delisting.c_factor;
+    }
+    let remaining_time = i128(delisting.end_time - now);
+    let period = i128(delisting.end_time - delisting.start_time);
+    i128(delisting.c_factor).fixed_mul_floor(e, &remaining_time, &period) as u32
+}
+
+/// Check if a reserve has finished delisting
+///
+/// ### Arguments
+/// * `asset` - The underlying asset of the reserve
+pub fn is_delisted(e: &Env, asset: &Address) -> bool {
+    let now = e.ledger().timestamp();
+    storage::get_res_delisting(e, asset).is_some_and(|delisting| delisting.end_time <= now)
+}
+
+/// Start delisting a reserve. The reserve is disabled, and its collateral factor ramps to zero
+/// over the delisting period.
+///
+/// ### Arguments
+/// * `asset` - The underlying asset of the reserve
+/// * `period` - The number of seconds the collateral factor ramps to zero over
+///
+/// ### Panics
+/// If the asset is not a reserve, the reserve is already being delisted, or the period is
+/// shorter than the minimum delisting period
+pub fn execute_delist_reserve(e: &Env, asset: &Address, period: u64) -> ReserveDelisting {
+    if !storage::has_res(e, asset)
+        || storage::has_res_delisting(e, asset)
+        || period < MIN_DELISTING_PERIOD
+    {
+        panic_with_error!(e, PoolError::InvalidDelisting);
+    }
+    let mut reserve_config = storage::get_res_config(e, asset);
+    // start the delisting from the effective factors of any ongoing factor ramp
+    if let Some(ramp) = storage::get_res_factor_ramp(e, asset) {
+        (reserve_config.c_factor, reserve_config.l_factor) =
+            calc_ramped_factors(e, &ramp, &reserve_config);
+        storage::del_res_factor_ramp(e, asset);
+    }
+    reserve_config.enabled = false;
+    storage::set_res_config(e, asset, &reserve_config);
+
+    let now = e.ledger().timestamp();
+    let delisting = ReserveDelisting {
+        c_factor: reserve_config.c_factor,
+        start_time: now,
+        end_time: now + period,
+    };
+    storage::set_res_delisting(e, asset, &delisting);
+    delisting
+}
+
+/// Clean a user's remaining positions in a delisted reserve. Collateral is moved to the user's
+/// uncollateralized supply, and liabilities valued at or under the dust limit are defaulted on.
+/// Larger liabilities can be liquidated regardless of the user's health factor instead.
+///
+/// ### Arguments
+/// * `user` - The address of the user
+/// * `asset` - The underlying asset of the delisted reserve
+///
+/// ### Returns
+/// * (i128, i128) - (b_tokens moved to supply, d_tokens defaulted on)
+///
+/// ### Panics
+/// If the reserve has not finished delisting, the user has an ongoing liquidation, or the user
+/// has no positions to clean
+pub fn execute_clean_delisted_position(e: &Env, user: &Address, asset: &Address) -> (i128, i128) {
+    if !is_delisted(e, asset) {
+        panic_with_error!(e, PoolError::InvalidDelisting);
+    }
+    if storage::has_auction(e, &(AuctionType::UserLiquidation as u32), user) {
+        panic_with_error!(e, PoolError::AuctionInProgress);
+    }
+
+    let mut pool = Pool::load(e);
+    let mut user_state = User::load(e, user);
+    let prev_positions = user_state.positions.clone();
+    let mut reserve = pool.load_reserve(e, asset, true);
+
+    let b_tokens = user_state.get_collateral(reserve.config.index);
+    if b_tokens > 0 {
+        user_state.remove_collateral(e, &mut reserve, b_tokens);
+        user_state.add_supply(e, &mut reserve, b_tokens);
+    }
+
+    let mut d_tokens_defaulted = 0;
+    let d_tokens = user_state.get_liabilities(reserve.config.index);
+    if d_tokens > 0 {
+        // the dust limit is a value, so it does not grow with the price of the asset
+        let oracle_scalar = 10i128.pow(pool.load_price_decimals(e));
+        let dust_limit = MAX_DELISTING_DUST.fixed_mul_floor(e, &oracle_scalar, &SCALAR_7);
+        let liability_base = pool.load_price(e, asset).fixed_mul_ceil(
+            e,
+            &reserve.to_asset_from_d_token(e, d_tokens),
+            &reserve.scalar,
+        );
+        if liability_base <= dust_limit {
+            user_state.default_liabilities(e, &mut reserve, d_tokens);
+            d_tokens_defaulted = d_tokens;
+        }
+    }
+
+    if b_tokens == 0 && d_tokens_defaulted == 0 {
+        panic_with_error!(e, PoolError::InvalidDelisting);
+    }
+
+    pool.cache_reserve(reserve);
+    update_isolated_debt(e, &mut pool, &prev_positions, &user_state.positions, false);
+    pool.store_cached_reserves(e);
+    user_state.store(e);
+    (b_tokens, d_tokens_defaulted)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        storage::{PoolConfig, ReserveFactorRamp},
+        testutils, AuctionData, Positions,
+    };
+    use sep_40_oracle::testutils::Asset;
+    use soroban_sdk::{
+        map,
+        testutils::{Address as _, Ledger, LedgerInfo},
+        vec, Symbol,
+    };
+
+    #[test]
+    fn test_calc_delisting_c_factor() {
+        let e = Env::default();
+        let delisting = ReserveDelisting {
+            c_factor: 0_8000000,
+            start_time: 1000,
+            end_time: 5000,
+        };
+
+        e.ledger().set(LedgerInfo {
+            timestamp: 500,
+            protocol_version: 22,
+            sequence_number: 1234,
+            network_id: Default::default(),
+            base_reserve: 10,
+            min_temp_entry_ttl: 10,
+            min_persistent_entry_ttl: 10,
+            max_entry_ttl: 3110400,
+        });
+        assert_eq!(calc_delisting_c_factor(&e, &delisting), 0_8000000);
+
+        e.ledger().set_timestamp(2000);
+        assert_eq!(calc_delisting_c_factor(&e, &delisting), 0_6000000);
+
+        e.ledger().set_timestamp(4999);
+        assert_eq!(calc_delisting_c_factor(&e, &delisting), 0_0002000);
+
+        e.ledger().set_timestamp(5000);
+        assert_eq!(calc_delisting_c_factor(&e, &delisting), 0);
+    }
+
+    #[test]
+    fn test_execute_delist_reserve() {
+        let e = Env::default();
+        e.mock_all_auths();
+        e.ledger().set(LedgerInfo {
+            timestamp: 1000,
+            protocol_version: 22,
+            sequence_number: 1234,
+            network_id: Default::default(),
+            base_reserve: 10,
+            min_temp_entry_ttl: 10,
+            min_persistent_entry_ttl: 10,
+            max_entry_ttl: 3110400,
+        });
+        let bombadil = Address::generate(&e);
+        let pool = testutils::create_pool(&e);
+
+        let (underlying, _) = testutils::create_token_contract(&e, &bombadil);
+        let (reserve_config, reserve_data) = testutils::default_reserve_meta();
+        testutils::create_reserve(&e, &pool, &underlying, &reserve_config, &reserve_data);
+
+        e.as_contract(&pool, || {
+            let delisting = execute_delist_reserve(&e, &underlying, MIN_DELISTING_PERIOD);
+            assert_eq!(delisting.c_factor, reserve_config.c_factor);
+            assert_eq!(delisting.start_time, 1000);
+            assert_eq!(delisting.end_time, 1000 + MIN_DELISTING_PERIOD);
+
+            let stored = storage::get_res_delisting(&e, &underlying).unwrap();
+            assert_eq!(stored.c_factor, reserve_config.c_factor);
+            assert_eq!(stored.end_time, 1000 + MIN_DELISTING_PERIOD);
+            assert_eq!(storage::get_res_config(&e, &underlying).enabled, false);
+        });
+    }
+
+    #[test]
+    fn test_execute_delist_reserve_during_factor_ramp() {
+        let e = Env::default();
+        e.mock_all_auths();
+        e.ledger().set(LedgerInfo {
+            timestamp: 1000,
+            protocol_version: 22,
+            sequence_number: 1234,
+            network_id: Default::default(),
+            base_reserve: 10,
+            min_temp_entry_ttl: 10,
+            min_persistent_entry_ttl: 10,
+            max_entry_ttl: 3110400,
+        });
+        let bombadil = Address::generate(&e);
+        let pool = testutils::create_pool(&e);
+
+        let (underlying, _) = testutils::create_token_contract(&e, &bombadil);
+        let (reserve_config, reserve_data) = testutils::default_reserve_meta();
+        testutils::create_reserve(&e, &pool, &underlying, &reserve_config, &reserve_data);
+
+        e.as_contract(&pool, || {
+            storage::set_res_factor_ramp(
+                &e,
+                &underlying,
+                &ReserveFactorRamp {
+                    c_factor: 0_9500000,
+                    l_factor: 1_0000000,
+                    start_time: 0,
+                    end_time: 2000,
+                },
+            );
+
+            let delisting = execute_delist_reserve(&e, &underlying, MIN_DELISTING_PERIOD);
+            assert_eq!(delisting.c_factor, 0_8500000);
+
+            let stored_config = storage::get_res_config(&e, &underlying);
+            assert_eq!(stored_config.c_factor, 0_8500000);
+            assert_eq!(stored_config.l_factor, 0_8750000);
+            assert!(storage::get_res_factor_ramp(&e, &underlying).is_none());
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "Error(Contract, #1231)")]
+    fn test_execute_delist_reserve_period_too_short() {
+        let e = Env::default();
+        e.mock_all_auths();
+        let bombadil = Address::generate(&e);
+        let pool = testutils::create_pool(&e);
+
+        let (underlying, _) = testutils::create_token_contract(&e, &bombadil);
+        let (reserve_config, reserve_data) = testutils::default_reserve_meta();
+        testutils::create_reserve(&e, &pool, &underlying, &reserve_config, &reserve_data);
+
+        e.as_contract(&pool, || {
+            execute_delist_reserve(&e, &underlying, MIN_DELISTING_PERIOD - 1);
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "Error(Contract, #1231)")]
+    fn test_execute_delist_reserve_already_delisting() {
+        let e = Env::default();
+        e.mock_all_auths();
+        let bombadil = Address::generate(&e);
+        let pool = testutils::create_pool(&e);
+
+        let (underlying, _) = testutils::create_token_contract(&e, &bombadil);
+        let (reserve_config, reserve_data) = testutils::default_reserve_meta();
+        testutils::create_reserve(&e, &pool, &underlying, &reserve_config, &reserve_data);
+
+        e.as_contract(&pool, || {
+            execute_delist_reserve(&e, &underlying, MIN_DELISTING_PERIOD);
+            execute_delist_reserve(&e, &underlying, MIN_DELISTING_PERIOD);
+        });
+    }
+
+    #[test]
+    fn test_execute_clean_delisted_position() {
+        let e = Env::default();
+        e.mock_all_auths();
+        e.ledger().set(LedgerInfo {
+            timestamp: 1000 + MIN_DELISTING_PERIOD,
+            protocol_version: 22,
+            sequence_number: 1234,
+            network_id: Default::default(),
+            base_reserve: 10,
+            min_temp_entry_ttl: 10,
+            min_persistent_entry_ttl: 10,
+            max_entry_ttl: 3110400,
+        });
+        let bombadil = Address::generate(&e);
+        let samwise = Address::generate(&e);
+        let pool = testutils::create_pool(&e);
+
+        let (underlying, _) = testutils::create_token_contract(&e, &bombadil);
+        let (mut reserve_config, mut reserve_data) = testutils::default_reserve_meta();
+        reserve_config.enabled = false;
+        reserve_data.last_time = 1000 + MIN_DELISTING_PERIOD;
+        testutils::create_reserve(&e, &pool, &underlying, &reserve_config, &reserve_data);
+
+        let (oracle, oracle_client) = testutils::create_mock_oracle(&e);
+        oracle_client.set_data(
+            &bombadil,
+            &Asset::Other(Symbol::new(&e, "USD")),
+            &vec![&e, Asset::Stellar(underlying.clone())],
+            &7,
+            &300,
+        );
+        oracle_client.set_price_stable(&vec![&e, 1_0000000]);
+
+        let pool_config = PoolConfig {
+            oracle,
+            min_collateral: 1_0000000,
+            bstop_rate: 0_2000000,
+            status: 0,
+            max_positions: 2,
+        };
+        let positions = Positions {
+            liabilities: map![&e, (0, 0_0100000)],
+            collateral: map![&e, (0, 20_0000000)],
+            supply: map![&e, (0, 5_0000000)],
+        };
+        e.as_contract(&pool, || {
+            storage::set_pool_config(&e, &pool_config);
+            storage::set_user_positions(&e, &samwise, &positions);
+            storage::set_res_delisting(
+                &e,
+                &underlying,
+                &ReserveDelisting {
+                    c_factor: 0_7500000,
+                    start_time: 1000,
+                    end_time: 1000 + MIN_DELISTING_PERIOD,
+                },
+            );
+
+            let (b_tokens, d_tokens) = execute_clean_delisted_position(&e, &samwise, &underlying);
+            assert_eq!(b_tokens, 20_0000000);
+            assert_eq!(d_tokens, 0_0100000);
+
+            let user = User::load(&e, &samwise);
+            assert_eq!(user.positions.collateral.len(), 0);
+            assert_eq!(user.positions.liabilities.len(), 0);
+            assert_eq!(user.get_supply(0), 25_0000000);
+
+            let new_reserve_data = storage::get_res_data(&e, &underlying);
+            assert_eq!(new_reserve_data.b_supply, reserve_data.b_supply);
+            assert_eq!(new_reserve_data.d_supply, reserve_data.d_supply - 0_0100000);
+            assert_eq!(new_reserve_data.b_rate, 999_900_000_000);
+        });
+    }
+
+    #[test]
+    fn test_execute_clean_delisted_position_keeps_liabilities_over_dust() {
+        let e = Env::default();
+        e.mock_all_auths();
+        e.ledger().set(LedgerInfo {
+            timestamp: 1000 + MIN_DELISTING_PERIOD,
+            protocol_version: 22,
+            sequence_number: 1234,
+            network_id: Default::default(),
+            base_reserve: 10,
+            min_temp_entry_ttl: 10,
+            min_persistent_entry_ttl: 10,
+            max_entry_ttl: 3110400,
+        });
+        let bombadil = Address::generate(&e);
+        let samwise = Address::generate(&e);
+        let pool = testutils::create_pool(&e);
+
+        let (underlying, _) = testutils::create_token_contract(&e, &bombadil);
+        let (mut reserve_config, mut reserve_data) = testutils::default_reserve_meta();
+        reserve_config.enabled = false;
+        reserve_data.last_time = 1000 + MIN_DELISTING_PERIOD;
+        testutils::create_reserve(&e, &pool, &underlying, &reserve_config, &reserve_data);
+
+        let (oracle, oracle_client) = testutils::create_mock_oracle(&e);
+        oracle_client.set_data(
+            &bombadil,
+            &Asset::Other(Symbol::new(&e, "USD")),
+            &vec![&e, Asset::Stellar(underlying.clone())],
+            &7,
+            &300,
+        );
+        oracle_client.set_price_stable(&vec![&e, 100_0000000]);
+
+        let pool_config = PoolConfig {
+            oracle,
+            min_collateral: 1_0000000,
+            bstop_rate: 0_2000000,
+            status: 0,
+            max_positions: 2,
+        };
+        let positions = Positions {
+            // worth 0.1 of the base asset, over the dust limit
+            liabilities: map![&e, (0, 0_0010000)],
+            collateral: map![&e, (0, 20_0000000)],
+            supply: map![&e],
+        };
+        e.as_contract(&pool, || {
+            storage::set_pool_config(&e, &pool_config);
+            storage::set_user_positions(&e, &samwise, &positions);
+            storage::set_res_delisting(
+                &e,
+                &underlying,
+                &ReserveDelisting {
+                    c_factor: 0_7500000,
+                    start_time: 1000,
+                    end_time: 1000 + MIN_DELISTING_PERIOD,
+                },
+            );
+
+            let (b_tokens, d_tokens) = execute_clean_delisted_position(&e, &samwise, &underlying);
+            assert_eq!(b_tokens, 20_0000000);
+            assert_eq!(d_tokens, 0);
+
+            let user = User::load(&e, &samwise);
+            assert_eq!(user.positions.collateral.len(), 0);
+            assert_eq!(user.get_liabilities(0), 0_0010000);
+            assert_eq!(user.get_supply(0), 20_0000000);
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "Error(Contract, #1231)")]
+    fn test_execute_clean_delisted_position_before_end() {
+        let e = Env::default();
+        e.mock_all_auths();
+        e.ledger().set(LedgerInfo {
+            timestamp: 999 + MIN_DELISTING_PERIOD,
+            protocol_version: 22,
+            sequence_number: 1234,
+            network_id: Default::default(),
+            base_reserve: 10,
+            min_temp_entry_ttl: 10,
+            min_persistent_entry_ttl: 10,
+            max_entry_ttl: 3110400,
+        });
+        let bombadil = Address::generate(&e);
+        let samwise = Address::generate(&e);
+        let pool = testutils::create_pool(&e);
+
+        let (underlying, _) = testutils::create_token_contract(&e, &bombadil);
+        let (reserve_config, reserve_data) = testutils::default_reserve_meta();
+        testutils::create_reserve(&e, &pool, &underlying, &reserve_config, &reserve_data);
+
+        let positions = Positions {
+            liabilities: map![&e],
+            collateral: map![&e, (0, 20_0000000)],
+            supply: map![&e],
+        };
+        e.as_contract(&pool, || {
+            storage::set_user_positions(&e, &samwise, &positions);
+            storage::set_res_delisting(
+                &e,
+                &underlying,
+                &ReserveDelisting {
+                    c_factor: 0_7500000,
+                    start_time: 1000,
+                    end_time: 1000 + MIN_DELISTING_PERIOD,
+                },
+            );
+
+            execute_clean_delisted_position(&e, &samwise, &underlying);
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "Error(Contract, #1212)")]
+    fn test_execute_clean_delisted_position_auction_in_progress() {
+        let e = Env::default();
+        e.mock_all_auths();
+        e.ledger().set(LedgerInfo {
+            timestamp: 1000 + MIN_DELISTING_PERIOD,
+            protocol_version: 22,
+            sequence_number: 1234,
+            network_id: Default::default(),
+            base_reserve: 10,
+            min_temp_entry_ttl: 10,
+            min_persistent_entry_ttl: 10,
+            max_entry_ttl: 3110400,
+        });
+        let bombadil = Address::generate(&e);
+        let samwise = Address::generate(&e);
+        let pool = testutils::create_pool(&e);
+
+        let (underlying, _) = testutils::create_token_contract(&e, &bombadil);
+        let (reserve_config, reserve_data) = testutils::default_reserve_meta();
+        testutils::create_reserve(&e, &pool, &underlying, &reserve_config, &reserve_data);
+
+        e.as_contract(&pool, || {
+            storage::set_res_delisting(
+                &e,
+                &underlying,
+                &ReserveDelisting {
+                    c_factor: 0_7500000,
+                    start_time: 1000,
+                    end_time: 1000 + MIN_DELISTING_PERIOD,
+                },
+            );
+            storage::set_auction(
+                &e,
+                &(AuctionType::UserLiquidation as u32),
+                &samwise,
+                &AuctionData {
+                    bid: map![&e],
+                    lot: map![&e],
+                    block: 100,
+                },
+            );
+
+            execute_clean_delisted_position(&e, &samwise, &underlying);
+        });
+    }
+}
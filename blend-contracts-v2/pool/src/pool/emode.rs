@@ -0,0 +1,405 @@
+use soroban_sdk::{panic_with_error, Env};
+
+use crate::{
+    constants::{SCALAR_7, SECONDS_PER_WEEK},
+    errors::PoolError,
+    storage::{self, EModeCategory, QueuedEModeInit},
+};
+
+/// Queue setting an e-mode category
+///
+/// ### Arguments
+/// * `category_id` - The id of the e-mode category
+/// * `category` - The e-mode category
+///
+/// ### Panics
+/// If the category is already queued or is invalid
+pub fn execute_queue_set_emode_category(e: &Env, category_id: u32, category: &EModeCategory) {
+    if storage::has_queued_emode_category(e, category_id) {
+        panic_with_error!(e, PoolError::BadRequest);
+    }
+    require_valid_emode_category(e, category_id, category);
+    let mut unlock_time = e.ledger().timestamp();
+    // require a timelock if pool status is not setup
+    if storage::get_pool_config(e).status != 6 {
+        unlock_time += SECONDS_PER_WEEK;
+    }
+    storage::set_queued_emode_category(
+        e,
+        category_id,
+        &QueuedEModeInit {
+            new_category: category.clone(),
+            unlock_time,
+        },
+    );
+}
+
+/// Cancel a queued e-mode category set
+///
+/// ### Arguments
+/// * `category_id` - The id of the e-mode category
+pub fn execute_cancel_queued_set_emode_category(e: &Env, category_id: u32) {
+    storage::del_queued_emode_category(e, category_id);
+}
+
+/// Execute a queued e-mode category set
+///
+/// ### Arguments
+/// * `category_id` - The id of the e-mode category
+///
+/// ### Panics
+/// If the category is not queued, is not unlocked, or is no longer valid
+pub fn execute_set_emode_category(e: &Env, category_id: u32) -> EModeCategory {
+    let queued_init = storage::get_queued_emode_category(e, category_id);
+    if queued_init.unlock_time > e.ledger().timestamp() {
+        panic_with_error!(e, PoolError::InitNotUnlocked);
+    }
+    // the reserves' factors may have changed since the category was queued
+    require_valid_emode_category(e, category_id, &queued_init.new_category);
+
+    storage::del_queued_emode_category(e, category_id);
+    storage::set_emode_category(e, category_id, &queued_init.new_category);
+    queued_init.new_category
+}
+
+/// Require that an e-mode category is valid
+///
+/// ### Panics
+/// If the category id is 0, the category factors are invalid, any asset is not a reserve or is
+/// included more than once, or the category factors are below any reserve's factors
+fn require_valid_emode_category(e: &Env, category_id: u32, category: &EModeCategory) {
+    if category_id == 0
+        || category.c_factor > SCALAR_7 as u32
+        || category.l_factor > SCALAR_7 as u32
+        || category.l_factor == 0
+    {
+        panic_with_error!(e, PoolError::InvalidEModeCategory);
+    }
+    for (index, asset) in category.assets.iter().enumerate() {
+        if !storage::has_res(e, &asset)
+            || category.assets.first_index_of(&asset) != Some(index as u32)
+        {
+            panic_with_error!(e, PoolError::InvalidEModeCategory);
+        }
+        let reserve_config = storage::get_res_config(e, &asset);
+        if category.c_factor < reserve_config.c_factor
+            || category.l_factor < reserve_config.l_factor
+        {
+            panic_with_error!(e, PoolError::InvalidEModeCategory);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testutils;
+    use soroban_sdk::{
+        testutils::{Address as _, Ledger},
+        vec, Address,
+    };
+
+    #[test]
+    fn test_execute_set_emode_category() {
+        let e = Env::default();
+        e.mock_all_auths();
+        let bombadil = Address::generate(&e);
+        let pool = testutils::create_pool(&e);
+
+        let (underlying_0, _) = testutils::create_token_contract(&e, &bombadil);
+        let (reserve_config, reserve_data) = testutils::default_reserve_meta();
+        testutils::create_reserve(&e, &pool, &underlying_0, &reserve_config, &reserve_data);
+
+        let (underlying_1, _) = testutils::create_token_contract(&e, &bombadil);
+        let (reserve_config, reserve_data) = testutils::default_reserve_meta();
+        testutils::create_reserve(&e, &pool, &underlying_1, &reserve_config, &reserve_data);
+
+        let category = EModeCategory {
+            c_factor: 0_9500000,
+            l_factor: 0_9700000,
+            assets: vec![&e, underlying_0.clone(), underlying_1.clone()],
+        };
+        e.as_contract(&pool, || {
+            execute_queue_set_emode_category(&e, 1, &category);
+            let queued_init = storage::get_queued_emode_category(&e, 1);
+            assert_eq!(queued_init.new_category.assets, category.assets);
+            assert_eq!(queued_init.unlock_time, e.ledger().timestamp());
+            assert!(storage::get_emode_category(&e, 1).is_none());
+
+            let set_category = execute_set_emode_category(&e, 1);
+            assert_eq!(set_category.c_factor, category.c_factor);
+            assert!(!storage::has_queued_emode_category(&e, 1));
+            let stored = storage::get_emode_category(&e, 1).unwrap();
+            assert_eq!(stored.c_factor, category.c_factor);
+            assert_eq!(stored.l_factor, category.l_factor);
+            assert_eq!(stored.assets, category.assets);
+            assert!(storage::get_emode_category(&e, 2).is_none());
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "Error(Contract, #1229)")]
+    fn test_execute_set_emode_category_zero_id() {
+        let e = Env::default();
+        e.mock_all_auths();
+        let bombadil = Address::generate(&e);
+        let pool = testutils::create_pool(&e);
+
+        let (underlying_0, _) = testutils::create_token_contract(&e, &bombadil);
+        let (reserve_config, reserve_data) = testutils::default_reserve_meta();
+        testutils::create_reserve(&e, &pool, &underlying_0, &reserve_config, &reserve_data);
+
+        let category = EModeCategory {
+            c_factor: 0_9500000,
+            l_factor: 0_9700000,
+            assets: vec![&e, underlying_0.clone()],
+        };
+        e.as_contract(&pool, || {
+            execute_queue_set_emode_category(&e, 0, &category);
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "Error(Contract, #1229)")]
+    fn test_execute_set_emode_category_c_factor_over_max() {
+        let e = Env::default();
+        e.mock_all_auths();
+        let bombadil = Address::generate(&e);
+        let pool = testutils::create_pool(&e);
+
+        let (underlying_0, _) = testutils::create_token_contract(&e, &bombadil);
+        let (reserve_config, reserve_data) = testutils::default_reserve_meta();
+        testutils::create_reserve(&e, &pool, &underlying_0, &reserve_config, &reserve_data);
+
+        let category = EModeCategory {
+            c_factor: 1_0000001,
+            l_factor: 0_9700000,
+            assets: vec![&e, underlying_0.clone()],
+        };
+        e.as_contract(&pool, || {
+            execute_queue_set_emode_category(&e, 1, &category);
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "Error(Contract, #1229)")]
+    fn test_execute_set_emode_category_l_factor_over_max() {
+        let e = Env::default();
+        e.mock_all_auths();
+        let bombadil = Address::generate(&e);
+        let pool = testutils::create_pool(&e);
+
+        let (underlying_0, _) = testutils::create_token_contract(&e, &bombadil);
+        let (reserve_config, reserve_data) = testutils::default_reserve_meta();
+        testutils::create_reserve(&e, &pool, &underlying_0, &reserve_config, &reserve_data);
+
+        let category = EModeCategory {
+            c_factor: 0_9500000,
+            l_factor: 1_0000001,
+            assets: vec![&e, underlying_0.clone()],
+        };
+        e.as_contract(&pool, || {
+            execute_queue_set_emode_category(&e, 1, &category);
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "Error(Contract, #1229)")]
+    fn test_execute_set_emode_category_zero_l_factor() {
+        let e = Env::default();
+        e.mock_all_auths();
+        let bombadil = Address::generate(&e);
+        let pool = testutils::create_pool(&e);
+
+        let (underlying_0, _) = testutils::create_token_contract(&e, &bombadil);
+        let (reserve_config, reserve_data) = testutils::default_reserve_meta();
+        testutils::create_reserve(&e, &pool, &underlying_0, &reserve_config, &reserve_data);
+
+        let category = EModeCategory {
+            c_factor: 0_9500000,
+            l_factor: 0,
+            assets: vec![&e, underlying_0.clone()],
+        };
+        e.as_contract(&pool, || {
+            execute_queue_set_emode_category(&e, 1, &category);
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "Error(Contract, #1229)")]
+    fn test_execute_set_emode_category_requires_reserve() {
+        let e = Env::default();
+        e.mock_all_auths();
+        let bombadil = Address::generate(&e);
+        let pool = testutils::create_pool(&e);
+
+        let (underlying_0, _) = testutils::create_token_contract(&e, &bombadil);
+        let (reserve_config, reserve_data) = testutils::default_reserve_meta();
+        testutils::create_reserve(&e, &pool, &underlying_0, &reserve_config, &reserve_data);
+
+        let category = EModeCategory {
+            c_factor: 0_9500000,
+            l_factor: 0_9700000,
+            assets: vec![&e, underlying_0.clone(), Address::generate(&e)],
+        };
+        e.as_contract(&pool, || {
+            execute_queue_set_emode_category(&e, 1, &category);
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "Error(Contract, #1229)")]
+    fn test_execute_set_emode_category_duplicate() {
+        let e = Env::default();
+        e.mock_all_auths();
+        let bombadil = Address::generate(&e);
+        let pool = testutils::create_pool(&e);
+
+        let (underlying_0, _) = testutils::create_token_contract(&e, &bombadil);
+        let (reserve_config, reserve_data) = testutils::default_reserve_meta();
+        testutils::create_reserve(&e, &pool, &underlying_0, &reserve_config, &reserve_data);
+
+        let category = EModeCategory {
+            c_factor: 0_9500000,
+            l_factor: 0_9700000,
+            assets: vec![&e, underlying_0.clone(), underlying_0.clone()],
+        };
+        e.as_contract(&pool, || {
+            execute_queue_set_emode_category(&e, 1, &category);
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "Error(Contract, #1229)")]
+    fn test_execute_queue_set_emode_category_below_reserve_factor() {
+        let e = Env::default();
+        e.mock_all_auths();
+        let bombadil = Address::generate(&e);
+        let pool = testutils::create_pool(&e);
+
+        let (underlying_0, _) = testutils::create_token_contract(&e, &bombadil);
+        let (reserve_config, reserve_data) = testutils::default_reserve_meta();
+        testutils::create_reserve(&e, &pool, &underlying_0, &reserve_config, &reserve_data);
+
+        let (underlying_1, _) = testutils::create_token_contract(&e, &bombadil);
+        let (mut reserve_config, reserve_data) = testutils::default_reserve_meta();
+        reserve_config.c_factor = 0_9600000;
+        testutils::create_reserve(&e, &pool, &underlying_1, &reserve_config, &reserve_data);
+
+        let category = EModeCategory {
+            c_factor: 0_9500000,
+            l_factor: 0_9700000,
+            assets: vec![&e, underlying_0.clone(), underlying_1.clone()],
+        };
+        e.as_contract(&pool, || {
+            execute_queue_set_emode_category(&e, 1, &category);
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "Error(Contract, #1200)")]
+    fn test_execute_queue_set_emode_category_already_queued() {
+        let e = Env::default();
+        e.mock_all_auths();
+        let bombadil = Address::generate(&e);
+        let pool = testutils::create_pool(&e);
+
+        let (underlying_0, _) = testutils::create_token_contract(&e, &bombadil);
+        let (reserve_config, reserve_data) = testutils::default_reserve_meta();
+        testutils::create_reserve(&e, &pool, &underlying_0, &reserve_config, &reserve_data);
+
+        let category = EModeCategory {
+            c_factor: 0_9500000,
+            l_factor: 0_9700000,
+            assets: vec![&e, underlying_0.clone()],
+        };
+        e.as_contract(&pool, || {
+            execute_queue_set_emode_category(&e, 1, &category);
+            execute_queue_set_emode_category(&e, 1, &category);
+        });
+    }
+
+    #[test]
+    fn test_execute_cancel_queued_set_emode_category() {
+        let e = Env::default();
+        e.mock_all_auths();
+        let bombadil = Address::generate(&e);
+        let pool = testutils::create_pool(&e);
+
+        let (underlying_0, _) = testutils::create_token_contract(&e, &bombadil);
+        let (reserve_config, reserve_data) = testutils::default_reserve_meta();
+        testutils::create_reserve(&e, &pool, &underlying_0, &reserve_config, &reserve_data);
+
+        let category = EModeCategory {
+            c_factor: 0_9500000,
+            l_factor: 0_9700000,
+            assets: vec![&e, underlying_0.clone()],
+        };
+        e.as_contract(&pool, || {
+            execute_queue_set_emode_category(&e, 1, &category);
+            execute_cancel_queued_set_emode_category(&e, 1);
+            assert!(!storage::has_queued_emode_category(&e, 1));
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "Error(Contract, #1203)")]
+    fn test_execute_set_emode_category_requires_timelock() {
+        let e = Env::default();
+        e.mock_all_auths();
+        e.ledger().set_timestamp(1000);
+        let bombadil = Address::generate(&e);
+        let pool = testutils::create_pool(&e);
+
+        let (underlying_0, _) = testutils::create_token_contract(&e, &bombadil);
+        let (reserve_config, reserve_data) = testutils::default_reserve_meta();
+        testutils::create_reserve(&e, &pool, &underlying_0, &reserve_config, &reserve_data);
+
+        let category = EModeCategory {
+            c_factor: 0_9500000,
+            l_factor: 0_9700000,
+            assets: vec![&e, underlying_0.clone()],
+        };
+        e.as_contract(&pool, || {
+            let mut pool_config = storage::get_pool_config(&e);
+            pool_config.status = 0;
+            storage::set_pool_config(&e, &pool_config);
+
+            execute_queue_set_emode_category(&e, 1, &category);
+            let queued_init = storage::get_queued_emode_category(&e, 1);
+            assert_eq!(queued_init.unlock_time, 1000 + SECONDS_PER_WEEK);
+
+            e.ledger().set_timestamp(1000 + SECONDS_PER_WEEK - 1);
+            execute_set_emode_category(&e, 1);
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "Error(Contract, #1229)")]
+    fn test_execute_set_emode_category_revalidates_factors() {
+        let e = Env::default();
+        e.mock_all_auths();
+        let bombadil = Address::generate(&e);
+        let pool = testutils::create_pool(&e);
+
+        let (underlying_0, _) = testutils::create_token_contract(&e, &bombadil);
+        let (reserve_config, reserve_data) = testutils::default_reserve_meta();
+        testutils::create_reserve(&e, &pool, &underlying_0, &reserve_config, &reserve_data);
+
+        let category = EModeCategory {
+            c_factor: 0_9500000,
+            l_factor: 0_9700000,
+            assets: vec![&e, underlying_0.clone()],
+        };
+        e.as_contract(&pool, || {
+            execute_queue_set_emode_category(&e, 1, &category);
+
+            // the reserve's liability factor is raised above the category's
+            let mut reserve_config = storage::get_res_config(&e, &underlying_0);
+            reserve_config.l_factor = 0_9800000;
+            storage::set_res_config(&e, &underlying_0, &reserve_config);
+
+            execute_set_emode_category(&e, 1);
+        });
+    }
+}
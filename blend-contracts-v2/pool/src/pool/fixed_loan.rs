@@ -0,0 +1,298 @@
+use cast::i128;
+use soroban_fixed_point_math::SorobanFixedPoint;
+use soroban_sdk::{Env, Map};
+
+use crate::{
+    constants::{FIXED_LOAN_PREMIUM, SCALAR_7, SECONDS_PER_YEAR},
+    storage::{self, FixedLoan},
+};
+
+use super::{Pool, Reserve, User};
+
+/// Quote the annual rate a fixed-term loan can lock against the reserve. The rate is the reserve's
+/// current variable borrow rate plus a premium.
+///
+/// ### Arguments
+/// * `reserve` - The reserve the loan is taken against, including the new fixed-term debt
+///
+/// ### Returns
+/// * u32 - The annual rate (7 decimals)
+pub fn quote_fixed_rate(e: &Env, reserve: &Reserve) -> u32 {
+    (reserve.borrow_rate(e) + FIXED_LOAN_PREMIUM) as u32
+}
+
+/// Calculate the simple interest accrued on a fixed-term loan since it was last settled.
+///
+/// Interest accrues at the locked rate until the loan matures. After maturity, interest accrues at
+/// the greater of the locked rate and the reserve's current variable borrow rate.
+///
+/// ### Arguments
+/// * `reserve` - The reserve the loan is taken against
+/// * `loan` - The fixed-term loan
+///
+/// ### Returns
+/// * i128 - The interest accrued, in the underlying token's decimals
+pub fn calc_fixed_loan_interest(e: &Env, reserve: &Reserve, loan: &FixedLoan) -> i128 {
+    let now = e.ledger().timestamp();
+    if now <= loan.last_time {
+        return 0;
+    }
+    let locked_until = now.min(loan.maturity).max(loan.last_time);
+    let locked_time = i128(locked_until - loan.last_time);
+    let overdue_time = i128(now - locked_until);
+
+    let mut weighted_rate = i128(loan.rate) * locked_time;
+    if overdue_time > 0 {
+        weighted_rate += reserve.borrow_rate(e).max(i128(loan.rate)) * overdue_time;
+    }
+    loan.principal
+        .fixed_mul_ceil(e, &weighted_rate, &(SCALAR_7 * SECONDS_PER_YEAR))
+}
+
+/// Settle the interest accrued on a fixed-term loan. The interest is added to the loan's principal
+/// and credited to the reserve's suppliers.
+///
+/// ### Arguments
+/// * `reserve` - The reserve the loan is taken against
+/// * `bstop_rate` - The backstop take rate for the pool
+/// * `loan` - The fixed-term loan
+pub fn settle_fixed_loan(e: &Env, reserve: &mut Reserve, bstop_rate: u32, loan: &mut FixedLoan) {
+    let interest = calc_fixed_loan_interest(e, reserve, loan);
+    if interest > 0 {
+        reserve.accrue_fixed_interest(e, bstop_rate, interest);
+        loan.principal += interest;
+    }
+    loan.last_time = e.ledger().timestamp();
+}
+
+/// Convert all of a user's fixed-term loans into variable rate liabilities. Each loan is settled,
+/// and the amount owed is minted to the user as dTokens.
+///
+/// The updated reserves are cached in the pool, and neither the pool nor the user are stored.
+///
+/// ### Arguments
+/// * `pool` - The pool
+/// * `user` - The user holding the fixed-term loans
+pub fn convert_fixed_loans(e: &Env, pool: &mut Pool, user: &mut User) {
+    if user.fixed_loans.is_empty() {
+        return;
+    }
+    let reserve_list = storage::get_res_list(e);
+    for (index, mut loan) in user.fixed_loans.iter() {
+        let mut reserve = pool.load_reserve(e, &reserve_list.get_unchecked(index), true);
+        settle_fixed_loan(e, &mut reserve, pool.config.bstop_rate, &mut loan);
+        reserve.data.fixed_debt -= loan.principal;
+        let d_tokens_minted = reserve.to_d_token_up(e, loan.principal);
+        user.add_liabilities(e, &mut reserve, d_tokens_minted);
+        pool.cache_reserve(reserve);
+    }
+    user.fixed_loans = Map::new(e);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        constants::FIXED_LOAN_TERM,
+        storage::PoolConfig,
+        testutils::{self, create_pool},
+        Positions,
+    };
+    use soroban_sdk::{
+        map,
+        testutils::{Address as _, Ledger, LedgerInfo},
+        Address,
+    };
+
+    #[test]
+    fn test_quote_fixed_rate() {
+        let e = Env::default();
+
+        let reserve = testutils::default_reserve(&e);
+
+        let result = quote_fixed_rate(&e, &reserve);
+
+        assert_eq!(result, 0_0700000);
+    }
+
+    #[test]
+    fn test_calc_fixed_loan_interest_before_maturity() {
+        let e = Env::default();
+        e.ledger().set(LedgerInfo {
+            timestamp: 10000 + 31536000 / 2,
+            protocol_version: 22,
+            sequence_number: 1234,
+            network_id: Default::default(),
+            base_reserve: 10,
+            min_temp_entry_ttl: 10,
+            min_persistent_entry_ttl: 10,
+            max_entry_ttl: 3110400,
+        });
+
+        let reserve = testutils::default_reserve(&e);
+        let loan = FixedLoan {
+            principal: 100_0000000,
+            rate: 0_1000000,
+            last_time: 10000,
+            maturity: 10000 + 31536000,
+        };
+
+        let result = calc_fixed_loan_interest(&e, &reserve, &loan);
+
+        assert_eq!(result, 5_0000000);
+    }
+
+    #[test]
+    fn test_calc_fixed_loan_interest_overdue_uses_variable_rate() {
+        let e = Env::default();
+        e.ledger().set(LedgerInfo {
+            timestamp: 31536000,
+            protocol_version: 22,
+            sequence_number: 1234,
+            network_id: Default::default(),
+            base_reserve: 10,
+            min_temp_entry_ttl: 10,
+            min_persistent_entry_ttl: 10,
+            max_entry_ttl: 3110400,
+        });
+
+        // variable rate is 6%
+        let reserve = testutils::default_reserve(&e);
+        let loan = FixedLoan {
+            principal: 100_0000000,
+            rate: 0_0200000,
+            last_time: 0,
+            maturity: 31536000 / 2,
+        };
+
+        let result = calc_fixed_loan_interest(&e, &reserve, &loan);
+
+        // 1% at the locked rate, 3% at the variable rate
+        assert_eq!(result, 4_0000000);
+    }
+
+    #[test]
+    fn test_calc_fixed_loan_interest_overdue_keeps_locked_rate() {
+        let e = Env::default();
+        e.ledger().set(LedgerInfo {
+            timestamp: 31536000,
+            protocol_version: 22,
+            sequence_number: 1234,
+            network_id: Default::default(),
+            base_reserve: 10,
+            min_temp_entry_ttl: 10,
+            min_persistent_entry_ttl: 10,
+            max_entry_ttl: 3110400,
+        });
+
+        // variable rate is 6%
+        let reserve = testutils::default_reserve(&e);
+        let loan = FixedLoan {
+            principal: 100_0000000,
+            rate: 0_1000000,
+            last_time: 31536000 / 2,
+            maturity: 0,
+        };
+
+        let result = calc_fixed_loan_interest(&e, &reserve, &loan);
+
+        assert_eq!(result, 5_0000000);
+    }
+
+    #[test]
+    fn test_settle_fixed_loan() {
+        let e = Env::default();
+        e.ledger().set(LedgerInfo {
+            timestamp: 31536000 / 10,
+            protocol_version: 22,
+            sequence_number: 1234,
+            network_id: Default::default(),
+            base_reserve: 10,
+            min_temp_entry_ttl: 10,
+            min_persistent_entry_ttl: 10,
+            max_entry_ttl: 3110400,
+        });
+
+        let mut reserve = testutils::default_reserve(&e);
+        reserve.data.fixed_debt = 100_0000000;
+        let mut loan = FixedLoan {
+            principal: 100_0000000,
+            rate: 0_1000000,
+            last_time: 0,
+            maturity: FIXED_LOAN_TERM,
+        };
+
+        settle_fixed_loan(&e, &mut reserve, 0_2000000, &mut loan);
+
+        assert_eq!(loan.principal, 101_0000000);
+        assert_eq!(loan.last_time, 31536000 / 10);
+        assert_eq!(reserve.data.fixed_debt, 101_0000000);
+        assert_eq!(reserve.data.backstop_credit, 0_2000000);
+        assert_eq!(reserve.data.b_rate, 1_008_000_000_000);
+    }
+
+    #[test]
+    fn test_convert_fixed_loans() {
+        let e = Env::default();
+        e.mock_all_auths();
+        e.ledger().set(LedgerInfo {
+            timestamp: 31536000 / 10,
+            protocol_version: 22,
+            sequence_number: 1234,
+            network_id: Default::default(),
+            base_reserve: 10,
+            min_temp_entry_ttl: 10,
+            min_persistent_entry_ttl: 10,
+            max_entry_ttl: 3110400,
+        });
+
+        let bombadil = Address::generate(&e);
+        let samwise = Address::generate(&e);
+        let pool = create_pool(&e);
+
+        let (underlying, _) = testutils::create_token_contract(&e, &bombadil);
+        let (reserve_config, mut reserve_data) = testutils::default_reserve_meta();
+        reserve_data.fixed_debt = 10_0000000;
+        reserve_data.last_time = 31536000 / 10;
+        testutils::create_reserve(&e, &pool, &underlying, &reserve_config, &reserve_data);
+
+        let pool_config = PoolConfig {
+            oracle: Address::generate(&e),
+            min_collateral: 1_0000000,
+            bstop_rate: 0_2000000,
+            status: 0,
+            max_positions: 4,
+        };
+        e.as_contract(&pool, || {
+            storage::set_pool_config(&e, &pool_config);
+
+            let mut pool = Pool::load(&e);
+            let mut user = User {
+                address: samwise.clone(),
+                positions: Positions::env_default(&e),
+                emode: 0,
+                fixed_loans: map![
+                    &e,
+                    (
+                        0,
+                        FixedLoan {
+                            principal: 10_0000000,
+                            rate: 0_1000000,
+                            last_time: 0,
+                            maturity: FIXED_LOAN_TERM,
+                        }
+                    )
+                ],
+            };
+
+            convert_fixed_loans(&e, &mut pool, &mut user);
+
+            assert_eq!(user.fixed_loans.len(), 0);
+            assert_eq!(user.get_liabilities(0), 10_1000000);
+            let reserve = pool.load_reserve(&e, &underlying, false);
+            assert_eq!(reserve.data.fixed_debt, 0);
+            assert_eq!(reserve.data.d_supply, reserve_data.d_supply + 10_1000000);
+            assert_eq!(reserve.data.backstop_credit, 0_0200000);
+        });
+    }
+}
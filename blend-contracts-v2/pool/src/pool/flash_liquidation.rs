@@ -0,0 +1,295 @@
+use sep_41_token::TokenClient;
+use soroban_sdk::{contractclient, map, panic_with_error, Address, Env, Map};
+
+use crate::{
+    auctions::{self, AuctionData, AuctionType},
+    errors::PoolError,
+    events::PoolEvents,
+};
+
+use super::{actions::RequestType, pool::Pool, User};
+
+/// The interface of a contract that settles a flash liquidation
+#[contractclient(name = "LiquidationReceiverClient")]
+pub trait LiquidationReceiver {
+    /// Execute an operation with the underlying tokens of a filled liquidation lot. Once the
+    /// operation completes, `caller` must hold the bid's underlying tokens so the pool can
+    /// collect them.
+    ///
+    /// ### Arguments
+    /// * `caller` - The address of the filler
+    /// * `user` - The address of the user being liquidated
+    /// * `lot` - The underlying tokens sent to the receiver, mapped by asset
+    /// * `bid` - The underlying tokens the pool will collect from `caller`, mapped by asset
+    fn exec_liquidation(
+        e: Env,
+        caller: Address,
+        user: Address,
+        lot: Map<Address, i128>,
+        bid: Map<Address, i128>,
+    );
+}
+
+/// Fill a user liquidation auction without taking on its positions. The lot's collateral is
+/// withdrawn and its underlying is sent to the `receiver` contract, which is invoked with `filler`
+/// as the caller. Afterwards, the bid's liabilities are repaid by transferring their underlying
+/// from `filler` to the pool using `transfer_from`.
+///
+/// ### Arguments
+/// * `filler` - The address of the user filling the auction and repaying the bid
+/// * `receiver` - The address of the liquidation receiver contract
+/// * `user` - The address of the user being liquidated
+/// * `percent_filled` - The percentage of the auction being filled
+///
+/// ### Returns
+/// * AuctionData - The filled auction, in bTokens for the lot and dTokens for the bid
+///
+/// ### Panics
+/// If the pool does not allow auctions to be filled, the auction does not exist, or the bid is
+/// not repaid
+pub fn execute_flash_liquidation(
+    e: &Env,
+    filler: &Address,
+    receiver: &Address,
+    user: &Address,
+    percent_filled: u64,
+) -> AuctionData {
+    if filler == &e.current_contract_address() || receiver == &e.current_contract_address() {
+        panic_with_error!(e, PoolError::BadRequest);
+    }
+    let mut pool = Pool::load(e);
+    pool.require_action_allowed(e, RequestType::FillUserLiquidationAuction as u32);
+
+    // the filler only holds the auction's positions until they are settled below
+    let mut filler_state = User::load(e, filler);
+    let filled_auction = auctions::fill(
+        e,
+        &mut pool,
+        AuctionType::UserLiquidation as u32,
+        user,
+        &mut filler_state,
+        percent_filled,
+    );
+
+    let pool_address = e.current_contract_address();
+    let mut lot: Map<Address, i128> = map![e];
+    for (asset, b_tokens) in filled_auction.lot.iter() {
+        let mut reserve = pool.load_reserve(e, &asset, true);
+        filler_state.remove_collateral(e, &mut reserve, b_tokens);
+        let tokens_out = reserve.to_asset_from_b_token(e, b_tokens);
+        pool.cache_reserve(reserve);
+        TokenClient::new(e, &asset).transfer(&pool_address, receiver, &tokens_out);
+        lot.set(asset, tokens_out);
+    }
+    let mut bid: Map<Address, i128> = map![e];
+    for (asset, d_tokens) in filled_auction.bid.iter() {
+        let mut reserve = pool.load_reserve(e, &asset, true);
+        filler_state.remove_liabilities(e, &mut reserve, d_tokens);
+        bid.set(asset, reserve.to_asset_from_d_token(e, d_tokens));
+        pool.cache_reserve(reserve);
+    }
+
+    LiquidationReceiverClient::new(e, receiver).exec_liquidation(filler, user, &lot, &bid);
+
+    for (asset, tokens_in) in bid.iter() {
+        TokenClient::new(e, &asset).transfer_from(&pool_address, filler, &pool_address, &tokens_in);
+    }
+
+    pool.store_cached_reserves(e);
+    filler_state.store(e);
+
+    PoolEvents::flash_liquidation(e, user.clone(), filler.clone(), receiver.clone(), lot, bid);
+    PoolEvents::fill_auction(
+        e,
+        AuctionType::UserLiquidation as u32,
+        user.clone(),
+        filler.clone(),
+        percent_filled as i128,
+        filled_auction.clone(),
+    );
+    filled_auction
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        pool::Positions,
+        storage::{self, PoolConfig},
+        testutils::{self, create_pool},
+    };
+    use sep_41_token::testutils::MockTokenClient;
+    use soroban_sdk::{
+        testutils::{Address as _, Ledger, LedgerInfo},
+        vec,
+    };
+
+    fn set_sequence(e: &Env, sequence_number: u32) {
+        e.ledger().set(LedgerInfo {
+            timestamp: 12345,
+            protocol_version: 22,
+            sequence_number,
+            network_id: Default::default(),
+            base_reserve: 10,
+            min_temp_entry_ttl: 172800,
+            min_persistent_entry_ttl: 172800,
+            max_entry_ttl: 9999999,
+        });
+    }
+
+    /// Setup a pool with three reserves and a liquidation auction of samwise's position that
+    /// offers 10 of the first reserve and 1 of the second for 1 of the third
+    fn setup_liquidation(e: &Env) -> (Address, Address, Address, Address, Address) {
+        set_sequence(e, 100);
+        e.cost_estimate().budget().reset_unlimited();
+
+        let bombadil = Address::generate(e);
+        let samwise = Address::generate(e);
+        let pool = create_pool(e);
+        let (oracle, _) = testutils::create_mock_oracle(e);
+
+        let mut underlying = vec![e];
+        for _ in 0..3 {
+            let (asset, _) = testutils::create_token_contract(e, &bombadil);
+            let (reserve_config, mut reserve_data) = testutils::default_reserve_meta();
+            reserve_data.last_time = 12345;
+            testutils::create_reserve(e, &pool, &asset, &reserve_config, &reserve_data);
+            underlying.push_back(asset);
+        }
+        let underlying_0 = underlying.get_unchecked(0);
+        let underlying_1 = underlying.get_unchecked(1);
+        let underlying_2 = underlying.get_unchecked(2);
+
+        let pool_config = PoolConfig {
+            oracle,
+            min_collateral: 1_0000000,
+            bstop_rate: 0_1000000,
+            status: 0,
+            max_positions: 4,
+        };
+        let positions = Positions {
+            collateral: map![e, (0, 20_0000000), (1, 2_0000000)],
+            liabilities: map![e, (2, 2_0000000)],
+            supply: map![e],
+        };
+        let auction_data = AuctionData {
+            bid: map![e, (underlying_2.clone(), 1_0000000)],
+            lot: map![
+                e,
+                (underlying_0.clone(), 10_0000000),
+                (underlying_1.clone(), 1_0000000)
+            ],
+            block: 100,
+        };
+        e.as_contract(&pool, || {
+            storage::set_pool_config(e, &pool_config);
+            storage::set_user_positions(e, &samwise, &positions);
+            storage::set_auction(e, &0, &samwise, &auction_data);
+        });
+        (pool, samwise, underlying_0, underlying_1, underlying_2)
+    }
+
+    #[test]
+    fn test_execute_flash_liquidation() {
+        let e = Env::default();
+        e.mock_all_auths_allowing_non_root_auth();
+        let (pool, samwise, underlying_0, underlying_1, underlying_2) = setup_liquidation(&e);
+        let frodo = Address::generate(&e);
+        let (receiver, _) = testutils::create_mock_liquidation_receiver(&e);
+
+        let underlying_2_client = MockTokenClient::new(&e, &underlying_2);
+        underlying_2_client.mint(&receiver, &1_0000000);
+        underlying_2_client.approve(&frodo, &pool, &1_0000000, &10000);
+        let pool_balance_2 = underlying_2_client.balance(&pool);
+
+        // lot and bid are both at 100% after 200 blocks
+        set_sequence(&e, 300);
+        e.as_contract(&pool, || {
+            let filled = execute_flash_liquidation(&e, &frodo, &receiver, &samwise, 100);
+            assert_eq!(filled.lot.get_unchecked(underlying_0.clone()), 10_0000000);
+            assert_eq!(filled.bid.get_unchecked(underlying_2.clone()), 1_0000000);
+
+            assert!(!storage::has_auction(&e, &0, &samwise));
+            let samwise_positions = storage::get_user_positions(&e, &samwise);
+            assert_eq!(samwise_positions.collateral.get_unchecked(0), 10_0000000);
+            assert_eq!(samwise_positions.collateral.get_unchecked(1), 1_0000000);
+            assert_eq!(samwise_positions.liabilities.get_unchecked(2), 1_0000000);
+            let frodo_positions = storage::get_user_positions(&e, &frodo);
+            assert_eq!(frodo_positions.collateral.len(), 0);
+            assert_eq!(frodo_positions.liabilities.len(), 0);
+
+            assert_eq!(
+                storage::get_res_data(&e, &underlying_0).b_supply,
+                90_0000000
+            );
+            assert_eq!(
+                storage::get_res_data(&e, &underlying_1).b_supply,
+                99_0000000
+            );
+            assert_eq!(
+                storage::get_res_data(&e, &underlying_2).d_supply,
+                74_0000000
+            );
+        });
+        assert_eq!(
+            MockTokenClient::new(&e, &underlying_0).balance(&receiver),
+            10_0000000
+        );
+        assert_eq!(
+            MockTokenClient::new(&e, &underlying_1).balance(&receiver),
+            1_0000000
+        );
+        assert_eq!(underlying_2_client.balance(&receiver), 0);
+        assert_eq!(underlying_2_client.balance(&frodo), 0);
+        assert_eq!(
+            underlying_2_client.balance(&pool),
+            pool_balance_2 + 1_0000000
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_execute_flash_liquidation_bid_not_repaid() {
+        let e = Env::default();
+        e.mock_all_auths_allowing_non_root_auth();
+        let (pool, samwise, _, _, underlying_2) = setup_liquidation(&e);
+        let frodo = Address::generate(&e);
+        let (receiver, _) = testutils::create_mock_liquidation_receiver(&e);
+
+        // the receiver does not hold the bid tokens to return to frodo
+        MockTokenClient::new(&e, &underlying_2).approve(&frodo, &pool, &1_0000000, &10000);
+
+        set_sequence(&e, 300);
+        e.as_contract(&pool, || {
+            execute_flash_liquidation(&e, &frodo, &receiver, &samwise, 100);
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "Error(Contract, #1211)")]
+    fn test_execute_flash_liquidation_same_address() {
+        let e = Env::default();
+        e.mock_all_auths_allowing_non_root_auth();
+        let (pool, samwise, _, _, _) = setup_liquidation(&e);
+        let (receiver, _) = testutils::create_mock_liquidation_receiver(&e);
+
+        set_sequence(&e, 300);
+        e.as_contract(&pool, || {
+            execute_flash_liquidation(&e, &samwise, &receiver, &samwise, 100);
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "Error(Contract, #1200)")]
+    fn test_execute_flash_liquidation_pool_as_receiver() {
+        let e = Env::default();
+        e.mock_all_auths_allowing_non_root_auth();
+        let (pool, samwise, _, _, _) = setup_liquidation(&e);
+        let frodo = Address::generate(&e);
+
+        set_sequence(&e, 300);
+        e.as_contract(&pool, || {
+            execute_flash_liquidation(&e, &frodo, &pool, &samwise, 100);
+        });
+    }
+}
@@ -0,0 +1,235 @@
+use cast::i128;
+use moderc3156::FlashLoanClient;
+use sep_41_token::TokenClient;
+use soroban_fixed_point_math::SorobanFixedPoint;
+use soroban_sdk::{panic_with_error, Address, Env};
+
+use crate::{constants::SCALAR_7, errors::PoolError, events::PoolEvents};
+
+use super::{actions::RequestType, pool::Pool};
+
+/// Execute a flash loan that must be repaid, plus a fee, in the same transaction. The fee is
+/// accrued to the reserve's suppliers and backstop credit. No positions are created.
+///
+/// The tokens are sent to the `receiver` contract, which is invoked with `caller` as the caller.
+/// Afterwards, `amount` plus the fee is transferred from `caller` to the pool using
+/// `transfer_from`.
+///
+/// ### Arguments
+/// * `caller` - The address of the user taking the flash loan and repaying it
+/// * `receiver` - The address of the flash loan receiver contract
+/// * `asset` - The underlying asset to flash loan
+/// * `amount` - The amount of underlying tokens to flash loan
+///
+/// ### Returns
+/// * i128 - The fee paid, in the underlying token's decimals
+///
+/// ### Panics
+/// If the pool or reserve does not allow borrowing, or the pool balance is not restored plus the fee
+pub fn execute_flash_loan_simple(
+    e: &Env,
+    caller: &Address,
+    receiver: &Address,
+    asset: &Address,
+    amount: i128,
+) -> i128 {
+    if amount <= 0
+        || caller == &e.current_contract_address()
+        || receiver == &e.current_contract_address()
+    {
+        panic_with_error!(e, PoolError::BadRequest);
+    }
+    let mut pool = Pool::load(e);
+    pool.require_action_allowed(e, RequestType::Borrow as u32);
+    let mut reserve = pool.load_reserve(e, asset, true);
+    reserve.require_action_allowed(e, RequestType::Borrow as u32);
+
+    let fee = amount.fixed_mul_ceil(e, &i128(reserve.config.flash_fee), &SCALAR_7);
+    let token_client = TokenClient::new(e, asset);
+    let pool_address = e.current_contract_address();
+    let pre_balance = token_client.balance(&pool_address);
+
+    token_client.transfer(&pool_address, receiver, &amount);
+    FlashLoanClient::new(e, receiver).exec_op(caller, asset, &amount, &fee);
+    token_client.transfer_from(&pool_address, caller, &pool_address, &(amount + fee));
+
+    if token_client.balance(&pool_address) < pre_balance + fee {
+        panic_with_error!(e, PoolError::BalanceError);
+    }
+
+    reserve.accrue_flash_loan_fee(e, pool.config.bstop_rate, fee);
+    pool.cache_reserve(reserve);
+    pool.store_cached_reserves(e);
+
+    PoolEvents::flash_loan_simple(
+        e,
+        asset.clone(),
+        caller.clone(),
+        receiver.clone(),
+        amount,
+        fee,
+    );
+    fee
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        storage::{self, PoolConfig},
+        testutils,
+    };
+    use soroban_sdk::testutils::{Address as _, Ledger, LedgerInfo};
+
+    #[test]
+    fn test_execute_flash_loan_simple() {
+        let e = Env::default();
+        e.cost_estimate().budget().reset_unlimited();
+        e.mock_all_auths_allowing_non_root_auth();
+        e.ledger().set(LedgerInfo {
+            timestamp: 600,
+            protocol_version: 22,
+            sequence_number: 1234,
+            network_id: Default::default(),
+            base_reserve: 10,
+            min_temp_entry_ttl: 10,
+            min_persistent_entry_ttl: 10,
+            max_entry_ttl: 3110400,
+        });
+
+        let bombadil = Address::generate(&e);
+        let samwise = Address::generate(&e);
+        let pool = testutils::create_pool(&e);
+        let (flash_loan_receiver, _) = testutils::create_flashloan_receiver(&e);
+
+        let (underlying, underlying_client) = testutils::create_token_contract(&e, &bombadil);
+        let (mut reserve_config, mut reserve_data) = testutils::default_reserve_meta();
+        reserve_config.flash_fee = 0_0010000;
+        reserve_data.last_time = 600;
+        testutils::create_reserve(&e, &pool, &underlying, &reserve_config, &reserve_data);
+
+        let pool_config = PoolConfig {
+            oracle: Address::generate(&e),
+            min_collateral: 1_0000000,
+            bstop_rate: 0_1000000,
+            status: 0,
+            max_positions: 4,
+        };
+        e.as_contract(&pool, || {
+            storage::set_pool_config(&e, &pool_config);
+
+            underlying_client.mint(&samwise, &1_0000000);
+            underlying_client.approve(&samwise, &pool, &100_0000000, &10000);
+            let pre_pool_balance = underlying_client.balance(&pool);
+
+            let fee = execute_flash_loan_simple(
+                &e,
+                &samwise,
+                &flash_loan_receiver,
+                &underlying,
+                10_0000000,
+            );
+
+            assert_eq!(fee, 0_0100000);
+            assert_eq!(underlying_client.balance(&pool), pre_pool_balance + fee);
+            assert_eq!(underlying_client.balance(&samwise), 0_9900000);
+            assert_eq!(underlying_client.balance(&flash_loan_receiver), 0);
+
+            // no positions are created and the fee is accrued to the reserve
+            assert_eq!(
+                storage::get_user_positions(&e, &samwise).effective_count(),
+                0
+            );
+            let reserve_data_post = storage::get_res_data(&e, &underlying);
+            assert_eq!(reserve_data_post.d_supply, reserve_data.d_supply);
+            assert_eq!(reserve_data_post.b_supply, reserve_data.b_supply);
+            assert_eq!(reserve_data_post.backstop_credit, 0_0010000);
+            assert_eq!(reserve_data_post.b_rate, 1_000_090_000_000);
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "Error(Contract, #10)")]
+    fn test_execute_flash_loan_simple_requires_fee() {
+        let e = Env::default();
+        e.cost_estimate().budget().reset_unlimited();
+        e.mock_all_auths_allowing_non_root_auth();
+        e.ledger().set(LedgerInfo {
+            timestamp: 600,
+            protocol_version: 22,
+            sequence_number: 1234,
+            network_id: Default::default(),
+            base_reserve: 10,
+            min_temp_entry_ttl: 10,
+            min_persistent_entry_ttl: 10,
+            max_entry_ttl: 3110400,
+        });
+
+        let bombadil = Address::generate(&e);
+        let samwise = Address::generate(&e);
+        let pool = testutils::create_pool(&e);
+        let (flash_loan_receiver, _) = testutils::create_flashloan_receiver(&e);
+
+        let (underlying, underlying_client) = testutils::create_token_contract(&e, &bombadil);
+        let (mut reserve_config, mut reserve_data) = testutils::default_reserve_meta();
+        reserve_config.flash_fee = 0_0010000;
+        reserve_data.last_time = 600;
+        testutils::create_reserve(&e, &pool, &underlying, &reserve_config, &reserve_data);
+
+        let pool_config = PoolConfig {
+            oracle: Address::generate(&e),
+            min_collateral: 1_0000000,
+            bstop_rate: 0_1000000,
+            status: 0,
+            max_positions: 4,
+        };
+        e.as_contract(&pool, || {
+            storage::set_pool_config(&e, &pool_config);
+
+            // samwise does not hold the tokens to pay the fee
+            underlying_client.approve(&samwise, &pool, &100_0000000, &10000);
+
+            execute_flash_loan_simple(&e, &samwise, &flash_loan_receiver, &underlying, 10_0000000);
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "Error(Contract, #1223)")]
+    fn test_execute_flash_loan_simple_reserve_disabled() {
+        let e = Env::default();
+        e.mock_all_auths_allowing_non_root_auth();
+        e.ledger().set(LedgerInfo {
+            timestamp: 600,
+            protocol_version: 22,
+            sequence_number: 1234,
+            network_id: Default::default(),
+            base_reserve: 10,
+            min_temp_entry_ttl: 10,
+            min_persistent_entry_ttl: 10,
+            max_entry_ttl: 3110400,
+        });
+
+        let bombadil = Address::generate(&e);
+        let samwise = Address::generate(&e);
+        let pool = testutils::create_pool(&e);
+        let (flash_loan_receiver, _) = testutils::create_flashloan_receiver(&e);
+
+        let (underlying, _) = testutils::create_token_contract(&e, &bombadil);
+        let (mut reserve_config, reserve_data) = testutils::default_reserve_meta();
+        reserve_config.enabled = false;
+        testutils::create_reserve(&e, &pool, &underlying, &reserve_config, &reserve_data);
+
+        let pool_config = PoolConfig {
+            oracle: Address::generate(&e),
+            min_collateral: 1_0000000,
+            bstop_rate: 0_1000000,
+            status: 0,
+            max_positions: 4,
+        };
+        e.as_contract(&pool, || {
+            storage::set_pool_config(&e, &pool_config);
+
+            execute_flash_loan_simple(&e, &samwise, &flash_loan_receiver, &underlying, 10_0000000);
+        });
+    }
+}
@@ -1,10 +1,21 @@
+use cast::i128;
 use soroban_fixed_point_math::SorobanFixedPoint;
-use soroban_sdk::Env;
-
-use crate::{constants::SCALAR_7, storage};
-
-use super::{pool::Pool, Positions};
-
+use soroban_sdk::{contracttype, Address, Env, Vec};
+
+use crate::{
+    auctions,
+    constants::SCALAR_7,
+    storage::{self, EModeCategory},
+};
+
+use super::{
+    fixed_loan::{calc_fixed_loan_interest, convert_fixed_loans},
+    pool::Pool,
+    Positions, Reserve, User,
+};
+
+#[derive(Clone)]
+#[contracttype]
 pub struct PositionData {
     /// The effective collateral balance denominated in the base asset
     pub collateral_base: i128,
@@ -21,13 +32,26 @@ pub struct PositionData {
 impl PositionData {
     /// Calculate the position data for a given set of of positions
     ///
+    /// Reserves with TWAP pricing enabled value collateral at the lower of the spot and TWAP prices,
+    /// and liabilities at the higher of the two.
+    ///
+    /// If the positions are in an e-mode category and all liabilities are in the category, the
+    /// category's collateral and liability factors are used for the reserves in the category.
+    ///
     /// ### Arguments
     /// * pool - The pool
     /// * positions - The positions to calculate the health factor for
-    pub fn calculate_from_positions(e: &Env, pool: &mut Pool, positions: &Positions) -> Self {
+    /// * emode - The e-mode category id of the positions, or 0 if not in e-mode
+    pub fn calculate_from_positions(
+        e: &Env,
+        pool: &mut Pool,
+        positions: &Positions,
+        emode: u32,
+    ) -> Self {
         let oracle_scalar = 10i128.pow(pool.load_price_decimals(e));
 
         let reserve_list = storage::get_res_list(e);
+        let emode_category = load_active_emode(e, &reserve_list, positions, emode);
         let mut collateral_base = 0;
         let mut liability_base = 0;
         let mut collateral_raw = 0;
@@ -39,30 +63,38 @@ impl PositionData {
                 continue;
             }
             let reserve = pool.load_reserve(e, &reserve_list.get_unchecked(i), false);
-            let asset_to_base = pool.load_price(e, &reserve.asset);
+            let (collateral_to_base, liability_to_base) = load_reserve_prices(e, pool, &reserve);
+            let (c_factor, l_factor) = match &emode_category {
+                // reserves being delisted do not receive the category's collateral factor
+                Some(category)
+                    if category.assets.contains(&reserve.asset)
+                        && !storage::has_res_delisting(e, &reserve.asset) =>
+                {
+                    (category.c_factor, category.l_factor)
+                }
+                _ => (reserve.config.c_factor, reserve.config.l_factor),
+            };
 
             if b_token_balance > 0 {
                 // append users effective collateral to collateral_base
-                let asset_collateral = reserve.to_effective_asset_from_b_token(e, b_token_balance);
+                let asset_collateral_raw = reserve.to_asset_from_b_token(e, b_token_balance);
+                let asset_collateral =
+                    asset_collateral_raw.fixed_mul_floor(e, &i128(c_factor), &SCALAR_7);
                 collateral_base +=
-                    asset_to_base.fixed_mul_floor(e, &asset_collateral, &reserve.scalar);
-                collateral_raw += asset_to_base.fixed_mul_floor(
-                    e,
-                    &reserve.to_asset_from_b_token(e, b_token_balance),
-                    &reserve.scalar,
-                );
+                    collateral_to_base.fixed_mul_floor(e, &asset_collateral, &reserve.scalar);
+                collateral_raw +=
+                    collateral_to_base.fixed_mul_floor(e, &asset_collateral_raw, &reserve.scalar);
             }
 
             if d_token_balance > 0 {
                 // append users effective liability to liability_base
-                let asset_liability = reserve.to_effective_asset_from_d_token(e, d_token_balance);
+                let asset_liability_raw = reserve.to_asset_from_d_token(e, d_token_balance);
+                let asset_liability =
+                    asset_liability_raw.fixed_div_ceil(e, &i128(l_factor), &SCALAR_7);
                 liability_base +=
-                    asset_to_base.fixed_mul_ceil(e, &asset_liability, &reserve.scalar);
-                liability_raw += asset_to_base.fixed_mul_ceil(
-                    e,
-                    &reserve.to_asset_from_d_token(e, d_token_balance),
-                    &reserve.scalar,
-                );
+                    liability_to_base.fixed_mul_ceil(e, &asset_liability, &reserve.scalar);
+                liability_raw +=
+                    liability_to_base.fixed_mul_ceil(e, &asset_liability_raw, &reserve.scalar);
             }
 
             pool.cache_reserve(reserve);
@@ -77,12 +109,49 @@ impl PositionData {
         }
     }
 
+    /// Calculate the position data for a user, including the amount owed on any fixed-term loans.
+    ///
+    /// Fixed-term loans are valued with the reserve's liability factor, as users holding them
+    /// cannot be in e-mode.
+    ///
+    /// ### Arguments
+    /// * pool - The pool
+    /// * user - The user to calculate the health factor for
+    pub fn calculate_from_user(e: &Env, pool: &mut Pool, user: &User) -> Self {
+        let mut position_data =
+            Self::calculate_from_positions(e, pool, &user.positions, user.emode);
+        let reserve_list = storage::get_res_list(e);
+        for (index, loan) in user.fixed_loans.iter() {
+            let reserve = pool.load_reserve(e, &reserve_list.get_unchecked(index), false);
+            let (_, liability_to_base) = load_reserve_prices(e, pool, &reserve);
+            let asset_liability_raw = loan.principal + calc_fixed_loan_interest(e, &reserve, &loan);
+            let asset_liability =
+                asset_liability_raw.fixed_div_ceil(e, &i128(reserve.config.l_factor), &SCALAR_7);
+            position_data.liability_base +=
+                liability_to_base.fixed_mul_ceil(e, &asset_liability, &reserve.scalar);
+            position_data.liability_raw +=
+                liability_to_base.fixed_mul_ceil(e, &asset_liability_raw, &reserve.scalar);
+            pool.cache_reserve(reserve);
+        }
+        position_data
+    }
+
     /// Return the health factor as a ratio
     pub fn as_health_factor(&self, e: &Env) -> i128 {
         self.collateral_base
             .fixed_div_floor(e, &self.liability_base, &self.scalar)
     }
 
+    /// Return the health factor as a ratio expressed in 7 decimals, or i128::MAX if there
+    /// are no liabilities
+    pub fn as_health_factor_7(&self, e: &Env) -> i128 {
+        if self.liability_base == 0 {
+            return i128::MAX;
+        }
+        self.collateral_base
+            .fixed_div_floor(e, &self.liability_base, &SCALAR_7)
+    }
+
     // Check if the position data is over a maximum health factor
     // Note: max must be 7 decimals
     pub fn is_hf_over(&self, e: &Env, max: i128) -> bool {
@@ -110,11 +179,99 @@ impl PositionData {
     }
 }
 
+/// The health of a user's positions
+#[derive(Clone)]
+#[contracttype]
+pub struct UserHealth {
+    /// The position data of the user's positions
+    pub position_data: PositionData,
+    /// The health factor of the user's positions expressed in 7 decimals
+    pub health_factor: i128,
+    /// The maximum percent of the user's positions that can currently be liquidated, or 0 if the
+    /// user cannot be liquidated
+    pub max_liquidation_percent: u32,
+    /// If the user's liabilities can currently be transferred to the backstop as bad debt
+    pub bad_debt: bool,
+}
+
+/// Load the health of a user's positions, updated to the current ledger
+///
+/// ### Arguments
+/// * user - The address of the user
+pub fn load_user_health(e: &Env, user: &Address) -> UserHealth {
+    let mut pool = Pool::load(e);
+    let mut user_state = User::load(e, user);
+    // fixed-term loans are converted to variable rate liabilities when the user is liquidated
+    convert_fixed_loans(e, &mut pool, &mut user_state);
+    let position_data = PositionData::calculate_from_positions(
+        e,
+        &mut pool,
+        &user_state.positions,
+        user_state.emode,
+    );
+    let max_liquidation_percent =
+        auctions::calc_max_user_liq_percent(e, &mut pool, &user_state, &position_data);
+    // matches the requirements of `transfer_bad_debt_to_backstop`
+    let bad_debt = user != &storage::get_backstop(e)
+        && user_state.positions.collateral.is_empty()
+        && !user_state.positions.liabilities.is_empty();
+    UserHealth {
+        health_factor: position_data.as_health_factor_7(e),
+        position_data,
+        max_liquidation_percent,
+        bad_debt,
+    }
+}
+
+/// Load the prices to value a reserve's collateral and liabilities at in the oracle's base asset.
+///
+/// If TWAP pricing is enabled, collateral is valued at the lower of the spot and TWAP
+/// prices, and liabilities at the higher.
+///
+/// Returns a tuple of (collateral price, liability price)
+fn load_reserve_prices(e: &Env, pool: &mut Pool, reserve: &Reserve) -> (i128, i128) {
+    let asset_to_base = pool.load_price(e, &reserve.asset);
+    match reserve.config.twap_records {
+        0 => (asset_to_base, asset_to_base),
+        records => match pool.load_twap_price(e, &reserve.asset, records) {
+            Some(twap) => (asset_to_base.min(twap), asset_to_base.max(twap)),
+            None => (asset_to_base, asset_to_base),
+        },
+    }
+}
+
+/// Load the e-mode category for the positions if all liabilities are in the category
+///
+/// ### Arguments
+/// * reserve_list - The pool's reserve list
+/// * positions - The positions
+/// * emode - The e-mode category id of the positions, or 0 if not in e-mode
+fn load_active_emode(
+    e: &Env,
+    reserve_list: &Vec<Address>,
+    positions: &Positions,
+    emode: u32,
+) -> Option<EModeCategory> {
+    if emode == 0 {
+        return None;
+    }
+    let category = storage::get_emode_category(e, emode)?;
+    for index in positions.liabilities.keys() {
+        if !category.assets.contains(reserve_list.get_unchecked(index)) {
+            return None;
+        }
+    }
+    Some(category)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::{storage::PoolConfig, testutils};
-    use sep_40_oracle::testutils::Asset;
+    use crate::{
+        storage::{FixedLoan, PoolConfig},
+        testutils,
+    };
+    use sep_40_oracle::{testutils::Asset, PriceData};
     use soroban_sdk::{
         map,
         testutils::{Address as _, Ledger, LedgerInfo},
@@ -197,7 +354,8 @@ mod tests {
         e.as_contract(&pool, || {
             storage::set_pool_config(&e, &pool_config);
             let mut pool = Pool::load(&e);
-            let position_data = PositionData::calculate_from_positions(&e, &mut pool, &positions);
+            let position_data =
+                PositionData::calculate_from_positions(&e, &mut pool, &positions, 0);
             assert_eq!(position_data.collateral_base, 262_7985925);
             assert_eq!(position_data.liability_base, 185_2368828);
             assert_eq!(position_data.collateral_raw, 350_3984567);
@@ -206,6 +364,436 @@ mod tests {
         });
     }
 
+    #[test]
+    fn test_calculate_from_user_fixed_loans() {
+        let e = Env::default();
+        e.cost_estimate().budget().reset_unlimited();
+        e.mock_all_auths();
+
+        let bombadil = Address::generate(&e);
+        let samwise = Address::generate(&e);
+        let pool = testutils::create_pool(&e);
+        let (oracle, oracle_client) = testutils::create_mock_oracle(&e);
+
+        // a tenth of a year after the loan was taken
+        let timestamp = 31536000 / 10;
+        let (underlying_0, _) = testutils::create_token_contract(&e, &bombadil);
+        let (reserve_config, mut reserve_data) = testutils::default_reserve_meta();
+        reserve_data.fixed_debt = 10_0000000;
+        reserve_data.last_time = timestamp;
+        testutils::create_reserve(&e, &pool, &underlying_0, &reserve_config, &reserve_data);
+
+        oracle_client.set_data(
+            &bombadil,
+            &Asset::Other(Symbol::new(&e, "USD")),
+            &vec![&e, Asset::Stellar(underlying_0)],
+            &7,
+            &300,
+        );
+        oracle_client.set_price_stable(&vec![&e, 1_0000000]);
+
+        e.ledger().set(LedgerInfo {
+            timestamp,
+            protocol_version: 22,
+            sequence_number: 1234,
+            network_id: Default::default(),
+            base_reserve: 10,
+            min_temp_entry_ttl: 10,
+            min_persistent_entry_ttl: 10,
+            max_entry_ttl: 3110400,
+        });
+        let pool_config = PoolConfig {
+            oracle,
+            min_collateral: 1_0000000,
+            bstop_rate: 0_2000000,
+            status: 0,
+            max_positions: 5,
+        };
+
+        let user = User {
+            address: samwise,
+            positions: Positions {
+                liabilities: map![&e],
+                collateral: map![&e, (0, 100_0000000)],
+                supply: map![&e],
+            },
+            emode: 0,
+            fixed_loans: map![
+                &e,
+                (
+                    0,
+                    FixedLoan {
+                        principal: 10_0000000,
+                        rate: 0_1000000,
+                        last_time: 0,
+                        maturity: timestamp * 2,
+                    }
+                )
+            ],
+        };
+        e.as_contract(&pool, || {
+            storage::set_pool_config(&e, &pool_config);
+            let mut pool = Pool::load(&e);
+            let position_data = PositionData::calculate_from_user(&e, &mut pool, &user);
+            assert_eq!(position_data.collateral_base, 75_0000000);
+            assert_eq!(position_data.collateral_raw, 100_0000000);
+            // includes the interest accrued since the loan was last settled
+            assert_eq!(position_data.liability_base, 13_4666667);
+            assert_eq!(position_data.liability_raw, 10_1000000);
+            assert_eq!(position_data.scalar, SCALAR_7);
+        });
+    }
+
+    #[test]
+    fn test_calculate_from_positions_twap() {
+        let e = Env::default();
+        e.cost_estimate().budget().reset_unlimited();
+        e.mock_all_auths();
+
+        let bombadil = Address::generate(&e);
+        let pool = testutils::create_pool(&e);
+        let (oracle, oracle_client) = testutils::create_mock_historical_oracle(&e);
+
+        let (underlying_0, _) = testutils::create_token_contract(&e, &bombadil);
+        let (mut reserve_config, reserve_data) = testutils::default_reserve_meta();
+        reserve_config.twap_records = 3;
+        testutils::create_reserve(&e, &pool, &underlying_0, &reserve_config, &reserve_data);
+
+        let (underlying_1, _) = testutils::create_token_contract(&e, &bombadil);
+        let (mut reserve_config, reserve_data) = testutils::default_reserve_meta();
+        reserve_config.twap_records = 3;
+        testutils::create_reserve(&e, &pool, &underlying_1, &reserve_config, &reserve_data);
+
+        e.ledger().set(LedgerInfo {
+            timestamp: 10000,
+            protocol_version: 22,
+            sequence_number: 1234,
+            network_id: Default::default(),
+            base_reserve: 10,
+            min_temp_entry_ttl: 10,
+            min_persistent_entry_ttl: 10,
+            max_entry_ttl: 3110400,
+        });
+        let set_history = |asset: &Address, prices: [i128; 4]| {
+            let mut history = vec![&e];
+            for (i, price) in prices.iter().enumerate() {
+                history.push_back(PriceData {
+                    price: *price,
+                    timestamp: 10000 - 300 * i as u64,
+                });
+            }
+            oracle_client.set_history(asset, &history);
+        };
+        // collateral TWAP is below spot and liability TWAP is above spot
+        set_history(&underlying_0, [1_0000000, 0_8000000, 0_9000000, 0_1000000]);
+        set_history(&underlying_1, [2_0000000, 2_2000000, 2_4000000, 9_0000000]);
+
+        let pool_config = PoolConfig {
+            oracle,
+            min_collateral: 1_0000000,
+            bstop_rate: 0_2000000,
+            status: 0,
+            max_positions: 5,
+        };
+
+        let positions = Positions {
+            liabilities: map![&e, (1, 15_0000000)],
+            collateral: map![&e, (0, 100_0000000)],
+            supply: map![&e],
+        };
+        e.as_contract(&pool, || {
+            storage::set_pool_config(&e, &pool_config);
+            let mut pool = Pool::load(&e);
+            let position_data =
+                PositionData::calculate_from_positions(&e, &mut pool, &positions, 0);
+            assert_eq!(position_data.collateral_base, 67_5000000);
+            assert_eq!(position_data.liability_base, 44_0000000);
+            assert_eq!(position_data.collateral_raw, 90_0000000);
+            assert_eq!(position_data.liability_raw, 33_0000000);
+        });
+
+        // collateral TWAP is above spot and liability TWAP is below spot
+        set_history(&underlying_0, [1_0000000, 1_2000000, 1_4000000, 0_1000000]);
+        set_history(&underlying_1, [2_0000000, 1_8000000, 1_6000000, 0_1000000]);
+        e.as_contract(&pool, || {
+            let mut pool = Pool::load(&e);
+            let position_data =
+                PositionData::calculate_from_positions(&e, &mut pool, &positions, 0);
+            assert_eq!(position_data.collateral_base, 75_0000000);
+            assert_eq!(position_data.liability_base, 40_0000000);
+            assert_eq!(position_data.collateral_raw, 100_0000000);
+            assert_eq!(position_data.liability_raw, 30_0000000);
+        });
+    }
+
+    #[test]
+    fn test_calculate_from_positions_emode() {
+        let e = Env::default();
+        e.cost_estimate().budget().reset_unlimited();
+        e.mock_all_auths();
+
+        let bombadil = Address::generate(&e);
+        let pool = testutils::create_pool(&e);
+        let (oracle, oracle_client) = testutils::create_mock_oracle(&e);
+
+        let (underlying_0, _) = testutils::create_token_contract(&e, &bombadil);
+        let (reserve_config, reserve_data) = testutils::default_reserve_meta();
+        testutils::create_reserve(&e, &pool, &underlying_0, &reserve_config, &reserve_data);
+
+        let (underlying_1, _) = testutils::create_token_contract(&e, &bombadil);
+        let (reserve_config, reserve_data) = testutils::default_reserve_meta();
+        testutils::create_reserve(&e, &pool, &underlying_1, &reserve_config, &reserve_data);
+
+        let (underlying_2, _) = testutils::create_token_contract(&e, &bombadil);
+        let (reserve_config, reserve_data) = testutils::default_reserve_meta();
+        testutils::create_reserve(&e, &pool, &underlying_2, &reserve_config, &reserve_data);
+
+        oracle_client.set_data(
+            &bombadil,
+            &Asset::Other(Symbol::new(&e, "USD")),
+            &vec![
+                &e,
+                Asset::Stellar(underlying_0.clone()),
+                Asset::Stellar(underlying_1.clone()),
+                Asset::Stellar(underlying_2.clone()),
+            ],
+            &7,
+            &300,
+        );
+        oracle_client.set_price_stable(&vec![&e, 1_0000000, 1_0000000, 1_0000000]);
+
+        e.ledger().set(LedgerInfo {
+            timestamp: 0,
+            protocol_version: 22,
+            sequence_number: 1234,
+            network_id: Default::default(),
+            base_reserve: 10,
+            min_temp_entry_ttl: 10,
+            min_persistent_entry_ttl: 10,
+            max_entry_ttl: 3110400,
+        });
+        let pool_config = PoolConfig {
+            oracle,
+            min_collateral: 1_0000000,
+            bstop_rate: 0_2000000,
+            status: 0,
+            max_positions: 5,
+        };
+        let category = EModeCategory {
+            c_factor: 0_9500000,
+            l_factor: 0_9500000,
+            assets: vec![&e, underlying_0.clone(), underlying_1.clone()],
+        };
+
+        let positions = Positions {
+            liabilities: map![&e, (1, 50_0000000)],
+            collateral: map![&e, (0, 100_0000000)],
+            supply: map![&e],
+        };
+        e.as_contract(&pool, || {
+            storage::set_pool_config(&e, &pool_config);
+            storage::set_emode_category(&e, 1, &category);
+            let mut pool = Pool::load(&e);
+            let position_data =
+                PositionData::calculate_from_positions(&e, &mut pool, &positions, 0);
+            assert_eq!(position_data.collateral_base, 75_0000000);
+            assert_eq!(position_data.liability_base, 66_6666667);
+
+            let position_data =
+                PositionData::calculate_from_positions(&e, &mut pool, &positions, 1);
+            assert_eq!(position_data.collateral_base, 95_0000000);
+            assert_eq!(position_data.liability_base, 52_6315790);
+            assert_eq!(position_data.collateral_raw, 100_0000000);
+            assert_eq!(position_data.liability_raw, 50_0000000);
+        });
+    }
+
+    #[test]
+    fn test_calculate_from_positions_emode_liability_outside_category() {
+        let e = Env::default();
+        e.cost_estimate().budget().reset_unlimited();
+        e.mock_all_auths();
+
+        let bombadil = Address::generate(&e);
+        let pool = testutils::create_pool(&e);
+        let (oracle, oracle_client) = testutils::create_mock_oracle(&e);
+
+        let (underlying_0, _) = testutils::create_token_contract(&e, &bombadil);
+        let (reserve_config, reserve_data) = testutils::default_reserve_meta();
+        testutils::create_reserve(&e, &pool, &underlying_0, &reserve_config, &reserve_data);
+
+        let (underlying_1, _) = testutils::create_token_contract(&e, &bombadil);
+        let (reserve_config, reserve_data) = testutils::default_reserve_meta();
+        testutils::create_reserve(&e, &pool, &underlying_1, &reserve_config, &reserve_data);
+
+        let (underlying_2, _) = testutils::create_token_contract(&e, &bombadil);
+        let (reserve_config, reserve_data) = testutils::default_reserve_meta();
+        testutils::create_reserve(&e, &pool, &underlying_2, &reserve_config, &reserve_data);
+
+        oracle_client.set_data(
+            &bombadil,
+            &Asset::Other(Symbol::new(&e, "USD")),
+            &vec![
+                &e,
+                Asset::Stellar(underlying_0.clone()),
+                Asset::Stellar(underlying_1.clone()),
+                Asset::Stellar(underlying_2.clone()),
+            ],
+            &7,
+            &300,
+        );
+        oracle_client.set_price_stable(&vec![&e, 1_0000000, 1_0000000, 1_0000000]);
+
+        e.ledger().set(LedgerInfo {
+            timestamp: 0,
+            protocol_version: 22,
+            sequence_number: 1234,
+            network_id: Default::default(),
+            base_reserve: 10,
+            min_temp_entry_ttl: 10,
+            min_persistent_entry_ttl: 10,
+            max_entry_ttl: 3110400,
+        });
+        let pool_config = PoolConfig {
+            oracle,
+            min_collateral: 1_0000000,
+            bstop_rate: 0_2000000,
+            status: 0,
+            max_positions: 5,
+        };
+        let category = EModeCategory {
+            c_factor: 0_9500000,
+            l_factor: 0_9500000,
+            assets: vec![&e, underlying_0.clone(), underlying_1.clone()],
+        };
+
+        // a liability outside of the category disables the e-mode factors for all reserves
+        let positions = Positions {
+            liabilities: map![&e, (1, 25_0000000), (2, 25_0000000)],
+            collateral: map![&e, (0, 100_0000000)],
+            supply: map![&e],
+        };
+        e.as_contract(&pool, || {
+            storage::set_pool_config(&e, &pool_config);
+            storage::set_emode_category(&e, 1, &category);
+            let mut pool = Pool::load(&e);
+            let position_data =
+                PositionData::calculate_from_positions(&e, &mut pool, &positions, 1);
+            assert_eq!(position_data.collateral_base, 75_0000000);
+            assert_eq!(position_data.liability_base, 66_6666668);
+            assert_eq!(position_data.collateral_raw, 100_0000000);
+            assert_eq!(position_data.liability_raw, 50_0000000);
+        });
+    }
+
+    #[test]
+    fn test_load_user_health() {
+        let e = Env::default();
+        e.cost_estimate().budget().reset_unlimited();
+        e.mock_all_auths();
+
+        let bombadil = Address::generate(&e);
+        let samwise = Address::generate(&e);
+        let frodo = Address::generate(&e);
+        let pool = testutils::create_pool(&e);
+        let (oracle, oracle_client) = testutils::create_mock_oracle(&e);
+
+        let (underlying_0, _) = testutils::create_token_contract(&e, &bombadil);
+        let (reserve_config, reserve_data) = testutils::default_reserve_meta();
+        testutils::create_reserve(&e, &pool, &underlying_0, &reserve_config, &reserve_data);
+
+        let (underlying_1, _) = testutils::create_token_contract(&e, &bombadil);
+        let (reserve_config, reserve_data) = testutils::default_reserve_meta();
+        testutils::create_reserve(&e, &pool, &underlying_1, &reserve_config, &reserve_data);
+
+        oracle_client.set_data(
+            &bombadil,
+            &Asset::Other(Symbol::new(&e, "USD")),
+            &vec![
+                &e,
+                Asset::Stellar(underlying_0.clone()),
+                Asset::Stellar(underlying_1.clone()),
+            ],
+            &7,
+            &300,
+        );
+        oracle_client.set_price_stable(&vec![&e, 1_0000000, 1_0000000]);
+
+        e.ledger().set(LedgerInfo {
+            timestamp: 0,
+            protocol_version: 22,
+            sequence_number: 1234,
+            network_id: Default::default(),
+            base_reserve: 10,
+            min_temp_entry_ttl: 10,
+            min_persistent_entry_ttl: 10,
+            max_entry_ttl: 3110400,
+        });
+        let pool_config = PoolConfig {
+            oracle,
+            min_collateral: 1_0000000,
+            bstop_rate: 0_2000000,
+            status: 0,
+            max_positions: 5,
+        };
+
+        let healthy_positions = Positions {
+            liabilities: map![&e, (1, 50_0000000)],
+            collateral: map![&e, (0, 100_0000000)],
+            supply: map![&e],
+        };
+        let bad_debt_positions = Positions {
+            liabilities: map![&e, (1, 10_0000000)],
+            collateral: map![&e],
+            supply: map![&e, (0, 10_0000000)],
+        };
+        e.as_contract(&pool, || {
+            storage::set_pool_config(&e, &pool_config);
+            storage::set_backstop(&e, &Address::generate(&e));
+            storage::set_user_positions(&e, &samwise, &healthy_positions);
+            storage::set_user_positions(&e, &frodo, &bad_debt_positions);
+
+            let health = load_user_health(&e, &samwise);
+            assert_eq!(health.position_data.collateral_base, 75_0000000);
+            assert_eq!(health.position_data.collateral_raw, 100_0000000);
+            assert_eq!(health.position_data.liability_base, 66_6666667);
+            assert_eq!(health.position_data.liability_raw, 50_0000000);
+            assert_eq!(health.health_factor, 1_1249999);
+            assert_eq!(health.max_liquidation_percent, 0);
+            assert_eq!(health.bad_debt, false);
+
+            let health = load_user_health(&e, &frodo);
+            assert_eq!(health.position_data.collateral_base, 0);
+            assert_eq!(health.position_data.liability_base, 13_3333334);
+            assert_eq!(health.health_factor, 0);
+            assert_eq!(health.max_liquidation_percent, 0);
+            assert_eq!(health.bad_debt, true);
+        });
+    }
+
+    #[test]
+    fn test_as_health_factor_7() {
+        let e = Env::default();
+
+        let position_data = PositionData {
+            collateral_base: 9_1234567,
+            collateral_raw: 12_0000000,
+            liability_base: 9_1233333,
+            liability_raw: 7_0000000,
+            scalar: 1_000_000_000,
+        };
+        assert_eq!(position_data.as_health_factor_7(&e), 1_0000135);
+
+        let no_liabilities = PositionData {
+            collateral_base: 9_1234567,
+            collateral_raw: 12_0000000,
+            liability_base: 0,
+            liability_raw: 0,
+            scalar: 1_0000000,
+        };
+        assert_eq!(no_liabilities.as_health_factor_7(&e), i128::MAX);
+    }
+
     #[test]
     fn test_as_health_factor_rounds_floor() {
         let e = Env::default();
@@ -8,9 +8,39 @@ use crate::{
     PoolError,
 };
 
+/// The interest rate model of a reserve
+#[derive(Clone, PartialEq)]
+#[repr(u32)]
+pub enum InterestRateModel {
+    /// A three-kink curve with a fixed second kink at 95% utilization, scaled by the reactive rate modifier
+    Reactive = 0,
+    /// A three-kink curve with a configurable second kink and no rate modifier
+    Kinked = 1,
+    /// A fixed interest rate of `r_base`, regardless of utilization
+    Fixed = 2,
+}
+
+impl InterestRateModel {
+    /// Convert a u32 to an InterestRateModel
+    ///
+    /// ### Panics
+    /// If the value is not a valid InterestRateModel
+    pub fn from_u32(e: &Env, value: u32) -> Self {
+        match value {
+            0 => InterestRateModel::Reactive,
+            1 => InterestRateModel::Kinked,
+            2 => InterestRateModel::Fixed,
+            _ => panic_with_error!(e, PoolError::InvalidReserveMetadata),
+        }
+    }
+}
+
 /// Calculates the loan accrual ratio for the Reserve based on the current utilization and
 /// rate modifier for the reserve.
 ///
+/// Only the reactive interest rate model uses and updates the rate modifier. Other models return
+/// the rate modifier unchanged.
+///
 /// ### Arguments
 /// * `config` - The Reserve config to calculate an accrual for
 /// * `cur_util` - The current utilization rate of the reserve (7 decimals)
@@ -19,7 +49,6 @@ use crate::{
 ///
 /// ### Returns
 /// * (i128, i128) - (accrual amount scaled to 9 decimal places, new interest rate modifier scaled to 9 decimal places)
-#[allow(clippy::zero_prefixed_literal)]
 pub fn calc_accrual(
     e: &Env,
     config: &ReserveConfig,
@@ -27,24 +56,83 @@ pub fn calc_accrual(
     ir_mod: i128,
     last_time: u64,
 ) -> (i128, i128) {
-    let cur_ir: i128;
+    let delta_time = i128(e.ledger().timestamp() - last_time);
+    // this should never occur, but require some time to pass
+    if delta_time < 1 {
+        panic_with_error!(e, PoolError::InternalError);
+    }
+
+    let cur_ir = calc_borrow_rate(e, config, cur_util, ir_mod);
+    let new_ir_mod = match InterestRateModel::from_u32(e, config.ir_model) {
+        InterestRateModel::Reactive => calc_ir_mod(e, config, cur_util, ir_mod, delta_time),
+        InterestRateModel::Kinked | InterestRateModel::Fixed => ir_mod,
+    };
+
+    // calc accrual amount over blocks
+    // scale delta_time to 12 decimals so time_weight is scaled to 12 decimals
+    let delta_time_scaled = delta_time * SCALAR_12;
+    let time_weight = delta_time_scaled / SECONDS_PER_YEAR;
+    (
+        // accrual scaled to 12 decimals
+        SCALAR_12 + time_weight.fixed_mul_ceil(e, &cur_ir, &SCALAR_7),
+        new_ir_mod,
+    )
+}
+
+/// Calculates the current annual borrow rate for the Reserve based on its interest rate model
+///
+/// ### Arguments
+/// * `config` - The Reserve config to calculate the rate for
+/// * `cur_util` - The current utilization rate of the reserve (7 decimals)
+/// * `ir_mod` - The current interest rate modifier of the reserve (7 decimals)
+///
+/// ### Returns
+/// * i128 - The annual borrow rate (7 decimals)
+#[allow(clippy::zero_prefixed_literal)]
+pub fn calc_borrow_rate(e: &Env, config: &ReserveConfig, cur_util: i128, ir_mod: i128) -> i128 {
+    match InterestRateModel::from_u32(e, config.ir_model) {
+        InterestRateModel::Reactive => calc_kinked_rate(e, config, cur_util, 0_9500000, ir_mod),
+        InterestRateModel::Kinked => {
+            calc_kinked_rate(e, config, cur_util, i128(config.util_two), SCALAR_7)
+        }
+        InterestRateModel::Fixed => i128(config.r_base),
+    }
+}
+
+/// Calculates the interest rate of a three-kink curve, where the rate increases by `r_one` up to
+/// the target utilization, by `r_two` up to the second kink, and by `r_three` up to 100% utilization.
+///
+/// ### Arguments
+/// * `config` - The Reserve config to calculate the rate for
+/// * `cur_util` - The current utilization rate of the reserve (7 decimals)
+/// * `util_two` - The utilization rate of the second kink (7 decimals)
+/// * `ir_mod` - The interest rate modifier applied to the rate below the second kink (7 decimals)
+#[allow(clippy::zero_prefixed_literal)]
+fn calc_kinked_rate(
+    e: &Env,
+    config: &ReserveConfig,
+    cur_util: i128,
+    util_two: i128,
+    ir_mod: i128,
+) -> i128 {
     let target_util: i128 = i128(config.util);
     if cur_util <= target_util {
         let util_scalar = cur_util.fixed_div_ceil(e, &target_util, &SCALAR_7);
         let base_rate =
             util_scalar.fixed_mul_ceil(e, &i128(config.r_one), &SCALAR_7) + i128(config.r_base);
 
-        cur_ir = base_rate.fixed_mul_ceil(e, &ir_mod, &SCALAR_7);
-    } else if cur_util <= 0_9500000 {
+        base_rate.fixed_mul_ceil(e, &ir_mod, &SCALAR_7)
+    } else if cur_util <= util_two {
         let util_scalar =
-            (cur_util - target_util).fixed_div_ceil(e, &(0_9500000 - target_util), &SCALAR_7);
+            (cur_util - target_util).fixed_div_ceil(e, &(util_two - target_util), &SCALAR_7);
         let base_rate = util_scalar.fixed_mul_ceil(e, &i128(config.r_two), &SCALAR_7)
             + i128(config.r_one)
             + i128(config.r_base);
 
-        cur_ir = base_rate.fixed_mul_ceil(e, &ir_mod, &SCALAR_7);
+        base_rate.fixed_mul_ceil(e, &ir_mod, &SCALAR_7)
     } else {
-        let util_scalar = (cur_util - 0_9500000).fixed_div_ceil(e, &0_0500000, &SCALAR_7);
+        let util_scalar =
+            (cur_util - util_two).fixed_div_ceil(e, &(SCALAR_7 - util_two), &SCALAR_7);
         let extra_rate = util_scalar.fixed_mul_ceil(e, &i128(config.r_three), &SCALAR_7);
 
         let intersection = ir_mod.fixed_mul_ceil(
@@ -52,17 +140,27 @@ pub fn calc_accrual(
             &i128(config.r_two + config.r_one + config.r_base),
             &SCALAR_7,
         );
-        cur_ir = extra_rate + intersection;
+        extra_rate + intersection
     }
+}
 
-    // update rate_modifier
-    let delta_time = i128(e.ledger().timestamp() - last_time);
-    // this should never occur, but require some time to pass
-    if delta_time < 1 {
-        panic_with_error!(e, PoolError::InternalError);
-    }
+/// Calculates the new interest rate modifier based on the difference between the current and
+/// target utilization over the elapsed time.
+///
+/// ### Arguments
+/// * `config` - The Reserve config to calculate the rate modifier for
+/// * `cur_util` - The current utilization rate of the reserve (7 decimals)
+/// * `ir_mod` - The current interest rate modifier of the reserve (7 decimals)
+/// * `delta_time` - The seconds elapsed since the last accrual
+fn calc_ir_mod(
+    e: &Env,
+    config: &ReserveConfig,
+    cur_util: i128,
+    ir_mod: i128,
+    delta_time: i128,
+) -> i128 {
     // util dif 7 decimals
-    let util_dif = cur_util - target_util;
+    let util_dif = cur_util - i128(config.util);
     let new_ir_mod: i128;
     if util_dif >= 0 {
         // rate modifier increasing
@@ -87,16 +185,7 @@ pub fn calc_accrual(
             new_ir_mod = next_ir_mod;
         }
     }
-
-    // calc accrual amount over blocks
-    // scale delta_time to 12 decimals so time_weight is scaled to 12 decimals
-    let delta_time_scaled = delta_time * SCALAR_12;
-    let time_weight = delta_time_scaled / SECONDS_PER_YEAR;
-    (
-        // accrual scaled to 12 decimals
-        SCALAR_12 + time_weight.fixed_mul_ceil(e, &cur_ir, &SCALAR_7),
-        new_ir_mod,
-    )
+    new_ir_mod
 }
 
 #[cfg(test)]
@@ -121,6 +210,14 @@ mod tests {
             reactivity: 0_0000020,
             collateral_cap: 1000000000000000000,
             index: 0,
+            twap_records: 0,
+            borrow_cap: 1000000000000000000,
+            supply_cap: 1000000000000000000,
+            isolated: false,
+            debt_ceiling: 0,
+            ir_model: 0,
+            util_two: 0,
+            flash_fee: 0,
             enabled: true,
         };
         let ir_mod: i128 = 1_0000000;
@@ -159,6 +256,14 @@ mod tests {
             reactivity: 0_0000020,
             collateral_cap: 1000000000000000000,
             index: 0,
+            twap_records: 0,
+            borrow_cap: 1000000000000000000,
+            supply_cap: 1000000000000000000,
+            isolated: false,
+            debt_ceiling: 0,
+            ir_model: 0,
+            util_two: 0,
+            flash_fee: 0,
             enabled: true,
         };
         let ir_mod: i128 = 1_0000000;
@@ -197,6 +302,14 @@ mod tests {
             reactivity: 0_0000020,
             collateral_cap: 1000000000000000000,
             index: 0,
+            twap_records: 0,
+            borrow_cap: 1000000000000000000,
+            supply_cap: 1000000000000000000,
+            isolated: false,
+            debt_ceiling: 0,
+            ir_model: 0,
+            util_two: 0,
+            flash_fee: 0,
             enabled: true,
         };
         let ir_mod: i128 = 1_0000000;
@@ -235,6 +348,14 @@ mod tests {
             reactivity: 0_0000020,
             collateral_cap: 1000000000000000000,
             index: 0,
+            twap_records: 0,
+            borrow_cap: 1000000000000000000,
+            supply_cap: 1000000000000000000,
+            isolated: false,
+            debt_ceiling: 0,
+            ir_model: 0,
+            util_two: 0,
+            flash_fee: 0,
             enabled: true,
         };
         let ir_mod: i128 = 9_9970000;
@@ -272,6 +393,14 @@ mod tests {
             reactivity: 0_0000020,
             collateral_cap: 1000000000000000000,
             index: 0,
+            twap_records: 0,
+            borrow_cap: 1000000000000000000,
+            supply_cap: 1000000000000000000,
+            isolated: false,
+            debt_ceiling: 0,
+            ir_model: 0,
+            util_two: 0,
+            flash_fee: 0,
             enabled: true,
         };
         let ir_mod: i128 = 0_1500000;
@@ -309,6 +438,14 @@ mod tests {
             reactivity: 0,
             collateral_cap: 1000000000000000000,
             index: 0,
+            twap_records: 0,
+            borrow_cap: 1000000000000000000,
+            supply_cap: 1000000000000000000,
+            isolated: false,
+            debt_ceiling: 0,
+            ir_model: 0,
+            util_two: 0,
+            flash_fee: 0,
             enabled: true,
         };
         let ir_mod: i128 = 1_0000000;
@@ -347,6 +484,14 @@ mod tests {
             reactivity: 0_0000020,
             collateral_cap: 1000000000000000000,
             index: 0,
+            twap_records: 0,
+            borrow_cap: 1000000000000000000,
+            supply_cap: 1000000000000000000,
+            isolated: false,
+            debt_ceiling: 0,
+            ir_model: 0,
+            util_two: 0,
+            flash_fee: 0,
             enabled: true,
         };
         let ir_mod: i128 = 0_1000000;
@@ -385,6 +530,14 @@ mod tests {
             reactivity: 0_0000020,
             collateral_cap: 1000000000000000000,
             index: 0,
+            twap_records: 0,
+            borrow_cap: 1000000000000000000,
+            supply_cap: 1000000000000000000,
+            isolated: false,
+            debt_ceiling: 0,
+            ir_model: 0,
+            util_two: 0,
+            flash_fee: 0,
             enabled: true,
         };
         let ir_mod: i128 = 1_0000000;
@@ -414,4 +567,156 @@ mod tests {
         assert_eq!(accrual_3, 1_000_003_963_724);
         assert_eq!(ir_mod_3, 1_0002065);
     }
+
+    #[test]
+    fn test_calc_accrual_kinked_model() {
+        let e = Env::default();
+
+        let reserve_config = ReserveConfig {
+            decimals: 7,
+            c_factor: 0_7500000,
+            l_factor: 0_7500000,
+            util: 0_7500000,
+            max_util: 0_9500000,
+            r_base: 0_0100000,
+            r_one: 0_0500000,
+            r_two: 0_5000000,
+            r_three: 1_5000000,
+            reactivity: 0_0000020,
+            collateral_cap: 1000000000000000000,
+            index: 0,
+            twap_records: 0,
+            borrow_cap: 1000000000000000000,
+            supply_cap: 1000000000000000000,
+            isolated: false,
+            debt_ceiling: 0,
+            ir_model: InterestRateModel::Kinked as u32,
+            util_two: 0_8500000,
+            flash_fee: 0,
+            enabled: true,
+        };
+        let ir_mod: i128 = 1_5000000;
+
+        e.ledger().set(LedgerInfo {
+            timestamp: 500,
+            protocol_version: 22,
+            sequence_number: 100,
+            network_id: Default::default(),
+            base_reserve: 10,
+            min_temp_entry_ttl: 10,
+            min_persistent_entry_ttl: 10,
+            max_entry_ttl: 3110400,
+        });
+
+        // rate modifier is ignored and not updated
+        let (accrual_0, ir_mod_0) = calc_accrual(&e, &reserve_config, 0_6565656, ir_mod, 0);
+        let (accrual_1, ir_mod_1) = calc_accrual(&e, &reserve_config, 0_7979797, ir_mod, 0);
+        let (accrual_2, ir_mod_2) = calc_accrual(&e, &reserve_config, 0_8800000, ir_mod, 0);
+        let (accrual_3, ir_mod_3) = calc_accrual(&e, &reserve_config, 0_9696969, ir_mod, 0);
+
+        assert_eq!(accrual_0, 1_000_000_852_536);
+        assert_eq!(ir_mod_0, 1_5000000);
+        assert_eq!(accrual_1, 1_000_004_754_860);
+        assert_eq!(ir_mod_1, 1_5000000);
+        assert_eq!(accrual_2, 1_000_013_635_210);
+        assert_eq!(ir_mod_2, 1_5000000);
+        assert_eq!(accrual_3, 1_000_027_856_561);
+        assert_eq!(ir_mod_3, 1_5000000);
+    }
+
+    #[test]
+    fn test_calc_accrual_fixed_model() {
+        let e = Env::default();
+
+        let reserve_config = ReserveConfig {
+            decimals: 7,
+            c_factor: 0_7500000,
+            l_factor: 0_7500000,
+            util: 0_7500000,
+            max_util: 0_9500000,
+            r_base: 0_2500000,
+            r_one: 0_0500000,
+            r_two: 0_5000000,
+            r_three: 1_5000000,
+            reactivity: 0_0000020,
+            collateral_cap: 1000000000000000000,
+            index: 0,
+            twap_records: 0,
+            borrow_cap: 1000000000000000000,
+            supply_cap: 1000000000000000000,
+            isolated: false,
+            debt_ceiling: 0,
+            ir_model: InterestRateModel::Fixed as u32,
+            util_two: 0,
+            flash_fee: 0,
+            enabled: true,
+        };
+        let ir_mod: i128 = 1_5000000;
+
+        e.ledger().set(LedgerInfo {
+            timestamp: 500,
+            protocol_version: 22,
+            sequence_number: 100,
+            network_id: Default::default(),
+            base_reserve: 10,
+            min_temp_entry_ttl: 10,
+            min_persistent_entry_ttl: 10,
+            max_entry_ttl: 3110400,
+        });
+
+        let (accrual_0, ir_mod_0) = calc_accrual(&e, &reserve_config, 0, ir_mod, 0);
+        let (accrual_1, ir_mod_1) = calc_accrual(&e, &reserve_config, 0_7565656, ir_mod, 0);
+        let (accrual_2, ir_mod_2) = calc_accrual(&e, &reserve_config, 0_9565656, ir_mod, 0);
+
+        assert_eq!(accrual_0, 1_000_003_963_724);
+        assert_eq!(ir_mod_0, 1_5000000);
+        assert_eq!(accrual_1, 1_000_003_963_724);
+        assert_eq!(ir_mod_1, 1_5000000);
+        assert_eq!(accrual_2, 1_000_003_963_724);
+        assert_eq!(ir_mod_2, 1_5000000);
+    }
+
+    #[test]
+    #[should_panic(expected = "Error(Contract, #1202)")]
+    fn test_calc_accrual_invalid_model() {
+        let e = Env::default();
+
+        let reserve_config = ReserveConfig {
+            decimals: 7,
+            c_factor: 0_7500000,
+            l_factor: 0_7500000,
+            util: 0_7500000,
+            max_util: 0_9500000,
+            r_base: 0_0100000,
+            r_one: 0_0500000,
+            r_two: 0_5000000,
+            r_three: 1_5000000,
+            reactivity: 0_0000020,
+            collateral_cap: 1000000000000000000,
+            index: 0,
+            twap_records: 0,
+            borrow_cap: 1000000000000000000,
+            supply_cap: 1000000000000000000,
+            isolated: false,
+            debt_ceiling: 0,
+            ir_model: 3,
+            util_two: 0,
+            flash_fee: 0,
+            enabled: true,
+        };
+        let ir_mod: i128 = 1_0000000;
+
+        e.ledger().set(LedgerInfo {
+            timestamp: 500,
+            protocol_version: 22,
+            sequence_number: 100,
+            network_id: Default::default(),
+            base_reserve: 10,
+            min_temp_entry_ttl: 10,
+            min_persistent_entry_ttl: 10,
+            max_entry_ttl: 3110400,
+        });
+
+        calc_accrual(&e, &reserve_config, 0_6565656, ir_mod, 0);
+    }
 }
@@ -0,0 +1,685 @@
+use soroban_fixed_point_math::SorobanFixedPoint;
+use soroban_sdk::{panic_with_error, vec, Address, Env, Map, Vec};
+
+use crate::{errors::PoolError, storage};
+
+use super::{Pool, Positions};
+
+/// Set the list of reserves that can be borrowed by users with isolated collateral
+///
+/// ### Arguments
+/// * `assets` - The underlying assets of the reserves
+///
+/// ### Panics
+/// If any asset is not a reserve, or is included more than once
+pub fn execute_set_isolated_borrow_list(e: &Env, assets: &Vec<Address>) {
+    for (index, asset) in assets.iter().enumerate() {
+        if !storage::has_res(e, &asset) || assets.first_index_of(&asset) != Some(index as u32) {
+            panic_with_error!(e, PoolError::BadRequest);
+        }
+    }
+    storage::set_isolated_borrow_list(e, assets);
+}
+
+/// Validate a user's new positions against the isolation rules and update the debt backed
+/// by any isolated collateral.
+///
+/// A user with isolated collateral can only hold liabilities in reserves on the isolated borrow list,
+/// and any increase in the debt backed by an isolated reserve must remain under the reserve's debt ceiling.
+///
+/// ### Arguments
+/// * `pool` - The pool
+/// * `prev_positions` - The user's positions before the update
+/// * `positions` - The user's positions after the update
+///
+/// ### Panics
+/// If the new positions violate the isolation rules
+pub fn require_isolation_rules(
+    e: &Env,
+    pool: &mut Pool,
+    prev_positions: &Positions,
+    positions: &Positions,
+) {
+    if !is_isolated_borrow_allowed(e, pool, positions) {
+        panic_with_error!(e, PoolError::InvalidIsolatedBorrow);
+    }
+    update_isolated_debt(e, pool, prev_positions, positions, true);
+}
+
+/// Check a user's new positions against the isolation rules without updating the debt backed
+/// by any isolated collateral.
+///
+/// ### Arguments
+/// * `pool` - The pool
+/// * `prev_positions` - The user's positions before the update
+/// * `positions` - The user's positions after the update
+///
+/// Returns the error the isolation rules would raise, if any
+pub fn check_isolation_rules(
+    e: &Env,
+    pool: &mut Pool,
+    prev_positions: &Positions,
+    positions: &Positions,
+) -> Option<PoolError> {
+    if !is_isolated_borrow_allowed(e, pool, positions) {
+        return Some(PoolError::InvalidIsolatedBorrow);
+    }
+    for (_, (_, exceeds_ceiling)) in calc_isolated_debt(e, pool, prev_positions, positions).iter() {
+        if exceeds_ceiling {
+            return Some(PoolError::ExceededDebtCeiling);
+        }
+    }
+    None
+}
+
+/// Update the debt backed by each isolated reserve a user holds as collateral, based on the change
+/// in the user's liability d_tokens.
+///
+/// The debt is tracked as d_tokens of each borrowed reserve, such that borrows and repayments net out
+/// exactly regardless of price changes. It must be called on any path that modifies the liabilities
+/// or collateral of a user.
+///
+/// ### Arguments
+/// * `pool` - The pool
+/// * `prev_positions` - The user's positions before the update
+/// * `positions` - The user's positions after the update
+/// * `enforce_ceiling` - If increases in debt should be checked against the reserve's debt ceiling
+///
+/// ### Panics
+/// If `enforce_ceiling` is set and the debt backed by an isolated reserve increases over its debt ceiling
+pub fn update_isolated_debt(
+    e: &Env,
+    pool: &mut Pool,
+    prev_positions: &Positions,
+    positions: &Positions,
+    enforce_ceiling: bool,
+) {
+    for (asset, (debt, exceeds_ceiling)) in
+        calc_isolated_debt(e, pool, prev_positions, positions).iter()
+    {
+        if enforce_ceiling && exceeds_ceiling {
+            panic_with_error!(e, PoolError::ExceededDebtCeiling);
+        }
+        storage::set_isolated_debt(e, &asset, &debt);
+    }
+}
+
+/// Check if the positions hold any isolated reserve as collateral
+///
+/// ### Arguments
+/// * `pool` - The pool
+/// * `positions` - The user's positions
+pub fn has_isolated_collateral(e: &Env, pool: &mut Pool, positions: &Positions) -> bool {
+    let reserve_list = storage::get_res_list(e);
+    !load_isolated_collateral(e, pool, &reserve_list, positions).is_empty()
+}
+
+/// Check that a user with isolated collateral only holds liabilities in reserves on the
+/// isolated borrow list
+fn is_isolated_borrow_allowed(e: &Env, pool: &mut Pool, positions: &Positions) -> bool {
+    let reserve_list = storage::get_res_list(e);
+    if load_isolated_collateral(e, pool, &reserve_list, positions).is_empty() {
+        return true;
+    }
+    let borrow_list = storage::get_isolated_borrow_list(e);
+    for index in positions.liabilities.keys() {
+        if !borrow_list.contains(reserve_list.get_unchecked(index)) {
+            return false;
+        }
+    }
+    true
+}
+
+/// Calculate the new debt backed by each isolated reserve affected by the change in positions
+///
+/// Returns a map of isolated reserve asset to a tuple of (new debt as a map of borrowed asset to d_tokens,
+/// if the debt increased over the reserve's debt ceiling)
+fn calc_isolated_debt(
+    e: &Env,
+    pool: &mut Pool,
+    prev_positions: &Positions,
+    positions: &Positions,
+) -> Map<Address, (Map<Address, i128>, bool)> {
+    let mut updates = Map::new(e);
+    let reserve_list = storage::get_res_list(e);
+    let prev_isolated = load_isolated_collateral(e, pool, &reserve_list, prev_positions);
+    let isolated = load_isolated_collateral(e, pool, &reserve_list, positions);
+    if prev_isolated.is_empty() && isolated.is_empty() {
+        return updates;
+    }
+
+    let mut assets = prev_isolated.clone();
+    for asset in isolated.iter() {
+        if !assets.contains(&asset) {
+            assets.push_back(asset);
+        }
+    }
+    let no_liabilities = Map::new(e);
+    for asset in assets.iter() {
+        let prev_backed = if prev_isolated.contains(&asset) {
+            &prev_positions.liabilities
+        } else {
+            &no_liabilities
+        };
+        let backed = if isolated.contains(&asset) {
+            &positions.liabilities
+        } else {
+            &no_liabilities
+        };
+        if prev_backed == backed {
+            continue;
+        }
+
+        let mut debt = storage::get_isolated_debt(e, &asset);
+        let mut increased = false;
+        for (index, d_tokens) in backed.iter() {
+            let change = d_tokens - prev_backed.get(index).unwrap_or(0);
+            increased = increased || change > 0;
+            apply_debt_change(&mut debt, reserve_list.get_unchecked(index), change);
+        }
+        for (index, d_tokens) in prev_backed.iter() {
+            if !backed.contains_key(index) {
+                apply_debt_change(&mut debt, reserve_list.get_unchecked(index), -d_tokens);
+            }
+        }
+
+        let mut exceeds_ceiling = false;
+        if increased {
+            let reserve = pool.load_reserve(e, &asset, false);
+            exceeds_ceiling = load_debt_value(e, pool, &debt) > reserve.config.debt_ceiling;
+            pool.cache_reserve(reserve);
+        }
+        updates.set(asset, (debt, exceeds_ceiling));
+    }
+    updates
+}
+
+/// Apply a change in d_tokens of a borrowed asset to the debt backed by an isolated reserve
+fn apply_debt_change(debt: &mut Map<Address, i128>, borrowed_asset: Address, change: i128) {
+    if change == 0 {
+        return;
+    }
+    let d_tokens = debt.get(borrowed_asset.clone()).unwrap_or(0) + change;
+    if d_tokens == 0 {
+        debt.remove(borrowed_asset);
+    } else {
+        debt.set(borrowed_asset, d_tokens);
+    }
+}
+
+/// Load the assets of the isolated reserves held as collateral in the positions
+fn load_isolated_collateral(
+    e: &Env,
+    pool: &mut Pool,
+    reserve_list: &Vec<Address>,
+    positions: &Positions,
+) -> Vec<Address> {
+    let mut isolated = vec![e];
+    for index in positions.collateral.keys() {
+        let reserve = pool.load_reserve(e, &reserve_list.get_unchecked(index), false);
+        if reserve.config.isolated {
+            isolated.push_back(reserve.asset.clone());
+        }
+        pool.cache_reserve(reserve);
+    }
+    isolated
+}
+
+/// Load the value of the debt backed by an isolated reserve at current prices, in the oracle's base asset
+fn load_debt_value(e: &Env, pool: &mut Pool, debt: &Map<Address, i128>) -> i128 {
+    let mut debt_value = 0;
+    for (asset, d_tokens) in debt.iter() {
+        let reserve = pool.load_reserve(e, &asset, false);
+        let asset_to_base = pool.load_price(e, &reserve.asset);
+        debt_value += asset_to_base.fixed_mul_ceil(
+            e,
+            &reserve.to_asset_from_d_token(e, d_tokens),
+            &reserve.scalar,
+        );
+        pool.cache_reserve(reserve);
+    }
+    debt_value
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{storage::PoolConfig, testutils};
+    use sep_40_oracle::testutils::{Asset, MockPriceOracleClient};
+    use soroban_sdk::{
+        map,
+        testutils::{Address as _, Ledger, LedgerInfo},
+        Symbol,
+    };
+
+    /// Creates a pool with an isolated reserve at index 0, and two regular reserves at index 1 and 2
+    fn setup_isolation_pool(e: &Env) -> (Address, Vec<Address>) {
+        let bombadil = Address::generate(e);
+        let pool = testutils::create_pool(e);
+        let (oracle, oracle_client) = testutils::create_mock_oracle(e);
+
+        let (underlying_0, _) = testutils::create_token_contract(e, &bombadil);
+        let (mut reserve_config, reserve_data) = testutils::default_reserve_meta();
+        reserve_config.isolated = true;
+        reserve_config.debt_ceiling = 100_0000000;
+        testutils::create_reserve(e, &pool, &underlying_0, &reserve_config, &reserve_data);
+
+        let (underlying_1, _) = testutils::create_token_contract(e, &bombadil);
+        let (reserve_config, reserve_data) = testutils::default_reserve_meta();
+        testutils::create_reserve(e, &pool, &underlying_1, &reserve_config, &reserve_data);
+
+        let (underlying_2, _) = testutils::create_token_contract(e, &bombadil);
+        let (reserve_config, reserve_data) = testutils::default_reserve_meta();
+        testutils::create_reserve(e, &pool, &underlying_2, &reserve_config, &reserve_data);
+
+        oracle_client.set_data(
+            &bombadil,
+            &Asset::Other(Symbol::new(e, "USD")),
+            &vec![
+                e,
+                Asset::Stellar(underlying_0.clone()),
+                Asset::Stellar(underlying_1.clone()),
+                Asset::Stellar(underlying_2.clone()),
+            ],
+            &7,
+            &300,
+        );
+        oracle_client.set_price_stable(&vec![e, 0_5000000, 1_0000000, 2_0000000]);
+
+        let pool_config = PoolConfig {
+            oracle,
+            min_collateral: 1_0000000,
+            bstop_rate: 0_2000000,
+            status: 0,
+            max_positions: 4,
+        };
+        e.as_contract(&pool, || {
+            storage::set_pool_config(e, &pool_config);
+        });
+        (pool, vec![e, underlying_0, underlying_1, underlying_2])
+    }
+
+    #[test]
+    fn test_execute_set_isolated_borrow_list() {
+        let e = Env::default();
+        e.mock_all_auths();
+        e.ledger().set(LedgerInfo {
+            timestamp: 0,
+            protocol_version: 22,
+            sequence_number: 1234,
+            network_id: Default::default(),
+            base_reserve: 10,
+            min_temp_entry_ttl: 10,
+            min_persistent_entry_ttl: 10,
+            max_entry_ttl: 3110400,
+        });
+
+        let (pool, assets) = setup_isolation_pool(&e);
+
+        e.as_contract(&pool, || {
+            let borrow_list = vec![&e, assets.get_unchecked(1), assets.get_unchecked(2)];
+            execute_set_isolated_borrow_list(&e, &borrow_list);
+            assert_eq!(storage::get_isolated_borrow_list(&e), borrow_list);
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "Error(Contract, #1200)")]
+    fn test_execute_set_isolated_borrow_list_requires_reserve() {
+        let e = Env::default();
+        e.mock_all_auths();
+        e.ledger().set(LedgerInfo {
+            timestamp: 0,
+            protocol_version: 22,
+            sequence_number: 1234,
+            network_id: Default::default(),
+            base_reserve: 10,
+            min_temp_entry_ttl: 10,
+            min_persistent_entry_ttl: 10,
+            max_entry_ttl: 3110400,
+        });
+
+        let (pool, assets) = setup_isolation_pool(&e);
+
+        e.as_contract(&pool, || {
+            let borrow_list = vec![&e, assets.get_unchecked(1), Address::generate(&e)];
+            execute_set_isolated_borrow_list(&e, &borrow_list);
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "Error(Contract, #1200)")]
+    fn test_execute_set_isolated_borrow_list_duplicate() {
+        let e = Env::default();
+        e.mock_all_auths();
+        e.ledger().set(LedgerInfo {
+            timestamp: 0,
+            protocol_version: 22,
+            sequence_number: 1234,
+            network_id: Default::default(),
+            base_reserve: 10,
+            min_temp_entry_ttl: 10,
+            min_persistent_entry_ttl: 10,
+            max_entry_ttl: 3110400,
+        });
+
+        let (pool, assets) = setup_isolation_pool(&e);
+
+        e.as_contract(&pool, || {
+            let borrow_list = vec![&e, assets.get_unchecked(1), assets.get_unchecked(1)];
+            execute_set_isolated_borrow_list(&e, &borrow_list);
+        });
+    }
+
+    #[test]
+    fn test_require_isolation_rules_tracks_debt() {
+        let e = Env::default();
+        e.mock_all_auths();
+        e.ledger().set(LedgerInfo {
+            timestamp: 0,
+            protocol_version: 22,
+            sequence_number: 1234,
+            network_id: Default::default(),
+            base_reserve: 10,
+            min_temp_entry_ttl: 10,
+            min_persistent_entry_ttl: 10,
+            max_entry_ttl: 3110400,
+        });
+
+        let (pool, assets) = setup_isolation_pool(&e);
+
+        let prev_positions = Positions {
+            liabilities: map![&e],
+            collateral: map![&e, (0, 1000_0000000)],
+            supply: map![&e],
+        };
+        let positions = Positions {
+            liabilities: map![&e, (1, 60_0000000)],
+            collateral: map![&e, (0, 1000_0000000)],
+            supply: map![&e],
+        };
+        let repaid_positions = Positions {
+            liabilities: map![&e, (1, 20_0000000)],
+            collateral: map![&e, (0, 1000_0000000)],
+            supply: map![&e],
+        };
+        e.as_contract(&pool, || {
+            storage::set_isolated_borrow_list(&e, &vec![&e, assets.get_unchecked(1)]);
+            storage::set_isolated_debt(
+                &e,
+                &assets.get_unchecked(0),
+                &map![&e, (assets.get_unchecked(1), 10_0000000)],
+            );
+            let mut pool = Pool::load(&e);
+
+            require_isolation_rules(&e, &mut pool, &prev_positions, &positions);
+            assert_eq!(
+                storage::get_isolated_debt(&e, &assets.get_unchecked(0)),
+                map![&e, (assets.get_unchecked(1), 70_0000000)]
+            );
+
+            // a price change does not affect the tracked debt
+            MockPriceOracleClient::new(&e, &pool.config.oracle)
+                .set_price_stable(&vec![&e, 0_5000000, 0_5000000, 2_0000000]);
+            let mut pool = Pool::load(&e);
+            require_isolation_rules(&e, &mut pool, &positions, &repaid_positions);
+            assert_eq!(
+                storage::get_isolated_debt(&e, &assets.get_unchecked(0)),
+                map![&e, (assets.get_unchecked(1), 30_0000000)]
+            );
+
+            // removing the isolated collateral removes the backed debt
+            require_isolation_rules(
+                &e,
+                &mut pool,
+                &repaid_positions,
+                &Positions::env_default(&e),
+            );
+            assert_eq!(
+                storage::get_isolated_debt(&e, &assets.get_unchecked(0)),
+                map![&e, (assets.get_unchecked(1), 10_0000000)]
+            );
+        });
+    }
+
+    #[test]
+    fn test_require_isolation_rules_ignores_non_isolated() {
+        let e = Env::default();
+        e.mock_all_auths();
+        e.ledger().set(LedgerInfo {
+            timestamp: 0,
+            protocol_version: 22,
+            sequence_number: 1234,
+            network_id: Default::default(),
+            base_reserve: 10,
+            min_temp_entry_ttl: 10,
+            min_persistent_entry_ttl: 10,
+            max_entry_ttl: 3110400,
+        });
+
+        let (pool, assets) = setup_isolation_pool(&e);
+
+        let positions = Positions {
+            liabilities: map![&e, (2, 60_0000000)],
+            collateral: map![&e, (1, 1000_0000000)],
+            supply: map![&e, (0, 1000_0000000)],
+        };
+        e.as_contract(&pool, || {
+            let mut pool = Pool::load(&e);
+
+            require_isolation_rules(&e, &mut pool, &Positions::env_default(&e), &positions);
+            assert!(storage::get_isolated_debt(&e, &assets.get_unchecked(0)).is_empty());
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "Error(Contract, #1227)")]
+    fn test_require_isolation_rules_borrow_not_allowed() {
+        let e = Env::default();
+        e.mock_all_auths();
+        e.ledger().set(LedgerInfo {
+            timestamp: 0,
+            protocol_version: 22,
+            sequence_number: 1234,
+            network_id: Default::default(),
+            base_reserve: 10,
+            min_temp_entry_ttl: 10,
+            min_persistent_entry_ttl: 10,
+            max_entry_ttl: 3110400,
+        });
+
+        let (pool, assets) = setup_isolation_pool(&e);
+
+        let positions = Positions {
+            liabilities: map![&e, (1, 10_0000000), (2, 10_0000000)],
+            collateral: map![&e, (0, 1000_0000000)],
+            supply: map![&e],
+        };
+        e.as_contract(&pool, || {
+            storage::set_isolated_borrow_list(&e, &vec![&e, assets.get_unchecked(1)]);
+            let mut pool = Pool::load(&e);
+
+            require_isolation_rules(&e, &mut pool, &Positions::env_default(&e), &positions);
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "Error(Contract, #1228)")]
+    fn test_require_isolation_rules_exceeds_debt_ceiling() {
+        let e = Env::default();
+        e.mock_all_auths();
+        e.ledger().set(LedgerInfo {
+            timestamp: 0,
+            protocol_version: 22,
+            sequence_number: 1234,
+            network_id: Default::default(),
+            base_reserve: 10,
+            min_temp_entry_ttl: 10,
+            min_persistent_entry_ttl: 10,
+            max_entry_ttl: 3110400,
+        });
+
+        let (pool, assets) = setup_isolation_pool(&e);
+
+        let positions = Positions {
+            liabilities: map![&e, (1, 90_0000001)],
+            collateral: map![&e, (0, 1000_0000000)],
+            supply: map![&e],
+        };
+        e.as_contract(&pool, || {
+            storage::set_isolated_borrow_list(&e, &vec![&e, assets.get_unchecked(1)]);
+            storage::set_isolated_debt(
+                &e,
+                &assets.get_unchecked(0),
+                &map![&e, (assets.get_unchecked(1), 10_0000000)],
+            );
+            let mut pool = Pool::load(&e);
+
+            require_isolation_rules(&e, &mut pool, &Positions::env_default(&e), &positions);
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "Error(Contract, #1228)")]
+    fn test_require_isolation_rules_exceeds_debt_ceiling_multiple_assets() {
+        let e = Env::default();
+        e.mock_all_auths();
+        e.ledger().set(LedgerInfo {
+            timestamp: 0,
+            protocol_version: 22,
+            sequence_number: 1234,
+            network_id: Default::default(),
+            base_reserve: 10,
+            min_temp_entry_ttl: 10,
+            min_persistent_entry_ttl: 10,
+            max_entry_ttl: 3110400,
+        });
+
+        let (pool, assets) = setup_isolation_pool(&e);
+
+        let positions = Positions {
+            liabilities: map![&e, (1, 60_0000001)],
+            collateral: map![&e, (0, 1000_0000000)],
+            supply: map![&e],
+        };
+        e.as_contract(&pool, || {
+            storage::set_isolated_borrow_list(
+                &e,
+                &vec![&e, assets.get_unchecked(1), assets.get_unchecked(2)],
+            );
+            // 20 d_tokens of reserve 2 are worth 40 at current prices
+            storage::set_isolated_debt(
+                &e,
+                &assets.get_unchecked(0),
+                &map![&e, (assets.get_unchecked(2), 20_0000000)],
+            );
+            let mut pool = Pool::load(&e);
+
+            require_isolation_rules(&e, &mut pool, &Positions::env_default(&e), &positions);
+        });
+    }
+
+    #[test]
+    fn test_check_isolation_rules() {
+        let e = Env::default();
+        e.mock_all_auths();
+        e.ledger().set(LedgerInfo {
+            timestamp: 0,
+            protocol_version: 22,
+            sequence_number: 1234,
+            network_id: Default::default(),
+            base_reserve: 10,
+            min_temp_entry_ttl: 10,
+            min_persistent_entry_ttl: 10,
+            max_entry_ttl: 3110400,
+        });
+
+        let (pool, assets) = setup_isolation_pool(&e);
+
+        let positions = Positions {
+            liabilities: map![&e, (1, 90_0000001)],
+            collateral: map![&e, (0, 1000_0000000)],
+            supply: map![&e],
+        };
+        let outside_positions = Positions {
+            liabilities: map![&e, (2, 1_0000000)],
+            collateral: map![&e, (0, 1000_0000000)],
+            supply: map![&e],
+        };
+        e.as_contract(&pool, || {
+            storage::set_isolated_borrow_list(&e, &vec![&e, assets.get_unchecked(1)]);
+            storage::set_isolated_debt(
+                &e,
+                &assets.get_unchecked(0),
+                &map![&e, (assets.get_unchecked(1), 10_0000000)],
+            );
+            let mut pool = Pool::load(&e);
+
+            let prev_positions = Positions::env_default(&e);
+            assert_eq!(
+                check_isolation_rules(&e, &mut pool, &prev_positions, &positions),
+                Some(PoolError::ExceededDebtCeiling)
+            );
+            assert_eq!(
+                check_isolation_rules(&e, &mut pool, &prev_positions, &outside_positions),
+                Some(PoolError::InvalidIsolatedBorrow)
+            );
+
+            let mut repaid_positions = positions.clone();
+            repaid_positions.liabilities.set(1, 80_0000000);
+            assert_eq!(
+                check_isolation_rules(&e, &mut pool, &prev_positions, &repaid_positions),
+                None
+            );
+
+            // the isolated debt is not updated
+            assert_eq!(
+                storage::get_isolated_debt(&e, &assets.get_unchecked(0)),
+                map![&e, (assets.get_unchecked(1), 10_0000000)]
+            );
+        });
+    }
+
+    #[test]
+    fn test_update_isolated_debt_without_ceiling() {
+        let e = Env::default();
+        e.mock_all_auths();
+        e.ledger().set(LedgerInfo {
+            timestamp: 0,
+            protocol_version: 22,
+            sequence_number: 1234,
+            network_id: Default::default(),
+            base_reserve: 10,
+            min_temp_entry_ttl: 10,
+            min_persistent_entry_ttl: 10,
+            max_entry_ttl: 3110400,
+        });
+
+        let (pool, assets) = setup_isolation_pool(&e);
+
+        let prev_positions = Positions {
+            liabilities: map![&e, (1, 50_0000000)],
+            collateral: map![&e, (0, 1000_0000000)],
+            supply: map![&e],
+        };
+        let positions = Positions {
+            liabilities: map![&e, (1, 150_0000000)],
+            collateral: map![&e, (0, 1000_0000000)],
+            supply: map![&e],
+        };
+        e.as_contract(&pool, || {
+            storage::set_isolated_debt(
+                &e,
+                &assets.get_unchecked(0),
+                &map![&e, (assets.get_unchecked(1), 50_0000000)],
+            );
+            let mut pool = Pool::load(&e);
+
+            update_isolated_debt(&e, &mut pool, &prev_positions, &positions, false);
+            assert_eq!(
+                storage::get_isolated_debt(&e, &assets.get_unchecked(0)),
+                map![&e, (assets.get_unchecked(1), 150_0000000)]
+            );
+        });
+    }
+}
@@ -6,18 +6,60 @@ pub use bad_debt::transfer_bad_debt_to_backstop;
 
 mod config;
 pub use config::{
-    execute_cancel_queued_set_reserve, execute_initialize, execute_queue_set_reserve,
-    execute_set_reserve, execute_update_pool,
+    execute_cancel_queued_set_auction_config, execute_cancel_queued_set_oracle_config,
+    execute_cancel_queued_set_reserve, execute_initialize, execute_queue_set_auction_config,
+    execute_queue_set_oracle_config, execute_queue_set_reserve, execute_set_auction_config,
+    execute_set_keeper_config, execute_set_oracle_config, execute_set_reserve, execute_update_pool,
 };
 
+mod delegation;
+pub use delegation::{execute_approve_delegation, execute_delegated_borrow};
+
+mod delisting;
+pub use delisting::{execute_clean_delisted_position, execute_delist_reserve, is_delisted};
+
+mod emode;
+pub use emode::{
+    execute_cancel_queued_set_emode_category, execute_queue_set_emode_category,
+    execute_set_emode_category,
+};
+
+mod fixed_loan;
+pub use fixed_loan::convert_fixed_loans;
+
+mod flash_liquidation;
+pub use flash_liquidation::execute_flash_liquidation;
+
+mod flash_loan;
+pub use flash_loan::execute_flash_loan_simple;
+
 mod health_factor;
-pub use health_factor::PositionData;
+pub use health_factor::{load_user_health, PositionData, UserHealth};
 
 mod interest;
 
+mod isolation;
+pub use isolation::{execute_set_isolated_borrow_list, update_isolated_debt};
+
+mod orders;
+pub use orders::{execute_cancel_order, execute_order, execute_set_order, OrderCondition};
+
+mod signed;
+pub use signed::{
+    execute_cancel_signed, execute_set_signing_key, execute_submit_signed, SignedSubmit,
+};
+
+mod sub_account;
+pub use sub_account::sub_account_address;
+
 mod submit;
 
-pub use submit::{execute_submit, execute_submit_with_flash_loan};
+pub use submit::{
+    execute_simulate_submit, execute_submit, execute_submit_with_flash_loan, SubmitSimulation,
+};
+
+mod transfer;
+pub use transfer::execute_transfer_positions;
 
 #[allow(clippy::module_inception)]
 mod pool;
@@ -0,0 +1,430 @@
+use sep_41_token::TokenClient;
+use soroban_sdk::{panic_with_error, Address, Env};
+
+use crate::{
+    constants::MAX_CONDITIONAL_ORDERS,
+    errors::PoolError,
+    events::PoolEvents,
+    storage::{self, ConditionalOrder},
+};
+
+use super::{health_factor::PositionData, pool::Pool, submit::execute_submit, Positions, User};
+
+/// The condition that triggers a conditional order
+#[derive(Clone, PartialEq)]
+#[repr(u32)]
+pub enum OrderCondition {
+    HealthFactorBelow = 0,
+    HealthFactorAbove = 1,
+    UtilizationBelow = 2,
+    UtilizationAbove = 3,
+}
+
+impl OrderCondition {
+    /// Convert a u32 to an OrderCondition
+    ///
+    /// ### Panics
+    /// If the value is not a valid OrderCondition
+    pub fn from_u32(e: &Env, value: u32) -> Self {
+        match value {
+            0 => OrderCondition::HealthFactorBelow,
+            1 => OrderCondition::HealthFactorAbove,
+            2 => OrderCondition::UtilizationBelow,
+            3 => OrderCondition::UtilizationAbove,
+            _ => panic_with_error!(e, PoolError::InvalidConditionalOrder),
+        }
+    }
+}
+
+/// Register a conditional order for a user. Registering an order with an existing id replaces it.
+///
+/// ### Arguments
+/// * `user` - The address of the user
+/// * `order_id` - The id of the order
+/// * `order` - The conditional order
+///
+/// ### Panics
+/// If the order is invalid or expired, or the user has too many orders
+pub fn execute_set_order(e: &Env, user: &Address, order_id: u32, order: &ConditionalOrder) {
+    let condition = OrderCondition::from_u32(e, order.condition);
+    if order.threshold <= 0
+        || order.requests.is_empty()
+        || order.expiration <= e.ledger().timestamp()
+    {
+        panic_with_error!(e, PoolError::InvalidConditionalOrder);
+    }
+    if order.fee < 0 {
+        panic_with_error!(e, PoolError::NegativeAmountError);
+    }
+    if (condition == OrderCondition::UtilizationBelow
+        || condition == OrderCondition::UtilizationAbove)
+        && !storage::has_res(e, &order.reserve)
+    {
+        panic_with_error!(e, PoolError::InvalidConditionalOrder);
+    }
+
+    let mut orders = storage::get_user_orders(e, user);
+    orders.set(order_id, order.clone());
+    if orders.len() > MAX_CONDITIONAL_ORDERS {
+        panic_with_error!(e, PoolError::InvalidConditionalOrder);
+    }
+    storage::set_user_orders(e, user, &orders);
+}
+
+/// Cancel a user's conditional order
+///
+/// ### Arguments
+/// * `user` - The address of the user
+/// * `order_id` - The id of the order
+///
+/// ### Panics
+/// If the order does not exist
+pub fn execute_cancel_order(e: &Env, user: &Address, order_id: u32) {
+    let mut orders = storage::get_user_orders(e, user);
+    if orders.remove(order_id).is_none() {
+        panic_with_error!(e, PoolError::InvalidConditionalOrder);
+    }
+    storage::set_user_orders(e, user, &orders);
+}
+
+/// Execute a user's conditional order if its condition holds. The order is removed and its
+/// requests are submitted for the user, who sends and receives any tokens. Tokens owed to the
+/// pool and the keeper fee are transferred from the user with `transfer_from`.
+///
+/// ### Arguments
+/// * `keeper` - The address executing the order and receiving the fee
+/// * `user` - The address of the user who registered the order
+/// * `order_id` - The id of the order
+///
+/// ### Returns
+/// * Positions - The user's positions after the order is executed
+///
+/// ### Panics
+/// If the order does not exist or is expired, the condition does not hold, or the requests are
+/// not able to be completed
+pub fn execute_order(e: &Env, keeper: &Address, user: &Address, order_id: u32) -> Positions {
+    let mut orders = storage::get_user_orders(e, user);
+    let order = match orders.get(order_id) {
+        Some(order) if order.expiration > e.ledger().timestamp() => order,
+        _ => panic_with_error!(e, PoolError::InvalidConditionalOrder),
+    };
+    if !is_condition_met(e, user, &order) {
+        panic_with_error!(e, PoolError::OrderConditionNotMet);
+    }
+    orders.remove(order_id);
+    storage::set_user_orders(e, user, &orders);
+
+    let positions = execute_submit(e, user, user, user, order.requests, true);
+
+    if order.fee > 0 {
+        TokenClient::new(e, &order.fee_asset).transfer_from(
+            &e.current_contract_address(),
+            user,
+            keeper,
+            &order.fee,
+        );
+    }
+
+    PoolEvents::execute_order(
+        e,
+        user.clone(),
+        keeper.clone(),
+        order_id,
+        order.fee_asset,
+        order.fee,
+    );
+    positions
+}
+
+/// Check if a conditional order's condition holds for a user
+fn is_condition_met(e: &Env, user: &Address, order: &ConditionalOrder) -> bool {
+    let mut pool = Pool::load(e);
+    match OrderCondition::from_u32(e, order.condition) {
+        OrderCondition::HealthFactorBelow => {
+            let user_state = User::load(e, user);
+            PositionData::calculate_from_user(e, &mut pool, &user_state)
+                .is_hf_under(e, order.threshold)
+        }
+        OrderCondition::HealthFactorAbove => {
+            let user_state = User::load(e, user);
+            PositionData::calculate_from_user(e, &mut pool, &user_state)
+                .is_hf_over(e, order.threshold)
+        }
+        OrderCondition::UtilizationBelow => {
+            let reserve = pool.load_reserve(e, &order.reserve, false);
+            reserve.utilization(e) < order.threshold
+        }
+        OrderCondition::UtilizationAbove => {
+            let reserve = pool.load_reserve(e, &order.reserve, false);
+            reserve.utilization(e) > order.threshold
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        pool::{Request, RequestType},
+        storage::PoolConfig,
+        testutils::{self, create_pool},
+    };
+    use sep_40_oracle::testutils::Asset;
+    use sep_41_token::testutils::MockTokenClient;
+    use soroban_sdk::{
+        map,
+        testutils::{Address as _, Ledger, LedgerInfo},
+        vec, Symbol,
+    };
+
+    /// Setup a pool with two reserves with prices of 1 and 5 where samwise has supplied
+    /// collateral of the first reserve and borrowed the second
+    fn setup_pool(e: &Env) -> (Address, Address, Address, Address) {
+        e.ledger().set(LedgerInfo {
+            timestamp: 600,
+            protocol_version: 22,
+            sequence_number: 1234,
+            network_id: Default::default(),
+            base_reserve: 10,
+            min_temp_entry_ttl: 10,
+            min_persistent_entry_ttl: 10,
+            max_entry_ttl: 3110400,
+        });
+
+        let bombadil = Address::generate(e);
+        let samwise = Address::generate(e);
+        let pool = create_pool(e);
+        let (oracle, oracle_client) = testutils::create_mock_oracle(e);
+
+        let (underlying_0, _) = testutils::create_token_contract(e, &bombadil);
+        let (reserve_config, mut reserve_data) = testutils::default_reserve_meta();
+        reserve_data.last_time = 600;
+        testutils::create_reserve(e, &pool, &underlying_0, &reserve_config, &reserve_data);
+
+        let (underlying_1, underlying_1_client) = testutils::create_token_contract(e, &bombadil);
+        testutils::create_reserve(e, &pool, &underlying_1, &reserve_config, &reserve_data);
+
+        oracle_client.set_data(
+            &bombadil,
+            &Asset::Other(Symbol::new(e, "USD")),
+            &vec![
+                e,
+                Asset::Stellar(underlying_0.clone()),
+                Asset::Stellar(underlying_1.clone()),
+            ],
+            &7,
+            &300,
+        );
+        oracle_client.set_price_stable(&vec![e, 1_0000000, 5_0000000]);
+
+        underlying_1_client.mint(&samwise, &3_0000000);
+        underlying_1_client.approve(&samwise, &pool, &10_0000000, &10000);
+
+        let pool_config = PoolConfig {
+            oracle,
+            min_collateral: 1_0000000,
+            bstop_rate: 0_1000000,
+            status: 0,
+            max_positions: 4,
+        };
+        e.as_contract(&pool, || {
+            storage::set_pool_config(e, &pool_config);
+            // hf = (100 * 0.75) / (10 * 5 / 0.75) = 1.125
+            storage::set_user_positions(
+                e,
+                &samwise,
+                &Positions {
+                    liabilities: map![e, (1, 10_0000000)],
+                    collateral: map![e, (0, 100_0000000)],
+                    supply: map![e],
+                },
+            );
+        });
+        (pool, samwise, underlying_0, underlying_1)
+    }
+
+    fn repay_order(
+        e: &Env,
+        underlying_1: &Address,
+        condition: u32,
+        threshold: i128,
+    ) -> ConditionalOrder {
+        ConditionalOrder {
+            condition,
+            reserve: underlying_1.clone(),
+            threshold,
+            requests: vec![
+                e,
+                Request {
+                    request_type: RequestType::Repay as u32,
+                    address: underlying_1.clone(),
+                    amount: 2_0000000,
+                },
+            ],
+            fee_asset: underlying_1.clone(),
+            fee: 0_1000000,
+            expiration: 1000,
+        }
+    }
+
+    #[test]
+    fn test_execute_set_and_cancel_order() {
+        let e = Env::default();
+        e.mock_all_auths();
+        let (pool, samwise, _, underlying_1) = setup_pool(&e);
+
+        e.as_contract(&pool, || {
+            let order = repay_order(&e, &underlying_1, 0, 1_2000000);
+            execute_set_order(&e, &samwise, 1, &order);
+            execute_set_order(&e, &samwise, 2, &order);
+
+            let orders = storage::get_user_orders(&e, &samwise);
+            assert_eq!(orders.len(), 2);
+            assert_eq!(orders.get_unchecked(1).threshold, 1_2000000);
+
+            execute_cancel_order(&e, &samwise, 1);
+            let orders = storage::get_user_orders(&e, &samwise);
+            assert_eq!(orders.len(), 1);
+            assert!(orders.contains_key(2));
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "Error(Contract, #1235)")]
+    fn test_execute_set_order_invalid_condition() {
+        let e = Env::default();
+        e.mock_all_auths();
+        let (pool, samwise, _, underlying_1) = setup_pool(&e);
+
+        e.as_contract(&pool, || {
+            let order = repay_order(&e, &underlying_1, 4, 1_2000000);
+            execute_set_order(&e, &samwise, 1, &order);
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "Error(Contract, #1235)")]
+    fn test_execute_set_order_max_orders() {
+        let e = Env::default();
+        e.mock_all_auths();
+        let (pool, samwise, _, underlying_1) = setup_pool(&e);
+
+        e.as_contract(&pool, || {
+            let order = repay_order(&e, &underlying_1, 0, 1_2000000);
+            for order_id in 0..(MAX_CONDITIONAL_ORDERS + 1) {
+                execute_set_order(&e, &samwise, order_id, &order);
+            }
+        });
+    }
+
+    #[test]
+    fn test_execute_order_health_factor_below() {
+        let e = Env::default();
+        e.cost_estimate().budget().reset_unlimited();
+        e.mock_all_auths();
+        let (pool, samwise, _, underlying_1) = setup_pool(&e);
+        let keeper = Address::generate(&e);
+        let underlying_1_client = MockTokenClient::new(&e, &underlying_1);
+
+        e.as_contract(&pool, || {
+            let order = repay_order(&e, &underlying_1, 0, 1_2000000);
+            execute_set_order(&e, &samwise, 1, &order);
+
+            let positions = execute_order(&e, &keeper, &samwise, 1);
+
+            assert_eq!(positions.liabilities.get_unchecked(1), 8_0000000);
+            assert_eq!(underlying_1_client.balance(&samwise), 0_9000000);
+            assert_eq!(underlying_1_client.balance(&keeper), 0_1000000);
+            assert_eq!(storage::get_user_orders(&e, &samwise).len(), 0);
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "Error(Contract, #1236)")]
+    fn test_execute_order_health_factor_not_met() {
+        let e = Env::default();
+        e.cost_estimate().budget().reset_unlimited();
+        e.mock_all_auths();
+        let (pool, samwise, _, underlying_1) = setup_pool(&e);
+        let keeper = Address::generate(&e);
+
+        e.as_contract(&pool, || {
+            let order = repay_order(&e, &underlying_1, 0, 1_1000000);
+            execute_set_order(&e, &samwise, 1, &order);
+
+            execute_order(&e, &keeper, &samwise, 1);
+        });
+    }
+
+    #[test]
+    fn test_execute_order_utilization_below() {
+        let e = Env::default();
+        e.cost_estimate().budget().reset_unlimited();
+        e.mock_all_auths();
+        let (pool, samwise, underlying_0, _) = setup_pool(&e);
+        let keeper = Address::generate(&e);
+        let underlying_0_client = MockTokenClient::new(&e, &underlying_0);
+
+        e.as_contract(&pool, || {
+            // reserve utilization is 75%
+            let order = ConditionalOrder {
+                condition: OrderCondition::UtilizationBelow as u32,
+                reserve: underlying_0.clone(),
+                threshold: 0_8000000,
+                requests: vec![
+                    &e,
+                    Request {
+                        request_type: RequestType::WithdrawCollateral as u32,
+                        address: underlying_0.clone(),
+                        amount: 10_0000000,
+                    },
+                ],
+                fee_asset: underlying_0.clone(),
+                fee: 0,
+                expiration: 1000,
+            };
+            execute_set_order(&e, &samwise, 1, &order);
+
+            let positions = execute_order(&e, &keeper, &samwise, 1);
+
+            assert_eq!(positions.collateral.get_unchecked(0), 90_0000000);
+            assert_eq!(underlying_0_client.balance(&samwise), 10_0000000);
+            assert_eq!(underlying_0_client.balance(&keeper), 0);
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "Error(Contract, #1236)")]
+    fn test_execute_order_utilization_above_not_met() {
+        let e = Env::default();
+        e.cost_estimate().budget().reset_unlimited();
+        e.mock_all_auths();
+        let (pool, samwise, _, underlying_1) = setup_pool(&e);
+        let keeper = Address::generate(&e);
+
+        e.as_contract(&pool, || {
+            let order = repay_order(&e, &underlying_1, 3, 0_8000000);
+            execute_set_order(&e, &samwise, 1, &order);
+
+            execute_order(&e, &keeper, &samwise, 1);
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "Error(Contract, #1235)")]
+    fn test_execute_order_expired() {
+        let e = Env::default();
+        e.cost_estimate().budget().reset_unlimited();
+        e.mock_all_auths();
+        let (pool, samwise, _, underlying_1) = setup_pool(&e);
+        let keeper = Address::generate(&e);
+
+        e.as_contract(&pool, || {
+            let order = repay_order(&e, &underlying_1, 0, 1_2000000);
+            execute_set_order(&e, &samwise, 1, &order);
+
+            e.ledger().set_timestamp(1000);
+            execute_order(&e, &keeper, &samwise, 1);
+        });
+    }
+}
@@ -1,21 +1,23 @@
-use soroban_sdk::{map, panic_with_error, unwrap::UnwrapOptimized, vec, Address, Env, Map, Vec};
+use soroban_sdk::{map, panic_with_error, vec, Address, Env, Map, Vec};
 
-use sep_40_oracle::{Asset, PriceFeedClient};
+use sep_40_oracle::{Asset, PriceData, PriceFeedClient};
 
 use crate::{
     errors::PoolError,
-    storage::{self, PoolConfig},
-    Positions,
+    events::PoolEvents,
+    storage::{self, OracleConfig, PoolConfig},
 };
 
-use super::reserve::Reserve;
+use super::{actions::RequestType, reserve::Reserve, User};
 
 pub struct Pool {
     pub config: PoolConfig,
+    pub oracle_config: OracleConfig,
     pub reserves: Map<Address, Reserve>,
     reserves_to_store: Vec<Address>,
     price_decimals: Option<u32>,
     prices: Map<Address, i128>,
+    twap_prices: Map<Address, Option<i128>>,
 }
 
 impl Pool {
@@ -24,10 +26,12 @@ impl Pool {
         let pool_config = storage::get_pool_config(e);
         Pool {
             config: pool_config,
+            oracle_config: storage::get_oracle_config(e),
             reserves: map![e],
             reserves_to_store: vec![e],
             price_decimals: None,
             prices: map![e],
+            twap_prices: map![e],
         }
     }
 
@@ -74,8 +78,14 @@ impl Pool {
     /// * `action_type` - The type of action being performed
     pub fn require_action_allowed(&self, e: &Env, action_type: u32) {
         // disable borrowing or auction cancellation for any non-active pool and disable supplying for any frozen pool
-        if (self.config.status > 1 && (action_type == 4 || action_type == 9))
-            || (self.config.status > 3 && (action_type == 2 || action_type == 0))
+        if (self.config.status > 1
+            && (action_type == RequestType::Borrow as u32
+                || action_type == RequestType::DeleteLiquidationAuction as u32
+                || action_type == RequestType::BorrowFixed as u32
+                || action_type == RequestType::RollFixedLoan as u32))
+            || (self.config.status > 3
+                && (action_type == RequestType::SupplyCollateral as u32
+                    || action_type == RequestType::Supply as u32))
         {
             panic_with_error!(e, PoolError::InvalidPoolStatus);
         }
@@ -84,14 +94,14 @@ impl Pool {
     /// Require that a position does not violate the maximum number of positions, or panic.
     ///
     /// ### Arguments
-    /// * `positions` - The user's positions
+    /// * `user` - The user, whose fixed-term loans count as positions
     /// * `previous_num` - The number of positions the user previously had
     ///
     /// ### Panics
     /// If the user has more positions than the maximum allowed and they are not
     /// decreasing their number of positions
-    pub fn require_under_max(&self, e: &Env, positions: &Positions, previous_num: u32) {
-        let new_num = positions.effective_count();
+    pub fn require_under_max(&self, e: &Env, user: &User, previous_num: u32) {
+        let new_num = user.effective_count();
         if new_num > previous_num && self.config.max_positions < new_num {
             panic_with_error!(e, PoolError::MaxPositionsExceeded)
         }
@@ -109,26 +119,157 @@ impl Pool {
         decimals
     }
 
-    /// Load a price from the Pool's oracle. Returns a cached version if one already exists.
+    /// Load a price from the Pool's oracles. Returns a cached version if one already exists.
+    ///
+    /// If the pool has a quorum set, the median price of all valid oracle prices is used. Otherwise,
+    /// the backup oracles are used in order if the primary oracle is unable to provide a valid price.
     ///
     /// ### Arguments
     /// * asset - The address of the underlying asset
     ///
     /// ### Panics
-    /// If the price is invalid due to being over a day old or being less than or equal to 0
+    /// If no valid price can be found, or if the quorum of valid prices is not met. A price is invalid
+    /// if it is over a day old or is less than or equal to 0.
     pub fn load_price(&mut self, e: &Env, asset: &Address) -> i128 {
         if let Some(price) = self.prices.get(asset.clone()) {
             return price;
         }
-        let oracle_client = PriceFeedClient::new(e, &self.config.oracle);
-        let oracle_asset = Asset::Stellar(asset.clone());
-        let price_data = oracle_client.lastprice(&oracle_asset).unwrap_optimized();
-        if price_data.timestamp + 24 * 60 * 60 < e.ledger().timestamp() || price_data.price <= 0 {
-            panic_with_error!(e, PoolError::InvalidPrice);
+        let (price, sources) = match self.select_price(e, |oracle| fetch_price(e, oracle, asset)) {
+            Some(selected) => selected,
+            None => panic_with_error!(e, PoolError::InvalidPrice),
+        };
+        if self.oracle_config.quorum > 0 {
+            PoolEvents::oracle_median(e, asset.clone(), sources, price);
+        } else {
+            let source = sources.get_unchecked(0);
+            if source != self.config.oracle {
+                PoolEvents::oracle_fallback(e, asset.clone(), source, price);
+            }
         }
-        self.prices.set(asset.clone(), price_data.price);
-        price_data.price
+        self.prices.set(asset.clone(), price);
+        price
     }
+
+    /// Load a time-weighted average price from the Pool's oracles by averaging the last `records`
+    /// prices reported for the asset. Returns a cached version if one already exists.
+    ///
+    /// The oracles are selected the same way as `load_price`. If the pool has a quorum set, the
+    /// median TWAP of all oracles that report valid records is used. Otherwise, the backup oracles
+    /// are used in order if the primary oracle is unable to provide any valid records.
+    ///
+    /// Records that are over a day old or are less than or equal to 0 are ignored. Returns None if
+    /// the oracles are unable to provide any valid records, or if the quorum is not met.
+    ///
+    /// ### Arguments
+    /// * asset - The address of the underlying asset
+    /// * records - The number of records to average
+    pub fn load_twap_price(&mut self, e: &Env, asset: &Address, records: u32) -> Option<i128> {
+        if let Some(twap) = self.twap_prices.get(asset.clone()) {
+            return twap;
+        }
+        let twap = self
+            .select_price(e, |oracle| fetch_twap(e, oracle, asset, records))
+            .map(|(twap, _)| twap);
+        self.twap_prices.set(asset.clone(), twap);
+        twap
+    }
+
+    /// Select a price from the Pool's oracles, where `fetch` loads a price from a single oracle.
+    ///
+    /// If the pool has a quorum set, the median of all prices is used. Otherwise, the backup
+    /// oracles are used in order if the primary oracle is unable to provide a price.
+    ///
+    /// Returns the price and the oracles it was sourced from, or None if no price can be found or
+    /// the quorum is not met
+    fn select_price(
+        &self,
+        e: &Env,
+        fetch: impl Fn(&Address) -> Option<i128>,
+    ) -> Option<(i128, Vec<Address>)> {
+        let mut oracles = vec![e, self.config.oracle.clone()];
+        oracles.append(&self.oracle_config.oracles);
+        if self.oracle_config.quorum == 0 {
+            for oracle in oracles.iter() {
+                if let Some(price) = fetch(&oracle) {
+                    return Some((price, vec![e, oracle]));
+                }
+            }
+            return None;
+        }
+
+        let mut sources: Vec<Address> = vec![e];
+        let mut sorted_prices: Vec<i128> = vec![e];
+        for oracle in oracles.iter() {
+            if let Some(price) = fetch(&oracle) {
+                let index = sorted_prices
+                    .iter()
+                    .position(|sorted_price| sorted_price > price)
+                    .unwrap_or(sorted_prices.len() as usize);
+                sorted_prices.insert(index as u32, price);
+                sources.push_back(oracle);
+            }
+        }
+
+        let count = sorted_prices.len();
+        if count == 0 || count < self.oracle_config.quorum {
+            return None;
+        }
+        let median = if count % 2 == 1 {
+            sorted_prices.get_unchecked(count / 2)
+        } else {
+            (sorted_prices.get_unchecked(count / 2 - 1) + sorted_prices.get_unchecked(count / 2))
+                / 2
+        };
+        Some((median, sources))
+    }
+}
+
+/// Fetch the last price for an asset from an oracle. Returns None if the oracle fails or
+/// the price is invalid due to being over a day old or being less than or equal to 0.
+///
+/// ### Arguments
+/// * oracle - The address of the oracle
+/// * asset - The address of the underlying asset
+fn fetch_price(e: &Env, oracle: &Address, asset: &Address) -> Option<i128> {
+    let oracle_client = PriceFeedClient::new(e, oracle);
+    let oracle_asset = Asset::Stellar(asset.clone());
+    match oracle_client.try_lastprice(&oracle_asset) {
+        Ok(Ok(Some(price_data))) if is_valid_price(e, &price_data) => Some(price_data.price),
+        _ => None,
+    }
+}
+
+/// Fetch the average of the last `records` prices for an asset from an oracle. Returns None if
+/// the oracle fails or has no valid records. Records that are over a day old or are less than or
+/// equal to 0 are ignored.
+///
+/// ### Arguments
+/// * oracle - The address of the oracle
+/// * asset - The address of the underlying asset
+/// * records - The number of records to average
+fn fetch_twap(e: &Env, oracle: &Address, asset: &Address, records: u32) -> Option<i128> {
+    let oracle_client = PriceFeedClient::new(e, oracle);
+    let oracle_asset = Asset::Stellar(asset.clone());
+    let mut total: i128 = 0;
+    let mut count: i128 = 0;
+    if let Ok(Ok(Some(history))) = oracle_client.try_prices(&oracle_asset, &records) {
+        for price_data in history.iter().take(records as usize) {
+            if is_valid_price(e, &price_data) {
+                total += price_data.price;
+                count += 1;
+            }
+        }
+    }
+    if count > 0 {
+        Some(total / count)
+    } else {
+        None
+    }
+}
+
+/// Check if a price is valid. A price is invalid if it is over a day old or is less than or equal to 0.
+fn is_valid_price(e: &Env, price_data: &PriceData) -> bool {
+    price_data.timestamp + 24 * 60 * 60 >= e.ledger().timestamp() && price_data.price > 0
 }
 
 #[cfg(test)]
@@ -139,7 +280,7 @@ mod tests {
         Symbol,
     };
 
-    use crate::{pool::User, storage::ReserveData, testutils};
+    use crate::{storage::ReserveData, testutils, Positions};
 
     use super::*;
 
@@ -190,6 +331,7 @@ mod tests {
                     ir_mod: 0,
                     b_supply: 0,
                     d_supply: 0,
+                    fixed_debt: 0,
                     last_time: 0,
                     backstop_credit: 0,
                 },
@@ -274,6 +416,7 @@ mod tests {
                     ir_mod: 0,
                     b_supply: 0,
                     d_supply: 0,
+                    fixed_debt: 0,
                     last_time: 0,
                     backstop_credit: 0,
                 },
@@ -674,6 +817,377 @@ mod tests {
         });
     }
 
+    #[test]
+    fn test_load_twap_price() {
+        let e = Env::default();
+        e.mock_all_auths_allowing_non_root_auth();
+
+        e.ledger().set(LedgerInfo {
+            timestamp: 1000 + 24 * 60 * 60,
+            protocol_version: 22,
+            sequence_number: 1234,
+            network_id: Default::default(),
+            base_reserve: 10,
+            min_temp_entry_ttl: 10,
+            min_persistent_entry_ttl: 10,
+            max_entry_ttl: 3110400,
+        });
+
+        let pool = testutils::create_pool(&e);
+        let asset_0 = Address::generate(&e);
+        let asset_1 = Address::generate(&e);
+        let (oracle, oracle_client) = testutils::create_mock_historical_oracle(&e);
+        // zero and stale records are ignored
+        oracle_client.set_history(
+            &asset_0,
+            &vec![
+                &e,
+                PriceData {
+                    price: 1_1000000,
+                    timestamp: 1000 + 24 * 60 * 60,
+                },
+                PriceData {
+                    price: 0,
+                    timestamp: 1000 + 24 * 60 * 60 - 300,
+                },
+                PriceData {
+                    price: 1_0000000,
+                    timestamp: 1000,
+                },
+                PriceData {
+                    price: 5_0000000,
+                    timestamp: 999,
+                },
+            ],
+        );
+
+        let pool_config = PoolConfig {
+            oracle,
+            min_collateral: 1_0000000,
+            bstop_rate: 0_2000000,
+            status: 0,
+            max_positions: 2,
+        };
+        e.as_contract(&pool, || {
+            storage::set_pool_config(&e, &pool_config);
+            let mut pool = Pool::load(&e);
+
+            let twap = pool.load_twap_price(&e, &asset_0, 4);
+            assert_eq!(twap, Some(1_0500000));
+
+            // no history
+            let twap = pool.load_twap_price(&e, &asset_1, 4);
+            assert_eq!(twap, None);
+
+            // verify the twap is cached
+            oracle_client.set_history(
+                &asset_0,
+                &vec![
+                    &e,
+                    PriceData {
+                        price: 2_0000000,
+                        timestamp: 1000 + 24 * 60 * 60,
+                    },
+                ],
+            );
+            let twap = pool.load_twap_price(&e, &asset_0, 4);
+            assert_eq!(twap, Some(1_0500000));
+        });
+    }
+
+    #[test]
+    fn test_load_twap_price_falls_back_to_backup_oracle() {
+        let e = Env::default();
+        e.mock_all_auths_allowing_non_root_auth();
+
+        e.ledger().set(LedgerInfo {
+            timestamp: 1000 + 24 * 60 * 60,
+            protocol_version: 22,
+            sequence_number: 1234,
+            network_id: Default::default(),
+            base_reserve: 10,
+            min_temp_entry_ttl: 10,
+            min_persistent_entry_ttl: 10,
+            max_entry_ttl: 3110400,
+        });
+
+        let pool = testutils::create_pool(&e);
+        let asset = Address::generate(&e);
+        let (oracle, oracle_client) = testutils::create_mock_historical_oracle(&e);
+        let (backup, backup_client) = testutils::create_mock_historical_oracle(&e);
+        // the primary oracle only has stale records
+        oracle_client.set_history(
+            &asset,
+            &vec![
+                &e,
+                PriceData {
+                    price: 1_0000000,
+                    timestamp: 999,
+                },
+            ],
+        );
+        backup_client.set_history(
+            &asset,
+            &vec![
+                &e,
+                PriceData {
+                    price: 1_2000000,
+                    timestamp: 1000 + 24 * 60 * 60,
+                },
+                PriceData {
+                    price: 1_1000000,
+                    timestamp: 1000 + 24 * 60 * 60 - 300,
+                },
+            ],
+        );
+
+        let pool_config = PoolConfig {
+            oracle,
+            min_collateral: 1_0000000,
+            bstop_rate: 0_2000000,
+            status: 0,
+            max_positions: 2,
+        };
+        let oracle_config = OracleConfig {
+            oracles: vec![&e, backup],
+            quorum: 0,
+        };
+        e.as_contract(&pool, || {
+            storage::set_pool_config(&e, &pool_config);
+            storage::set_oracle_config(&e, &oracle_config);
+            let mut pool = Pool::load(&e);
+
+            let twap = pool.load_twap_price(&e, &asset, 4);
+            assert_eq!(twap, Some(1_1500000));
+        });
+    }
+
+    #[test]
+    fn test_load_twap_price_median() {
+        let e = Env::default();
+        e.mock_all_auths_allowing_non_root_auth();
+
+        e.ledger().set(LedgerInfo {
+            timestamp: 1000 + 24 * 60 * 60,
+            protocol_version: 22,
+            sequence_number: 1234,
+            network_id: Default::default(),
+            base_reserve: 10,
+            min_temp_entry_ttl: 10,
+            min_persistent_entry_ttl: 10,
+            max_entry_ttl: 3110400,
+        });
+
+        let pool = testutils::create_pool(&e);
+        let asset_0 = Address::generate(&e);
+        let asset_1 = Address::generate(&e);
+        let (oracle, oracle_client) = testutils::create_mock_historical_oracle(&e);
+        let (backup_0, backup_0_client) = testutils::create_mock_historical_oracle(&e);
+        let (backup_1, backup_1_client) = testutils::create_mock_historical_oracle(&e);
+        for (client, price) in [
+            (&oracle_client, 1_0000000),
+            (&backup_0_client, 1_2000000),
+            (&backup_1_client, 5_0000000),
+        ] {
+            client.set_history(
+                &asset_0,
+                &vec![
+                    &e,
+                    PriceData {
+                        price,
+                        timestamp: 1000 + 24 * 60 * 60,
+                    },
+                ],
+            );
+        }
+        // only the primary oracle reports asset_1
+        oracle_client.set_history(
+            &asset_1,
+            &vec![
+                &e,
+                PriceData {
+                    price: 1_0000000,
+                    timestamp: 1000 + 24 * 60 * 60,
+                },
+            ],
+        );
+
+        let pool_config = PoolConfig {
+            oracle,
+            min_collateral: 1_0000000,
+            bstop_rate: 0_2000000,
+            status: 0,
+            max_positions: 2,
+        };
+        let oracle_config = OracleConfig {
+            oracles: vec![&e, backup_0, backup_1],
+            quorum: 2,
+        };
+        e.as_contract(&pool, || {
+            storage::set_pool_config(&e, &pool_config);
+            storage::set_oracle_config(&e, &oracle_config);
+            let mut pool = Pool::load(&e);
+
+            let twap = pool.load_twap_price(&e, &asset_0, 4);
+            assert_eq!(twap, Some(1_2000000));
+
+            // the quorum is not met
+            let twap = pool.load_twap_price(&e, &asset_1, 4);
+            assert_eq!(twap, None);
+        });
+    }
+
+    #[test]
+    fn test_load_price_falls_back_to_backup_oracle() {
+        let e = Env::default();
+        e.mock_all_auths_allowing_non_root_auth();
+
+        e.ledger().set(LedgerInfo {
+            timestamp: 1000 + 24 * 60 * 60 + 1,
+            protocol_version: 22,
+            sequence_number: 1234,
+            network_id: Default::default(),
+            base_reserve: 10,
+            min_temp_entry_ttl: 10,
+            min_persistent_entry_ttl: 10,
+            max_entry_ttl: 3110400,
+        });
+
+        let pool = testutils::create_pool(&e);
+        let asset = Address::generate(&e);
+        let oracles = testutils::create_mock_oracles(&e, 3, &vec![&e, asset.clone()], 7);
+        let (oracle, oracle_client) = &oracles[0];
+        let (backup_0, backup_0_client) = &oracles[1];
+        let (backup_1, backup_1_client) = &oracles[2];
+        // primary is stale, first backup is zero
+        oracle_client.set_price(&vec![&e, 123], &1000);
+        backup_0_client.set_price_stable(&vec![&e, 0]);
+        backup_1_client.set_price_stable(&vec![&e, 125]);
+        let pool_config = PoolConfig {
+            oracle: oracle.clone(),
+            min_collateral: 1_0000000,
+            bstop_rate: 0_2000000,
+            status: 0,
+            max_positions: 2,
+        };
+        let oracle_config = OracleConfig {
+            oracles: vec![&e, backup_0.clone(), backup_1.clone()],
+            quorum: 0,
+        };
+        e.as_contract(&pool, || {
+            storage::set_pool_config(&e, &pool_config);
+            storage::set_oracle_config(&e, &oracle_config);
+            let mut pool = Pool::load(&e);
+
+            let price = pool.load_price(&e, &asset);
+            assert_eq!(price, 125);
+        });
+    }
+
+    #[test]
+    fn test_load_price_median() {
+        let e = Env::default();
+        e.mock_all_auths_allowing_non_root_auth();
+
+        e.ledger().set(LedgerInfo {
+            timestamp: 1000 + 24 * 60 * 60 + 1,
+            protocol_version: 22,
+            sequence_number: 1234,
+            network_id: Default::default(),
+            base_reserve: 10,
+            min_temp_entry_ttl: 10,
+            min_persistent_entry_ttl: 10,
+            max_entry_ttl: 3110400,
+        });
+
+        let pool = testutils::create_pool(&e);
+        let asset = Address::generate(&e);
+        let oracles = testutils::create_mock_oracles(&e, 4, &vec![&e, asset.clone()], 7);
+        let (oracle, oracle_client) = &oracles[0];
+        let (backup_0, backup_0_client) = &oracles[1];
+        let (backup_1, backup_1_client) = &oracles[2];
+        let (backup_2, backup_2_client) = &oracles[3];
+        oracle_client.set_price_stable(&vec![&e, 1_0500000]);
+        backup_0_client.set_price_stable(&vec![&e, 1_0000000]);
+        backup_1_client.set_price_stable(&vec![&e, 9_0000000]);
+        // stale backup is ignored
+        backup_2_client.set_price(&vec![&e, 1_0200000], &1000);
+        let pool_config = PoolConfig {
+            oracle: oracle.clone(),
+            min_collateral: 1_0000000,
+            bstop_rate: 0_2000000,
+            status: 0,
+            max_positions: 2,
+        };
+        let oracle_config = OracleConfig {
+            oracles: vec![&e, backup_0.clone(), backup_1.clone(), backup_2.clone()],
+            quorum: 3,
+        };
+        e.as_contract(&pool, || {
+            storage::set_pool_config(&e, &pool_config);
+            storage::set_oracle_config(&e, &oracle_config);
+            let mut pool = Pool::load(&e);
+
+            let price = pool.load_price(&e, &asset);
+            assert_eq!(price, 1_0500000);
+
+            // even number of valid prices averages the middle two
+            let mut pool = Pool::load(&e);
+            pool.oracle_config.quorum = 2;
+            oracle_client.set_price_stable(&vec![&e, 1_0400000]);
+            backup_1_client.set_price(&vec![&e, 9_0000000], &1000);
+            let price = pool.load_price(&e, &asset);
+            assert_eq!(price, 1_0200000);
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "Error(Contract, #1210)")]
+    fn test_load_price_median_panics_if_quorum_not_met() {
+        let e = Env::default();
+        e.mock_all_auths_allowing_non_root_auth();
+
+        e.ledger().set(LedgerInfo {
+            timestamp: 1000 + 24 * 60 * 60 + 1,
+            protocol_version: 22,
+            sequence_number: 1234,
+            network_id: Default::default(),
+            base_reserve: 10,
+            min_temp_entry_ttl: 10,
+            min_persistent_entry_ttl: 10,
+            max_entry_ttl: 3110400,
+        });
+
+        let pool = testutils::create_pool(&e);
+        let asset = Address::generate(&e);
+        let oracles = testutils::create_mock_oracles(&e, 3, &vec![&e, asset.clone()], 7);
+        let (oracle, oracle_client) = &oracles[0];
+        let (backup_0, backup_0_client) = &oracles[1];
+        let (backup_1, backup_1_client) = &oracles[2];
+        oracle_client.set_price_stable(&vec![&e, 1_0000000]);
+        backup_0_client.set_price(&vec![&e, 1_0000000], &1000);
+        backup_1_client.set_price_stable(&vec![&e, 1_0100000]);
+        let pool_config = PoolConfig {
+            oracle: oracle.clone(),
+            min_collateral: 1_0000000,
+            bstop_rate: 0_2000000,
+            status: 0,
+            max_positions: 2,
+        };
+        let oracle_config = OracleConfig {
+            oracles: vec![&e, backup_0.clone(), backup_1.clone()],
+            quorum: 3,
+        };
+        e.as_contract(&pool, || {
+            storage::set_pool_config(&e, &pool_config);
+            storage::set_oracle_config(&e, &oracle_config);
+            let mut pool = Pool::load(&e);
+
+            pool.load_price(&e, &asset);
+            assert!(false);
+        });
+    }
+
     #[test]
     fn test_require_under_max_empty() {
         let e = Env::default();
@@ -686,6 +1200,8 @@ mod tests {
         let mut user = User {
             address: samwise.clone(),
             positions: Positions::env_default(&e),
+            emode: 0,
+            fixed_loans: map![&e],
         };
         let pool_config = PoolConfig {
             oracle,
@@ -696,12 +1212,12 @@ mod tests {
         };
         e.as_contract(&pool, || {
             storage::set_pool_config(&e, &pool_config);
-            let prev_positions = user.positions.effective_count();
+            let prev_positions = user.effective_count();
 
             let pool = Pool::load(&e);
             user.add_collateral(&e, &mut reserve_0, 1);
 
-            pool.require_under_max(&e, &user.positions, prev_positions);
+            pool.require_under_max(&e, &user, prev_positions);
         });
     }
 
@@ -720,6 +1236,8 @@ mod tests {
         let mut user = User {
             address: samwise.clone(),
             positions: Positions::env_default(&e),
+            emode: 0,
+            fixed_loans: map![&e],
         };
         let pool_config = PoolConfig {
             oracle,
@@ -733,12 +1251,12 @@ mod tests {
             user.add_supply(&e, &mut reserve_0, 42);
             user.add_supply(&e, &mut reserve_1, 42);
             user.add_collateral(&e, &mut reserve_1, 1);
-            let prev_positions = user.positions.effective_count();
+            let prev_positions = user.effective_count();
 
             let pool = Pool::load(&e);
             user.add_liabilities(&e, &mut reserve_1, 2);
 
-            pool.require_under_max(&e, &user.positions, prev_positions);
+            pool.require_under_max(&e, &user, prev_positions);
         });
     }
 
@@ -757,6 +1275,8 @@ mod tests {
         let mut user = User {
             address: samwise.clone(),
             positions: Positions::env_default(&e),
+            emode: 0,
+            fixed_loans: map![&e],
         };
         let pool_config = PoolConfig {
             oracle,
@@ -771,12 +1291,12 @@ mod tests {
             user.add_collateral(&e, &mut reserve_1, 42);
             user.add_liabilities(&e, &mut reserve_0, 123);
             user.add_liabilities(&e, &mut reserve_1, 123);
-            let prev_positions = user.positions.effective_count();
+            let prev_positions = user.effective_count();
 
             let pool = Pool::load(&e);
             user.remove_collateral(&e, &mut reserve_1, 42);
 
-            pool.require_under_max(&e, &user.positions, prev_positions);
+            pool.require_under_max(&e, &user, prev_positions);
         });
     }
 
@@ -795,6 +1315,8 @@ mod tests {
         let mut user = User {
             address: samwise.clone(),
             positions: Positions::env_default(&e),
+            emode: 0,
+            fixed_loans: map![&e],
         };
         let (oracle, _) = testutils::create_mock_oracle(&e);
         let pool_config = PoolConfig {
@@ -808,12 +1330,12 @@ mod tests {
             storage::set_pool_config(&e, &pool_config);
             user.add_collateral(&e, &mut reserve_0, 123);
             user.add_liabilities(&e, &mut reserve_0, 789);
-            let prev_positions = user.positions.effective_count();
+            let prev_positions = user.effective_count();
 
             let pool = Pool::load(&e);
             user.add_liabilities(&e, &mut reserve_1, 42);
 
-            pool.require_under_max(&e, &user.positions, prev_positions);
+            pool.require_under_max(&e, &user, prev_positions);
         });
     }
 }
@@ -9,7 +9,11 @@ use crate::{
     storage::{self, PoolConfig, ReserveConfig, ReserveData},
 };
 
-use super::interest::calc_accrual;
+use super::{
+    config::calc_ramped_factors,
+    delisting::calc_delisting_c_factor,
+    interest::{calc_accrual, calc_borrow_rate},
+};
 
 #[derive(Clone, Debug)]
 #[contracttype]
@@ -42,6 +46,17 @@ impl Reserve {
             data: reserve_data,
         };
 
+        // the factors of a reserve with a scheduled ramp move linearly to the configured factors
+        if let Some(ramp) = storage::get_res_factor_ramp(e, asset) {
+            (reserve.config.c_factor, reserve.config.l_factor) =
+                calc_ramped_factors(e, &ramp, &reserve.config);
+        }
+
+        // the collateral factor of a reserve being delisted ramps to zero
+        if let Some(delisting) = storage::get_res_delisting(e, asset) {
+            reserve.config.c_factor = calc_delisting_c_factor(e, &delisting);
+        }
+
         // short circuit if the reserve has already been updated this ledger
         if e.ledger().timestamp() == reserve.data.last_time {
             return reserve;
@@ -107,6 +122,31 @@ impl Reserve {
         }
     }
 
+    /// Accrue a fee paid on a simple flash loan to the reserve supply.
+    ///
+    /// ### Arguments
+    /// * bstop_rate - The backstop take rate for the pool
+    /// * fee - The amount of underlying tokens paid as a fee
+    pub fn accrue_flash_loan_fee(&mut self, e: &Env, bstop_rate: u32, fee: i128) {
+        self.accrue(e, bstop_rate, fee);
+    }
+
+    /// Accrue interest paid on fixed-term loans to the reserve supply. The interest is added to the
+    /// reserve's fixed-term debt, as it is owed by the borrowers.
+    ///
+    /// ### Arguments
+    /// * bstop_rate - The backstop take rate for the pool
+    /// * interest - The amount of interest accrued on fixed-term loans
+    pub fn accrue_fixed_interest(&mut self, e: &Env, bstop_rate: u32, interest: i128) {
+        self.accrue(e, bstop_rate, interest);
+        self.data.fixed_debt += interest;
+    }
+
+    /// Fetch the current annual variable borrow rate for the reserve normalized to 7 decimals
+    pub fn borrow_rate(&self, e: &Env) -> i128 {
+        calc_borrow_rate(e, &self.config, self.utilization(e), self.data.ir_mod)
+    }
+
     /// Fetch the current utilization rate for the reserve normalized to 7 decimals
     pub fn utilization(&self, e: &Env) -> i128 {
         self.total_liabilities(e)
@@ -120,6 +160,25 @@ impl Reserve {
         }
     }
 
+    /// Require that the total liabilities of the reserve are below the borrow cap, or panic.
+    pub fn require_below_borrow_cap(&self, e: &Env) {
+        if self.total_liabilities(e) > self.config.borrow_cap {
+            panic_with_error!(e, PoolError::ExceededBorrowCap)
+        }
+    }
+
+    /// Require that the total supply of the reserve is below the supply cap, or panic.
+    pub fn require_below_supply_cap(&self, e: &Env) {
+        if self.total_supply(e) > self.config.supply_cap {
+            panic_with_error!(e, PoolError::ExceededSupplyCap)
+        }
+    }
+
+    /// Fetch the amount of underlying tokens that can be supplied before the supply cap is reached
+    pub fn remaining_supply_capacity(&self, e: &Env) -> i128 {
+        (self.config.supply_cap - self.total_supply(e)).max(0)
+    }
+
     /// Check the action is allowed according to the reserve status, or panic.
     ///
     /// ### Arguments
@@ -130,15 +189,17 @@ impl Reserve {
             if action_type == RequestType::Supply as u32
                 || action_type == RequestType::SupplyCollateral as u32
                 || action_type == RequestType::Borrow as u32
+                || action_type == RequestType::BorrowFixed as u32
+                || action_type == RequestType::RollFixedLoan as u32
             {
                 panic_with_error!(e, PoolError::ReserveDisabled);
             }
         }
     }
 
-    /// Fetch the total liabilities for the reserve in underlying tokens
+    /// Fetch the total liabilities for the reserve in underlying tokens, including fixed-term debt
     pub fn total_liabilities(&self, e: &Env) -> i128 {
-        self.to_asset_from_d_token(e, self.data.d_supply)
+        self.to_asset_from_d_token(e, self.data.d_supply) + self.data.fixed_debt
     }
 
     /// Fetch the total supply for the reserve in underlying tokens
@@ -273,6 +334,129 @@ mod tests {
         });
     }
 
+    #[test]
+    fn test_load_reserve_factor_ramp() {
+        let e = Env::default();
+        e.mock_all_auths();
+
+        e.ledger().set(LedgerInfo {
+            timestamp: 3000,
+            protocol_version: 22,
+            sequence_number: 123456,
+            network_id: Default::default(),
+            base_reserve: 10,
+            min_temp_entry_ttl: 10,
+            min_persistent_entry_ttl: 10,
+            max_entry_ttl: 3110400,
+        });
+
+        let bombadil = Address::generate(&e);
+        let pool = testutils::create_pool(&e);
+        let oracle = Address::generate(&e);
+
+        let (underlying, _) = testutils::create_token_contract(&e, &bombadil);
+        let (reserve_config, reserve_data) = testutils::default_reserve_meta();
+        testutils::create_reserve(&e, &pool, &underlying, &reserve_config, &reserve_data);
+
+        let pool_config = PoolConfig {
+            oracle,
+            min_collateral: 1_0000000,
+            bstop_rate: 0_2000000,
+            status: 0,
+            max_positions: 5,
+        };
+        e.as_contract(&pool, || {
+            storage::set_pool_config(&e, &pool_config);
+            storage::set_res_factor_ramp(
+                &e,
+                &underlying,
+                &storage::ReserveFactorRamp {
+                    c_factor: 0_9500000,
+                    l_factor: 1_0000000,
+                    start_time: 1000,
+                    end_time: 5000,
+                },
+            );
+            let reserve = Reserve::load(&e, &pool_config, &underlying);
+
+            assert_eq!(reserve.config.c_factor, 0_8500000);
+            assert_eq!(reserve.config.l_factor, 0_8750000);
+            // the stored config is not modified
+            let stored_config = storage::get_res_config(&e, &underlying);
+            assert_eq!(stored_config.c_factor, reserve_config.c_factor);
+            assert_eq!(stored_config.l_factor, reserve_config.l_factor);
+
+            // the configured factors are used once the ramp ends
+            e.ledger().set(LedgerInfo {
+                timestamp: 5000,
+                protocol_version: 22,
+                sequence_number: 123456,
+                network_id: Default::default(),
+                base_reserve: 10,
+                min_temp_entry_ttl: 10,
+                min_persistent_entry_ttl: 10,
+                max_entry_ttl: 3110400,
+            });
+            let reserve = Reserve::load(&e, &pool_config, &underlying);
+            assert_eq!(reserve.config.c_factor, reserve_config.c_factor);
+            assert_eq!(reserve.config.l_factor, reserve_config.l_factor);
+        });
+    }
+
+    #[test]
+    fn test_load_reserve_delisting_ramps_c_factor() {
+        let e = Env::default();
+        e.mock_all_auths();
+
+        e.ledger().set(LedgerInfo {
+            timestamp: 3000,
+            protocol_version: 22,
+            sequence_number: 123456,
+            network_id: Default::default(),
+            base_reserve: 10,
+            min_temp_entry_ttl: 10,
+            min_persistent_entry_ttl: 10,
+            max_entry_ttl: 3110400,
+        });
+
+        let bombadil = Address::generate(&e);
+        let pool = testutils::create_pool(&e);
+        let oracle = Address::generate(&e);
+
+        let (underlying, _) = testutils::create_token_contract(&e, &bombadil);
+        let (reserve_config, reserve_data) = testutils::default_reserve_meta();
+        testutils::create_reserve(&e, &pool, &underlying, &reserve_config, &reserve_data);
+
+        let pool_config = PoolConfig {
+            oracle,
+            min_collateral: 1_0000000,
+            bstop_rate: 0_2000000,
+            status: 0,
+            max_positions: 5,
+        };
+        e.as_contract(&pool, || {
+            storage::set_pool_config(&e, &pool_config);
+            storage::set_res_delisting(
+                &e,
+                &underlying,
+                &storage::ReserveDelisting {
+                    c_factor: 0_8000000,
+                    start_time: 1000,
+                    end_time: 5000,
+                },
+            );
+            let reserve = Reserve::load(&e, &pool_config, &underlying);
+
+            assert_eq!(reserve.config.c_factor, 0_4000000);
+            assert_eq!(reserve.config.l_factor, reserve_config.l_factor);
+            // the stored config is not modified
+            assert_eq!(
+                storage::get_res_config(&e, &underlying).c_factor,
+                reserve_config.c_factor
+            );
+        });
+    }
+
     #[test]
     fn test_load_reserve_accrues_b_rate() {
         let e = Env::default();
@@ -566,6 +750,63 @@ mod tests {
         reserve.require_utilization_below_max(&e);
     }
 
+    #[test]
+    fn test_require_below_borrow_cap_pass() {
+        let e = Env::default();
+
+        let mut reserve = testutils::default_reserve(&e);
+        reserve.config.borrow_cap = 65_0000000;
+        reserve.data.b_supply = 99_0000000;
+        reserve.data.d_supply = 65_0000000;
+
+        reserve.require_below_borrow_cap(&e);
+        // no panic
+        assert!(true);
+    }
+
+    #[test]
+    #[should_panic(expected = "Error(Contract, #1225)")]
+    fn test_require_below_borrow_cap_panic() {
+        let e = Env::default();
+
+        let mut reserve = testutils::default_reserve(&e);
+        reserve.config.borrow_cap = 65_0000000;
+        reserve.data.b_supply = 99_0000000;
+        reserve.data.d_supply = 65_0000001;
+
+        reserve.require_below_borrow_cap(&e);
+    }
+
+    #[test]
+    #[should_panic(expected = "Error(Contract, #1226)")]
+    fn test_require_below_supply_cap_panic() {
+        let e = Env::default();
+
+        let mut reserve = testutils::default_reserve(&e);
+        reserve.config.supply_cap = 99_0000000;
+        reserve.data.b_supply = 99_0000001;
+        reserve.data.d_supply = 65_0000000;
+
+        reserve.require_below_supply_cap(&e);
+    }
+
+    #[test]
+    fn test_remaining_supply_capacity() {
+        let e = Env::default();
+
+        let mut reserve = testutils::default_reserve(&e);
+        reserve.config.supply_cap = 100_0000000;
+        reserve.data.b_rate = 1_100_000_000_000;
+        reserve.data.b_supply = 50_0000000;
+        reserve.data.d_supply = 25_0000000;
+
+        reserve.require_below_supply_cap(&e);
+        assert_eq!(reserve.remaining_supply_capacity(&e), 45_0000000);
+
+        reserve.config.supply_cap = 50_0000000;
+        assert_eq!(reserve.remaining_supply_capacity(&e), 0);
+    }
+
     /***** Token Transfer Math *****/
 
     #[test]
@@ -640,6 +881,32 @@ mod tests {
         assert_eq!(result, 118_5543250);
     }
 
+    #[test]
+    fn test_total_liabilities_includes_fixed_debt() {
+        let e = Env::default();
+
+        let mut reserve = testutils::default_reserve(&e);
+        reserve.data.d_rate = 1_823_912_692_000;
+        reserve.data.b_supply = 99_0000000;
+        reserve.data.d_supply = 65_0000000;
+        reserve.data.fixed_debt = 10_0000000;
+
+        let result = reserve.total_liabilities(&e);
+
+        assert_eq!(result, 128_5543250);
+    }
+
+    #[test]
+    fn test_borrow_rate() {
+        let e = Env::default();
+
+        let reserve = testutils::default_reserve(&e);
+
+        let result = reserve.borrow_rate(&e);
+
+        assert_eq!(result, 0_0600000);
+    }
+
     #[test]
     fn test_total_supply() {
         let e = Env::default();
@@ -793,4 +1060,20 @@ mod tests {
         assert_eq!(reserve.data.b_rate, 1_000_000_000_000);
         assert_eq!(reserve.data.last_time, 0);
     }
+
+    #[test]
+    fn test_accrue_fixed_interest() {
+        let e = Env::default();
+        e.mock_all_auths();
+
+        let mut reserve = testutils::default_reserve(&e);
+        reserve.data.backstop_credit = 0_1234567;
+        reserve.data.fixed_debt = 50_0000000;
+
+        reserve.accrue_fixed_interest(&e, 0_2000000, 10_0000000);
+        assert_eq!(reserve.data.backstop_credit, 2_0000000 + 0_1234567);
+        assert_eq!(reserve.data.b_rate, 1_080_000_000_000);
+        assert_eq!(reserve.data.fixed_debt, 60_0000000);
+        assert_eq!(reserve.data.last_time, 0);
+    }
 }
@@ -0,0 +1,402 @@
+use sep_41_token::TokenClient;
+use soroban_sdk::{contracttype, panic_with_error, xdr::ToXdr, Address, BytesN, Env, Vec};
+
+use crate::{errors::PoolError, events::PoolEvents, storage};
+
+use super::{actions::Request, submit::execute_submit, Positions};
+
+/// A set of requests signed off-chain by a user's registered signing key
+#[derive(Clone)]
+#[contracttype]
+pub struct SignedSubmit {
+    pub from: Address, // the address whose positions are modified and who sends and receives tokens
+    pub requests: Vec<Request>, // the requests to be processed
+    pub nonce: u64,    // the nonce of the signed request, which can only be used once
+    pub expiration: u64, // the time the signed request expires
+    pub fee_asset: Address, // the token the relayer fee is paid in
+    pub fee: i128,     // the fee paid to the relayer, in the fee asset's decimals
+    pub relayer: Option<Address>, // the only relayer allowed to submit the request, or None to allow any relayer
+}
+
+/// Register the ed25519 public key a user signs off-chain requests with. Registering a new key
+/// replaces the previous one.
+///
+/// ### Arguments
+/// * `user` - The address of the user
+/// * `signing_key` - The ed25519 public key
+pub fn execute_set_signing_key(e: &Env, user: &Address, signing_key: &BytesN<32>) {
+    storage::set_signing_key(e, user, signing_key);
+}
+
+/// Cancel a signed request before it is submitted by marking its nonce as used
+///
+/// ### Arguments
+/// * `user` - The address of the user
+/// * `nonce` - The nonce of the signed request
+///
+/// ### Panics
+/// If the nonce has already been used
+pub fn execute_cancel_signed(e: &Env, user: &Address, nonce: u64) {
+    if storage::is_nonce_used(e, user, nonce) {
+        panic_with_error!(e, PoolError::InvalidSignedRequest);
+    }
+    storage::set_nonce_used(e, user, nonce);
+}
+
+/// Submit a set of requests signed off-chain by `signed.from`. The signature must be an ed25519
+/// signature by the user's registered signing key over the XDR of `(pool, signed)`.
+///
+/// Tokens owed to the pool are transferred from the user with `transfer_from`, so the user must
+/// have approved the pool. After the requests are processed, the relayer fee is transferred from
+/// the user to the relayer the same way.
+///
+/// ### Arguments
+/// * `relayer` - The address submitting the signed request and receiving the fee
+/// * `signed` - The signed request
+/// * `signature` - The ed25519 signature of the signed request
+///
+/// ### Returns
+/// * Positions - The user's positions after the requests are processed
+///
+/// ### Panics
+/// If the user has no signing key, the signed request is expired, the nonce has been used, the
+/// signed request is bound to a different relayer, the signature is invalid, or the requests are
+/// not able to be completed
+pub fn execute_submit_signed(
+    e: &Env,
+    relayer: &Address,
+    signed: SignedSubmit,
+    signature: &BytesN<64>,
+) -> Positions {
+    if signed.fee < 0 {
+        panic_with_error!(e, PoolError::NegativeAmountError);
+    }
+    if signed.expiration <= e.ledger().timestamp()
+        || storage::is_nonce_used(e, &signed.from, signed.nonce)
+        || signed
+            .relayer
+            .as_ref()
+            .is_some_and(|bound| bound != relayer)
+    {
+        panic_with_error!(e, PoolError::InvalidSignedRequest);
+    }
+    let signing_key = match storage::get_signing_key(e, &signed.from) {
+        Some(signing_key) => signing_key,
+        None => panic_with_error!(e, PoolError::InvalidSignedRequest),
+    };
+    let message = (e.current_contract_address(), signed.clone()).to_xdr(e);
+    e.crypto().ed25519_verify(&signing_key, &message, signature);
+    storage::set_nonce_used(e, &signed.from, signed.nonce);
+
+    let positions = execute_submit(
+        e,
+        &signed.from,
+        &signed.from,
+        &signed.from,
+        signed.requests,
+        true,
+    );
+
+    if signed.fee > 0 {
+        TokenClient::new(e, &signed.fee_asset).transfer_from(
+            &e.current_contract_address(),
+            &signed.from,
+            relayer,
+            &signed.fee,
+        );
+    }
+
+    PoolEvents::submit_signed(
+        e,
+        signed.from,
+        relayer.clone(),
+        signed.nonce,
+        signed.fee_asset,
+        signed.fee,
+    );
+    positions
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        pool::RequestType,
+        storage::PoolConfig,
+        testutils::{self, create_pool},
+    };
+    use ed25519_dalek::{Signer, SigningKey};
+    use sep_40_oracle::testutils::Asset;
+    use sep_41_token::testutils::MockTokenClient;
+    use soroban_sdk::{
+        testutils::{Address as _, Ledger, LedgerInfo},
+        vec, Symbol,
+    };
+
+    /// Setup a pool with one reserve where samwise holds tokens and has approved the pool
+    fn setup_pool(e: &Env) -> (Address, Address, Address) {
+        e.ledger().set(LedgerInfo {
+            timestamp: 600,
+            protocol_version: 22,
+            sequence_number: 1234,
+            network_id: Default::default(),
+            base_reserve: 10,
+            min_temp_entry_ttl: 10,
+            min_persistent_entry_ttl: 10,
+            max_entry_ttl: 3110400,
+        });
+
+        let bombadil = Address::generate(e);
+        let samwise = Address::generate(e);
+        let pool = create_pool(e);
+        let (oracle, oracle_client) = testutils::create_mock_oracle(e);
+
+        let (underlying, underlying_client) = testutils::create_token_contract(e, &bombadil);
+        let (reserve_config, mut reserve_data) = testutils::default_reserve_meta();
+        reserve_data.last_time = 600;
+        testutils::create_reserve(e, &pool, &underlying, &reserve_config, &reserve_data);
+
+        oracle_client.set_data(
+            &bombadil,
+            &Asset::Other(Symbol::new(e, "USD")),
+            &vec![e, Asset::Stellar(underlying.clone())],
+            &7,
+            &300,
+        );
+        oracle_client.set_price_stable(&vec![e, 1_0000000]);
+
+        underlying_client.mint(&samwise, &10_0000000);
+        underlying_client.approve(&samwise, &pool, &10_0000000, &10000);
+
+        let pool_config = PoolConfig {
+            oracle,
+            min_collateral: 1_0000000,
+            bstop_rate: 0_1000000,
+            status: 0,
+            max_positions: 4,
+        };
+        e.as_contract(&pool, || {
+            storage::set_pool_config(e, &pool_config);
+        });
+        (pool, samwise, underlying)
+    }
+
+    fn sign(e: &Env, pool: &Address, signer: &SigningKey, signed: &SignedSubmit) -> BytesN<64> {
+        let message: std::vec::Vec<u8> = (pool.clone(), signed.clone()).to_xdr(e).iter().collect();
+        BytesN::from_array(e, &signer.sign(&message).to_bytes())
+    }
+
+    fn signed_supply(e: &Env, samwise: &Address, underlying: &Address, nonce: u64) -> SignedSubmit {
+        SignedSubmit {
+            from: samwise.clone(),
+            requests: vec![
+                e,
+                Request {
+                    request_type: RequestType::SupplyCollateral as u32,
+                    address: underlying.clone(),
+                    amount: 5_0000000,
+                },
+            ],
+            nonce,
+            expiration: 1000,
+            fee_asset: underlying.clone(),
+            fee: 0_1000000,
+            relayer: None,
+        }
+    }
+
+    #[test]
+    fn test_execute_submit_signed() {
+        let e = Env::default();
+        e.cost_estimate().budget().reset_unlimited();
+        e.mock_all_auths();
+        let (pool, samwise, underlying) = setup_pool(&e);
+        let relayer = Address::generate(&e);
+        let underlying_client = MockTokenClient::new(&e, &underlying);
+        let signer = SigningKey::from_bytes(&[7u8; 32]);
+
+        e.as_contract(&pool, || {
+            execute_set_signing_key(
+                &e,
+                &samwise,
+                &BytesN::from_array(&e, &signer.verifying_key().to_bytes()),
+            );
+            let signed = signed_supply(&e, &samwise, &underlying, 1);
+            let signature = sign(&e, &pool, &signer, &signed);
+
+            let positions = execute_submit_signed(&e, &relayer, signed, &signature);
+
+            assert_eq!(positions.collateral.get_unchecked(0), 5_0000000);
+            assert_eq!(underlying_client.balance(&samwise), 4_9000000);
+            assert_eq!(underlying_client.balance(&relayer), 0_1000000);
+            assert!(storage::is_nonce_used(&e, &samwise, 1));
+            assert!(!storage::is_nonce_used(&e, &samwise, 2));
+        });
+    }
+
+    #[test]
+    fn test_execute_submit_signed_bound_relayer() {
+        let e = Env::default();
+        e.cost_estimate().budget().reset_unlimited();
+        e.mock_all_auths();
+        let (pool, samwise, underlying) = setup_pool(&e);
+        let relayer = Address::generate(&e);
+        let underlying_client = MockTokenClient::new(&e, &underlying);
+        let signer = SigningKey::from_bytes(&[7u8; 32]);
+
+        e.as_contract(&pool, || {
+            execute_set_signing_key(
+                &e,
+                &samwise,
+                &BytesN::from_array(&e, &signer.verifying_key().to_bytes()),
+            );
+            let mut signed = signed_supply(&e, &samwise, &underlying, 1);
+            signed.relayer = Some(relayer.clone());
+            let signature = sign(&e, &pool, &signer, &signed);
+
+            let positions = execute_submit_signed(&e, &relayer, signed, &signature);
+
+            assert_eq!(positions.collateral.get_unchecked(0), 5_0000000);
+            assert_eq!(underlying_client.balance(&relayer), 0_1000000);
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "Error(Contract, #1234)")]
+    fn test_execute_submit_signed_wrong_relayer() {
+        let e = Env::default();
+        e.cost_estimate().budget().reset_unlimited();
+        e.mock_all_auths();
+        let (pool, samwise, underlying) = setup_pool(&e);
+        let relayer = Address::generate(&e);
+        let frontrunner = Address::generate(&e);
+        let signer = SigningKey::from_bytes(&[7u8; 32]);
+
+        e.as_contract(&pool, || {
+            execute_set_signing_key(
+                &e,
+                &samwise,
+                &BytesN::from_array(&e, &signer.verifying_key().to_bytes()),
+            );
+            let mut signed = signed_supply(&e, &samwise, &underlying, 1);
+            signed.relayer = Some(relayer.clone());
+            let signature = sign(&e, &pool, &signer, &signed);
+
+            execute_submit_signed(&e, &frontrunner, signed, &signature);
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "Error(Contract, #1234)")]
+    fn test_execute_submit_signed_replay() {
+        let e = Env::default();
+        e.cost_estimate().budget().reset_unlimited();
+        e.mock_all_auths();
+        let (pool, samwise, underlying) = setup_pool(&e);
+        let relayer = Address::generate(&e);
+        let signer = SigningKey::from_bytes(&[7u8; 32]);
+
+        e.as_contract(&pool, || {
+            execute_set_signing_key(
+                &e,
+                &samwise,
+                &BytesN::from_array(&e, &signer.verifying_key().to_bytes()),
+            );
+            let signed = signed_supply(&e, &samwise, &underlying, 1);
+            let signature = sign(&e, &pool, &signer, &signed);
+
+            execute_submit_signed(&e, &relayer, signed.clone(), &signature);
+            execute_submit_signed(&e, &relayer, signed, &signature);
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "Error(Contract, #1234)")]
+    fn test_execute_submit_signed_cancelled() {
+        let e = Env::default();
+        e.cost_estimate().budget().reset_unlimited();
+        e.mock_all_auths();
+        let (pool, samwise, underlying) = setup_pool(&e);
+        let relayer = Address::generate(&e);
+        let signer = SigningKey::from_bytes(&[7u8; 32]);
+
+        e.as_contract(&pool, || {
+            execute_set_signing_key(
+                &e,
+                &samwise,
+                &BytesN::from_array(&e, &signer.verifying_key().to_bytes()),
+            );
+            let signed = signed_supply(&e, &samwise, &underlying, 1);
+            let signature = sign(&e, &pool, &signer, &signed);
+
+            execute_cancel_signed(&e, &samwise, 1);
+            execute_submit_signed(&e, &relayer, signed, &signature);
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "Error(Contract, #1234)")]
+    fn test_execute_submit_signed_expired() {
+        let e = Env::default();
+        e.cost_estimate().budget().reset_unlimited();
+        e.mock_all_auths();
+        let (pool, samwise, underlying) = setup_pool(&e);
+        let relayer = Address::generate(&e);
+        let signer = SigningKey::from_bytes(&[7u8; 32]);
+
+        e.as_contract(&pool, || {
+            execute_set_signing_key(
+                &e,
+                &samwise,
+                &BytesN::from_array(&e, &signer.verifying_key().to_bytes()),
+            );
+            let mut signed = signed_supply(&e, &samwise, &underlying, 1);
+            signed.expiration = 600;
+            let signature = sign(&e, &pool, &signer, &signed);
+
+            execute_submit_signed(&e, &relayer, signed, &signature);
+        });
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_execute_submit_signed_wrong_signer() {
+        let e = Env::default();
+        e.cost_estimate().budget().reset_unlimited();
+        e.mock_all_auths();
+        let (pool, samwise, underlying) = setup_pool(&e);
+        let relayer = Address::generate(&e);
+        let signer = SigningKey::from_bytes(&[7u8; 32]);
+        let other_signer = SigningKey::from_bytes(&[8u8; 32]);
+
+        e.as_contract(&pool, || {
+            execute_set_signing_key(
+                &e,
+                &samwise,
+                &BytesN::from_array(&e, &signer.verifying_key().to_bytes()),
+            );
+            let signed = signed_supply(&e, &samwise, &underlying, 1);
+            let signature = sign(&e, &pool, &other_signer, &signed);
+
+            execute_submit_signed(&e, &relayer, signed, &signature);
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "Error(Contract, #1234)")]
+    fn test_execute_submit_signed_no_signing_key() {
+        let e = Env::default();
+        e.cost_estimate().budget().reset_unlimited();
+        e.mock_all_auths();
+        let (pool, samwise, underlying) = setup_pool(&e);
+        let relayer = Address::generate(&e);
+        let signer = SigningKey::from_bytes(&[7u8; 32]);
+
+        e.as_contract(&pool, || {
+            let signed = signed_supply(&e, &samwise, &underlying, 1);
+            let signature = sign(&e, &pool, &signer, &signed);
+
+            execute_submit_signed(&e, &relayer, signed, &signature);
+        });
+    }
+}
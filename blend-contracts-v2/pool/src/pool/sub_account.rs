@@ -0,0 +1,157 @@
+use soroban_sdk::{xdr::ToXdr, Address, Env};
+
+/// Fetch the address that holds a user's sub-account in the pool. Each sub-account has its own
+/// positions, health, auctions and emissions, keyed by the returned address.
+///
+/// Sub-account 0 is the user's own address. Other sub-accounts are derived as the address of a
+/// contract deployed by the pool with a salt of the user and sub-account id. The pool never
+/// deploys contracts, so no one can act as a sub-account address directly.
+///
+/// The derived address maps one-to-one to `(user, sub_id)`. It is used instead of keying storage
+/// by `(user, sub_id)` because every piece of per-user state (positions, auctions, emissions,
+/// fixed loans) is keyed by address. Liquidations, bad debt and emissions therefore apply to each
+/// sub-account separately without any changes.
+///
+/// Sub-accounts can only be used through `submit_sub_account` and `claim_sub_account`. The
+/// sub-account address cannot authorize anything itself. As a result, sub-accounts do not support
+/// `transfer_positions`, delegation, flash loans or signed submits.
+///
+/// ### Arguments
+/// * `user` - The address of the user who owns the sub-account
+/// * `sub_id` - The id of the sub-account
+pub fn sub_account_address(e: &Env, user: &Address, sub_id: u32) -> Address {
+    if sub_id == 0 {
+        return user.clone();
+    }
+    let salt = e.crypto().sha256(&(user.clone(), sub_id).to_xdr(e));
+    e.deployer()
+        .with_address(e.current_contract_address(), salt.to_bytes())
+        .deployed_address()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        pool::{execute_submit, Request},
+        storage::{self, PoolConfig},
+        testutils, RequestType,
+    };
+    use sep_40_oracle::testutils::Asset;
+    use soroban_sdk::{
+        testutils::{Address as _, Ledger, LedgerInfo},
+        vec, Symbol,
+    };
+
+    #[test]
+    fn test_sub_account_address() {
+        let e = Env::default();
+        let pool = testutils::create_pool(&e);
+        let samwise = Address::generate(&e);
+        let frodo = Address::generate(&e);
+
+        e.as_contract(&pool, || {
+            assert_eq!(sub_account_address(&e, &samwise, 0), samwise);
+
+            let sub_1 = sub_account_address(&e, &samwise, 1);
+            assert_ne!(sub_1, samwise);
+            assert_eq!(sub_account_address(&e, &samwise, 1), sub_1);
+            assert_ne!(sub_account_address(&e, &samwise, 2), sub_1);
+            assert_ne!(sub_account_address(&e, &frodo, 1), sub_1);
+        });
+    }
+
+    #[test]
+    fn test_submit_sub_account_positions_are_isolated() {
+        let e = Env::default();
+        e.cost_estimate().budget().reset_unlimited();
+        e.mock_all_auths_allowing_non_root_auth();
+
+        e.ledger().set(LedgerInfo {
+            timestamp: 600,
+            protocol_version: 22,
+            sequence_number: 1234,
+            network_id: Default::default(),
+            base_reserve: 10,
+            min_temp_entry_ttl: 10,
+            min_persistent_entry_ttl: 10,
+            max_entry_ttl: 3110400,
+        });
+
+        let bombadil = Address::generate(&e);
+        let samwise = Address::generate(&e);
+        let pool = testutils::create_pool(&e);
+        let (oracle, oracle_client) = testutils::create_mock_oracle(&e);
+
+        let (underlying_0, underlying_0_client) = testutils::create_token_contract(&e, &bombadil);
+        let (reserve_config, reserve_data) = testutils::default_reserve_meta();
+        testutils::create_reserve(&e, &pool, &underlying_0, &reserve_config, &reserve_data);
+
+        let (underlying_1, underlying_1_client) = testutils::create_token_contract(&e, &bombadil);
+        let (reserve_config, reserve_data) = testutils::default_reserve_meta();
+        testutils::create_reserve(&e, &pool, &underlying_1, &reserve_config, &reserve_data);
+
+        underlying_0_client.mint(&samwise, &16_0000000);
+
+        oracle_client.set_data(
+            &bombadil,
+            &Asset::Other(Symbol::new(&e, "USD")),
+            &vec![
+                &e,
+                Asset::Stellar(underlying_0.clone()),
+                Asset::Stellar(underlying_1.clone()),
+            ],
+            &7,
+            &300,
+        );
+        oracle_client.set_price_stable(&vec![&e, 1_0000000, 5_0000000]);
+
+        let pool_config = PoolConfig {
+            oracle,
+            min_collateral: 1_0000000,
+            bstop_rate: 0_1000000,
+            status: 0,
+            max_positions: 2,
+        };
+        e.as_contract(&pool, || {
+            e.mock_all_auths_allowing_non_root_auth();
+            storage::set_pool_config(&e, &pool_config);
+
+            let sub_1 = sub_account_address(&e, &samwise, 1);
+            let requests = vec![
+                &e,
+                Request {
+                    request_type: RequestType::SupplyCollateral as u32,
+                    address: underlying_0.clone(),
+                    amount: 15_0000000,
+                },
+                Request {
+                    request_type: RequestType::Borrow as u32,
+                    address: underlying_1.clone(),
+                    amount: 1_5000000,
+                },
+            ];
+            let positions = execute_submit(&e, &sub_1, &samwise, &samwise, requests, false);
+
+            assert_eq!(positions.collateral.get_unchecked(0), 14_9999884);
+            assert_eq!(positions.liabilities.get_unchecked(1), 1_4999983);
+            assert_eq!(
+                storage::get_user_positions(&e, &sub_1)
+                    .collateral
+                    .get_unchecked(0),
+                14_9999884
+            );
+            // the owner's positions and other sub-accounts are untouched
+            assert_eq!(
+                storage::get_user_positions(&e, &samwise).effective_count(),
+                0
+            );
+            let sub_2 = sub_account_address(&e, &samwise, 2);
+            assert_eq!(storage::get_user_positions(&e, &sub_2).effective_count(), 0);
+
+            // tokens move to and from the owner
+            assert_eq!(underlying_0_client.balance(&samwise), 1_0000000);
+            assert_eq!(underlying_1_client.balance(&samwise), 1_5000000);
+        });
+    }
+}
@@ -1,16 +1,39 @@
 use moderc3156::FlashLoanClient;
 use sep_41_token::TokenClient;
-use soroban_sdk::{panic_with_error, Address, Env, Map, Vec};
+use soroban_sdk::{contracttype, panic_with_error, vec, Address, Env, Map, Vec};
 
-use crate::{events::PoolEvents, storage, AuctionType, PoolError};
+use crate::{
+    dependencies::MultiFlashLoanClient, events::PoolEvents, storage, AuctionType, PoolError,
+};
 
 use super::{
-    actions::{build_actions_from_request, Actions, Request},
+    actions::{build_actions_from_request, execute_swaps, Actions, Request, RequestType},
     health_factor::PositionData,
+    isolation::{check_isolation_rules, has_isolated_collateral, require_isolation_rules},
     pool::Pool,
     FlashLoan, Positions, User,
 };
 
+/// The result of simulating a submit against the pool
+#[derive(Clone)]
+#[contracttype]
+pub struct SubmitSimulation {
+    /// The positions of the user after the requests are processed
+    pub positions: Positions,
+    /// The tokens the spender would transfer to the pool
+    pub spender_transfer: Map<Address, i128>,
+    /// The tokens the pool would transfer to the receiver
+    pub pool_transfer: Map<Address, i128>,
+    /// The health factor of the positions expressed in 7 decimals
+    pub health_factor: i128,
+    /// The effective collateral of the positions denominated in the base asset
+    pub collateral_base: i128,
+    /// The effective liabilities of the positions denominated in the base asset
+    pub liability_base: i128,
+    /// The codes of the errors the submit would fail validation with
+    pub errors: Vec<u32>,
+}
+
 /// Execute a set of updates for a user against the pool.
 ///
 /// ### Arguments
@@ -39,18 +62,22 @@ pub fn execute_submit(
     let mut pool = Pool::load(e);
     let mut from_state = User::load(e, from);
 
-    let prev_positions_count = from_state.positions.effective_count();
+    let prev_positions = from_state.positions.clone();
+    let prev_count = from_state.effective_count();
 
-    let actions = build_actions_from_request(e, &mut pool, &mut from_state, requests);
+    let mut actions = build_actions_from_request(e, &mut pool, &mut from_state, requests);
 
     validate_submit(
         e,
         &mut pool,
         &from_state,
-        prev_positions_count,
+        &prev_positions,
+        prev_count,
         actions.check_health,
     );
 
+    execute_swaps(e, &mut actions, from);
+
     if use_allowance {
         handle_transfer_with_allowance(e, &actions, spender, to);
     } else {
@@ -64,59 +91,68 @@ pub fn execute_submit(
     from_state.positions
 }
 
-/// Same as `execute_submit` but specifically made for performing a flash loan borrow before
-/// the other submitted requests.
+/// Same as `execute_submit` but specifically made for performing a flash loan borrow of one or more
+/// assets before the other submitted requests. The receiver contract is invoked a single time, with
+/// `exec_op` for one asset or `exec_op_multi` for multiple assets.
 pub fn execute_submit_with_flash_loan(
     e: &Env,
     from: &Address,
     flash_loan: FlashLoan,
     requests: Vec<Request>,
 ) -> Positions {
-    if from == &e.current_contract_address() {
+    if from == &e.current_contract_address() || flash_loan.assets.is_empty() {
         panic_with_error!(e, &PoolError::BadRequest);
     }
     let mut pool = Pool::load(e);
     let mut from_state = User::load(e, from);
 
-    let prev_positions_count = from_state.positions.effective_count();
+    let prev_positions = from_state.positions.clone();
+    let prev_count = from_state.effective_count();
 
     // note: we add the flash loan liabilities before processing the other
     // requests.
-    {
-        let mut reserve = pool.load_reserve(e, &flash_loan.asset, true);
-        let d_tokens_minted = reserve.to_d_token_up(e, flash_loan.amount);
-        from_state.add_liabilities(e, &mut reserve, d_tokens_minted);
-        reserve.require_utilization_below_max(e);
-
-        PoolEvents::flash_loan(
+    let mut tokens: Vec<Address> = vec![e];
+    let mut amounts: Vec<i128> = vec![e];
+    let mut fees: Vec<i128> = vec![e];
+    for (asset, amount) in flash_loan.assets.iter() {
+        add_flash_loan_liabilities(
             e,
-            flash_loan.asset.clone(),
-            from.clone(),
-            flash_loan.contract.clone(),
-            flash_loan.amount,
-            d_tokens_minted,
+            &mut pool,
+            &mut from_state,
+            &flash_loan.contract,
+            &asset,
+            amount,
         );
+        tokens.push_back(asset);
+        amounts.push_back(amount);
+        fees.push_back(0);
     }
 
-    let actions = build_actions_from_request(e, &mut pool, &mut from_state, requests);
+    let mut actions = build_actions_from_request(e, &mut pool, &mut from_state, requests);
 
     // always check health since flash_borrow requires it
-    validate_submit(e, &mut pool, &from_state, prev_positions_count, true);
-
-    // we deal with the flashloan transfer before the others to allow the flash
-    // loan to yield the repaid or supplied amount in the transfers.
-    TokenClient::new(e, &flash_loan.asset).transfer(
-        &e.current_contract_address(),
-        &flash_loan.contract,
-        &flash_loan.amount,
-    );
-    // calls the receiver contract with "from" as the caller
-    FlashLoanClient::new(&e, &flash_loan.contract).exec_op(
-        &from,
-        &flash_loan.asset,
-        &flash_loan.amount,
-        &0,
-    );
+    validate_submit(e, &mut pool, &from_state, &prev_positions, prev_count, true);
+
+    execute_swaps(e, &mut actions, from);
+
+    // we deal with the flashloan transfers before the others to allow the flash
+    // loan to yield the repaid or supplied amounts in the transfers.
+    for (asset, amount) in flash_loan.assets.iter() {
+        TokenClient::new(e, &asset).transfer(
+            &e.current_contract_address(),
+            &flash_loan.contract,
+            &amount,
+        );
+    }
+    // calls the receiver contract with "from" as the caller. A single asset uses the moderc3156
+    // interface, and multiple assets are passed to the receiver in one call.
+    if flash_loan.assets.len() == 1 {
+        let (asset, amount) = flash_loan.assets.get_unchecked(0);
+        FlashLoanClient::new(&e, &flash_loan.contract).exec_op(&from, &asset, &amount, &0);
+    } else {
+        MultiFlashLoanClient::new(&e, &flash_loan.contract)
+            .exec_op_multi(&from, &tokens, &amounts, &fees);
+    }
 
     // note: at this point, the pool has sum_by_asset(actions.flash_borrow.1) for each involed asset, but the user also has
     // increased liabilities. These will have to be either fully repaid by now in the requests following the flash borrow
@@ -132,22 +168,126 @@ pub fn execute_submit_with_flash_loan(
     from_state.positions
 }
 
+/// Add the liabilities of a flash loan to a user and verify the reserve can support the borrow.
+///
+/// The updated reserve is cached so the minted d_tokens are stored with the reserve, even if no
+/// other request touches it.
+fn add_flash_loan_liabilities(
+    e: &Env,
+    pool: &mut Pool,
+    from_state: &mut User,
+    contract: &Address,
+    asset: &Address,
+    amount: i128,
+) {
+    let mut reserve = pool.load_reserve(e, asset, true);
+    let d_tokens_minted = reserve.to_d_token_up(e, amount);
+    from_state.add_liabilities(e, &mut reserve, d_tokens_minted);
+    reserve.require_utilization_below_max(e);
+    reserve.require_below_borrow_cap(e);
+
+    PoolEvents::flash_loan(
+        e,
+        asset.clone(),
+        from_state.address.clone(),
+        contract.clone(),
+        amount,
+        d_tokens_minted,
+    );
+    pool.cache_reserve(reserve);
+}
+
+/// Simulate a set of updates for a user against the pool without transferring tokens or
+/// storing the updated positions.
+///
+/// Auction fills and deletions cannot be simulated, as they modify the state of other users, and
+/// deleverage requests cannot be simulated, as they swap the pool's tokens through the router.
+///
+/// ### Arguments
+/// * from - The address of the user whose positions are being modified
+/// * requests - A vec of requests to be processed
+///
+/// ### Panics
+/// If any request is invalid or contains an auction or deleverage request. Failures from validating the
+/// resulting positions are returned as error codes instead.
+pub fn execute_simulate_submit(
+    e: &Env,
+    from: &Address,
+    requests: Vec<Request>,
+) -> SubmitSimulation {
+    for request in requests.iter() {
+        match RequestType::from_u32(e, request.request_type) {
+            RequestType::FillUserLiquidationAuction
+            | RequestType::FillBadDebtAuction
+            | RequestType::FillInterestAuction
+            | RequestType::DeleteLiquidationAuction
+            | RequestType::Deleverage => panic_with_error!(e, PoolError::BadRequest),
+            _ => {}
+        }
+    }
+    let mut pool = Pool::load(e);
+    let mut from_state = User::load(e, from);
+
+    let prev_positions = from_state.positions.clone();
+    let prev_count = from_state.effective_count();
+
+    let actions = build_actions_from_request(e, &mut pool, &mut from_state, requests);
+
+    // run the same checks as `validate_submit`, but collect the errors instead of panicking
+    let mut errors: Vec<u32> = vec![e];
+    let new_num = from_state.effective_count();
+    if new_num > prev_count && pool.config.max_positions < new_num {
+        errors.push_back(PoolError::MaxPositionsExceeded as u32);
+    }
+    if storage::has_auction(e, &(AuctionType::UserLiquidation as u32), from) {
+        errors.push_back(PoolError::AuctionInProgress as u32);
+    }
+    if let Some(error) = check_isolation_rules(e, &mut pool, &prev_positions, &from_state.positions)
+    {
+        errors.push_back(error as u32);
+    } else if !from_state.fixed_loans.is_empty()
+        && has_isolated_collateral(e, &mut pool, &from_state.positions)
+    {
+        errors.push_back(PoolError::InvalidIsolatedBorrow as u32);
+    }
+
+    let position_data = PositionData::calculate_from_user(e, &mut pool, &from_state);
+    if actions.check_health && from_state.has_liabilities() {
+        if position_data.is_hf_under(e, 1_0000100) {
+            errors.push_back(PoolError::InvalidHf as u32);
+        } else if position_data.collateral_base < pool.config.min_collateral {
+            errors.push_back(PoolError::MinCollateralNotMet as u32);
+        }
+    }
+    SubmitSimulation {
+        positions: from_state.positions,
+        spender_transfer: actions.spender_transfer,
+        pool_transfer: actions.pool_transfer,
+        health_factor: position_data.as_health_factor_7(e),
+        collateral_base: position_data.collateral_base,
+        liability_base: position_data.liability_base,
+        errors,
+    }
+}
+
 /// Validate submit results in a valid state for the pool and user.
 ///
 /// ### Arguments
 /// * pool - The pool state. Writes the oracle cache if oracle data is fetched.
 /// * from_state - The user state for "from"
-/// * prev_positions_count - The initial number of positions for "from"
+/// * prev_positions - The initial positions for "from"
+/// * prev_count - The initial number of effective positions for "from", including fixed-term loans
 /// * check_health - A bool indicating if the health factor should be checked
-fn validate_submit(
+pub fn validate_submit(
     e: &Env,
     pool: &mut Pool,
     from_state: &User,
-    prev_positions_count: u32,
+    prev_positions: &Positions,
+    prev_count: u32,
     check_health: bool,
 ) {
     // Verify max positions haven't been exceeded
-    pool.require_under_max(e, &from_state.positions, prev_positions_count);
+    pool.require_under_max(e, from_state, prev_count);
 
     // Verify "from" does not have an active liquidation post requests
     if storage::has_auction(
@@ -158,10 +298,18 @@ fn validate_submit(
         panic_with_error!(e, PoolError::AuctionInProgress);
     }
 
+    // panics if the new positions break the isolation rules, and tracks debt backed by isolated collateral
+    require_isolation_rules(e, pool, prev_positions, &from_state.positions);
+    // fixed-term loans cannot be backed by isolated collateral
+    if !from_state.fixed_loans.is_empty() && has_isolated_collateral(e, pool, &from_state.positions)
+    {
+        panic_with_error!(e, PoolError::InvalidIsolatedBorrow);
+    }
+
     // panics if the new positions set does not meet the health factor requirement
     // min is 1.0000100 to prevent rounding errors
     if check_health && from_state.has_liabilities() {
-        let position_data = PositionData::calculate_from_positions(e, pool, &from_state.positions);
+        let position_data = PositionData::calculate_from_user(e, pool, from_state);
         if position_data.is_hf_under(e, 1_0000100) {
             panic_with_error!(e, PoolError::InvalidHf);
         } else if position_data.collateral_base < pool.config.min_collateral {
@@ -330,6 +478,104 @@ mod tests {
         });
     }
 
+    #[test]
+    fn test_submit_deleverage() {
+        let e = Env::default();
+        e.cost_estimate().budget().reset_unlimited();
+        e.mock_all_auths_allowing_non_root_auth();
+
+        e.ledger().set(LedgerInfo {
+            timestamp: 600,
+            protocol_version: 22,
+            sequence_number: 1234,
+            network_id: Default::default(),
+            base_reserve: 10,
+            min_temp_entry_ttl: 10,
+            min_persistent_entry_ttl: 10,
+            max_entry_ttl: 3110400,
+        });
+
+        let bombadil = Address::generate(&e);
+        let samwise = Address::generate(&e);
+        let merry = Address::generate(&e);
+        let pool = testutils::create_pool(&e);
+        let (oracle, oracle_client) = testutils::create_mock_oracle(&e);
+        let (router, router_client) = testutils::create_mock_swap_router(&e);
+
+        let (underlying_0, underlying_0_client) = testutils::create_token_contract(&e, &bombadil);
+        let (reserve_config, mut reserve_data) = testutils::default_reserve_meta();
+        reserve_data.last_time = 600;
+        testutils::create_reserve(&e, &pool, &underlying_0, &reserve_config, &reserve_data);
+
+        let (underlying_1, underlying_1_client) = testutils::create_token_contract(&e, &bombadil);
+        testutils::create_reserve(&e, &pool, &underlying_1, &reserve_config, &reserve_data);
+
+        underlying_1_client.mint(&router, &100_0000000);
+        router_client.set_rate(&0_5000000);
+
+        oracle_client.set_data(
+            &bombadil,
+            &Asset::Other(Symbol::new(&e, "USD")),
+            &vec![
+                &e,
+                Asset::Stellar(underlying_0.clone()),
+                Asset::Stellar(underlying_1.clone()),
+            ],
+            &7,
+            &300,
+        );
+        oracle_client.set_price_stable(&vec![&e, 1_0000000, 1_0000000]);
+
+        let pool_config = PoolConfig {
+            oracle,
+            min_collateral: 1_0000000,
+            bstop_rate: 0_1000000,
+            status: 0,
+            max_positions: 2,
+        };
+        e.as_contract(&pool, || {
+            storage::set_pool_config(&e, &pool_config);
+            storage::set_router(&e, &router);
+            storage::set_user_positions(
+                &e,
+                &samwise,
+                &Positions {
+                    liabilities: map![&e, (1, 5_0000000)],
+                    collateral: map![&e, (0, 20_0000000)],
+                    supply: map![&e],
+                },
+            );
+
+            let pre_pool_balance_1 = underlying_1_client.balance(&pool);
+
+            let requests = vec![
+                &e,
+                Request {
+                    request_type: RequestType::Deleverage as u32,
+                    address: underlying_0.clone(),
+                    amount: 6_0000000,
+                },
+                Request {
+                    request_type: RequestType::Repay as u32,
+                    address: underlying_1.clone(),
+                    amount: 2_9000000,
+                },
+            ];
+            let positions = execute_submit(&e, &samwise, &samwise, &merry, requests, false);
+
+            // the swap returns 3 tokens, where the minimum output of 2.9 is repaid and the
+            // remainder is sent to "to"
+            assert_eq!(positions.collateral.get_unchecked(0), 14_0000000);
+            assert_eq!(positions.liabilities.get_unchecked(1), 2_1000000);
+            assert_eq!(underlying_0_client.balance(&router), 6_0000000);
+            assert_eq!(
+                underlying_1_client.balance(&pool),
+                pre_pool_balance_1 + 2_9000000
+            );
+            assert_eq!(underlying_1_client.balance(&merry), 0_1000000);
+        });
+    }
+
     #[test]
     fn test_submit_use_allowance() {
         let e = Env::default();
@@ -827,43 +1073,46 @@ mod tests {
     }
 
     #[test]
-    #[should_panic(expected = "Error(Contract, #1200)")]
-    fn test_submit_from_is_not_self() {
+    fn test_simulate_submit() {
         let e = Env::default();
-        e.cost_estimate().budget().reset_unlimited();
-        e.mock_all_auths_allowing_non_root_auth();
-
-        e.ledger().set(LedgerInfo {
-            timestamp: 600,
-            protocol_version: 22,
-            sequence_number: 1234,
-            network_id: Default::default(),
-            base_reserve: 10,
-            min_temp_entry_ttl: 10,
-            min_persistent_entry_ttl: 10,
-            max_entry_ttl: 3110400,
-        });
+        e.mock_all_auths();
 
         let bombadil = Address::generate(&e);
         let samwise = Address::generate(&e);
         let pool = testutils::create_pool(&e);
         let (oracle, oracle_client) = testutils::create_mock_oracle(&e);
 
-        let (underlying_0, underlying_0_client) = testutils::create_token_contract(&e, &bombadil);
+        let (underlying_0, _) = testutils::create_token_contract(&e, &bombadil);
         let (reserve_config, reserve_data) = testutils::default_reserve_meta();
         testutils::create_reserve(&e, &pool, &underlying_0, &reserve_config, &reserve_data);
 
-        underlying_0_client.mint(&samwise, &16_0000000);
+        let (underlying_1, _) = testutils::create_token_contract(&e, &bombadil);
+        let (reserve_config, reserve_data) = testutils::default_reserve_meta();
+        testutils::create_reserve(&e, &pool, &underlying_1, &reserve_config, &reserve_data);
 
         oracle_client.set_data(
             &bombadil,
             &Asset::Other(Symbol::new(&e, "USD")),
-            &vec![&e, Asset::Stellar(underlying_0.clone())],
+            &vec![
+                &e,
+                Asset::Stellar(underlying_0.clone()),
+                Asset::Stellar(underlying_1.clone()),
+            ],
             &7,
             &300,
         );
-        oracle_client.set_price_stable(&vec![&e, 1_0000000]);
+        oracle_client.set_price_stable(&vec![&e, 1_0000000, 5_0000000]);
 
+        e.ledger().set(LedgerInfo {
+            timestamp: 0,
+            protocol_version: 22,
+            sequence_number: 1234,
+            network_id: Default::default(),
+            base_reserve: 10,
+            min_temp_entry_ttl: 10,
+            min_persistent_entry_ttl: 10,
+            max_entry_ttl: 3110400,
+        });
         let pool_config = PoolConfig {
             oracle,
             min_collateral: 1_0000000,
@@ -872,59 +1121,90 @@ mod tests {
             max_positions: 2,
         };
         e.as_contract(&pool, || {
-            e.mock_all_auths_allowing_non_root_auth();
             storage::set_pool_config(&e, &pool_config);
 
             let requests = vec![
                 &e,
                 Request {
                     request_type: RequestType::SupplyCollateral as u32,
-                    address: underlying_0,
+                    address: underlying_0.clone(),
                     amount: 15_0000000,
                 },
+                Request {
+                    request_type: RequestType::Borrow as u32,
+                    address: underlying_1.clone(),
+                    amount: 1_0000000,
+                },
             ];
-            execute_submit(&e, &pool, &samwise, &samwise, requests, false);
+            let simulation = execute_simulate_submit(&e, &samwise, requests);
+
+            assert_eq!(simulation.positions.collateral.get_unchecked(0), 15_0000000);
+            assert_eq!(simulation.positions.liabilities.get_unchecked(1), 1_0000000);
+            assert_eq!(simulation.spender_transfer.len(), 1);
+            assert_eq!(
+                simulation
+                    .spender_transfer
+                    .get_unchecked(underlying_0.clone()),
+                15_0000000
+            );
+            assert_eq!(simulation.pool_transfer.len(), 1);
+            assert_eq!(
+                simulation.pool_transfer.get_unchecked(underlying_1.clone()),
+                1_0000000
+            );
+            assert_eq!(simulation.collateral_base, 11_2500000);
+            assert_eq!(simulation.liability_base, 6_6666667);
+            assert_eq!(simulation.health_factor, 1_6874999);
+            assert_eq!(simulation.errors.len(), 0);
+
+            // nothing is written to the ledger
+            let positions = storage::get_user_positions(&e, &samwise);
+            assert_eq!(positions.collateral.len(), 0);
+            assert_eq!(positions.liabilities.len(), 0);
         });
     }
 
     #[test]
-    #[should_panic(expected = "Error(Contract, #1200)")]
-    fn test_submit_spender_is_not_self() {
+    fn test_simulate_submit_reports_errors() {
         let e = Env::default();
-        e.cost_estimate().budget().reset_unlimited();
-        e.mock_all_auths_allowing_non_root_auth();
-
-        e.ledger().set(LedgerInfo {
-            timestamp: 600,
-            protocol_version: 22,
-            sequence_number: 1234,
-            network_id: Default::default(),
-            base_reserve: 10,
-            min_temp_entry_ttl: 10,
-            min_persistent_entry_ttl: 10,
-            max_entry_ttl: 3110400,
-        });
+        e.mock_all_auths();
 
         let bombadil = Address::generate(&e);
         let samwise = Address::generate(&e);
         let pool = testutils::create_pool(&e);
         let (oracle, oracle_client) = testutils::create_mock_oracle(&e);
 
-        let (underlying_0, underlying_0_client) = testutils::create_token_contract(&e, &bombadil);
+        let (underlying_0, _) = testutils::create_token_contract(&e, &bombadil);
         let (reserve_config, reserve_data) = testutils::default_reserve_meta();
         testutils::create_reserve(&e, &pool, &underlying_0, &reserve_config, &reserve_data);
 
-        underlying_0_client.mint(&samwise, &16_0000000);
+        let (underlying_1, _) = testutils::create_token_contract(&e, &bombadil);
+        let (reserve_config, reserve_data) = testutils::default_reserve_meta();
+        testutils::create_reserve(&e, &pool, &underlying_1, &reserve_config, &reserve_data);
 
         oracle_client.set_data(
             &bombadil,
             &Asset::Other(Symbol::new(&e, "USD")),
-            &vec![&e, Asset::Stellar(underlying_0.clone())],
+            &vec![
+                &e,
+                Asset::Stellar(underlying_0.clone()),
+                Asset::Stellar(underlying_1.clone()),
+            ],
             &7,
             &300,
         );
-        oracle_client.set_price_stable(&vec![&e, 1_0000000]);
+        oracle_client.set_price_stable(&vec![&e, 1_0000000, 5_0000000]);
 
+        e.ledger().set(LedgerInfo {
+            timestamp: 0,
+            protocol_version: 22,
+            sequence_number: 1234,
+            network_id: Default::default(),
+            base_reserve: 10,
+            min_temp_entry_ttl: 10,
+            min_persistent_entry_ttl: 10,
+            max_entry_ttl: 3110400,
+        });
         let pool_config = PoolConfig {
             oracle,
             min_collateral: 1_0000000,
@@ -933,43 +1213,218 @@ mod tests {
             max_positions: 2,
         };
         e.as_contract(&pool, || {
-            e.mock_all_auths_allowing_non_root_auth();
             storage::set_pool_config(&e, &pool_config);
 
             let requests = vec![
                 &e,
                 Request {
                     request_type: RequestType::SupplyCollateral as u32,
-                    address: underlying_0,
+                    address: underlying_0.clone(),
                     amount: 15_0000000,
                 },
+                Request {
+                    request_type: RequestType::Borrow as u32,
+                    address: underlying_1.clone(),
+                    amount: 1_7500000,
+                },
             ];
-            execute_submit(&e, &samwise, &pool, &samwise, requests, false);
+            let simulation = execute_simulate_submit(&e, &samwise, requests);
+
+            assert!(simulation.health_factor < 1_0000000);
+            assert_eq!(simulation.errors, vec![&e, PoolError::InvalidHf as u32]);
         });
     }
 
     #[test]
     #[should_panic(expected = "Error(Contract, #1200)")]
-    fn test_submit_to_is_not_self() {
+    fn test_simulate_submit_auction_request_panics() {
         let e = Env::default();
-        e.cost_estimate().budget().reset_unlimited();
-        e.mock_all_auths_allowing_non_root_auth();
-
-        e.ledger().set(LedgerInfo {
-            timestamp: 600,
-            protocol_version: 22,
-            sequence_number: 1234,
-            network_id: Default::default(),
-            base_reserve: 10,
-            min_temp_entry_ttl: 10,
-            min_persistent_entry_ttl: 10,
-            max_entry_ttl: 3110400,
-        });
+        e.mock_all_auths();
 
-        let bombadil = Address::generate(&e);
         let samwise = Address::generate(&e);
+        let frodo = Address::generate(&e);
         let pool = testutils::create_pool(&e);
-        let (oracle, oracle_client) = testutils::create_mock_oracle(&e);
+
+        e.as_contract(&pool, || {
+            let requests = vec![
+                &e,
+                Request {
+                    request_type: RequestType::FillUserLiquidationAuction as u32,
+                    address: frodo.clone(),
+                    amount: 50,
+                },
+            ];
+            execute_simulate_submit(&e, &samwise, requests);
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "Error(Contract, #1200)")]
+    fn test_simulate_submit_deleverage_request_panics() {
+        let e = Env::default();
+        e.mock_all_auths();
+
+        let samwise = Address::generate(&e);
+        let underlying = Address::generate(&e);
+        let pool = testutils::create_pool(&e);
+
+        e.as_contract(&pool, || {
+            let requests = vec![
+                &e,
+                Request {
+                    request_type: RequestType::Deleverage as u32,
+                    address: underlying.clone(),
+                    amount: 1_0000000,
+                },
+            ];
+            execute_simulate_submit(&e, &samwise, requests);
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "Error(Contract, #1200)")]
+    fn test_submit_from_is_not_self() {
+        let e = Env::default();
+        e.cost_estimate().budget().reset_unlimited();
+        e.mock_all_auths_allowing_non_root_auth();
+
+        e.ledger().set(LedgerInfo {
+            timestamp: 600,
+            protocol_version: 22,
+            sequence_number: 1234,
+            network_id: Default::default(),
+            base_reserve: 10,
+            min_temp_entry_ttl: 10,
+            min_persistent_entry_ttl: 10,
+            max_entry_ttl: 3110400,
+        });
+
+        let bombadil = Address::generate(&e);
+        let samwise = Address::generate(&e);
+        let pool = testutils::create_pool(&e);
+        let (oracle, oracle_client) = testutils::create_mock_oracle(&e);
+
+        let (underlying_0, underlying_0_client) = testutils::create_token_contract(&e, &bombadil);
+        let (reserve_config, reserve_data) = testutils::default_reserve_meta();
+        testutils::create_reserve(&e, &pool, &underlying_0, &reserve_config, &reserve_data);
+
+        underlying_0_client.mint(&samwise, &16_0000000);
+
+        oracle_client.set_data(
+            &bombadil,
+            &Asset::Other(Symbol::new(&e, "USD")),
+            &vec![&e, Asset::Stellar(underlying_0.clone())],
+            &7,
+            &300,
+        );
+        oracle_client.set_price_stable(&vec![&e, 1_0000000]);
+
+        let pool_config = PoolConfig {
+            oracle,
+            min_collateral: 1_0000000,
+            bstop_rate: 0_1000000,
+            status: 0,
+            max_positions: 2,
+        };
+        e.as_contract(&pool, || {
+            e.mock_all_auths_allowing_non_root_auth();
+            storage::set_pool_config(&e, &pool_config);
+
+            let requests = vec![
+                &e,
+                Request {
+                    request_type: RequestType::SupplyCollateral as u32,
+                    address: underlying_0,
+                    amount: 15_0000000,
+                },
+            ];
+            execute_submit(&e, &pool, &samwise, &samwise, requests, false);
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "Error(Contract, #1200)")]
+    fn test_submit_spender_is_not_self() {
+        let e = Env::default();
+        e.cost_estimate().budget().reset_unlimited();
+        e.mock_all_auths_allowing_non_root_auth();
+
+        e.ledger().set(LedgerInfo {
+            timestamp: 600,
+            protocol_version: 22,
+            sequence_number: 1234,
+            network_id: Default::default(),
+            base_reserve: 10,
+            min_temp_entry_ttl: 10,
+            min_persistent_entry_ttl: 10,
+            max_entry_ttl: 3110400,
+        });
+
+        let bombadil = Address::generate(&e);
+        let samwise = Address::generate(&e);
+        let pool = testutils::create_pool(&e);
+        let (oracle, oracle_client) = testutils::create_mock_oracle(&e);
+
+        let (underlying_0, underlying_0_client) = testutils::create_token_contract(&e, &bombadil);
+        let (reserve_config, reserve_data) = testutils::default_reserve_meta();
+        testutils::create_reserve(&e, &pool, &underlying_0, &reserve_config, &reserve_data);
+
+        underlying_0_client.mint(&samwise, &16_0000000);
+
+        oracle_client.set_data(
+            &bombadil,
+            &Asset::Other(Symbol::new(&e, "USD")),
+            &vec![&e, Asset::Stellar(underlying_0.clone())],
+            &7,
+            &300,
+        );
+        oracle_client.set_price_stable(&vec![&e, 1_0000000]);
+
+        let pool_config = PoolConfig {
+            oracle,
+            min_collateral: 1_0000000,
+            bstop_rate: 0_1000000,
+            status: 0,
+            max_positions: 2,
+        };
+        e.as_contract(&pool, || {
+            e.mock_all_auths_allowing_non_root_auth();
+            storage::set_pool_config(&e, &pool_config);
+
+            let requests = vec![
+                &e,
+                Request {
+                    request_type: RequestType::SupplyCollateral as u32,
+                    address: underlying_0,
+                    amount: 15_0000000,
+                },
+            ];
+            execute_submit(&e, &samwise, &pool, &samwise, requests, false);
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "Error(Contract, #1200)")]
+    fn test_submit_to_is_not_self() {
+        let e = Env::default();
+        e.cost_estimate().budget().reset_unlimited();
+        e.mock_all_auths_allowing_non_root_auth();
+
+        e.ledger().set(LedgerInfo {
+            timestamp: 600,
+            protocol_version: 22,
+            sequence_number: 1234,
+            network_id: Default::default(),
+            base_reserve: 10,
+            min_temp_entry_ttl: 10,
+            min_persistent_entry_ttl: 10,
+            max_entry_ttl: 3110400,
+        });
+
+        let bombadil = Address::generate(&e);
+        let samwise = Address::generate(&e);
+        let pool = testutils::create_pool(&e);
+        let (oracle, oracle_client) = testutils::create_mock_oracle(&e);
 
         let (underlying_0, underlying_0_client) = testutils::create_token_contract(&e, &bombadil);
         let (reserve_config, reserve_data) = testutils::default_reserve_meta();
@@ -1090,6 +1545,80 @@ mod tests {
         });
     }
 
+    #[test]
+    #[should_panic(expected = "Error(Contract, #1208)")]
+    fn test_submit_over_max_positions_fixed_loan() {
+        let e = Env::default();
+        e.cost_estimate().budget().reset_unlimited();
+        e.mock_all_auths_allowing_non_root_auth();
+
+        e.ledger().set(LedgerInfo {
+            timestamp: 600,
+            protocol_version: 22,
+            sequence_number: 1234,
+            network_id: Default::default(),
+            base_reserve: 10,
+            min_temp_entry_ttl: 10,
+            min_persistent_entry_ttl: 10,
+            max_entry_ttl: 3110400,
+        });
+
+        let bombadil = Address::generate(&e);
+        let samwise = Address::generate(&e);
+        let pool = testutils::create_pool(&e);
+        let (oracle, oracle_client) = testutils::create_mock_oracle(&e);
+
+        let (underlying_0, _) = testutils::create_token_contract(&e, &bombadil);
+        let (reserve_config, reserve_data) = testutils::default_reserve_meta();
+        testutils::create_reserve(&e, &pool, &underlying_0, &reserve_config, &reserve_data);
+
+        let (underlying_1, _) = testutils::create_token_contract(&e, &bombadil);
+        let (reserve_config, reserve_data) = testutils::default_reserve_meta();
+        testutils::create_reserve(&e, &pool, &underlying_1, &reserve_config, &reserve_data);
+
+        oracle_client.set_data(
+            &bombadil,
+            &Asset::Other(Symbol::new(&e, "USD")),
+            &vec![
+                &e,
+                Asset::Stellar(underlying_0.clone()),
+                Asset::Stellar(underlying_1.clone()),
+            ],
+            &7,
+            &300,
+        );
+        oracle_client.set_price_stable(&vec![&e, 1_0000000, 5_0000000]);
+
+        let pool_config = PoolConfig {
+            oracle,
+            min_collateral: 1_0000000,
+            bstop_rate: 0_1000000,
+            status: 0,
+            max_positions: 2,
+        };
+        let user_positions = Positions {
+            liabilities: map![&e, (0, 1_0000000)],
+            collateral: map![&e, (0, 15_0000000)],
+            supply: map![&e],
+        };
+        e.as_contract(&pool, || {
+            e.mock_all_auths_allowing_non_root_auth();
+            storage::set_pool_config(&e, &pool_config);
+            storage::set_user_positions(&e, &samwise, &user_positions);
+
+            // the fixed-term loan counts as a third position
+            let requests = vec![
+                &e,
+                Request {
+                    request_type: RequestType::BorrowFixed as u32,
+                    address: underlying_1,
+                    amount: 1_0000000,
+                },
+            ];
+            execute_submit(&e, &samwise, &samwise, &samwise, requests, false);
+        });
+    }
+
     #[test]
     fn test_submit_over_max_positions_decrease_allowed() {
         let e = Env::default();
@@ -1538,8 +2067,7 @@ mod tests {
             // -> max util is 95%
             let flash_loan: FlashLoan = FlashLoan {
                 contract: flash_loan_receiver,
-                asset: underlying_0,
-                amount: 25_0000000,
+                assets: vec![&e, (underlying_0, 25_0000000)],
             };
 
             let requests = vec![
@@ -1580,7 +2108,7 @@ mod tests {
     }
 
     #[test]
-    fn test_submit_with_flash_loan_process_flash_loan_first() {
+    fn test_submit_with_flash_loan_stores_reserve() {
         let e = Env::default();
         e.cost_estimate().budget().reset_unlimited();
         e.mock_all_auths_allowing_non_root_auth();
@@ -1603,7 +2131,7 @@ mod tests {
 
         let (flash_loan_receiver, _) = testutils::create_flashloan_receiver(&e);
 
-        let (underlying_0, underlying_0_client) = testutils::create_token_contract(&e, &bombadil);
+        let (underlying_0, _) = testutils::create_token_contract(&e, &bombadil);
         let (mut reserve_config, mut reserve_data) = testutils::default_reserve_meta();
         reserve_config.max_util = 9500000;
         reserve_data.b_supply = 100_0000000;
@@ -1637,24 +2165,223 @@ mod tests {
         e.as_contract(&pool, || {
             storage::set_pool_config(&e, &pool_config);
 
-            underlying_0_client.mint(&samwise, &1_0000000);
-            underlying_0_client.approve(&samwise, &pool, &100_0000000, &10000);
+            underlying_1_client.mint(&samwise, &25_0000000);
+            underlying_1_client.approve(&samwise, &pool, &100_0000000, &10000);
 
-            let pre_pool_balance_0 = underlying_0_client.balance(&pool);
-            let pre_pool_balance_1 = underlying_1_client.balance(&pool);
+            let pre_d_supply = storage::get_res_data(&e, &underlying_0).d_supply;
 
-            // pool has 100 supplied and 50 borrowed for asset_0
-            // -> max util is 95%
+            // no request touches the flash loaned reserve
             let flash_loan: FlashLoan = FlashLoan {
                 contract: flash_loan_receiver,
-                asset: underlying_0.clone(),
-                amount: 25_0000000,
+                assets: vec![&e, (underlying_0.clone(), 25_0000000)],
             };
-
             let requests = vec![
                 &e,
                 Request {
-                    request_type: RequestType::Repay as u32,
+                    request_type: RequestType::SupplyCollateral as u32,
+                    address: underlying_1,
+                    amount: 25_0000000,
+                },
+            ];
+            let positions = execute_submit_with_flash_loan(&e, &samwise, flash_loan, requests);
+
+            let d_tokens_minted = positions.liabilities.get_unchecked(0);
+            assert_eq!(d_tokens_minted, 249999794);
+            assert_eq!(
+                storage::get_res_data(&e, &underlying_0).d_supply,
+                pre_d_supply + d_tokens_minted
+            );
+        });
+    }
+
+    #[test]
+    fn test_submit_with_flash_loan_multiple_assets() {
+        let e = Env::default();
+        e.cost_estimate().budget().reset_unlimited();
+        e.mock_all_auths_allowing_non_root_auth();
+
+        e.ledger().set(LedgerInfo {
+            timestamp: 600,
+            protocol_version: 22,
+            sequence_number: 1234,
+            network_id: Default::default(),
+            base_reserve: 10,
+            min_temp_entry_ttl: 10,
+            min_persistent_entry_ttl: 10,
+            max_entry_ttl: 3110400,
+        });
+
+        let bombadil = Address::generate(&e);
+        let samwise = Address::generate(&e);
+        let pool = testutils::create_pool(&e);
+        let (oracle, oracle_client) = testutils::create_mock_oracle(&e);
+
+        let (flash_loan_receiver, _) = testutils::create_flashloan_receiver(&e);
+
+        let (underlying_0, underlying_0_client) = testutils::create_token_contract(&e, &bombadil);
+        let (mut reserve_config, mut reserve_data) = testutils::default_reserve_meta();
+        reserve_config.max_util = 9500000;
+        reserve_data.b_supply = 100_0000000;
+        reserve_data.d_supply = 50_0000000;
+        testutils::create_reserve(&e, &pool, &underlying_0, &reserve_config, &reserve_data);
+
+        let (underlying_1, underlying_1_client) = testutils::create_token_contract(&e, &bombadil);
+        let (reserve_config, reserve_data) = testutils::default_reserve_meta();
+        testutils::create_reserve(&e, &pool, &underlying_1, &reserve_config, &reserve_data);
+
+        oracle_client.set_data(
+            &bombadil,
+            &Asset::Other(Symbol::new(&e, "USD")),
+            &vec![
+                &e,
+                Asset::Stellar(underlying_0.clone()),
+                Asset::Stellar(underlying_1.clone()),
+            ],
+            &7,
+            &300,
+        );
+        oracle_client.set_price_stable(&vec![&e, 1_0000000, 5_0000000]);
+
+        let pool_config = PoolConfig {
+            oracle,
+            min_collateral: 1_0000000,
+            bstop_rate: 0_1000000,
+            status: 0,
+            max_positions: 4,
+        };
+        e.as_contract(&pool, || {
+            storage::set_pool_config(&e, &pool_config);
+
+            underlying_1_client.mint(&samwise, &25_0000000);
+            underlying_1_client.approve(&samwise, &pool, &100_0000000, &10000);
+
+            let pre_pool_balance_0 = underlying_0_client.balance(&pool);
+            let pre_pool_balance_1 = underlying_1_client.balance(&pool);
+
+            // pool has 100 supplied and 50 borrowed for asset_0
+            // -> max util is 95%
+            let flash_loan = FlashLoan {
+                contract: flash_loan_receiver,
+                assets: vec![
+                    &e,
+                    (underlying_0.clone(), 25_0000000),
+                    (underlying_1.clone(), 1_0000000),
+                ],
+            };
+
+            let requests = vec![
+                &e,
+                Request {
+                    request_type: RequestType::SupplyCollateral as u32,
+                    address: underlying_1.clone(),
+                    amount: 25_0000000,
+                },
+            ];
+            let positions = execute_submit_with_flash_loan(&e, &samwise, flash_loan, requests);
+
+            assert_eq!(positions.liabilities.len(), 2);
+            assert_eq!(positions.collateral.len(), 1);
+            assert_eq!(positions.supply.len(), 0);
+            assert_eq!(positions.collateral.get_unchecked(1), 249999807);
+            // actual is 24.999979375 - rounds up
+            assert_eq!(positions.liabilities.get_unchecked(0), 249999794);
+            assert_eq!(positions.liabilities.get_unchecked(1), 9999989);
+
+            assert_eq!(
+                underlying_0_client.balance(&pool),
+                pre_pool_balance_0 - 25_0000000
+            );
+            assert_eq!(
+                underlying_1_client.balance(&pool),
+                pre_pool_balance_1 + 25_0000000 - 1_0000000
+            );
+
+            assert_eq!(underlying_0_client.balance(&samwise), 25_0000000);
+            assert_eq!(underlying_1_client.balance(&samwise), 1_0000000);
+
+            // check allowance is used
+            assert_eq!(
+                underlying_1_client.allowance(&samwise, &pool),
+                100_0000000 - 25_0000000
+            );
+        });
+    }
+
+    #[test]
+    fn test_submit_with_flash_loan_process_flash_loan_first() {
+        let e = Env::default();
+        e.cost_estimate().budget().reset_unlimited();
+        e.mock_all_auths_allowing_non_root_auth();
+
+        e.ledger().set(LedgerInfo {
+            timestamp: 600,
+            protocol_version: 22,
+            sequence_number: 1234,
+            network_id: Default::default(),
+            base_reserve: 10,
+            min_temp_entry_ttl: 10,
+            min_persistent_entry_ttl: 10,
+            max_entry_ttl: 3110400,
+        });
+
+        let bombadil = Address::generate(&e);
+        let samwise = Address::generate(&e);
+        let pool = testutils::create_pool(&e);
+        let (oracle, oracle_client) = testutils::create_mock_oracle(&e);
+
+        let (flash_loan_receiver, _) = testutils::create_flashloan_receiver(&e);
+
+        let (underlying_0, underlying_0_client) = testutils::create_token_contract(&e, &bombadil);
+        let (mut reserve_config, mut reserve_data) = testutils::default_reserve_meta();
+        reserve_config.max_util = 9500000;
+        reserve_data.b_supply = 100_0000000;
+        reserve_data.d_supply = 50_0000000;
+        testutils::create_reserve(&e, &pool, &underlying_0, &reserve_config, &reserve_data);
+
+        let (underlying_1, underlying_1_client) = testutils::create_token_contract(&e, &bombadil);
+        let (reserve_config, reserve_data) = testutils::default_reserve_meta();
+        testutils::create_reserve(&e, &pool, &underlying_1, &reserve_config, &reserve_data);
+
+        oracle_client.set_data(
+            &bombadil,
+            &Asset::Other(Symbol::new(&e, "USD")),
+            &vec![
+                &e,
+                Asset::Stellar(underlying_0.clone()),
+                Asset::Stellar(underlying_1.clone()),
+            ],
+            &7,
+            &300,
+        );
+        oracle_client.set_price_stable(&vec![&e, 1_0000000, 5_0000000]);
+
+        let pool_config = PoolConfig {
+            oracle,
+            min_collateral: 1_0000000,
+            bstop_rate: 0_1000000,
+            status: 0,
+            max_positions: 4,
+        };
+        e.as_contract(&pool, || {
+            storage::set_pool_config(&e, &pool_config);
+
+            underlying_0_client.mint(&samwise, &1_0000000);
+            underlying_0_client.approve(&samwise, &pool, &100_0000000, &10000);
+
+            let pre_pool_balance_0 = underlying_0_client.balance(&pool);
+            let pre_pool_balance_1 = underlying_1_client.balance(&pool);
+
+            // pool has 100 supplied and 50 borrowed for asset_0
+            // -> max util is 95%
+            let flash_loan: FlashLoan = FlashLoan {
+                contract: flash_loan_receiver,
+                assets: vec![&e, (underlying_0.clone(), 25_0000000)],
+            };
+
+            let requests = vec![
+                &e,
+                Request {
+                    request_type: RequestType::Repay as u32,
                     address: underlying_0,
                     amount: 25_0000010,
                 },
@@ -1746,8 +2473,7 @@ mod tests {
             // -> max util is 95%
             let flash_loan: FlashLoan = FlashLoan {
                 contract: flash_loan_receiver,
-                asset: underlying_0,
-                amount: 25_0000000,
+                assets: vec![&e, (underlying_0, 25_0000000)],
             };
 
             let requests = vec![
@@ -1828,8 +2554,173 @@ mod tests {
             // -> max util is 95%
             let flash_loan: FlashLoan = FlashLoan {
                 contract: flash_loan_receiver,
-                asset: underlying_0,
-                amount: 46_0000000,
+                assets: vec![&e, (underlying_0, 46_0000000)],
+            };
+
+            let requests = vec![
+                &e,
+                Request {
+                    request_type: RequestType::SupplyCollateral as u32,
+                    address: underlying_1,
+                    amount: 50_0000000,
+                },
+            ];
+            execute_submit_with_flash_loan(&e, &samwise, flash_loan, requests);
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "Error(Contract, #1207)")]
+    fn test_submit_with_flash_loan_multiple_assets_checks_max_util() {
+        let e = Env::default();
+        e.cost_estimate().budget().reset_unlimited();
+        e.mock_all_auths_allowing_non_root_auth();
+
+        e.ledger().set(LedgerInfo {
+            timestamp: 600,
+            protocol_version: 22,
+            sequence_number: 1234,
+            network_id: Default::default(),
+            base_reserve: 10,
+            min_temp_entry_ttl: 10,
+            min_persistent_entry_ttl: 10,
+            max_entry_ttl: 3110400,
+        });
+
+        let bombadil = Address::generate(&e);
+        let samwise = Address::generate(&e);
+        let pool = testutils::create_pool(&e);
+        let (oracle, oracle_client) = testutils::create_mock_oracle(&e);
+
+        let (flash_loan_receiver, _) = testutils::create_flashloan_receiver(&e);
+
+        let (underlying_0, _) = testutils::create_token_contract(&e, &bombadil);
+        let (mut reserve_config, mut reserve_data) = testutils::default_reserve_meta();
+        reserve_config.max_util = 9500000;
+        reserve_data.b_supply = 100_0000000;
+        reserve_data.d_supply = 50_0000000;
+        testutils::create_reserve(&e, &pool, &underlying_0, &reserve_config, &reserve_data);
+
+        let (underlying_1, underlying_1_client) = testutils::create_token_contract(&e, &bombadil);
+        let (reserve_config, reserve_data) = testutils::default_reserve_meta();
+        testutils::create_reserve(&e, &pool, &underlying_1, &reserve_config, &reserve_data);
+
+        oracle_client.set_data(
+            &bombadil,
+            &Asset::Other(Symbol::new(&e, "USD")),
+            &vec![
+                &e,
+                Asset::Stellar(underlying_0.clone()),
+                Asset::Stellar(underlying_1.clone()),
+            ],
+            &7,
+            &300,
+        );
+        oracle_client.set_price_stable(&vec![&e, 1_0000000, 5_0000000]);
+
+        let pool_config = PoolConfig {
+            oracle,
+            min_collateral: 1_0000000,
+            bstop_rate: 0_1000000,
+            status: 0,
+            max_positions: 4,
+        };
+        e.as_contract(&pool, || {
+            storage::set_pool_config(&e, &pool_config);
+
+            underlying_1_client.mint(&samwise, &50_0000000);
+            underlying_1_client.approve(&samwise, &pool, &100_0000000, &10000);
+
+            // pool has 100 supplied and 50 borrowed for asset_0
+            // -> max util is 95%
+            let flash_loan = FlashLoan {
+                contract: flash_loan_receiver,
+                assets: vec![
+                    &e,
+                    (underlying_1.clone(), 1_0000000),
+                    (underlying_0.clone(), 46_0000000),
+                ],
+            };
+
+            let requests = vec![
+                &e,
+                Request {
+                    request_type: RequestType::SupplyCollateral as u32,
+                    address: underlying_1,
+                    amount: 50_0000000,
+                },
+            ];
+            execute_submit_with_flash_loan(&e, &samwise, flash_loan, requests);
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "Error(Contract, #1225)")]
+    fn test_submit_with_flash_loan_checks_borrow_cap() {
+        let e = Env::default();
+        e.cost_estimate().budget().reset_unlimited();
+        e.mock_all_auths_allowing_non_root_auth();
+
+        e.ledger().set(LedgerInfo {
+            timestamp: 600,
+            protocol_version: 22,
+            sequence_number: 1234,
+            network_id: Default::default(),
+            base_reserve: 10,
+            min_temp_entry_ttl: 10,
+            min_persistent_entry_ttl: 10,
+            max_entry_ttl: 3110400,
+        });
+
+        let bombadil = Address::generate(&e);
+        let samwise = Address::generate(&e);
+        let pool = testutils::create_pool(&e);
+        let (oracle, oracle_client) = testutils::create_mock_oracle(&e);
+
+        let (flash_loan_receiver, _) = testutils::create_flashloan_receiver(&e);
+
+        let (underlying_0, _) = testutils::create_token_contract(&e, &bombadil);
+        let (mut reserve_config, mut reserve_data) = testutils::default_reserve_meta();
+        reserve_config.borrow_cap = 60_0000000;
+        reserve_data.b_supply = 100_0000000;
+        reserve_data.d_supply = 50_0000000;
+        testutils::create_reserve(&e, &pool, &underlying_0, &reserve_config, &reserve_data);
+
+        let (underlying_1, underlying_1_client) = testutils::create_token_contract(&e, &bombadil);
+        let (reserve_config, reserve_data) = testutils::default_reserve_meta();
+        testutils::create_reserve(&e, &pool, &underlying_1, &reserve_config, &reserve_data);
+
+        oracle_client.set_data(
+            &bombadil,
+            &Asset::Other(Symbol::new(&e, "USD")),
+            &vec![
+                &e,
+                Asset::Stellar(underlying_0.clone()),
+                Asset::Stellar(underlying_1.clone()),
+            ],
+            &7,
+            &300,
+        );
+        oracle_client.set_price_stable(&vec![&e, 1_0000000, 5_0000000]);
+
+        let pool_config = PoolConfig {
+            oracle,
+            min_collateral: 1_0000000,
+            bstop_rate: 0_1000000,
+            status: 0,
+            max_positions: 4,
+        };
+        e.as_contract(&pool, || {
+            storage::set_pool_config(&e, &pool_config);
+
+            underlying_1_client.mint(&samwise, &50_0000000);
+            underlying_1_client.approve(&samwise, &pool, &100_0000000, &10000);
+
+            // pool has 100 supplied and 50 borrowed for asset_0
+            // -> borrow cap is 60
+            let flash_loan: FlashLoan = FlashLoan {
+                contract: flash_loan_receiver,
+                assets: vec![&e, (underlying_0, 10_0000001)],
             };
 
             let requests = vec![
@@ -1914,8 +2805,7 @@ mod tests {
 
             let flash_loan: FlashLoan = FlashLoan {
                 contract: flash_loan_receiver,
-                asset: underlying_0,
-                amount: 1_0000000,
+                assets: vec![&e, (underlying_0, 1_0000000)],
             };
             let requests = vec![
                 &e,
@@ -2001,8 +2891,7 @@ mod tests {
 
             let flash_loan: FlashLoan = FlashLoan {
                 contract: flash_loan_receiver,
-                asset: underlying_0,
-                amount: 1_0000000,
+                assets: vec![&e, (underlying_0, 1_0000000)],
             };
             let requests = vec![
                 &e,
@@ -2113,8 +3002,7 @@ mod tests {
 
             let flash_loan: FlashLoan = FlashLoan {
                 contract: flash_loan_receiver,
-                asset: underlying_0.clone(),
-                amount: 1_0000000,
+                assets: vec![&e, (underlying_0.clone(), 1_0000000)],
             };
             let requests = vec![
                 &e,
@@ -2192,8 +3080,7 @@ mod tests {
 
             let flash_loan: FlashLoan = FlashLoan {
                 contract: flash_loan_receiver,
-                asset: underlying_1.clone(),
-                amount: 5_0000000,
+                assets: vec![&e, (underlying_1.clone(), 5_0000000)],
             };
             let requests = vec![
                 &e,
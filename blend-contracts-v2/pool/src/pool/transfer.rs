@@ -0,0 +1,395 @@
+use soroban_sdk::{panic_with_error, Address, Env, Vec};
+
+use crate::errors::PoolError;
+
+use super::{pool::Pool, submit::validate_submit, Positions, User};
+
+/// Move a user's positions in a set of reserves to another address. Collateral, supply,
+/// liabilities and fixed-term loans in each reserve are moved in full.
+///
+/// ### Arguments
+/// * `from` - The address of the user whose positions are being moved
+/// * `to` - The address of the user receiving the positions
+/// * `reserves` - The underlying assets of the reserves to move positions in
+///
+/// ### Returns
+/// * (Positions, Positions) - The new positions for `from` and `to`
+///
+/// ### Panics
+/// If no positions are moved, either user has an ongoing liquidation, either user would
+/// exceed the max positions or be left with an invalid health factor, or `to` already holds
+/// a fixed-term loan in a reserve being moved
+pub fn execute_transfer_positions(
+    e: &Env,
+    from: &Address,
+    to: &Address,
+    reserves: Vec<Address>,
+) -> (Positions, Positions) {
+    if from == to || from == &e.current_contract_address() || to == &e.current_contract_address() {
+        panic_with_error!(e, PoolError::BadRequest);
+    }
+    let mut pool = Pool::load(e);
+    let mut from_state = User::load(e, from);
+    let mut to_state = User::load(e, to);
+    let prev_from_positions = from_state.positions.clone();
+    let prev_to_positions = to_state.positions.clone();
+    let prev_from_count = from_state.effective_count();
+    let prev_to_count = to_state.effective_count();
+
+    let mut moved = false;
+    for asset in reserves.iter() {
+        let mut reserve = pool.load_reserve(e, &asset, true);
+        let index = reserve.config.index;
+
+        let collateral = from_state.get_collateral(index);
+        if collateral > 0 {
+            from_state.remove_collateral(e, &mut reserve, collateral);
+            to_state.add_collateral(e, &mut reserve, collateral);
+            moved = true;
+        }
+        let supply = from_state.get_supply(index);
+        if supply > 0 {
+            from_state.remove_supply(e, &mut reserve, supply);
+            to_state.add_supply(e, &mut reserve, supply);
+            moved = true;
+        }
+        let liabilities = from_state.get_liabilities(index);
+        if liabilities > 0 {
+            from_state.remove_liabilities(e, &mut reserve, liabilities);
+            to_state.add_liabilities(e, &mut reserve, liabilities);
+            moved = true;
+        }
+        if let Some(loan) = from_state.fixed_loans.get(index) {
+            if to_state.fixed_loans.contains_key(index) {
+                panic_with_error!(e, PoolError::InvalidFixedLoan);
+            }
+            from_state.fixed_loans.remove(index);
+            to_state.fixed_loans.set(index, loan);
+            moved = true;
+        }
+        pool.cache_reserve(reserve);
+    }
+    if !moved {
+        panic_with_error!(e, PoolError::BadRequest);
+    }
+
+    validate_submit(
+        e,
+        &mut pool,
+        &from_state,
+        &prev_from_positions,
+        prev_from_count,
+        true,
+    );
+    validate_submit(
+        e,
+        &mut pool,
+        &to_state,
+        &prev_to_positions,
+        prev_to_count,
+        true,
+    );
+
+    pool.store_cached_reserves(e);
+    from_state.store(e);
+    to_state.store(e);
+
+    (from_state.positions, to_state.positions)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        storage::{self, FixedLoan, PoolConfig},
+        testutils, AuctionData, AuctionType,
+    };
+    use sep_40_oracle::testutils::Asset;
+    use soroban_sdk::{
+        map,
+        testutils::{Address as _, Ledger, LedgerInfo},
+        vec, Symbol,
+    };
+
+    /// Setup a pool with two reserves last updated at timestamp 600 with prices of 1 and 5
+    fn setup_pool(e: &Env, max_positions: u32) -> (Address, Address, Address) {
+        let bombadil = Address::generate(e);
+        let pool = testutils::create_pool(e);
+        let (oracle, oracle_client) = testutils::create_mock_oracle(e);
+
+        let (underlying_0, _) = testutils::create_token_contract(e, &bombadil);
+        let (reserve_config, mut reserve_data) = testutils::default_reserve_meta();
+        reserve_data.last_time = 600;
+        testutils::create_reserve(e, &pool, &underlying_0, &reserve_config, &reserve_data);
+
+        let (underlying_1, _) = testutils::create_token_contract(e, &bombadil);
+        testutils::create_reserve(e, &pool, &underlying_1, &reserve_config, &reserve_data);
+
+        oracle_client.set_data(
+            &bombadil,
+            &Asset::Other(Symbol::new(e, "USD")),
+            &vec![
+                e,
+                Asset::Stellar(underlying_0.clone()),
+                Asset::Stellar(underlying_1.clone()),
+            ],
+            &7,
+            &300,
+        );
+        oracle_client.set_price_stable(&vec![e, 1_0000000, 5_0000000]);
+
+        let pool_config = PoolConfig {
+            oracle,
+            min_collateral: 1_0000000,
+            bstop_rate: 0_1000000,
+            status: 0,
+            max_positions,
+        };
+        e.as_contract(&pool, || {
+            storage::set_pool_config(e, &pool_config);
+        });
+        (pool, underlying_0, underlying_1)
+    }
+
+    #[test]
+    fn test_execute_transfer_positions() {
+        let e = Env::default();
+        e.mock_all_auths();
+        e.ledger().set(LedgerInfo {
+            timestamp: 600,
+            protocol_version: 22,
+            sequence_number: 1234,
+            network_id: Default::default(),
+            base_reserve: 10,
+            min_temp_entry_ttl: 10,
+            min_persistent_entry_ttl: 10,
+            max_entry_ttl: 3110400,
+        });
+
+        let (pool, underlying_0, underlying_1) = setup_pool(&e, 4);
+        let samwise = Address::generate(&e);
+        let frodo = Address::generate(&e);
+
+        e.as_contract(&pool, || {
+            storage::set_user_positions(
+                &e,
+                &samwise,
+                &Positions {
+                    liabilities: map![&e, (1, 2_0000000)],
+                    collateral: map![&e, (0, 100_0000000)],
+                    supply: map![&e, (1, 1_0000000)],
+                },
+            );
+            storage::set_user_positions(
+                &e,
+                &frodo,
+                &Positions {
+                    liabilities: map![&e],
+                    collateral: map![&e, (0, 10_0000000)],
+                    supply: map![&e],
+                },
+            );
+            storage::set_user_fixed_loans(
+                &e,
+                &samwise,
+                &map![
+                    &e,
+                    (
+                        1,
+                        FixedLoan {
+                            principal: 1_0000000,
+                            rate: 0_1000000,
+                            last_time: 600,
+                            maturity: 10000,
+                        }
+                    )
+                ],
+            );
+
+            let (from_positions, to_positions) = execute_transfer_positions(
+                &e,
+                &samwise,
+                &frodo,
+                vec![&e, underlying_0.clone(), underlying_1.clone()],
+            );
+
+            assert_eq!(from_positions.effective_count(), 0);
+            assert_eq!(to_positions.collateral.get_unchecked(0), 110_0000000);
+            assert_eq!(to_positions.supply.get_unchecked(1), 1_0000000);
+            assert_eq!(to_positions.liabilities.get_unchecked(1), 2_0000000);
+            assert_eq!(
+                storage::get_user_positions(&e, &samwise).effective_count(),
+                0
+            );
+            assert_eq!(
+                storage::get_user_positions(&e, &frodo)
+                    .collateral
+                    .get_unchecked(0),
+                110_0000000
+            );
+            assert_eq!(storage::get_user_fixed_loans(&e, &samwise).len(), 0);
+            assert_eq!(
+                storage::get_user_fixed_loans(&e, &frodo)
+                    .get_unchecked(1)
+                    .principal,
+                1_0000000
+            );
+
+            // reserve supplies are unchanged
+            let reserve_data = storage::get_res_data(&e, &underlying_0);
+            assert_eq!(reserve_data.b_supply, 100_0000000);
+            let reserve_data = storage::get_res_data(&e, &underlying_1);
+            assert_eq!(reserve_data.d_supply, 75_0000000);
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "Error(Contract, #1205)")]
+    fn test_execute_transfer_positions_leaves_from_unhealthy() {
+        let e = Env::default();
+        e.mock_all_auths();
+        e.ledger().set(LedgerInfo {
+            timestamp: 600,
+            protocol_version: 22,
+            sequence_number: 1234,
+            network_id: Default::default(),
+            base_reserve: 10,
+            min_temp_entry_ttl: 10,
+            min_persistent_entry_ttl: 10,
+            max_entry_ttl: 3110400,
+        });
+
+        let (pool, underlying_0, _) = setup_pool(&e, 4);
+        let samwise = Address::generate(&e);
+        let frodo = Address::generate(&e);
+
+        e.as_contract(&pool, || {
+            storage::set_user_positions(
+                &e,
+                &samwise,
+                &Positions {
+                    liabilities: map![&e, (1, 2_0000000)],
+                    collateral: map![&e, (0, 100_0000000)],
+                    supply: map![&e],
+                },
+            );
+
+            // only the collateral is moved
+            execute_transfer_positions(&e, &samwise, &frodo, vec![&e, underlying_0.clone()]);
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "Error(Contract, #1208)")]
+    fn test_execute_transfer_positions_max_positions() {
+        let e = Env::default();
+        e.mock_all_auths();
+        e.ledger().set(LedgerInfo {
+            timestamp: 600,
+            protocol_version: 22,
+            sequence_number: 1234,
+            network_id: Default::default(),
+            base_reserve: 10,
+            min_temp_entry_ttl: 10,
+            min_persistent_entry_ttl: 10,
+            max_entry_ttl: 3110400,
+        });
+
+        let (pool, underlying_0, _) = setup_pool(&e, 1);
+        let samwise = Address::generate(&e);
+        let frodo = Address::generate(&e);
+
+        e.as_contract(&pool, || {
+            storage::set_user_positions(
+                &e,
+                &samwise,
+                &Positions {
+                    liabilities: map![&e],
+                    collateral: map![&e, (0, 10_0000000)],
+                    supply: map![&e],
+                },
+            );
+            storage::set_user_positions(
+                &e,
+                &frodo,
+                &Positions {
+                    liabilities: map![&e],
+                    collateral: map![&e, (1, 10_0000000)],
+                    supply: map![&e],
+                },
+            );
+
+            execute_transfer_positions(&e, &samwise, &frodo, vec![&e, underlying_0.clone()]);
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "Error(Contract, #1212)")]
+    fn test_execute_transfer_positions_auction_in_progress() {
+        let e = Env::default();
+        e.mock_all_auths();
+        e.ledger().set(LedgerInfo {
+            timestamp: 600,
+            protocol_version: 22,
+            sequence_number: 1234,
+            network_id: Default::default(),
+            base_reserve: 10,
+            min_temp_entry_ttl: 10,
+            min_persistent_entry_ttl: 10,
+            max_entry_ttl: 3110400,
+        });
+
+        let (pool, underlying_0, _) = setup_pool(&e, 4);
+        let samwise = Address::generate(&e);
+        let frodo = Address::generate(&e);
+
+        e.as_contract(&pool, || {
+            storage::set_user_positions(
+                &e,
+                &samwise,
+                &Positions {
+                    liabilities: map![&e],
+                    collateral: map![&e, (0, 10_0000000)],
+                    supply: map![&e],
+                },
+            );
+            storage::set_auction(
+                &e,
+                &(AuctionType::UserLiquidation as u32),
+                &frodo,
+                &AuctionData {
+                    bid: map![&e],
+                    lot: map![&e],
+                    block: 100,
+                },
+            );
+
+            execute_transfer_positions(&e, &samwise, &frodo, vec![&e, underlying_0.clone()]);
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "Error(Contract, #1200)")]
+    fn test_execute_transfer_positions_nothing_moved() {
+        let e = Env::default();
+        e.mock_all_auths();
+        e.ledger().set(LedgerInfo {
+            timestamp: 600,
+            protocol_version: 22,
+            sequence_number: 1234,
+            network_id: Default::default(),
+            base_reserve: 10,
+            min_temp_entry_ttl: 10,
+            min_persistent_entry_ttl: 10,
+            max_entry_ttl: 3110400,
+        });
+
+        let (pool, underlying_0, _) = setup_pool(&e, 4);
+        let samwise = Address::generate(&e);
+        let frodo = Address::generate(&e);
+
+        e.as_contract(&pool, || {
+            execute_transfer_positions(&e, &samwise, &frodo, vec![&e, underlying_0.clone()]);
+        });
+    }
+}
@@ -1,7 +1,13 @@
 use soroban_fixed_point_math::SorobanFixedPoint;
 use soroban_sdk::{contracttype, panic_with_error, Address, Env, Map};
 
-use crate::{constants::SCALAR_12, emissions, storage, validator::require_nonnegative, PoolError};
+use crate::{
+    constants::SCALAR_12,
+    emissions,
+    storage::{self, FixedLoan},
+    validator::require_nonnegative,
+    PoolError,
+};
 
 use super::{Pool, Reserve};
 
@@ -38,6 +44,8 @@ impl Positions {
 pub struct User {
     pub address: Address,
     pub positions: Positions,
+    pub emode: u32, // the e-mode category id the user has opted into, or 0 if not in e-mode
+    pub fixed_loans: Map<u32, FixedLoan>, // Map of Reserve Index to fixed-term loan
 }
 
 impl User {
@@ -46,17 +54,31 @@ impl User {
         User {
             address: address.clone(),
             positions: storage::get_user_positions(e, address),
+            emode: storage::get_user_emode(e, address),
+            fixed_loans: storage::get_user_fixed_loans(e, address),
         }
     }
 
-    /// Store the user's positions to the ledger
+    /// Store the user's positions, e-mode category, and fixed-term loans to the ledger
     pub fn store(&self, e: &Env) {
         storage::set_user_positions(e, &self.address, &self.positions);
+        if self.emode != storage::get_user_emode(e, &self.address) {
+            storage::set_user_emode(e, &self.address, self.emode);
+        }
+        if self.fixed_loans != storage::get_user_fixed_loans(e, &self.address) {
+            storage::set_user_fixed_loans(e, &self.address, &self.fixed_loans);
+        }
     }
 
-    /// Check if the user has liabilities
+    /// Get the number of effective positions the user holds, counting each fixed-term loan as a
+    /// position. This is the count limited by the max number of allowed positions by the pool.
+    pub fn effective_count(&self) -> u32 {
+        self.positions.effective_count() + self.fixed_loans.len()
+    }
+
+    /// Check if the user has liabilities, including fixed-term loans
     pub fn has_liabilities(&self) -> bool {
-        !self.positions.liabilities.is_empty()
+        !self.positions.liabilities.is_empty() || !self.fixed_loans.is_empty()
     }
 
     /// Get the debtToken position for the reserve at the given index
@@ -291,6 +313,8 @@ mod tests {
                 liabilities: map![&e],
                 supply: map![&e],
             },
+            emode: 0,
+            fixed_loans: map![&e],
         };
         e.as_contract(&pool, || {
             user.store(&e);
@@ -303,6 +327,49 @@ mod tests {
         });
     }
 
+    #[test]
+    fn test_load_and_store_fixed_loans() {
+        let e = Env::default();
+        e.mock_all_auths();
+        let samwise = Address::generate(&e);
+        let pool = testutils::create_pool(&e);
+
+        let mut user = User {
+            address: samwise.clone(),
+            positions: Positions::env_default(&e),
+            emode: 0,
+            fixed_loans: map![
+                &e,
+                (
+                    1,
+                    FixedLoan {
+                        principal: 10_0000000,
+                        rate: 0_0700000,
+                        last_time: 100,
+                        maturity: 200,
+                    }
+                )
+            ],
+        };
+        e.as_contract(&pool, || {
+            assert_eq!(user.has_liabilities(), true);
+            user.store(&e);
+            let loaded_user = User::load(&e, &samwise);
+            assert_eq!(loaded_user.fixed_loans.len(), 1);
+            let loan = loaded_user.fixed_loans.get_unchecked(1);
+            assert_eq!(loan.principal, 10_0000000);
+            assert_eq!(loan.rate, 0_0700000);
+            assert_eq!(loan.last_time, 100);
+            assert_eq!(loan.maturity, 200);
+
+            user.fixed_loans.remove(1);
+            assert_eq!(user.has_liabilities(), false);
+            user.store(&e);
+            let loaded_user = User::load(&e, &samwise);
+            assert_eq!(loaded_user.fixed_loans.len(), 0);
+        });
+    }
+
     #[test]
     fn test_liabilities() {
         let e = Env::default();
@@ -320,6 +387,8 @@ mod tests {
         let mut user = User {
             address: samwise.clone(),
             positions: Positions::env_default(&e),
+            emode: 0,
+            fixed_loans: map![&e],
         };
         e.as_contract(&pool, || {
             assert_eq!(user.get_liabilities(0), 0);
@@ -365,6 +434,8 @@ mod tests {
         let mut user = User {
             address: samwise.clone(),
             positions: Positions::env_default(&e),
+            emode: 0,
+            fixed_loans: map![&e],
         };
         e.as_contract(&pool, || {
             assert_eq!(user.get_liabilities(0), 0);
@@ -412,6 +483,8 @@ mod tests {
                 collateral: map![&e],
                 supply: map![&e],
             },
+            emode: 0,
+            fixed_loans: map![&e],
         };
 
         e.as_contract(&pool, || {
@@ -457,6 +530,8 @@ mod tests {
         let mut user = User {
             address: samwise.clone(),
             positions: Positions::env_default(&e),
+            emode: 0,
+            fixed_loans: map![&e],
         };
         e.as_contract(&pool, || {
             assert_eq!(user.get_liabilities(0), 0);
@@ -506,6 +581,8 @@ mod tests {
                 collateral: map![&e],
                 supply: map![&e],
             },
+            emode: 0,
+            fixed_loans: map![&e],
         };
         e.as_contract(&pool, || {
             let res_0_d_token_index = reserve_0.config.index * 2 + 0;
@@ -549,6 +626,8 @@ mod tests {
         let mut user = User {
             address: samwise.clone(),
             positions: Positions::env_default(&e),
+            emode: 0,
+            fixed_loans: map![&e],
         };
         e.as_contract(&pool, || {
             user.add_liabilities(&e, &mut reserve_0, 123);
@@ -574,6 +653,8 @@ mod tests {
         let mut user = User {
             address: samwise.clone(),
             positions: Positions::env_default(&e),
+            emode: 0,
+            fixed_loans: map![&e],
         };
         e.as_contract(&pool, || {
             assert_eq!(user.get_liabilities(0), 0);
@@ -613,6 +694,8 @@ mod tests {
         let mut user = User {
             address: samwise.clone(),
             positions: Positions::env_default(&e),
+            emode: 0,
+            fixed_loans: map![&e],
         };
         e.as_contract(&pool, || {
             assert_eq!(user.get_liabilities(0), 0);
@@ -647,6 +730,8 @@ mod tests {
         let mut user = User {
             address: samwise.clone(),
             positions: Positions::env_default(&e),
+            emode: 0,
+            fixed_loans: map![&e],
         };
         e.as_contract(&pool, || {
             assert_eq!(user.get_liabilities(0), 0);
@@ -688,6 +773,8 @@ mod tests {
         let mut user = User {
             address: samwise.clone(),
             positions: Positions::env_default(&e),
+            emode: 0,
+            fixed_loans: map![&e],
         };
         e.as_contract(&pool, || {
             assert_eq!(user.get_collateral(0), 0);
@@ -725,6 +812,8 @@ mod tests {
         let mut user = User {
             address: samwise.clone(),
             positions: Positions::env_default(&e),
+            emode: 0,
+            fixed_loans: map![&e],
         };
         e.as_contract(&pool, || {
             assert_eq!(user.get_collateral(0), 0);
@@ -772,6 +861,8 @@ mod tests {
                 collateral: map![&e, (reserve_0.config.index, 700)],
                 supply: map![&e, (reserve_0.config.index, 300)],
             },
+            emode: 0,
+            fixed_loans: map![&e],
         };
         e.as_contract(&pool, || {
             let res_0_d_token_index = reserve_0.config.index * 2 + 1;
@@ -816,6 +907,8 @@ mod tests {
         let mut user = User {
             address: samwise.clone(),
             positions: Positions::env_default(&e),
+            emode: 0,
+            fixed_loans: map![&e],
         };
         e.as_contract(&pool, || {
             assert_eq!(user.get_collateral(0), 0);
@@ -866,6 +959,8 @@ mod tests {
                 collateral: map![&e, (reserve_0.config.index, 700)],
                 supply: map![&e, (reserve_0.config.index, 300)],
             },
+            emode: 0,
+            fixed_loans: map![&e],
         };
         e.as_contract(&pool, || {
             let res_0_d_token_index = reserve_0.config.index * 2 + 1;
@@ -910,6 +1005,8 @@ mod tests {
         let mut user = User {
             address: samwise.clone(),
             positions: Positions::env_default(&e),
+            emode: 0,
+            fixed_loans: map![&e],
         };
         e.as_contract(&pool, || {
             user.add_collateral(&e, &mut reserve_0, 123);
@@ -936,6 +1033,8 @@ mod tests {
         let mut user = User {
             address: samwise.clone(),
             positions: Positions::env_default(&e),
+            emode: 0,
+            fixed_loans: map![&e],
         };
         e.as_contract(&pool, || {
             assert_eq!(user.get_supply(0), 0);
@@ -973,6 +1072,8 @@ mod tests {
         let mut user = User {
             address: samwise.clone(),
             positions: Positions::env_default(&e),
+            emode: 0,
+            fixed_loans: map![&e],
         };
         e.as_contract(&pool, || {
             assert_eq!(user.get_supply(0), 0);
@@ -1020,6 +1121,8 @@ mod tests {
                 collateral: map![&e, (reserve_0.config.index, 700)],
                 supply: map![&e, (reserve_0.config.index, 300)],
             },
+            emode: 0,
+            fixed_loans: map![&e],
         };
         e.as_contract(&pool, || {
             let res_0_d_token_index = reserve_0.config.index * 2 + 1;
@@ -1064,6 +1167,8 @@ mod tests {
         let mut user = User {
             address: samwise.clone(),
             positions: Positions::env_default(&e),
+            emode: 0,
+            fixed_loans: map![&e],
         };
         e.as_contract(&pool, || {
             assert_eq!(user.get_supply(0), 0);
@@ -1114,6 +1219,8 @@ mod tests {
                 collateral: map![&e, (reserve_0.config.index, 700)],
                 supply: map![&e, (reserve_0.config.index, 300)],
             },
+            emode: 0,
+            fixed_loans: map![&e],
         };
         e.as_contract(&pool, || {
             let res_0_d_token_index = reserve_0.config.index * 2 + 1;
@@ -1158,6 +1265,8 @@ mod tests {
         let mut user = User {
             address: samwise.clone(),
             positions: Positions::env_default(&e),
+            emode: 0,
+            fixed_loans: map![&e],
         };
         e.as_contract(&pool, || {
             user.add_supply(&e, &mut reserve_0, 123);
@@ -1182,6 +1291,8 @@ mod tests {
         let mut user = User {
             address: samwise.clone(),
             positions: Positions::env_default(&e),
+            emode: 0,
+            fixed_loans: map![&e],
         };
         e.as_contract(&pool, || {
             user.add_supply(&e, &mut reserve_0, 123);
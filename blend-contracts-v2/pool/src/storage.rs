@@ -1,9 +1,14 @@
 use soroban_sdk::{
-    contracttype, map, panic_with_error, unwrap::UnwrapOptimized, vec, Address, Env, IntoVal, Map,
-    String, Symbol, TryFromVal, Val, Vec,
+    contracttype, map, panic_with_error, unwrap::UnwrapOptimized, vec, Address, BytesN, Env,
+    IntoVal, Map, String, Symbol, TryFromVal, Val, Vec,
 };
 
-use crate::{auctions::AuctionData, constants::MAX_RESERVES, pool::Positions, PoolError};
+use crate::{
+    auctions::AuctionData,
+    constants::{DEFAULT_AUCTION_BID_DECAY, DEFAULT_AUCTION_LOT_RAMP, MAX_RESERVES},
+    pool::{Positions, Request},
+    PoolError,
+};
 
 /********** Ledger Thresholds **********/
 
@@ -31,6 +36,126 @@ pub struct PoolConfig {
     pub max_positions: u32, // the maximum number of effective positions (collateral + liabilities) a single user can hold
 }
 
+/// The pool's backup oracle configuration
+#[derive(Clone)]
+#[contracttype]
+pub struct OracleConfig {
+    pub oracles: Vec<Address>, // the contract addresses of the backup oracles, in order of priority
+    pub quorum: u32, // the number of valid prices required to use the median price, or 0 to only use backups when the primary oracle fails
+}
+
+#[derive(Clone)]
+#[contracttype]
+pub struct QueuedOracleInit {
+    pub new_config: OracleConfig,
+    pub unlock_time: u64,
+}
+
+/// The Dutch auction curve of an auction type. The lot scales from `start_premium` to 100% over
+/// `lot_ramp` blocks, then the bid scales from 100% to 0% over `bid_decay` blocks.
+#[derive(Clone)]
+#[contracttype]
+pub struct AuctionCurve {
+    pub lot_ramp: u32,      // the number of blocks the lot scales up to 100% over
+    pub bid_decay: u32, // the number of blocks the bid scales down to 0% over, once the lot reaches 100%
+    pub start_premium: u32, // the percentage of the lot offered when the auction starts, expressed in 7 decimals
+}
+
+impl Default for AuctionCurve {
+    fn default() -> Self {
+        AuctionCurve {
+            lot_ramp: DEFAULT_AUCTION_LOT_RAMP,
+            bid_decay: DEFAULT_AUCTION_BID_DECAY,
+            start_premium: 0,
+        }
+    }
+}
+
+/// The rewards paid to keepers for creating auctions and transferring bad debt, expressed in 7 decimals
+#[derive(Clone, Default)]
+#[contracttype]
+pub struct KeeperConfig {
+    pub lot_share: u32, // the share of each filled user liquidation or interest auction lot paid to the keeper that created it
+    pub bad_debt_share: u32, // the share of the bad debt transferred to the backstop paid to the keeper from the backstop credit
+}
+
+#[derive(Clone)]
+#[contracttype]
+pub struct QueuedAuctionInit {
+    pub new_config: Map<u32, AuctionCurve>,
+    pub unlock_time: u64,
+}
+
+/// A fixed-rate, fixed-term loan a user holds against a reserve
+#[derive(Clone)]
+#[contracttype]
+pub struct FixedLoan {
+    pub principal: i128, // the amount owed, including interest accrued up to last_time, in the underlying token's decimals
+    pub rate: u32,       // the locked annual interest rate expressed in 7 decimals
+    pub last_time: u64,  // the last time interest was accrued to the principal
+    pub maturity: u64,   // the time the locked rate expires
+}
+
+/// An allowance a delegator has granted a delegate to borrow a reserve against the delegator's
+/// positions
+#[derive(Clone)]
+#[contracttype]
+pub struct CreditAllowance {
+    pub amount: i128,    // the amount of underlying tokens the delegate can still borrow
+    pub expiration: u64, // the time the allowance expires
+}
+
+/// An order a user has registered to be executed by a keeper once its condition holds
+#[derive(Clone)]
+#[contracttype]
+pub struct ConditionalOrder {
+    pub condition: u32,         // the type of condition that triggers the order
+    pub reserve: Address,       // the reserve a utilization condition is checked against
+    pub threshold: i128, // the health factor or utilization the condition is checked against (7 decimals)
+    pub requests: Vec<Request>, // the requests submitted when the order is executed
+    pub fee_asset: Address, // the token the keeper fee is paid in
+    pub fee: i128,       // the fee paid to the keeper, in the fee asset's decimals
+    pub expiration: u64, // the time the order expires
+}
+
+/// The delisting schedule of a reserve. The reserve's collateral factor ramps linearly from
+/// `c_factor` to zero between `start_time` and `end_time`.
+#[derive(Clone)]
+#[contracttype]
+pub struct ReserveDelisting {
+    pub c_factor: u32, // the collateral factor of the reserve when the delisting started expressed in 7 decimals
+    pub start_time: u64, // the time the delisting started
+    pub end_time: u64, // the time the collateral factor reaches zero
+}
+
+/// A scheduled change of a reserve's collateral and liability factors. The factors ramp linearly
+/// from `c_factor` and `l_factor` to the reserve's configured factors between `start_time` and
+/// `end_time`.
+#[derive(Clone)]
+#[contracttype]
+pub struct ReserveFactorRamp {
+    pub c_factor: u32, // the collateral factor when the ramp started expressed in 7 decimals
+    pub l_factor: u32, // the liability factor when the ramp started expressed in 7 decimals
+    pub start_time: u64, // the time the ramp started
+    pub end_time: u64, // the time the factors reach the reserve's configured factors
+}
+
+/// An efficiency mode category of correlated reserves with elevated collateral and liability factors
+#[derive(Clone)]
+#[contracttype]
+pub struct EModeCategory {
+    pub c_factor: u32, // the collateral factor for reserves in the category expressed in 7 decimals
+    pub l_factor: u32, // the liability factor for reserves in the category expressed in 7 decimals
+    pub assets: Vec<Address>, // the underlying assets of the reserves in the category
+}
+
+#[derive(Clone)]
+#[contracttype]
+pub struct QueuedEModeInit {
+    pub new_category: EModeCategory,
+    pub unlock_time: u64,
+}
+
 /// The pool's emission config
 #[derive(Clone)]
 #[contracttype]
@@ -55,7 +180,15 @@ pub struct ReserveConfig {
     pub r_three: u32, // the R3 value in the interest rate formula scaled expressed in 7 decimals
     pub reactivity: u32, // the reactivity constant for the reserve scaled expressed in 7 decimals
     pub collateral_cap: i128, // the total amount of underlying tokens that can be used as collateral
-    pub enabled: bool,        // the enabled flag of the reserve
+    pub twap_records: u32, // the number of oracle records averaged for conservative TWAP pricing, or 0 to use spot prices only
+    pub borrow_cap: i128,  // the total amount of underlying tokens that can be borrowed
+    pub supply_cap: i128, // the total amount of underlying tokens that can be supplied, including collateral
+    pub isolated: bool, // the isolation flag, restricting users with the reserve as collateral to borrowing only isolation borrowable reserves
+    pub debt_ceiling: i128, // the maximum value of the debt that can be backed by the reserve when isolated, in the oracle's base asset decimals
+    pub ir_model: u32, // the interest rate model of the reserve (0 = reactive, 1 = kinked, 2 = fixed)
+    pub util_two: u32, // the second utilization kink for the kinked interest rate model scaled expressed in 7 decimals
+    pub flash_fee: u32, // the fee charged on the amount of simple flash loans expressed in 7 decimals
+    pub enabled: bool,  // the enabled flag of the reserve
 }
 
 #[derive(Clone)]
@@ -63,6 +196,7 @@ pub struct ReserveConfig {
 pub struct QueuedReserveInit {
     pub new_config: ReserveConfig,
     pub unlock_time: u64,
+    pub ramp_period: u64, // the number of seconds the collateral and liability factors ramp to their new values over
 }
 
 /// The data for a reserve asset
@@ -74,8 +208,9 @@ pub struct ReserveData {
     pub ir_mod: i128,   // the interest rate curve modifier with 7 decimals
     pub b_supply: i128, // the total supply of b tokens, in the underlying token's decimals
     pub d_supply: i128, // the total supply of d tokens, in the underlying token's decimals
+    pub fixed_debt: i128, // the total fixed-term debt owed to the reserve, in the underlying token's decimals
     pub backstop_credit: i128, // the amount of underlying tokens currently owed to the backstop
-    pub last_time: u64, // the last block the data was updated
+    pub last_time: u64,   // the last block the data was updated
 }
 
 /// The emission data for the reserve b or d token
@@ -103,8 +238,15 @@ const NAME_KEY: &str = "Name";
 const BACKSTOP_KEY: &str = "Backstop";
 const BLND_TOKEN_KEY: &str = "BLNDTkn";
 const POOL_CONFIG_KEY: &str = "Config";
+const ORACLE_CONFIG_KEY: &str = "OracleCfg";
+const ORACLE_INIT_KEY: &str = "OracleInit";
+const AUCTION_CONFIG_KEY: &str = "AuctionCfg";
+const AUCTION_INIT_KEY: &str = "AuctionInit";
 const RES_LIST_KEY: &str = "ResList";
 const POOL_EMIS_KEY: &str = "PoolEmis";
+const ISO_BORROW_KEY: &str = "IsoBorrow";
+const ROUTER_KEY: &str = "Router";
+const KEEPER_CONFIG_KEY: &str = "KeeperCfg";
 
 #[derive(Clone)]
 #[contracttype]
@@ -113,6 +255,20 @@ pub struct UserReserveKey {
     reserve_id: u32,
 }
 
+#[derive(Clone)]
+#[contracttype]
+pub struct DelegationKey {
+    delegator: Address, // the Address whose positions are borrowed against
+    delegate: Address,  // the Address allowed to borrow
+}
+
+#[derive(Clone)]
+#[contracttype]
+pub struct NonceKey {
+    user: Address, // the Address that signed the request
+    nonce: u64,    // the nonce of the signed request
+}
+
 #[derive(Clone)]
 #[contracttype]
 pub struct AuctionKey {
@@ -120,6 +276,13 @@ pub struct AuctionKey {
     auct_type: u32, // the type of auction taking place
 }
 
+#[derive(Clone)]
+#[contracttype]
+pub struct AuctionIndexKey {
+    auct_type: u32, // the type of auction indexed
+    slot: u32,      // the position in the auction index
+}
+
 #[derive(Clone)]
 #[contracttype]
 pub enum PoolDataKey {
@@ -137,6 +300,36 @@ pub enum PoolDataKey {
     UserEmis(UserReserveKey),
     // The auction's data
     Auction(AuctionKey),
+    // The debt backed by an isolated reserve's collateral
+    IsoDebt(Address),
+    // A map of e-mode category id to e-mode category
+    EMode(u32),
+    // A map of e-mode category id to queued e-mode category init
+    EModeInit(u32),
+    // The e-mode category id a user has opted into
+    UserEMode(Address),
+    // Map of reserve index to the fixed-term loans a user holds
+    FixedLoans(Address),
+    // The delisting schedule of a reserve
+    Delisting(Address),
+    // The scheduled collateral and liability factor ramp of a reserve
+    FactorRamp(Address),
+    // Map of underlying asset to the credit allowance a delegator has granted a delegate
+    Delegation(DelegationKey),
+    // The ed25519 public key a user signs off-chain requests with
+    SigningKey(Address),
+    // Whether a user's signed request nonce has been used
+    UsedNonce(NonceKey),
+    // Map of order id to the conditional orders a user has registered
+    Orders(Address),
+    // The number of users in the auction index of an auction type
+    AuctionCount(u32),
+    // The user at a position in the auction index of an auction type
+    AuctionIndex(AuctionIndexKey),
+    // The position of a user in the auction index of an auction type
+    AuctionSlot(AuctionKey),
+    // The keeper that created an auction
+    AuctionKeeper(AuctionKey),
 }
 
 /********** Storage **********/
@@ -198,6 +391,217 @@ pub fn set_user_positions(e: &Env, user: &Address, positions: &Positions) {
         .extend_ttl(&key, LEDGER_THRESHOLD_USER, LEDGER_BUMP_USER);
 }
 
+/// Fetch the e-mode category id the user has opted into, or 0 if the user is not in e-mode
+///
+/// ### Arguments
+/// * `user` - The address of the user
+pub fn get_user_emode(e: &Env, user: &Address) -> u32 {
+    let key = PoolDataKey::UserEMode(user.clone());
+    get_persistent_default(e, &key, || 0u32, LEDGER_THRESHOLD_USER, LEDGER_BUMP_USER)
+}
+
+/// Set the e-mode category id the user has opted into
+///
+/// ### Arguments
+/// * `user` - The address of the user
+/// * `category_id` - The e-mode category id, or 0 to leave e-mode
+pub fn set_user_emode(e: &Env, user: &Address, category_id: u32) {
+    let key = PoolDataKey::UserEMode(user.clone());
+    if category_id == 0 {
+        e.storage().persistent().remove(&key);
+        return;
+    }
+    e.storage()
+        .persistent()
+        .set::<PoolDataKey, u32>(&key, &category_id);
+    e.storage()
+        .persistent()
+        .extend_ttl(&key, LEDGER_THRESHOLD_USER, LEDGER_BUMP_USER);
+}
+
+/// Fetch the fixed-term loans a user holds, mapped by reserve index
+///
+/// ### Arguments
+/// * `user` - The address of the user
+pub fn get_user_fixed_loans(e: &Env, user: &Address) -> Map<u32, FixedLoan> {
+    let key = PoolDataKey::FixedLoans(user.clone());
+    get_persistent_default(
+        e,
+        &key,
+        || Map::new(e),
+        LEDGER_THRESHOLD_USER,
+        LEDGER_BUMP_USER,
+    )
+}
+
+/// Set the fixed-term loans a user holds, mapped by reserve index
+///
+/// ### Arguments
+/// * `user` - The address of the user
+/// * `loans` - The fixed-term loans
+pub fn set_user_fixed_loans(e: &Env, user: &Address, loans: &Map<u32, FixedLoan>) {
+    let key = PoolDataKey::FixedLoans(user.clone());
+    if loans.is_empty() {
+        e.storage().persistent().remove(&key);
+        return;
+    }
+    e.storage()
+        .persistent()
+        .set::<PoolDataKey, Map<u32, FixedLoan>>(&key, loans);
+    e.storage()
+        .persistent()
+        .extend_ttl(&key, LEDGER_THRESHOLD_USER, LEDGER_BUMP_USER);
+}
+
+/// Fetch the credit allowances a delegator has granted a delegate, mapped by underlying asset
+///
+/// ### Arguments
+/// * `delegator` - The address of the user whose positions are borrowed against
+/// * `delegate` - The address of the user allowed to borrow
+pub fn get_credit_delegation(
+    e: &Env,
+    delegator: &Address,
+    delegate: &Address,
+) -> Map<Address, CreditAllowance> {
+    let key = PoolDataKey::Delegation(DelegationKey {
+        delegator: delegator.clone(),
+        delegate: delegate.clone(),
+    });
+    get_persistent_default(
+        e,
+        &key,
+        || Map::new(e),
+        LEDGER_THRESHOLD_USER,
+        LEDGER_BUMP_USER,
+    )
+}
+
+/// Set the credit allowances a delegator has granted a delegate, mapped by underlying asset
+///
+/// ### Arguments
+/// * `delegator` - The address of the user whose positions are borrowed against
+/// * `delegate` - The address of the user allowed to borrow
+/// * `allowances` - The credit allowances
+pub fn set_credit_delegation(
+    e: &Env,
+    delegator: &Address,
+    delegate: &Address,
+    allowances: &Map<Address, CreditAllowance>,
+) {
+    let key = PoolDataKey::Delegation(DelegationKey {
+        delegator: delegator.clone(),
+        delegate: delegate.clone(),
+    });
+    if allowances.is_empty() {
+        e.storage().persistent().remove(&key);
+        return;
+    }
+    e.storage()
+        .persistent()
+        .set::<PoolDataKey, Map<Address, CreditAllowance>>(&key, allowances);
+    e.storage()
+        .persistent()
+        .extend_ttl(&key, LEDGER_THRESHOLD_USER, LEDGER_BUMP_USER);
+}
+
+/// Fetch the ed25519 public key a user signs off-chain requests with, if one is registered
+///
+/// ### Arguments
+/// * `user` - The address of the user
+pub fn get_signing_key(e: &Env, user: &Address) -> Option<BytesN<32>> {
+    let key = PoolDataKey::SigningKey(user.clone());
+    let signing_key = e
+        .storage()
+        .persistent()
+        .get::<PoolDataKey, BytesN<32>>(&key);
+    if signing_key.is_some() {
+        e.storage()
+            .persistent()
+            .extend_ttl(&key, LEDGER_THRESHOLD_USER, LEDGER_BUMP_USER);
+    }
+    signing_key
+}
+
+/// Set the ed25519 public key a user signs off-chain requests with
+///
+/// ### Arguments
+/// * `user` - The address of the user
+/// * `signing_key` - The ed25519 public key
+pub fn set_signing_key(e: &Env, user: &Address, signing_key: &BytesN<32>) {
+    let key = PoolDataKey::SigningKey(user.clone());
+    e.storage()
+        .persistent()
+        .set::<PoolDataKey, BytesN<32>>(&key, signing_key);
+    e.storage()
+        .persistent()
+        .extend_ttl(&key, LEDGER_THRESHOLD_USER, LEDGER_BUMP_USER);
+}
+
+/// Check if a user's signed request nonce has been used
+///
+/// ### Arguments
+/// * `user` - The address of the user
+/// * `nonce` - The nonce of the signed request
+pub fn is_nonce_used(e: &Env, user: &Address, nonce: u64) -> bool {
+    let key = PoolDataKey::UsedNonce(NonceKey {
+        user: user.clone(),
+        nonce,
+    });
+    e.storage().persistent().has(&key)
+}
+
+/// Mark a user's signed request nonce as used
+///
+/// ### Arguments
+/// * `user` - The address of the user
+/// * `nonce` - The nonce of the signed request
+pub fn set_nonce_used(e: &Env, user: &Address, nonce: u64) {
+    let key = PoolDataKey::UsedNonce(NonceKey {
+        user: user.clone(),
+        nonce,
+    });
+    e.storage()
+        .persistent()
+        .set::<PoolDataKey, bool>(&key, &true);
+    e.storage()
+        .persistent()
+        .extend_ttl(&key, LEDGER_THRESHOLD_USER, LEDGER_BUMP_USER);
+}
+
+/// Fetch the conditional orders a user has registered, mapped by order id
+///
+/// ### Arguments
+/// * `user` - The address of the user
+pub fn get_user_orders(e: &Env, user: &Address) -> Map<u32, ConditionalOrder> {
+    let key = PoolDataKey::Orders(user.clone());
+    get_persistent_default(
+        e,
+        &key,
+        || Map::new(e),
+        LEDGER_THRESHOLD_USER,
+        LEDGER_BUMP_USER,
+    )
+}
+
+/// Set the conditional orders a user has registered, mapped by order id
+///
+/// ### Arguments
+/// * `user` - The address of the user
+/// * `orders` - The conditional orders
+pub fn set_user_orders(e: &Env, user: &Address, orders: &Map<u32, ConditionalOrder>) {
+    let key = PoolDataKey::Orders(user.clone());
+    if orders.is_empty() {
+        e.storage().persistent().remove(&key);
+        return;
+    }
+    e.storage()
+        .persistent()
+        .set::<PoolDataKey, Map<u32, ConditionalOrder>>(&key, orders);
+    e.storage()
+        .persistent()
+        .extend_ttl(&key, LEDGER_THRESHOLD_USER, LEDGER_BUMP_USER);
+}
+
 /********** Admin **********/
 
 // Fetch the current admin Address
@@ -252,51 +656,220 @@ pub fn get_backstop(e: &Env) -> Address {
 /// * `backstop` - The address of the backstop
 pub fn set_backstop(e: &Env, backstop: &Address) {
     e.storage()
-        .instance()
-        .set::<Symbol, Address>(&Symbol::new(e, BACKSTOP_KEY), backstop);
+        .instance()
+        .set::<Symbol, Address>(&Symbol::new(e, BACKSTOP_KEY), backstop);
+}
+
+/********** External Token Contracts **********/
+
+/// Fetch the BLND token ID
+pub fn get_blnd_token(e: &Env) -> Address {
+    e.storage()
+        .instance()
+        .get(&Symbol::new(e, BLND_TOKEN_KEY))
+        .unwrap_optimized()
+}
+
+/// Set a new BLND token ID
+///
+/// ### Arguments
+/// * `blnd_token_id` - The ID of the BLND token
+pub fn set_blnd_token(e: &Env, blnd_token_id: &Address) {
+    e.storage()
+        .instance()
+        .set::<Symbol, Address>(&Symbol::new(e, BLND_TOKEN_KEY), blnd_token_id);
+}
+
+/********** Pool Config **********/
+
+/// Fetch the pool configuration
+///
+/// ### Panics
+/// If the pool's config is not set
+pub fn get_pool_config(e: &Env) -> PoolConfig {
+    e.storage()
+        .instance()
+        .get(&Symbol::new(e, POOL_CONFIG_KEY))
+        .unwrap_optimized()
+}
+
+/// Set the pool configuration
+///
+/// ### Arguments
+/// * `config` - The contract address of the oracle
+pub fn set_pool_config(e: &Env, config: &PoolConfig) {
+    e.storage()
+        .instance()
+        .set::<Symbol, PoolConfig>(&Symbol::new(e, POOL_CONFIG_KEY), config);
+}
+
+/********** Oracle Config **********/
+
+/// Fetch the backup oracle configuration, or an empty configuration if none is set
+pub fn get_oracle_config(e: &Env) -> OracleConfig {
+    e.storage()
+        .instance()
+        .get(&Symbol::new(e, ORACLE_CONFIG_KEY))
+        .unwrap_or(OracleConfig {
+            oracles: vec![e],
+            quorum: 0,
+        })
+}
+
+/// Set the backup oracle configuration
+///
+/// ### Arguments
+/// * `config` - The backup oracle configuration
+pub fn set_oracle_config(e: &Env, config: &OracleConfig) {
+    e.storage()
+        .instance()
+        .set::<Symbol, OracleConfig>(&Symbol::new(e, ORACLE_CONFIG_KEY), config);
+}
+
+/// Fetch a queued oracle configuration
+///
+/// ### Panics
+/// If the oracle configuration has not been queued
+pub fn get_queued_oracle_config(e: &Env) -> QueuedOracleInit {
+    e.storage()
+        .temporary()
+        .get::<Symbol, QueuedOracleInit>(&Symbol::new(e, ORACLE_INIT_KEY))
+        .unwrap_optimized()
+}
+
+/// Check if an oracle configuration is actively queued
+pub fn has_queued_oracle_config(e: &Env) -> bool {
+    e.storage()
+        .temporary()
+        .has(&Symbol::new(e, ORACLE_INIT_KEY))
+}
+
+/// Set a new queued oracle configuration
+///
+/// ### Arguments
+/// * `oracle_init` - The queued oracle configuration
+pub fn set_queued_oracle_config(e: &Env, oracle_init: &QueuedOracleInit) {
+    let key = Symbol::new(e, ORACLE_INIT_KEY);
+    e.storage()
+        .temporary()
+        .set::<Symbol, QueuedOracleInit>(&key, oracle_init);
+    e.storage()
+        .temporary()
+        .extend_ttl(&key, LEDGER_THRESHOLD_USER, LEDGER_BUMP_USER);
+}
+
+/// Delete a queued oracle configuration
+pub fn del_queued_oracle_config(e: &Env) {
+    e.storage()
+        .temporary()
+        .remove(&Symbol::new(e, ORACLE_INIT_KEY));
+}
+
+/********** Auction Config **********/
+
+/// Fetch the Dutch auction curves of the pool, mapped by auction type. Auction types without a
+/// curve use the default curve.
+pub fn get_auction_config(e: &Env) -> Map<u32, AuctionCurve> {
+    e.storage()
+        .instance()
+        .get(&Symbol::new(e, AUCTION_CONFIG_KEY))
+        .unwrap_or(Map::new(e))
+}
+
+/// Fetch the Dutch auction curve of an auction type
+///
+/// ### Arguments
+/// * `auction_type` - The type of auction
+pub fn get_auction_curve(e: &Env, auction_type: u32) -> AuctionCurve {
+    get_auction_config(e).get(auction_type).unwrap_or_default()
+}
+
+/// Set the Dutch auction curves of the pool
+///
+/// ### Arguments
+/// * `config` - The Dutch auction curves, mapped by auction type
+pub fn set_auction_config(e: &Env, config: &Map<u32, AuctionCurve>) {
+    e.storage()
+        .instance()
+        .set::<Symbol, Map<u32, AuctionCurve>>(&Symbol::new(e, AUCTION_CONFIG_KEY), config);
+}
+
+/// Fetch a queued auction configuration
+///
+/// ### Panics
+/// If the auction configuration has not been queued
+pub fn get_queued_auction_config(e: &Env) -> QueuedAuctionInit {
+    e.storage()
+        .temporary()
+        .get::<Symbol, QueuedAuctionInit>(&Symbol::new(e, AUCTION_INIT_KEY))
+        .unwrap_optimized()
+}
+
+/// Check if an auction configuration is actively queued
+pub fn has_queued_auction_config(e: &Env) -> bool {
+    e.storage()
+        .temporary()
+        .has(&Symbol::new(e, AUCTION_INIT_KEY))
+}
+
+/// Set a new queued auction configuration
+///
+/// ### Arguments
+/// * `auction_init` - The queued auction configuration
+pub fn set_queued_auction_config(e: &Env, auction_init: &QueuedAuctionInit) {
+    let key = Symbol::new(e, AUCTION_INIT_KEY);
+    e.storage()
+        .temporary()
+        .set::<Symbol, QueuedAuctionInit>(&key, auction_init);
+    e.storage()
+        .temporary()
+        .extend_ttl(&key, LEDGER_THRESHOLD_USER, LEDGER_BUMP_USER);
+}
+
+/// Delete a queued auction configuration
+pub fn del_queued_auction_config(e: &Env) {
+    e.storage()
+        .temporary()
+        .remove(&Symbol::new(e, AUCTION_INIT_KEY));
 }
 
-/********** External Token Contracts **********/
+/********** Swap Router **********/
 
-/// Fetch the BLND token ID
-pub fn get_blnd_token(e: &Env) -> Address {
+/// Fetch the swap router used for deleverage requests, if one is set
+pub fn get_router(e: &Env) -> Option<Address> {
     e.storage()
         .instance()
-        .get(&Symbol::new(e, BLND_TOKEN_KEY))
-        .unwrap_optimized()
+        .get::<Symbol, Address>(&Symbol::new(e, ROUTER_KEY))
 }
 
-/// Set a new BLND token ID
+/// Set the swap router used for deleverage requests
 ///
 /// ### Arguments
-/// * `blnd_token_id` - The ID of the BLND token
-pub fn set_blnd_token(e: &Env, blnd_token_id: &Address) {
+/// * `router` - The contract address of the swap router
+pub fn set_router(e: &Env, router: &Address) {
     e.storage()
         .instance()
-        .set::<Symbol, Address>(&Symbol::new(e, BLND_TOKEN_KEY), blnd_token_id);
+        .set::<Symbol, Address>(&Symbol::new(e, ROUTER_KEY), router);
 }
 
-/********** Pool Config **********/
+/********** Keeper Config **********/
 
-/// Fetch the pool configuration
-///
-/// ### Panics
-/// If the pool's config is not set
-pub fn get_pool_config(e: &Env) -> PoolConfig {
+/// Fetch the keeper reward configuration of the pool. Defaults to no rewards.
+pub fn get_keeper_config(e: &Env) -> KeeperConfig {
     e.storage()
         .instance()
-        .get(&Symbol::new(e, POOL_CONFIG_KEY))
-        .unwrap_optimized()
+        .get::<Symbol, KeeperConfig>(&Symbol::new(e, KEEPER_CONFIG_KEY))
+        .unwrap_or_default()
 }
 
-/// Set the pool configuration
+/// Set the keeper reward configuration of the pool
 ///
 /// ### Arguments
-/// * `config` - The contract address of the oracle
-pub fn set_pool_config(e: &Env, config: &PoolConfig) {
+/// * `config` - The keeper reward configuration
+pub fn set_keeper_config(e: &Env, config: &KeeperConfig) {
     e.storage()
         .instance()
-        .set::<Symbol, PoolConfig>(&Symbol::new(e, POOL_CONFIG_KEY), config);
+        .set::<Symbol, KeeperConfig>(&Symbol::new(e, KEEPER_CONFIG_KEY), config);
 }
 
 /********** Reserve Config (ResConfig) **********/
@@ -469,6 +1042,233 @@ pub fn push_res_list(e: &Env, asset: &Address) -> u32 {
     new_index
 }
 
+/********** E-Mode **********/
+
+/// Fetch an e-mode category
+///
+/// ### Arguments
+/// * `category_id` - The id of the e-mode category
+pub fn get_emode_category(e: &Env, category_id: u32) -> Option<EModeCategory> {
+    let key = PoolDataKey::EMode(category_id);
+    let category = e
+        .storage()
+        .persistent()
+        .get::<PoolDataKey, EModeCategory>(&key);
+    if category.is_some() {
+        e.storage()
+            .persistent()
+            .extend_ttl(&key, LEDGER_THRESHOLD_SHARED, LEDGER_BUMP_SHARED);
+    }
+    category
+}
+
+/// Set an e-mode category
+///
+/// ### Arguments
+/// * `category_id` - The id of the e-mode category
+/// * `category` - The e-mode category
+pub fn set_emode_category(e: &Env, category_id: u32, category: &EModeCategory) {
+    let key = PoolDataKey::EMode(category_id);
+    e.storage()
+        .persistent()
+        .set::<PoolDataKey, EModeCategory>(&key, category);
+    e.storage()
+        .persistent()
+        .extend_ttl(&key, LEDGER_THRESHOLD_SHARED, LEDGER_BUMP_SHARED);
+}
+
+/// Fetch a queued e-mode category set
+///
+/// ### Arguments
+/// * `category_id` - The id of the e-mode category
+///
+/// ### Panics
+/// If the category is not queued
+pub fn get_queued_emode_category(e: &Env, category_id: u32) -> QueuedEModeInit {
+    let key = PoolDataKey::EModeInit(category_id);
+    e.storage()
+        .temporary()
+        .get::<PoolDataKey, QueuedEModeInit>(&key)
+        .unwrap_optimized()
+}
+
+/// Check if an e-mode category set is actively queued
+///
+/// ### Arguments
+/// * `category_id` - The id of the e-mode category
+pub fn has_queued_emode_category(e: &Env, category_id: u32) -> bool {
+    let key = PoolDataKey::EModeInit(category_id);
+    e.storage().temporary().has(&key)
+}
+
+/// Set a new queued e-mode category set
+///
+/// ### Arguments
+/// * `category_id` - The id of the e-mode category
+/// * `emode_init` - The queued e-mode category
+pub fn set_queued_emode_category(e: &Env, category_id: u32, emode_init: &QueuedEModeInit) {
+    let key = PoolDataKey::EModeInit(category_id);
+    e.storage()
+        .temporary()
+        .set::<PoolDataKey, QueuedEModeInit>(&key, emode_init);
+    e.storage()
+        .temporary()
+        .extend_ttl(&key, LEDGER_THRESHOLD_USER, LEDGER_BUMP_USER);
+}
+
+/// Delete a queued e-mode category set
+///
+/// ### Arguments
+/// * `category_id` - The id of the e-mode category
+pub fn del_queued_emode_category(e: &Env, category_id: u32) {
+    let key = PoolDataKey::EModeInit(category_id);
+    e.storage().temporary().remove(&key);
+}
+
+/********** Isolation **********/
+
+/// Fetch the list of reserves that can be borrowed by users with isolated collateral
+pub fn get_isolated_borrow_list(e: &Env) -> Vec<Address> {
+    get_persistent_default(
+        e,
+        &Symbol::new(e, ISO_BORROW_KEY),
+        || vec![e],
+        LEDGER_THRESHOLD_SHARED,
+        LEDGER_BUMP_SHARED,
+    )
+}
+
+/// Set the list of reserves that can be borrowed by users with isolated collateral
+///
+/// ### Arguments
+/// * `assets` - The underlying assets of the reserves
+pub fn set_isolated_borrow_list(e: &Env, assets: &Vec<Address>) {
+    let key = Symbol::new(e, ISO_BORROW_KEY);
+    e.storage()
+        .persistent()
+        .set::<Symbol, Vec<Address>>(&key, assets);
+    e.storage()
+        .persistent()
+        .extend_ttl(&key, LEDGER_THRESHOLD_SHARED, LEDGER_BUMP_SHARED);
+}
+
+/// Fetch the debt backed by an isolated reserve's collateral, as a map of borrowed asset to d_tokens
+///
+/// ### Arguments
+/// * `asset` - The contract address of the isolated reserve's asset
+pub fn get_isolated_debt(e: &Env, asset: &Address) -> Map<Address, i128> {
+    get_persistent_default(
+        e,
+        &PoolDataKey::IsoDebt(asset.clone()),
+        || map![e],
+        LEDGER_THRESHOLD_SHARED,
+        LEDGER_BUMP_SHARED,
+    )
+}
+
+/// Set the debt backed by an isolated reserve's collateral, as a map of borrowed asset to d_tokens
+///
+/// ### Arguments
+/// * `asset` - The contract address of the isolated reserve's asset
+/// * `debt` - The debt backed by the reserve
+pub fn set_isolated_debt(e: &Env, asset: &Address, debt: &Map<Address, i128>) {
+    let key = PoolDataKey::IsoDebt(asset.clone());
+    e.storage()
+        .persistent()
+        .set::<PoolDataKey, Map<Address, i128>>(&key, debt);
+    e.storage()
+        .persistent()
+        .extend_ttl(&key, LEDGER_THRESHOLD_SHARED, LEDGER_BUMP_SHARED);
+}
+
+/********** Factor Ramps **********/
+
+/// Fetch the factor ramp of a reserve, if one has been scheduled
+///
+/// ### Arguments
+/// * `asset` - The contract address of the asset
+pub fn get_res_factor_ramp(e: &Env, asset: &Address) -> Option<ReserveFactorRamp> {
+    let key = PoolDataKey::FactorRamp(asset.clone());
+    let ramp = e
+        .storage()
+        .persistent()
+        .get::<PoolDataKey, ReserveFactorRamp>(&key);
+    if ramp.is_some() {
+        e.storage()
+            .persistent()
+            .extend_ttl(&key, LEDGER_THRESHOLD_SHARED, LEDGER_BUMP_SHARED);
+    }
+    ramp
+}
+
+/// Set the factor ramp of a reserve
+///
+/// ### Arguments
+/// * `asset` - The contract address of the asset
+/// * `ramp` - The factor ramp
+pub fn set_res_factor_ramp(e: &Env, asset: &Address, ramp: &ReserveFactorRamp) {
+    let key = PoolDataKey::FactorRamp(asset.clone());
+    e.storage()
+        .persistent()
+        .set::<PoolDataKey, ReserveFactorRamp>(&key, ramp);
+    e.storage()
+        .persistent()
+        .extend_ttl(&key, LEDGER_THRESHOLD_SHARED, LEDGER_BUMP_SHARED);
+}
+
+/// Delete the factor ramp of a reserve
+///
+/// ### Arguments
+/// * `asset` - The contract address of the asset
+pub fn del_res_factor_ramp(e: &Env, asset: &Address) {
+    let key = PoolDataKey::FactorRamp(asset.clone());
+    e.storage().persistent().remove(&key);
+}
+
+/********** Delisting **********/
+
+/// Fetch the delisting schedule of a reserve, if the reserve is being delisted
+///
+/// ### Arguments
+/// * `asset` - The contract address of the asset
+pub fn get_res_delisting(e: &Env, asset: &Address) -> Option<ReserveDelisting> {
+    let key = PoolDataKey::Delisting(asset.clone());
+    let delisting = e
+        .storage()
+        .persistent()
+        .get::<PoolDataKey, ReserveDelisting>(&key);
+    if delisting.is_some() {
+        e.storage()
+            .persistent()
+            .extend_ttl(&key, LEDGER_THRESHOLD_SHARED, LEDGER_BUMP_SHARED);
+    }
+    delisting
+}
+
+/// Checks if a reserve is being delisted
+///
+/// ### Arguments
+/// * `asset` - The contract address of the asset
+pub fn has_res_delisting(e: &Env, asset: &Address) -> bool {
+    let key = PoolDataKey::Delisting(asset.clone());
+    e.storage().persistent().has(&key)
+}
+
+/// Set the delisting schedule of a reserve
+///
+/// ### Arguments
+/// * `asset` - The contract address of the asset
+/// * `delisting` - The delisting schedule
+pub fn set_res_delisting(e: &Env, asset: &Address, delisting: &ReserveDelisting) {
+    let key = PoolDataKey::Delisting(asset.clone());
+    e.storage()
+        .persistent()
+        .set::<PoolDataKey, ReserveDelisting>(&key, delisting);
+    e.storage()
+        .persistent()
+        .extend_ttl(&key, LEDGER_THRESHOLD_SHARED, LEDGER_BUMP_SHARED);
+}
+
 /********** Reserve Emissions **********/
 
 /// Fetch the emission data for the reserve b or d token
@@ -616,6 +1416,8 @@ pub fn set_auction(e: &Env, auction_type: &u32, user: &Address, auction_data: &A
     e.storage()
         .temporary()
         .extend_ttl(&key, LEDGER_THRESHOLD_SHARED, LEDGER_BUMP_SHARED);
+
+    index_auction(e, auction_type, user);
 }
 
 /// Remove an auction
@@ -629,4 +1431,198 @@ pub fn del_auction(e: &Env, auction_type: &u32, user: &Address) {
         auct_type: *auction_type,
     });
     e.storage().temporary().remove(&key);
+    e.storage()
+        .temporary()
+        .remove(&PoolDataKey::AuctionKeeper(AuctionKey {
+            user: user.clone(),
+            auct_type: *auction_type,
+        }));
+
+    let slot_key = PoolDataKey::AuctionSlot(AuctionKey {
+        user: user.clone(),
+        auct_type: *auction_type,
+    });
+    if let Some(slot) = e.storage().temporary().get::<PoolDataKey, u32>(&slot_key) {
+        remove_auction_index(e, auction_type, slot);
+    }
+}
+
+/// Fetch the keeper that created an auction, if it is owed a reward when the auction is filled
+///
+/// ### Arguments
+/// * `auction_type` - The type of auction
+/// * `user` - The user who is auctioning off assets
+pub fn get_auction_keeper(e: &Env, auction_type: &u32, user: &Address) -> Option<Address> {
+    let key = PoolDataKey::AuctionKeeper(AuctionKey {
+        user: user.clone(),
+        auct_type: *auction_type,
+    });
+    e.storage().temporary().get::<PoolDataKey, Address>(&key)
+}
+
+/// Set the keeper that created an auction. The keeper is removed when the auction is deleted.
+///
+/// ### Arguments
+/// * `auction_type` - The type of auction
+/// * `user` - The user who is auctioning off assets
+/// * `keeper` - The address of the keeper
+pub fn set_auction_keeper(e: &Env, auction_type: &u32, user: &Address, keeper: &Address) {
+    let key = PoolDataKey::AuctionKeeper(AuctionKey {
+        user: user.clone(),
+        auct_type: *auction_type,
+    });
+    e.storage()
+        .temporary()
+        .set::<PoolDataKey, Address>(&key, keeper);
+    e.storage()
+        .temporary()
+        .extend_ttl(&key, LEDGER_THRESHOLD_SHARED, LEDGER_BUMP_SHARED);
+}
+
+/// Fetch the number of users in the auction index of an auction type. Users whose auction has
+/// expired are counted until they are removed from the index.
+///
+/// ### Arguments
+/// * `auction_type` - The type of auction
+pub fn get_auction_count(e: &Env, auction_type: &u32) -> u32 {
+    let key = PoolDataKey::AuctionCount(*auction_type);
+    e.storage()
+        .temporary()
+        .get::<PoolDataKey, u32>(&key)
+        .unwrap_or(0)
+}
+
+/// Set the number of users in the auction index of an auction type
+///
+/// ### Arguments
+/// * `auction_type` - The type of auction
+/// * `count` - The number of users in the auction index
+fn set_auction_count(e: &Env, auction_type: &u32, count: u32) {
+    let key = PoolDataKey::AuctionCount(*auction_type);
+    e.storage()
+        .temporary()
+        .set::<PoolDataKey, u32>(&key, &count);
+    e.storage()
+        .temporary()
+        .extend_ttl(&key, LEDGER_THRESHOLD_SHARED, LEDGER_BUMP_SHARED);
+}
+
+/// Fetch the user at a position in the auction index of an auction type. Returns None if the
+/// position has expired.
+///
+/// ### Arguments
+/// * `auction_type` - The type of auction
+/// * `slot` - The position in the auction index
+pub fn get_auction_index(e: &Env, auction_type: &u32, slot: u32) -> Option<Address> {
+    let key = PoolDataKey::AuctionIndex(AuctionIndexKey {
+        auct_type: *auction_type,
+        slot,
+    });
+    e.storage().temporary().get::<PoolDataKey, Address>(&key)
+}
+
+/// Set the user at a position in the auction index of an auction type, and the user's position
+///
+/// ### Arguments
+/// * `auction_type` - The type of auction
+/// * `slot` - The position in the auction index
+/// * `user` - The user who is auctioning off assets
+fn set_auction_index(e: &Env, auction_type: &u32, slot: u32, user: &Address) {
+    let index_key = PoolDataKey::AuctionIndex(AuctionIndexKey {
+        auct_type: *auction_type,
+        slot,
+    });
+    e.storage()
+        .temporary()
+        .set::<PoolDataKey, Address>(&index_key, user);
+    e.storage()
+        .temporary()
+        .extend_ttl(&index_key, LEDGER_THRESHOLD_SHARED, LEDGER_BUMP_SHARED);
+    let slot_key = PoolDataKey::AuctionSlot(AuctionKey {
+        user: user.clone(),
+        auct_type: *auction_type,
+    });
+    e.storage()
+        .temporary()
+        .set::<PoolDataKey, u32>(&slot_key, &slot);
+    e.storage()
+        .temporary()
+        .extend_ttl(&slot_key, LEDGER_THRESHOLD_SHARED, LEDGER_BUMP_SHARED);
+}
+
+/// Add a user to the auction index of an auction type, or extend their index entries if they are
+/// already indexed. Before a user is appended, the last user in the index is removed if their
+/// auction no longer exists.
+///
+/// ### Arguments
+/// * `auction_type` - The type of auction
+/// * `user` - The user who is auctioning off assets
+fn index_auction(e: &Env, auction_type: &u32, user: &Address) {
+    let mut count = get_auction_count(e, auction_type);
+    let slot_key = PoolDataKey::AuctionSlot(AuctionKey {
+        user: user.clone(),
+        auct_type: *auction_type,
+    });
+    if let Some(slot) = e.storage().temporary().get::<PoolDataKey, u32>(&slot_key) {
+        if slot < count && get_auction_index(e, auction_type, slot).as_ref() == Some(user) {
+            set_auction_index(e, auction_type, slot, user);
+            set_auction_count(e, auction_type, count);
+            return;
+        }
+    }
+
+    if count > 0 {
+        let last_is_active = match get_auction_index(e, auction_type, count - 1) {
+            Some(last_user) => has_auction(e, auction_type, &last_user),
+            None => false,
+        };
+        if !last_is_active {
+            remove_auction_index(e, auction_type, count - 1);
+            count -= 1;
+        }
+    }
+    set_auction_index(e, auction_type, count, user);
+    set_auction_count(e, auction_type, count + 1);
+}
+
+/// Remove the user at a position in the auction index of an auction type. The last user in the
+/// index is moved into the position, so the index stays contiguous.
+///
+/// ### Arguments
+/// * `auction_type` - The type of auction
+/// * `slot` - The position in the auction index
+pub fn remove_auction_index(e: &Env, auction_type: &u32, slot: u32) {
+    let count = get_auction_count(e, auction_type);
+    if slot >= count {
+        return;
+    }
+    if let Some(user) = get_auction_index(e, auction_type, slot) {
+        e.storage()
+            .temporary()
+            .remove(&PoolDataKey::AuctionSlot(AuctionKey {
+                user,
+                auct_type: *auction_type,
+            }));
+    }
+
+    let last = count - 1;
+    if slot != last {
+        match get_auction_index(e, auction_type, last) {
+            Some(last_user) => set_auction_index(e, auction_type, slot, &last_user),
+            None => e
+                .storage()
+                .temporary()
+                .remove(&PoolDataKey::AuctionIndex(AuctionIndexKey {
+                    auct_type: *auction_type,
+                    slot,
+                })),
+        }
+    }
+    e.storage()
+        .temporary()
+        .remove(&PoolDataKey::AuctionIndex(AuctionIndexKey {
+            auct_type: *auction_type,
+            slot: last,
+        }));
+    set_auction_count(e, auction_type, last);
 }
@@ -7,10 +7,16 @@ use crate::{
     PoolContract,
 };
 use blend_contract_sdk::emitter::{Client as EmitterClient, WASM as EmitterWASM};
-use sep_40_oracle::testutils::{MockPriceOracleClient, MockPriceOracleWASM};
+use sep_40_oracle::{
+    testutils::{MockPriceOracleClient, MockPriceOracleWASM},
+    Asset, PriceData,
+};
 use sep_41_token::testutils::{MockTokenClient, MockTokenWASM};
 use soroban_fixed_point_math::SorobanFixedPoint;
-use soroban_sdk::{testutils::Address as _, vec, Address, BytesN, Env, IntoVal, String};
+use soroban_sdk::{
+    contract, contractimpl, testutils::Address as _, vec, Address, BytesN, Env, IntoVal, Map,
+    String, Symbol, Vec,
+};
 
 use backstop::{BackstopClient, BackstopContract};
 use mock_pool_factory::{MockPoolFactory, MockPoolFactoryClient, PoolInitMeta};
@@ -78,6 +84,76 @@ pub(crate) fn create_mock_oracle(e: &Env) -> (Address, MockPriceOracleClient) {
     )
 }
 
+/// Create a set of mock oracles that each report USD prices for `assets` in the given decimals,
+/// for tests of a primary oracle with backups. The first oracle is expected to be used as the
+/// primary oracle.
+pub(crate) fn create_mock_oracles<'a>(
+    e: &Env,
+    n: u32,
+    assets: &Vec<Address>,
+    decimals: u32,
+) -> std::vec::Vec<(Address, MockPriceOracleClient<'a>)> {
+    let admin = Address::generate(e);
+    let mut oracle_assets = vec![e];
+    for asset in assets.iter() {
+        oracle_assets.push_back(Asset::Stellar(asset));
+    }
+    let mut oracles = std::vec::Vec::new();
+    for _ in 0..n {
+        let (oracle, oracle_client) = create_mock_oracle(e);
+        oracle_client.set_data(
+            &admin,
+            &Asset::Other(Symbol::new(e, "USD")),
+            &oracle_assets,
+            &decimals,
+            &300,
+        );
+        oracles.push((oracle, oracle_client));
+    }
+    oracles
+}
+
+/// A mock SEP-40 oracle that reports a settable price history for each asset, used to test
+/// TWAP pricing. The history is ordered from most recent to oldest.
+#[contract]
+pub struct MockHistoricalOracle;
+
+#[contractimpl]
+impl MockHistoricalOracle {
+    pub fn set_history(e: Env, asset: Address, history: Vec<PriceData>) {
+        e.storage().instance().set(&asset, &history);
+    }
+
+    pub fn decimals(_e: Env) -> u32 {
+        7
+    }
+
+    pub fn lastprice(e: Env, asset: Asset) -> Option<PriceData> {
+        Self::prices(e, asset, 1).and_then(|history| history.first())
+    }
+
+    pub fn prices(e: Env, asset: Asset, records: u32) -> Option<Vec<PriceData>> {
+        match asset {
+            Asset::Stellar(address) => e
+                .storage()
+                .instance()
+                .get::<Address, Vec<PriceData>>(&address)
+                .map(|history| history.slice(0..records.min(history.len()))),
+            Asset::Other(_) => None,
+        }
+    }
+}
+
+pub(crate) fn create_mock_historical_oracle<'a>(
+    e: &Env,
+) -> (Address, MockHistoricalOracleClient<'a>) {
+    let contract_address = e.register(MockHistoricalOracle {}, ());
+    (
+        contract_address.clone(),
+        MockHistoricalOracleClient::new(e, &contract_address),
+    )
+}
+
 //***** Pool Factory ******
 
 pub(crate) fn create_mock_pool_factory(e: &Env) -> (Address, MockPoolFactoryClient) {
@@ -193,6 +269,84 @@ pub fn create_flashloan_receiver<'a>(
     )
 }
 
+//***** Swap Router *****
+
+/// A mock swap router that pays out a settable rate of the output token for each input token,
+/// expressed in 7 decimals. The router must hold enough of the output token to pay out.
+#[contract]
+pub struct MockSwapRouter;
+
+#[contractimpl]
+impl MockSwapRouter {
+    pub fn set_rate(e: Env, rate: i128) {
+        e.storage().instance().set(&Symbol::new(&e, "Rate"), &rate);
+    }
+
+    pub fn swap(
+        e: Env,
+        _token_in: Address,
+        token_out: Address,
+        amount_in: i128,
+        _min_out: i128,
+        to: Address,
+    ) -> i128 {
+        let rate: i128 = e
+            .storage()
+            .instance()
+            .get(&Symbol::new(&e, "Rate"))
+            .unwrap_or(SCALAR_7);
+        let amount_out = amount_in.fixed_mul_floor(&e, &rate, &SCALAR_7);
+        MockTokenClient::new(&e, &token_out).transfer(
+            &e.current_contract_address(),
+            &to,
+            &amount_out,
+        );
+        amount_out
+    }
+}
+
+pub(crate) fn create_mock_swap_router<'a>(e: &Env) -> (Address, MockSwapRouterClient<'a>) {
+    let contract_address = e.register(MockSwapRouter {}, ());
+    (
+        contract_address.clone(),
+        MockSwapRouterClient::new(e, &contract_address),
+    )
+}
+
+/// A mock liquidation receiver that pays the caller the bid out of its own balance of the bid
+/// assets and keeps the lot
+#[contract]
+pub struct MockLiquidationReceiver;
+
+#[contractimpl]
+impl MockLiquidationReceiver {
+    pub fn exec_liquidation(
+        e: Env,
+        caller: Address,
+        _user: Address,
+        _lot: Map<Address, i128>,
+        bid: Map<Address, i128>,
+    ) {
+        for (asset, amount) in bid.iter() {
+            MockTokenClient::new(&e, &asset).transfer(
+                &e.current_contract_address(),
+                &caller,
+                &amount,
+            );
+        }
+    }
+}
+
+pub(crate) fn create_mock_liquidation_receiver<'a>(
+    e: &Env,
+) -> (Address, MockLiquidationReceiverClient<'a>) {
+    let contract_address = e.register(MockLiquidationReceiver {}, ());
+    (
+        contract_address.clone(),
+        MockLiquidationReceiverClient::new(e, &contract_address),
+    )
+}
+
 //************************************************
 //            Object Creation Helpers
 //************************************************
@@ -215,6 +369,14 @@ pub(crate) fn default_reserve(e: &Env) -> Reserve {
             reactivity: 0_0000020, // 2e-6
             index: 0,
             collateral_cap: 1000000000000000000,
+            twap_records: 0,
+            borrow_cap: 1000000000000000000,
+            supply_cap: 1000000000000000000,
+            isolated: false,
+            debt_ceiling: 0,
+            ir_model: 0,
+            util_two: 0,
+            flash_fee: 0,
             enabled: true,
         },
         data: ReserveData {
@@ -223,6 +385,7 @@ pub(crate) fn default_reserve(e: &Env) -> Reserve {
             ir_mod: SCALAR_7,
             b_supply: 100_0000000,
             d_supply: 75_0000000,
+            fixed_debt: 0,
             last_time: 0,
             backstop_credit: 0,
         },
@@ -245,6 +408,14 @@ pub(crate) fn default_reserve_meta() -> (ReserveConfig, ReserveData) {
             reactivity: 0_0000020, // 2e-6
             index: 0,
             collateral_cap: 1000000000000000000,
+            twap_records: 0,
+            borrow_cap: 1000000000000000000,
+            supply_cap: 1000000000000000000,
+            isolated: false,
+            debt_ceiling: 0,
+            ir_model: 0,
+            util_two: 0,
+            flash_fee: 0,
             enabled: true,
         },
         ReserveData {
@@ -253,6 +424,7 @@ pub(crate) fn default_reserve_meta() -> (ReserveConfig, ReserveData) {
             ir_mod: SCALAR_7,
             b_supply: 100_0000000,
             d_supply: 75_0000000,
+            fixed_debt: 0,
             last_time: 0,
             backstop_credit: 0,
         },
@@ -288,7 +460,8 @@ pub(crate) fn create_reserve(
     let total_liabilities =
         reserve_data
             .d_supply
-            .fixed_mul_floor(e, &reserve_data.d_rate, &SCALAR_12);
+            .fixed_mul_floor(e, &reserve_data.d_rate, &SCALAR_12)
+            + reserve_data.fixed_debt;
     let to_mint_pool = total_supply - total_liabilities + reserve_data.backstop_credit;
     underlying_client
         .mock_all_auths()
@@ -19,6 +19,14 @@ pub fn default_reserve_metadata() -> ReserveConfig {
         reactivity: 0_0000020, // 2e-6
         index: 0,
         collateral_cap: 1000000000000000000,
+        twap_records: 0,
+        borrow_cap: 1000000000000000000,
+        supply_cap: 1000000000000000000,
+        isolated: false,
+        debt_ceiling: 0,
+        ir_model: 0,
+        util_two: 0,
+        flash_fee: 0,
         enabled: true,
     }
 }
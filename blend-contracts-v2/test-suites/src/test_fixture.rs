@@ -205,7 +205,7 @@ impl TestFixture<'_> {
         let token = &self.tokens[asset_index];
         pool_fixture
             .pool
-            .queue_set_reserve(&token.address, reserve_config);
+            .queue_set_reserve(&token.address, reserve_config, &0);
         let index = pool_fixture.pool.set_reserve(&token.address);
         pool_fixture.reserves.insert(asset_index, index);
         self.pools.insert(pool_index, pool_fixture);
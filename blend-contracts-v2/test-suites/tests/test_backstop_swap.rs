@@ -434,14 +434,14 @@ fn deploy_v2_pool(
     xlm_config.l_factor = 0_750_0000;
     xlm_config.util = 0_500_0000;
     xlm_config.max_util = 0_900_0000;
-    pool_client.queue_set_reserve(&xlm, &xlm_config);
+    pool_client.queue_set_reserve(&xlm, &xlm_config, &0);
     pool_client.set_reserve(&xlm);
 
     let mut usdc_config = default_reserve_metadata();
     usdc_config.c_factor = 0_900_0000;
     usdc_config.l_factor = 0_950_0000;
     usdc_config.util = 0_800_0000;
-    pool_client.queue_set_reserve(&usdc, &usdc_config);
+    pool_client.queue_set_reserve(&usdc, &usdc_config, &0);
     pool_client.set_reserve(&usdc);
 
     // split emissions equally between XLM and USDC supplying
@@ -46,10 +46,10 @@ fn test_flashloan() {
         &approval_ledger,
     );
 
+    let flash_amount = 1_000 * SCALAR_7;
     let flash_loan = FlashLoan {
         contract: receiver_address.clone(),
-        asset: xlm_address.clone(),
-        amount: 1_000 * SCALAR_7,
+        assets: vec![&fixture.env, (xlm_address.clone(), flash_amount)],
     };
     let supply_amount = 50 * SCALAR_7;
     let repay_amount = 900 * SCALAR_7;
@@ -94,8 +94,8 @@ fn test_flashloan() {
                         vec![
                             &fixture.env,
                             samwise.to_val(),
-                            flash_loan.asset.to_val(),
-                            flash_loan.amount.into_val(&fixture.env),
+                            xlm_address.to_val(),
+                            flash_amount.into_val(&fixture.env),
                             0i128.into_val(&fixture.env),
                         ]
                     )),
@@ -112,13 +112,12 @@ fn test_flashloan() {
     let stable_res_data = pool_fixture.pool.get_reserve(&stable_address);
 
     let flash_loan_events = vec![&fixture.env, events.get_unchecked(0)];
-    let flash_loan_d_tokens_minted = flash_loan
-        .amount
+    let flash_loan_d_tokens_minted = flash_amount
         .fixed_div_ceil(xlm_res_data.data.d_rate, SCALAR_12)
         .unwrap();
     let flash_loan_event_data: soroban_sdk::Vec<Val> = vec![
         &fixture.env,
-        flash_loan.amount.into_val(&fixture.env),
+        flash_amount.into_val(&fixture.env),
         flash_loan_d_tokens_minted.into_val(&fixture.env),
     ];
     assert_eq!(
@@ -129,7 +128,7 @@ fn test_flashloan() {
                 pool_fixture.pool.address.clone(),
                 (
                     Symbol::new(&fixture.env, "flash_loan"),
-                    flash_loan.asset.clone(),
+                    xlm_address.clone(),
                     samwise.clone(),
                     flash_loan.contract.clone(),
                 )
@@ -208,15 +207,201 @@ fn test_flashloan() {
     );
     assert_eq!(
         xlm.balance(&pool_fixture.pool.address),
-        pool_starting_xlm_balance - flash_loan.amount + repay_amount
+        pool_starting_xlm_balance - flash_amount + repay_amount
     );
 
     assert_eq!(
         xlm.balance(&samwise),
-        starting_xlm_balance + flash_loan.amount - repay_amount
+        starting_xlm_balance + flash_amount - repay_amount
     );
     assert_eq!(
         stable.balance(&samwise),
         starting_stable_balance - supply_amount
     );
 }
+
+#[test]
+fn test_flashloan_multiple_assets() {
+    let fixture = create_fixture_with_data(false);
+    let pool_fixture = &fixture.pools[0];
+
+    let xlm = &fixture.tokens[TokenIndex::XLM];
+    let xlm_address = xlm.address.clone();
+    let stable = &fixture.tokens[TokenIndex::STABLE];
+    let stable_address = stable.address.clone();
+
+    let (receiver_address, _) = create_flashloan_receiver(&fixture.env);
+
+    let samwise = Address::generate(&fixture.env);
+
+    let pool_starting_xlm_balance = xlm.balance(&pool_fixture.pool.address);
+    let pool_starting_stable_balance = stable.balance(&pool_fixture.pool.address);
+    let starting_xlm_balance = 100 * SCALAR_7;
+    let starting_stable_balance = 100 * SCALAR_7;
+    let approval_ledger = fixture.env.ledger().sequence() + 17280;
+
+    xlm.mint(&samwise, &starting_xlm_balance);
+    xlm.approve(
+        &samwise,
+        &pool_fixture.pool.address,
+        &i128::MAX,
+        &approval_ledger,
+    );
+    stable.mint(&samwise, &starting_stable_balance);
+    stable.approve(
+        &samwise,
+        &pool_fixture.pool.address,
+        &starting_stable_balance,
+        &approval_ledger,
+    );
+
+    let xlm_flash_amount = 1_000 * SCALAR_7;
+    let stable_flash_amount = 100 * 10i128.pow(6);
+    let flash_loan = FlashLoan {
+        contract: receiver_address.clone(),
+        assets: vec![
+            &fixture.env,
+            (xlm_address.clone(), xlm_flash_amount),
+            (stable_address.clone(), stable_flash_amount),
+        ],
+    };
+    let supply_amount = 50 * SCALAR_7;
+    let repay_amount = 900 * SCALAR_7;
+    let requests: Vec<Request> = vec![
+        &fixture.env,
+        Request {
+            request_type: RequestType::SupplyCollateral as u32,
+            address: stable_address.clone(),
+            amount: supply_amount,
+        },
+        Request {
+            request_type: RequestType::Repay as u32,
+            address: xlm_address.clone(),
+            amount: repay_amount,
+        },
+    ];
+
+    let result = pool_fixture
+        .pool
+        .flash_loan(&samwise, &flash_loan, &requests);
+
+    // validate auth - the receiver is invoked once with all assets
+    assert_eq!(
+        fixture.env.auths()[0],
+        (
+            samwise.clone(),
+            AuthorizedInvocation {
+                function: AuthorizedFunction::Contract((
+                    pool_fixture.pool.address.clone(),
+                    Symbol::new(&fixture.env, "flash_loan"),
+                    vec![
+                        &fixture.env,
+                        samwise.to_val(),
+                        flash_loan.into_val(&fixture.env),
+                        requests.to_val(),
+                    ]
+                )),
+                sub_invocations: std::vec![AuthorizedInvocation {
+                    function: AuthorizedFunction::Contract((
+                        receiver_address.clone(),
+                        Symbol::new(&fixture.env, "exec_op_multi"),
+                        vec![
+                            &fixture.env,
+                            samwise.to_val(),
+                            vec![&fixture.env, xlm_address.clone(), stable_address.clone()]
+                                .into_val(&fixture.env),
+                            vec![&fixture.env, xlm_flash_amount, stable_flash_amount]
+                                .into_val(&fixture.env),
+                            vec![&fixture.env, 0i128, 0i128].into_val(&fixture.env),
+                        ]
+                    )),
+                    sub_invocations: std::vec![]
+                }]
+            }
+        )
+    );
+
+    // validate a flash loan event is emitted for each asset
+    let events = fixture.env.events().all();
+    let xlm_res_data = pool_fixture.pool.get_reserve(&xlm_address);
+    let stable_res_data = pool_fixture.pool.get_reserve(&stable_address);
+
+    let xlm_d_tokens_minted = xlm_flash_amount
+        .fixed_div_ceil(xlm_res_data.data.d_rate, SCALAR_12)
+        .unwrap();
+    let stable_d_tokens_minted = stable_flash_amount
+        .fixed_div_ceil(stable_res_data.data.d_rate, SCALAR_12)
+        .unwrap();
+    let xlm_event_data: soroban_sdk::Vec<Val> = vec![
+        &fixture.env,
+        xlm_flash_amount.into_val(&fixture.env),
+        xlm_d_tokens_minted.into_val(&fixture.env),
+    ];
+    let stable_event_data: soroban_sdk::Vec<Val> = vec![
+        &fixture.env,
+        stable_flash_amount.into_val(&fixture.env),
+        stable_d_tokens_minted.into_val(&fixture.env),
+    ];
+    assert_eq!(
+        vec![
+            &fixture.env,
+            events.get_unchecked(0),
+            events.get_unchecked(1)
+        ],
+        vec![
+            &fixture.env,
+            (
+                pool_fixture.pool.address.clone(),
+                (
+                    Symbol::new(&fixture.env, "flash_loan"),
+                    xlm_address.clone(),
+                    samwise.clone(),
+                    receiver_address.clone(),
+                )
+                    .into_val(&fixture.env),
+                xlm_event_data.into_val(&fixture.env),
+            ),
+            (
+                pool_fixture.pool.address.clone(),
+                (
+                    Symbol::new(&fixture.env, "flash_loan"),
+                    stable_address.clone(),
+                    samwise.clone(),
+                    receiver_address.clone(),
+                )
+                    .into_val(&fixture.env),
+                stable_event_data.into_val(&fixture.env),
+            )
+        ]
+    );
+
+    // validate results
+    let repay_d_tokens_burned = repay_amount
+        .fixed_div_floor(xlm_res_data.data.d_rate, SCALAR_12)
+        .unwrap();
+    assert_eq!(result.collateral.len(), 1);
+    assert_eq!(result.liabilities.len(), 2);
+    assert_eq!(result.supply.len(), 0);
+    assert_eq!(
+        result.liabilities.get_unchecked(1),
+        xlm_d_tokens_minted - repay_d_tokens_burned
+    );
+    assert_eq!(result.liabilities.get_unchecked(0), stable_d_tokens_minted);
+
+    assert_eq!(
+        stable.balance(&pool_fixture.pool.address),
+        pool_starting_stable_balance - stable_flash_amount + supply_amount
+    );
+    assert_eq!(
+        xlm.balance(&pool_fixture.pool.address),
+        pool_starting_xlm_balance - xlm_flash_amount + repay_amount
+    );
+    assert_eq!(
+        xlm.balance(&samwise),
+        starting_xlm_balance + xlm_flash_amount - repay_amount
+    );
+    assert_eq!(
+        stable.balance(&samwise),
+        starting_stable_balance + stable_flash_amount - supply_amount
+    );
+}
@@ -187,6 +187,7 @@ fn test_liquidations() {
     // Start an interest auction
     // type 2 is an interest auction
     let auction_data = pool_fixture.pool.new_auction(
+        &frodo,
         &2u32,
         &fixture.backstop.address,
         &vec![&fixture.env, fixture.lp.address.clone()],
@@ -236,6 +237,7 @@ fn test_liquidations() {
     );
     // Start a liquidation auction
     let auction_data = pool_fixture.pool.new_auction(
+        &frodo,
         &0,
         &samwise,
         &vec![
@@ -450,6 +452,7 @@ fn test_liquidations() {
         .submit(&samwise, &samwise, &samwise, &blank_requests);
     let liq_pct = 100;
     let auction_data_2 = pool_fixture.pool.new_auction(
+        &frodo,
         &0,
         &samwise,
         &vec![
@@ -557,7 +560,7 @@ fn test_liquidations() {
         pool_fixture
             .pool
             .submit(&samwise, &samwise, &samwise, &blank_request);
-    pool_fixture.pool.bad_debt(&samwise);
+    pool_fixture.pool.bad_debt(&frodo, &samwise);
     let backstop_positions = pool_fixture.pool.submit(
         &fixture.backstop.address,
         &fixture.backstop.address,
@@ -576,6 +579,7 @@ fn test_liquidations() {
     // create a bad debt auction
     let auction_type: u32 = 1;
     let bad_debt_auction_data = pool_fixture.pool.new_auction(
+        &frodo,
         &1u32,
         &fixture.backstop.address,
         &vec![
@@ -689,7 +693,7 @@ fn test_liquidations() {
     );
     let new_auction = pool_fixture
         .pool
-        .get_auction(&(1 as u32), &fixture.backstop.address);
+        .get_auction_data(&(1 as u32), &fixture.backstop.address);
     assert_eq!(new_auction.bid.len(), 2);
     assert_eq!(new_auction.lot.len(), 1);
     assert_eq!(
@@ -723,6 +727,23 @@ fn test_liquidations() {
         SCALAR_7,
     );
     assert_eq!(new_auction.block, bad_debt_auction_data.block);
+    // the quote is scaled along the auction curve, and the bid is not scaled during the lot ramp
+    let quote = pool_fixture
+        .pool
+        .get_auction(&(1 as u32), &fixture.backstop.address);
+    assert_eq!(quote.bid.len(), 2);
+    assert_eq!(
+        quote
+            .bid
+            .get_unchecked(fixture.tokens[TokenIndex::STABLE].address.clone()),
+        new_auction
+            .bid
+            .get_unchecked(fixture.tokens[TokenIndex::STABLE].address.clone())
+    );
+    assert!(
+        quote.lot.get_unchecked(fixture.lp.address.clone())
+            < new_auction.lot.get_unchecked(fixture.lp.address.clone())
+    );
 
     // allow another 50 blocks to pass (150 total)
     fixture.jump_with_sequence(50 * 5);
@@ -825,6 +846,7 @@ fn test_liquidations() {
     // Liquidate sam
     let liq_pct: u32 = 100;
     let auction_data = pool_fixture.pool.new_auction(
+        &frodo,
         &0,
         &samwise,
         &vec![
@@ -872,7 +894,7 @@ fn test_liquidations() {
         .pool
         .submit(&samwise, &samwise, &samwise, &blank_request);
 
-    pool_fixture.pool.bad_debt(&samwise);
+    pool_fixture.pool.bad_debt(&frodo, &samwise);
 
     let events = fixture.env.events().all();
     let event = vec![&fixture.env, events.get_unchecked(events.len() - 1)];
@@ -896,6 +918,7 @@ fn test_liquidations() {
 
     // Create bad debt auction
     pool_fixture.pool.new_auction(
+        &frodo,
         &1u32,
         &fixture.backstop.address,
         &vec![
@@ -1001,6 +1024,8 @@ fn test_user_restore_position_and_delete_liquidation() {
     let stable_pool_index = pool_fixture.reserves[&TokenIndex::STABLE];
     let xlm_pool_index = pool_fixture.reserves[&TokenIndex::XLM];
 
+    let frodo = fixture.users.get(0).unwrap();
+
     // Create a user that is supply STABLE (cf = 90%, $1) and borrowing XLM (lf = 75%, $0.10)
     let samwise = Address::generate(&fixture.env);
     fixture.tokens[TokenIndex::STABLE].mint(&samwise, &(1100 * 10i128.pow(6)));
@@ -1032,6 +1057,7 @@ fn test_user_restore_position_and_delete_liquidation() {
         1_0000000,    // stable
     ]);
     pool_fixture.pool.new_auction(
+        &frodo,
         &0,
         &samwise,
         &vec![
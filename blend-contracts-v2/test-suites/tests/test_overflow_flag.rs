@@ -128,6 +128,7 @@ fn test_auction_underflow_panics() {
     // liquidate user
     let liq_pct = 100;
     let auction_data_2 = pool_fixture.pool.new_auction(
+        &frodo,
         &0,
         &samwise,
         &vec![
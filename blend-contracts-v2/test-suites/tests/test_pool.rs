@@ -616,7 +616,7 @@ fn test_pool_config() {
     reserve_config.c_factor = 0_200_0000;
     pool_fixture
         .pool
-        .queue_set_reserve(&blnd.address, &reserve_config);
+        .queue_set_reserve(&blnd.address, &reserve_config, &0);
     assert_eq!(
         fixture.env.auths()[0],
         (
@@ -628,7 +628,8 @@ fn test_pool_config() {
                     vec![
                         &fixture.env,
                         blnd.address.to_val(),
-                        reserve_config.into_val(&fixture.env)
+                        reserve_config.into_val(&fixture.env),
+                        0_u64.into_val(&fixture.env)
                     ]
                 )),
                 sub_invocations: std::vec![]
@@ -665,7 +666,7 @@ fn test_pool_config() {
     reserve_config.c_factor = 0;
     pool_fixture
         .pool
-        .queue_set_reserve(&blnd.address, &reserve_config);
+        .queue_set_reserve(&blnd.address, &reserve_config, &0);
     assert_eq!(
         fixture.env.auths()[0],
         (
@@ -677,7 +678,8 @@ fn test_pool_config() {
                     vec![
                         &fixture.env,
                         blnd.address.to_val(),
-                        reserve_config.into_val(&fixture.env)
+                        reserve_config.into_val(&fixture.env),
+                        0_u64.into_val(&fixture.env)
                     ]
                 )),
                 sub_invocations: std::vec![]
@@ -38,6 +38,14 @@ fn test_pool_interest() {
         r_three: 0,
         util: 50,
         collateral_cap: i64::MAX as i128,
+        twap_records: 0,
+        borrow_cap: i64::MAX as i128,
+        supply_cap: i64::MAX as i128,
+        isolated: false,
+        debt_ceiling: 0,
+        ir_model: 0,
+        util_two: 0,
+        flash_fee: 0,
         enabled: true,
     };
     fixture.create_pool_reserve(0, TokenIndex::XLM, &xlm_config);
@@ -58,6 +66,14 @@ fn test_pool_interest() {
         r_three: 0,
         util: 50,
         collateral_cap: i64::MAX as i128,
+        twap_records: 0,
+        borrow_cap: i64::MAX as i128,
+        supply_cap: i64::MAX as i128,
+        isolated: false,
+        debt_ceiling: 0,
+        ir_model: 0,
+        util_two: 0,
+        flash_fee: 0,
         enabled: true,
     };
     fixture.create_pool_reserve(0, TokenIndex::STABLE, &stable_config);
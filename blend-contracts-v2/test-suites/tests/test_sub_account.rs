@@ -0,0 +1,279 @@
+#![cfg(test)]
+use pool::{Request, RequestType};
+use soroban_sdk::{
+    testutils::{Address as _, AuthorizedFunction, AuthorizedInvocation},
+    vec, Address, IntoVal, Symbol, Vec,
+};
+use test_suites::{
+    create_fixture_with_data,
+    test_fixture::{TokenIndex, SCALAR_7},
+};
+
+#[test]
+fn test_submit_sub_account() {
+    let fixture = create_fixture_with_data(false);
+    let pool_fixture = &fixture.pools[0];
+    let stable = &fixture.tokens[TokenIndex::STABLE];
+    let xlm = &fixture.tokens[TokenIndex::XLM];
+    let stable_pool_index = pool_fixture.reserves[&TokenIndex::STABLE];
+    let xlm_pool_index = pool_fixture.reserves[&TokenIndex::XLM];
+
+    let samwise = Address::generate(&fixture.env);
+    stable.mint(&samwise, &(1000 * 10i128.pow(6)));
+
+    let sub_1 = pool_fixture.pool.get_sub_account(&samwise, &1);
+    assert_eq!(pool_fixture.pool.get_sub_account(&samwise, &0), samwise);
+    assert_ne!(sub_1, samwise);
+
+    let requests: Vec<Request> = vec![
+        &fixture.env,
+        Request {
+            request_type: RequestType::SupplyCollateral as u32,
+            address: stable.address.clone(),
+            amount: 1000 * 10i128.pow(6),
+        },
+        Request {
+            request_type: RequestType::Borrow as u32,
+            address: xlm.address.clone(),
+            amount: 1000 * SCALAR_7,
+        },
+    ];
+    let positions = pool_fixture
+        .pool
+        .submit_sub_account(&samwise, &1, &samwise, &samwise, &requests);
+
+    // the owner authorizes the submit for their sub-account
+    assert_eq!(
+        fixture.env.auths()[0],
+        (
+            samwise.clone(),
+            AuthorizedInvocation {
+                function: AuthorizedFunction::Contract((
+                    pool_fixture.pool.address.clone(),
+                    Symbol::new(&fixture.env, "submit_sub_account"),
+                    vec![
+                        &fixture.env,
+                        samwise.to_val(),
+                        1u32.into_val(&fixture.env),
+                        samwise.to_val(),
+                        samwise.to_val(),
+                        requests.to_val(),
+                    ]
+                )),
+                sub_invocations: std::vec![AuthorizedInvocation {
+                    function: AuthorizedFunction::Contract((
+                        stable.address.clone(),
+                        Symbol::new(&fixture.env, "transfer"),
+                        vec![
+                            &fixture.env,
+                            samwise.to_val(),
+                            pool_fixture.pool.address.to_val(),
+                            (1000 * 10i128.pow(6)).into_val(&fixture.env),
+                        ]
+                    )),
+                    sub_invocations: std::vec![]
+                }]
+            }
+        )
+    );
+
+    // the positions are held by the sub-account, and tokens move to and from the owner
+    assert_eq!(positions.collateral.len(), 1);
+    assert_eq!(positions.liabilities.len(), 1);
+    assert!(positions.collateral.get_unchecked(stable_pool_index) > 0);
+    assert!(positions.liabilities.get_unchecked(xlm_pool_index) > 0);
+    assert_eq!(
+        pool_fixture
+            .pool
+            .get_positions(&sub_1)
+            .collateral
+            .get_unchecked(stable_pool_index),
+        positions.collateral.get_unchecked(stable_pool_index)
+    );
+    assert_eq!(stable.balance(&samwise), 0);
+    assert_eq!(xlm.balance(&samwise), 1000 * SCALAR_7);
+
+    // the owner and other sub-accounts are untouched
+    assert_eq!(
+        pool_fixture.pool.get_positions(&samwise).effective_count(),
+        0
+    );
+    let sub_2 = pool_fixture.pool.get_sub_account(&samwise, &2);
+    assert_eq!(pool_fixture.pool.get_positions(&sub_2).effective_count(), 0);
+}
+
+#[test]
+fn test_claim_sub_account() {
+    let fixture = create_fixture_with_data(false);
+    let pool_fixture = &fixture.pools[0];
+    let xlm = &fixture.tokens[TokenIndex::XLM];
+    let blnd = &fixture.tokens[TokenIndex::BLND];
+    let xlm_pool_index = pool_fixture.reserves[&TokenIndex::XLM];
+
+    let samwise = Address::generate(&fixture.env);
+    xlm.mint(&samwise, &(10_000 * SCALAR_7));
+
+    let requests: Vec<Request> = vec![
+        &fixture.env,
+        Request {
+            request_type: RequestType::SupplyCollateral as u32,
+            address: xlm.address.clone(),
+            amount: 10_000 * SCALAR_7,
+        },
+    ];
+    pool_fixture
+        .pool
+        .submit_sub_account(&samwise, &1, &samwise, &samwise, &requests);
+
+    fixture.jump(60 * 60 * 24);
+
+    // only the sub-account holding the position accrues emissions
+    let reserve_token_ids = vec![&fixture.env, xlm_pool_index * 2 + 1];
+    let owner_claimed = pool_fixture
+        .pool
+        .claim(&samwise, &reserve_token_ids, &samwise);
+    assert_eq!(owner_claimed, 0);
+    let sub_2_claimed =
+        pool_fixture
+            .pool
+            .claim_sub_account(&samwise, &2, &reserve_token_ids, &samwise);
+    assert_eq!(sub_2_claimed, 0);
+
+    let sub_1_claimed =
+        pool_fixture
+            .pool
+            .claim_sub_account(&samwise, &1, &reserve_token_ids, &samwise);
+    assert_eq!(
+        fixture.env.auths()[0],
+        (
+            samwise.clone(),
+            AuthorizedInvocation {
+                function: AuthorizedFunction::Contract((
+                    pool_fixture.pool.address.clone(),
+                    Symbol::new(&fixture.env, "claim_sub_account"),
+                    vec![
+                        &fixture.env,
+                        samwise.to_val(),
+                        1u32.into_val(&fixture.env),
+                        reserve_token_ids.to_val(),
+                        samwise.to_val(),
+                    ]
+                )),
+                sub_invocations: std::vec![]
+            }
+        )
+    );
+    assert!(sub_1_claimed > 0);
+    assert_eq!(blnd.balance(&samwise), sub_1_claimed);
+
+    // the sub-account's emissions were claimed
+    let sub_1_claimed_again =
+        pool_fixture
+            .pool
+            .claim_sub_account(&samwise, &1, &reserve_token_ids, &samwise);
+    assert_eq!(sub_1_claimed_again, 0);
+}
+
+#[test]
+fn test_liquidate_sub_account_leaves_other_sub_accounts() {
+    let fixture = create_fixture_with_data(false);
+    let pool_fixture = &fixture.pools[0];
+    let stable = &fixture.tokens[TokenIndex::STABLE];
+    let xlm = &fixture.tokens[TokenIndex::XLM];
+
+    let stable_pool_index = pool_fixture.reserves[&TokenIndex::STABLE];
+
+    let frodo = fixture.users.get(0).unwrap();
+    let samwise = Address::generate(&fixture.env);
+    stable.mint(&samwise, &(2000 * 10i128.pow(6)));
+
+    // sub-account 1 borrows to 90% of its borrow limit, sub-account 2 stays well collateralized
+    let risky_requests: Vec<Request> = vec![
+        &fixture.env,
+        Request {
+            request_type: RequestType::SupplyCollateral as u32,
+            address: stable.address.clone(),
+            amount: 1000 * 10i128.pow(6),
+        },
+        Request {
+            request_type: RequestType::Borrow as u32,
+            address: xlm.address.clone(),
+            amount: 6075 * SCALAR_7,
+        },
+    ];
+    pool_fixture
+        .pool
+        .submit_sub_account(&samwise, &1, &samwise, &samwise, &risky_requests);
+    let safe_requests: Vec<Request> = vec![
+        &fixture.env,
+        Request {
+            request_type: RequestType::SupplyCollateral as u32,
+            address: stable.address.clone(),
+            amount: 1000 * 10i128.pow(6),
+        },
+        Request {
+            request_type: RequestType::Borrow as u32,
+            address: xlm.address.clone(),
+            amount: 1000 * SCALAR_7,
+        },
+    ];
+    pool_fixture
+        .pool
+        .submit_sub_account(&samwise, &2, &samwise, &samwise, &safe_requests);
+
+    let sub_1 = pool_fixture.pool.get_sub_account(&samwise, &1);
+    let sub_2 = pool_fixture.pool.get_sub_account(&samwise, &2);
+    let sub_2_positions = pool_fixture.pool.get_positions(&sub_2);
+
+    // simulate 20% XLM price increase, making sub-account 1 liquidatable
+    fixture.oracle.set_price_stable(&vec![
+        &fixture.env,
+        2000_0000000, // eth
+        1_0000000,    // usdc
+        0_1200000,    // xlm
+        1_0000000,    // stable
+    ]);
+    let bid = vec![&fixture.env, xlm.address.clone()];
+    let lot = vec![&fixture.env, stable.address.clone()];
+    pool_fixture
+        .pool
+        .new_auction(&frodo, &0, &sub_1, &bid, &lot, &50);
+    assert!(pool_fixture.pool.try_get_auction(&0, &sub_1).is_ok());
+
+    // the healthy sub-account and the owner cannot be liquidated
+    assert!(pool_fixture
+        .pool
+        .try_new_auction(&frodo, &0, &sub_2, &bid, &lot, &50)
+        .is_err());
+    assert!(pool_fixture
+        .pool
+        .try_new_auction(&frodo, &0, &samwise, &bid, &lot, &50)
+        .is_err());
+
+    fixture.jump_with_sequence(200 * 5);
+
+    let fill_requests: Vec<Request> = vec![
+        &fixture.env,
+        Request {
+            request_type: RequestType::FillUserLiquidationAuction as u32,
+            address: sub_1.clone(),
+            amount: 100,
+        },
+    ];
+    pool_fixture
+        .pool
+        .submit(&frodo, &frodo, &frodo, &fill_requests);
+    assert!(pool_fixture.pool.try_get_auction(&0, &sub_1).is_err());
+
+    // only sub-account 1 was liquidated
+    let sub_1_positions = pool_fixture.pool.get_positions(&sub_1);
+    assert!(sub_1_positions.collateral.get_unchecked(stable_pool_index) < 1000 * 10i128.pow(6));
+    let new_sub_2_positions = pool_fixture.pool.get_positions(&sub_2);
+    assert_eq!(new_sub_2_positions.collateral, sub_2_positions.collateral);
+    assert_eq!(new_sub_2_positions.liabilities, sub_2_positions.liabilities);
+    assert!(pool_fixture.pool.try_get_auction(&0, &sub_2).is_err());
+    assert_eq!(
+        pool_fixture.pool.get_positions(&samwise).effective_count(),
+        0
+    );
+}